@@ -0,0 +1,108 @@
+#ifndef JARKUP_H
+#define JARKUP_H
+
+/* Generated by cbindgen from jarkup-ffi. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// An HTML fragment.
+#define JARKUP_FORMAT_HTML 0
+
+// GitHub-flavored Markdown.
+#define JARKUP_FORMAT_MARKDOWN 1
+
+// Plain text.
+#define JARKUP_FORMAT_TEXT 2
+
+// Result of a call.
+typedef enum JarkupStatus {
+  JARKUP_STATUS_OK = 0,
+  // A required pointer argument was NULL.
+  JARKUP_STATUS_NULL_POINTER = 1,
+  // The input was not valid UTF-8.
+  JARKUP_STATUS_INVALID_UTF8 = 2,
+  // The input was not a valid jarkup document.
+  JARKUP_STATUS_INVALID_DOCUMENT = 3,
+  // The output format is not one of the `JARKUP_FORMAT_*` constants.
+  JARKUP_STATUS_INVALID_FORMAT = 4,
+  // The library panicked; the document handle is still safe to free.
+  JARKUP_STATUS_PANIC = 5,
+} JarkupStatus;
+
+// A parsed document.
+typedef struct JarkupDocument JarkupDocument;
+
+// Output format of [`jarkup_render`].
+typedef uint32_t JarkupFormat;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Parses `len` bytes of jarkup JSON at `json` into a new document, stored in
+// `*out` on success. `json` need not be NUL-terminated.
+//
+// # Safety
+//
+// `json` must point to `len` readable bytes and `out` must be valid for
+// writes.
+JarkupStatus jarkup_document_parse(const char *json, size_t len, JarkupDocument **out);
+
+// Releases a document. Passing NULL does nothing.
+//
+// # Safety
+//
+// `document` must be NULL or a handle from [`jarkup_document_parse`] that has
+// not been freed yet.
+void jarkup_document_free(JarkupDocument *document);
+
+// Renders a document in `format`, storing a new string in `*out` and, unless
+// `out_len` is NULL, its length in bytes in `*out_len`.
+//
+// # Safety
+//
+// `document` must be a live handle, `out` valid for writes and `out_len`
+// either NULL or valid for writes.
+JarkupStatus jarkup_render(const JarkupDocument *document,
+                           JarkupFormat format,
+                           char **out,
+                           size_t *out_len);
+
+// Validates a document, storing the problems found as a JSON array of
+// `{"path", "kind", "message"}` objects in `*out` and, unless they are NULL,
+// the length of that string in `*out_len` and the number of problems in
+// `*out_count`.
+//
+// # Safety
+//
+// `document` must be a live handle, `out` valid for writes, and `out_len` and
+// `out_count` either NULL or valid for writes.
+JarkupStatus jarkup_validate(const JarkupDocument *document,
+                             char **out,
+                             size_t *out_len,
+                             size_t *out_count);
+
+// Releases a string returned by this library. Passing NULL does nothing.
+//
+// # Safety
+//
+// `string` must be NULL or a string from this library that has not been
+// freed yet.
+void jarkup_string_free(char *string);
+
+// Returns the message of the last failed call on this thread, or NULL if the
+// last call succeeded. The message stays valid until the next call.
+const char *jarkup_last_error(void);
+
+// Returns the library version, e.g. `0.14.0`.
+const char *jarkup_version(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* JARKUP_H */
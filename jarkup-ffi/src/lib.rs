@@ -0,0 +1,285 @@
+//! C bindings for jarkup.
+//!
+//! A document is parsed from jarkup JSON into an opaque [`JarkupDocument`]
+//! handle, which can then be rendered or validated any number of times and is
+//! released with [`jarkup_document_free`]. Every fallible function returns a
+//! [`JarkupStatus`]; on failure [`jarkup_last_error`] describes what went wrong
+//! on the calling thread. Strings handed out are NUL-terminated UTF-8 owned by
+//! the caller, to be released with [`jarkup_string_free`]. Panics never cross
+//! the boundary: they are caught and reported as [`JarkupStatus::Panic`].
+//!
+//! `include/jarkup.h` is generated from this file with
+//! `cbindgen --config cbindgen.toml --output include/jarkup.h`.
+
+use std::{
+    any::Any,
+    cell::RefCell,
+    ffi::{CString, c_char},
+    panic::{self, AssertUnwindSafe},
+    ptr, slice,
+};
+
+use jarkup_rs::{
+    Component, html,
+    limits::{self, Limits},
+    markdown, plain,
+    validate::{self, ValidationErrorKind},
+};
+use serde_json::{Value, json};
+
+/// Result of a call.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JarkupStatus {
+    Ok = 0,
+    /// A required pointer argument was NULL.
+    NullPointer = 1,
+    /// The input was not valid UTF-8.
+    InvalidUtf8 = 2,
+    /// The input was not a valid jarkup document.
+    InvalidDocument = 3,
+    /// The output format is not one of the `JARKUP_FORMAT_*` constants.
+    InvalidFormat = 4,
+    /// The library panicked; the document handle is still safe to free.
+    Panic = 5,
+}
+
+/// Output format of [`jarkup_render`].
+pub type JarkupFormat = u32;
+
+/// An HTML fragment.
+pub const JARKUP_FORMAT_HTML: JarkupFormat = 0;
+/// GitHub-flavored Markdown.
+pub const JARKUP_FORMAT_MARKDOWN: JarkupFormat = 1;
+/// Plain text.
+pub const JARKUP_FORMAT_TEXT: JarkupFormat = 2;
+
+/// A parsed document.
+pub struct JarkupDocument {
+    components: Vec<Component>,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+struct Failure {
+    status: JarkupStatus,
+    message: String,
+}
+
+impl Failure {
+    fn new(status: JarkupStatus, message: impl Into<String>) -> Self {
+        Failure {
+            status,
+            message: message.into(),
+        }
+    }
+
+    fn null(argument: &str) -> Self {
+        Failure::new(
+            JarkupStatus::NullPointer,
+            format!("`{}` must not be NULL", argument),
+        )
+    }
+}
+
+/// Runs `call`, catching panics and recording the error message.
+fn run(call: impl FnOnce() -> Result<(), Failure>) -> JarkupStatus {
+    let result = panic::catch_unwind(AssertUnwindSafe(call))
+        .unwrap_or_else(|payload| Err(Failure::new(JarkupStatus::Panic, panic_message(&*payload))));
+    let (status, message) = match result {
+        Ok(()) => (JarkupStatus::Ok, None),
+        Err(failure) => (failure.status, Some(c_string(failure.message))),
+    };
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = message);
+    status
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic");
+    format!("panicked: {}", message)
+}
+
+/// Converts to a C string, replacing NUL characters with U+FFFD.
+fn c_string(text: String) -> CString {
+    let text = match text.contains('\0') {
+        true => text.replace('\0', "\u{FFFD}"),
+        false => text,
+    };
+    CString::new(text).unwrap_or_default()
+}
+
+/// Hands `text` over to the caller through `out` and `out_len`.
+///
+/// # Safety
+///
+/// `out` must be valid for writes, and `out_len` either NULL or valid for
+/// writes.
+unsafe fn hand_over(text: String, out: *mut *mut c_char, out_len: *mut usize) {
+    let text = c_string(text);
+    if !out_len.is_null() {
+        unsafe { *out_len = text.as_bytes().len() };
+    }
+    unsafe { *out = text.into_raw() };
+}
+
+/// Parses `len` bytes of jarkup JSON at `json` into a new document, stored in
+/// `*out` on success. `json` need not be NUL-terminated. Documents beyond the
+/// default `Limits` of `jarkup_rs::limits` are invalid.
+///
+/// # Safety
+///
+/// `json` must point to `len` readable bytes and `out` must be valid for
+/// writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn jarkup_document_parse(
+    json: *const c_char,
+    len: usize,
+    out: *mut *mut JarkupDocument,
+) -> JarkupStatus {
+    run(|| {
+        if out.is_null() {
+            return Err(Failure::null("out"));
+        }
+        unsafe { *out = ptr::null_mut() };
+        if json.is_null() {
+            return Err(Failure::null("json"));
+        }
+        let bytes = unsafe { slice::from_raw_parts(json.cast::<u8>(), len) };
+        let json = std::str::from_utf8(bytes)
+            .map_err(|error| Failure::new(JarkupStatus::InvalidUtf8, error.to_string()))?;
+        let components = limits::from_str::<Vec<Component>>(json, &Limits::default())
+            .map_err(|error| Failure::new(JarkupStatus::InvalidDocument, error.to_string()))?;
+        let document = Box::new(JarkupDocument { components });
+        unsafe { *out = Box::into_raw(document) };
+        Ok(())
+    })
+}
+
+/// Releases a document. Passing NULL does nothing.
+///
+/// # Safety
+///
+/// `document` must be NULL or a handle from [`jarkup_document_parse`] that has
+/// not been freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn jarkup_document_free(document: *mut JarkupDocument) {
+    if !document.is_null() {
+        drop(unsafe { Box::from_raw(document) });
+    }
+}
+
+/// Renders a document in `format`, storing a new string in `*out` and, unless
+/// `out_len` is NULL, its length in bytes in `*out_len`.
+///
+/// # Safety
+///
+/// `document` must be a live handle, `out` valid for writes and `out_len`
+/// either NULL or valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn jarkup_render(
+    document: *const JarkupDocument,
+    format: JarkupFormat,
+    out: *mut *mut c_char,
+    out_len: *mut usize,
+) -> JarkupStatus {
+    run(|| {
+        if out.is_null() {
+            return Err(Failure::null("out"));
+        }
+        unsafe { *out = ptr::null_mut() };
+        let Some(document) = (unsafe { document.as_ref() }) else {
+            return Err(Failure::null("document"));
+        };
+        let rendered = match format {
+            JARKUP_FORMAT_HTML => html::to_string(&document.components),
+            JARKUP_FORMAT_MARKDOWN => markdown::to_string(&document.components),
+            JARKUP_FORMAT_TEXT => plain::to_string(&document.components),
+            format => {
+                return Err(Failure::new(
+                    JarkupStatus::InvalidFormat,
+                    format!("unknown format {}", format),
+                ));
+            }
+        };
+        unsafe { hand_over(rendered, out, out_len) };
+        Ok(())
+    })
+}
+
+/// Validates a document, storing the problems found as a JSON array of
+/// `{"path", "kind", "message"}` objects in `*out` and, unless they are NULL,
+/// the length of that string in `*out_len` and the number of problems in
+/// `*out_count`.
+///
+/// # Safety
+///
+/// `document` must be a live handle, `out` valid for writes, and `out_len` and
+/// `out_count` either NULL or valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn jarkup_validate(
+    document: *const JarkupDocument,
+    out: *mut *mut c_char,
+    out_len: *mut usize,
+    out_count: *mut usize,
+) -> JarkupStatus {
+    run(|| {
+        if out.is_null() {
+            return Err(Failure::null("out"));
+        }
+        unsafe { *out = ptr::null_mut() };
+        let Some(document) = (unsafe { document.as_ref() }) else {
+            return Err(Failure::null("document"));
+        };
+        let errors = validate::validate(&document.components)
+            .into_iter()
+            .map(|error| {
+                let (kind, message) = match &error.kind {
+                    ValidationErrorKind::Mermaid(mermaid) => ("mermaid", mermaid.to_string()),
+                };
+                json!({ "path": error.path, "kind": kind, "message": message })
+            })
+            .collect::<Vec<Value>>();
+        if !out_count.is_null() {
+            unsafe { *out_count = errors.len() };
+        }
+        unsafe { hand_over(Value::Array(errors).to_string(), out, out_len) };
+        Ok(())
+    })
+}
+
+/// Releases a string returned by this library. Passing NULL does nothing.
+///
+/// # Safety
+///
+/// `string` must be NULL or a string from this library that has not been
+/// freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn jarkup_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(unsafe { CString::from_raw(string) });
+    }
+}
+
+/// Returns the message of the last failed call on this thread, or NULL if the
+/// last call succeeded. The message stays valid until the next call.
+#[unsafe(no_mangle)]
+pub extern "C" fn jarkup_last_error() -> *const c_char {
+    LAST_ERROR.with(|last_error| {
+        last_error
+            .borrow()
+            .as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// Returns the library version, e.g. `0.14.0`.
+#[unsafe(no_mangle)]
+pub extern "C" fn jarkup_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
@@ -0,0 +1,135 @@
+use std::{
+    ffi::{CStr, c_char},
+    fs, ptr,
+};
+
+use jarkup::*;
+use serde_json::Value;
+
+const DOCUMENT: &str = r#"[
+    {"type":"Heading","props":{"level":1},"slots":{"default":[{"type":"Text","props":{"text":"Intro"}}]}},
+    {"type":"Mermaid","props":{"code":"flowchart LR\n  A[Start --> B"}}
+]"#;
+
+fn parse(json: &str) -> (JarkupStatus, *mut JarkupDocument) {
+    let mut document = ptr::null_mut();
+    let status = unsafe { jarkup_document_parse(json.as_ptr().cast(), json.len(), &mut document) };
+    (status, document)
+}
+
+/// Takes ownership of a string returned by the library.
+fn take(string: *mut c_char) -> String {
+    let owned = unsafe { CStr::from_ptr(string) }
+        .to_str()
+        .unwrap()
+        .to_string();
+    unsafe { jarkup_string_free(string) };
+    owned
+}
+
+fn last_error() -> Option<String> {
+    let message = jarkup_last_error();
+    (!message.is_null()).then(|| {
+        unsafe { CStr::from_ptr(message) }
+            .to_string_lossy()
+            .into_owned()
+    })
+}
+
+#[test]
+fn render() {
+    let (status, document) = parse(DOCUMENT);
+    assert_eq!(status, JarkupStatus::Ok);
+    assert_eq!(last_error(), None);
+
+    let render = |format| {
+        let mut out = ptr::null_mut();
+        let mut len = 0;
+        let status = unsafe { jarkup_render(document, format, &mut out, &mut len) };
+        assert_eq!(status, JarkupStatus::Ok);
+        let rendered = take(out);
+        assert_eq!(rendered.len(), len);
+        rendered
+    };
+    assert!(render(JARKUP_FORMAT_HTML).contains(">Intro</h1>"));
+    assert!(render(JARKUP_FORMAT_MARKDOWN).starts_with("# Intro\n"));
+    assert_eq!(render(JARKUP_FORMAT_TEXT), "Intro\n");
+
+    let mut out = ptr::null_mut();
+    let status = unsafe { jarkup_render(document, 42, &mut out, ptr::null_mut()) };
+    assert_eq!(status, JarkupStatus::InvalidFormat);
+    assert!(out.is_null());
+    assert_eq!(last_error().as_deref(), Some("unknown format 42"));
+
+    unsafe { jarkup_document_free(document) };
+}
+
+#[test]
+fn validate() {
+    let (_, document) = parse(DOCUMENT);
+    let mut out = ptr::null_mut();
+    let mut count = 0;
+    let status = unsafe { jarkup_validate(document, &mut out, ptr::null_mut(), &mut count) };
+    assert_eq!(status, JarkupStatus::Ok);
+    assert_eq!(count, 1);
+    let errors: Value = serde_json::from_str(&take(out)).unwrap();
+    assert_eq!(errors[0]["path"], "/1");
+    assert_eq!(errors[0]["kind"], "mermaid");
+    unsafe { jarkup_document_free(document) };
+}
+
+#[test]
+fn errors() {
+    let (status, document) = parse(r#"[{"type":"Nope"}]"#);
+    assert_eq!(status, JarkupStatus::InvalidDocument);
+    assert!(document.is_null());
+    assert!(last_error().unwrap().starts_with("invalid document"));
+
+    let deep = r#"[{"type":"Callout","slots":{"default":"#.repeat(40) + "[]" + &"}}]".repeat(40);
+    let (status, document) = parse(&deep);
+    assert_eq!(status, JarkupStatus::InvalidDocument);
+    assert!(document.is_null());
+    assert!(last_error().unwrap().contains("nested deeper"));
+
+    let mut document = ptr::null_mut();
+    let bytes = b"[\xff]";
+    let status = unsafe { jarkup_document_parse(bytes.as_ptr().cast(), 3, &mut document) };
+    assert_eq!(status, JarkupStatus::InvalidUtf8);
+
+    let status = unsafe { jarkup_document_parse(ptr::null(), 0, &mut document) };
+    assert_eq!(status, JarkupStatus::NullPointer);
+    assert_eq!(last_error().as_deref(), Some("`json` must not be NULL"));
+
+    let mut out = ptr::null_mut();
+    let status =
+        unsafe { jarkup_render(ptr::null(), JARKUP_FORMAT_HTML, &mut out, ptr::null_mut()) };
+    assert_eq!(status, JarkupStatus::NullPointer);
+
+    unsafe {
+        jarkup_document_free(ptr::null_mut());
+        jarkup_string_free(ptr::null_mut());
+    }
+    let version = unsafe { CStr::from_ptr(jarkup_version()) };
+    assert_eq!(version.to_str().unwrap(), env!("CARGO_PKG_VERSION"));
+}
+
+#[test]
+fn header() {
+    let header =
+        fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/include/jarkup.h")).unwrap();
+    let source = fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/src/lib.rs")).unwrap();
+    let functions = source
+        .split("extern \"C\" fn ")
+        .skip(1)
+        .map(|rest| &rest[..rest.find('(').unwrap()])
+        .collect::<Vec<_>>();
+    assert_eq!(functions.len(), 7);
+    for function in functions {
+        assert!(
+            header.contains(&format!(" {}(", function))
+                || header.contains(&format!("*{}(", function)),
+            "include/jarkup.h does not declare {}; regenerate it with cbindgen",
+            function
+        );
+    }
+}
@@ -0,0 +1,408 @@
+"""Python bindings for jarkup, a JSON-based intermediate markup language."""
+
+from typing import Any, Literal, Optional, Sequence, Union
+
+Document = Union[str, Sequence["Component"], Sequence[dict[str, Any]]]
+
+class JarkupError(ValueError):
+    """Raised when a value is not a valid jarkup document."""
+
+class Component:
+    """A component, inline or block-level."""
+
+    type: str
+    id: Optional[str]
+    props: Optional[dict[str, Any]]
+    slots: Optional[dict[str, list["Component"]]]
+
+    def __init__(
+        self,
+        type: str,
+        props: Optional[dict[str, Any]] = None,
+        slots: Optional[dict[str, list["Component"]]] = None,
+        id: Optional[str] = None,
+    ) -> None: ...
+    @staticmethod
+    def from_json(json: str) -> "Component":
+        """Parses a single component from jarkup JSON."""
+    def to_json(self) -> str:
+        """Serializes the component to jarkup JSON."""
+    def to_dict(self) -> dict[str, Any]:
+        """Returns the component as plain dicts and lists, as `json.loads` would."""
+
+class Text(Component):
+    """A `Text` component."""
+
+    def __init__(
+        self,
+        props: Optional[dict[str, Any]] = None,
+        slots: Optional[dict[str, list[Component]]] = None,
+        id: Optional[str] = None,
+    ) -> None: ...
+    @property
+    def text(self) -> Optional[str]: ...
+    @property
+    def color(self) -> Optional[str]: ...
+    @property
+    def background_color(self) -> Optional[str]: ...
+    @property
+    def bold(self) -> Optional[bool]: ...
+    @property
+    def italic(self) -> Optional[bool]: ...
+    @property
+    def underline(self) -> Optional[bool]: ...
+    @property
+    def strikethrough(self) -> Optional[bool]: ...
+    @property
+    def katex(self) -> Optional[bool]: ...
+    @property
+    def code(self) -> Optional[bool]: ...
+    @property
+    def kbd(self) -> Optional[bool]: ...
+    @property
+    def ruby(self) -> Optional[str]: ...
+    @property
+    def href(self) -> Optional[str]: ...
+    @property
+    def favicon(self) -> Optional[str]: ...
+
+class Icon(Component):
+    """A `Icon` component."""
+
+    def __init__(
+        self,
+        props: Optional[dict[str, Any]] = None,
+        slots: Optional[dict[str, list[Component]]] = None,
+        id: Optional[str] = None,
+    ) -> None: ...
+    @property
+    def src(self) -> Optional[str]: ...
+    @property
+    def alt(self) -> Optional[str]: ...
+
+class Fragment(Component):
+    """A `Fragment` component."""
+
+    def __init__(
+        self,
+        props: Optional[dict[str, Any]] = None,
+        slots: Optional[dict[str, list[Component]]] = None,
+        id: Optional[str] = None,
+    ) -> None: ...
+
+class Heading(Component):
+    """A `Heading` component."""
+
+    def __init__(
+        self,
+        props: Optional[dict[str, Any]] = None,
+        slots: Optional[dict[str, list[Component]]] = None,
+        id: Optional[str] = None,
+    ) -> None: ...
+    @property
+    def level(self) -> Optional[Literal[1, 2, 3, 4, 5, 6]]: ...
+
+class Paragraph(Component):
+    """A `Paragraph` component."""
+
+    def __init__(
+        self,
+        props: Optional[dict[str, Any]] = None,
+        slots: Optional[dict[str, list[Component]]] = None,
+        id: Optional[str] = None,
+    ) -> None: ...
+    @property
+    def color(self) -> Optional[str]: ...
+    @property
+    def background_color(self) -> Optional[str]: ...
+
+class ListItem(Component):
+    """A `ListItem` component."""
+
+    def __init__(
+        self,
+        props: Optional[dict[str, Any]] = None,
+        slots: Optional[dict[str, list[Component]]] = None,
+        id: Optional[str] = None,
+    ) -> None: ...
+
+class List(Component):
+    """A `List` component."""
+
+    def __init__(
+        self,
+        props: Optional[dict[str, Any]] = None,
+        slots: Optional[dict[str, list[Component]]] = None,
+        id: Optional[str] = None,
+    ) -> None: ...
+    @property
+    def list_style(self) -> Optional[Literal["unordered", "ordered"]]: ...
+
+class BlockQuote(Component):
+    """A `BlockQuote` component."""
+
+    def __init__(
+        self,
+        props: Optional[dict[str, Any]] = None,
+        slots: Optional[dict[str, list[Component]]] = None,
+        id: Optional[str] = None,
+    ) -> None: ...
+    @property
+    def cite(self) -> Optional[str]: ...
+
+class Callout(Component):
+    """A `Callout` component."""
+
+    def __init__(
+        self,
+        props: Optional[dict[str, Any]] = None,
+        slots: Optional[dict[str, list[Component]]] = None,
+        id: Optional[str] = None,
+    ) -> None: ...
+    @property
+    def callout_type(self) -> Optional[Literal["note", "tip", "important", "warning", "caution"]]: ...
+
+class Divider(Component):
+    """A `Divider` component."""
+
+    def __init__(
+        self,
+        props: Optional[dict[str, Any]] = None,
+        slots: Optional[dict[str, list[Component]]] = None,
+        id: Optional[str] = None,
+    ) -> None: ...
+
+class Toggle(Component):
+    """A `Toggle` component."""
+
+    def __init__(
+        self,
+        props: Optional[dict[str, Any]] = None,
+        slots: Optional[dict[str, list[Component]]] = None,
+        id: Optional[str] = None,
+    ) -> None: ...
+
+class Bookmark(Component):
+    """A `Bookmark` component."""
+
+    def __init__(
+        self,
+        props: Optional[dict[str, Any]] = None,
+        slots: Optional[dict[str, list[Component]]] = None,
+        id: Optional[str] = None,
+    ) -> None: ...
+    @property
+    def url(self) -> Optional[str]: ...
+    @property
+    def title(self) -> Optional[str]: ...
+    @property
+    def description(self) -> Optional[str]: ...
+    @property
+    def image(self) -> Optional[str]: ...
+
+class File(Component):
+    """A `File` component."""
+
+    def __init__(
+        self,
+        props: Optional[dict[str, Any]] = None,
+        slots: Optional[dict[str, list[Component]]] = None,
+        id: Optional[str] = None,
+    ) -> None: ...
+    @property
+    def src(self) -> Optional[str]: ...
+    @property
+    def name(self) -> Optional[str]: ...
+
+class Image(Component):
+    """A `Image` component."""
+
+    def __init__(
+        self,
+        props: Optional[dict[str, Any]] = None,
+        slots: Optional[dict[str, list[Component]]] = None,
+        id: Optional[str] = None,
+    ) -> None: ...
+    @property
+    def src(self) -> Optional[str]: ...
+    @property
+    def alt(self) -> Optional[str]: ...
+    @property
+    def width(self) -> Optional[int]: ...
+    @property
+    def height(self) -> Optional[int]: ...
+    @property
+    def srcset(self) -> Optional[str]: ...
+    @property
+    def sizes(self) -> Optional[str]: ...
+    @property
+    def mime_type(self) -> Optional[str]: ...
+
+class CodeBlock(Component):
+    """A `CodeBlock` component."""
+
+    def __init__(
+        self,
+        props: Optional[dict[str, Any]] = None,
+        slots: Optional[dict[str, list[Component]]] = None,
+        id: Optional[str] = None,
+    ) -> None: ...
+    @property
+    def code(self) -> Optional[str]: ...
+    @property
+    def language(self) -> Optional[str]: ...
+
+class Katex(Component):
+    """A `Katex` component."""
+
+    def __init__(
+        self,
+        props: Optional[dict[str, Any]] = None,
+        slots: Optional[dict[str, list[Component]]] = None,
+        id: Optional[str] = None,
+    ) -> None: ...
+    @property
+    def expression(self) -> Optional[str]: ...
+
+class Mermaid(Component):
+    """A `Mermaid` component."""
+
+    def __init__(
+        self,
+        props: Optional[dict[str, Any]] = None,
+        slots: Optional[dict[str, list[Component]]] = None,
+        id: Optional[str] = None,
+    ) -> None: ...
+    @property
+    def code(self) -> Optional[str]: ...
+
+class Tab(Component):
+    """A `Tab` component."""
+
+    def __init__(
+        self,
+        props: Optional[dict[str, Any]] = None,
+        slots: Optional[dict[str, list[Component]]] = None,
+        id: Optional[str] = None,
+    ) -> None: ...
+    @property
+    def title(self) -> Optional[str]: ...
+
+class Tabs(Component):
+    """A `Tabs` component."""
+
+    def __init__(
+        self,
+        props: Optional[dict[str, Any]] = None,
+        slots: Optional[dict[str, list[Component]]] = None,
+        id: Optional[str] = None,
+    ) -> None: ...
+    @property
+    def title(self) -> Optional[str]: ...
+
+class Table(Component):
+    """A `Table` component."""
+
+    def __init__(
+        self,
+        props: Optional[dict[str, Any]] = None,
+        slots: Optional[dict[str, list[Component]]] = None,
+        id: Optional[str] = None,
+    ) -> None: ...
+    @property
+    def has_column_header(self) -> Optional[bool]: ...
+    @property
+    def has_row_header(self) -> Optional[bool]: ...
+    @property
+    def caption(self) -> Optional[str]: ...
+
+class TableRow(Component):
+    """A `TableRow` component."""
+
+    def __init__(
+        self,
+        props: Optional[dict[str, Any]] = None,
+        slots: Optional[dict[str, list[Component]]] = None,
+        id: Optional[str] = None,
+    ) -> None: ...
+
+class TableCell(Component):
+    """A `TableCell` component."""
+
+    def __init__(
+        self,
+        props: Optional[dict[str, Any]] = None,
+        slots: Optional[dict[str, list[Component]]] = None,
+        id: Optional[str] = None,
+    ) -> None: ...
+    @property
+    def is_header(self) -> Optional[bool]: ...
+
+class ColumnList(Component):
+    """A `ColumnList` component."""
+
+    def __init__(
+        self,
+        props: Optional[dict[str, Any]] = None,
+        slots: Optional[dict[str, list[Component]]] = None,
+        id: Optional[str] = None,
+    ) -> None: ...
+
+class Column(Component):
+    """A `Column` component."""
+
+    def __init__(
+        self,
+        props: Optional[dict[str, Any]] = None,
+        slots: Optional[dict[str, list[Component]]] = None,
+        id: Optional[str] = None,
+    ) -> None: ...
+    @property
+    def width_ratio(self) -> Optional[float]: ...
+
+class Unsupported(Component):
+    """A `Unsupported` component."""
+
+    def __init__(
+        self,
+        props: Optional[dict[str, Any]] = None,
+        slots: Optional[dict[str, list[Component]]] = None,
+        id: Optional[str] = None,
+    ) -> None: ...
+    @property
+    def details(self) -> Optional[str]: ...
+
+class ValidationIssue:
+    """A problem found by `validate`."""
+
+    path: str
+    kind: str
+    message: str
+
+class Assets:
+    """The icons, files and images a document references."""
+
+    icons: list[str]
+    files: list[str]
+    images: list[str]
+
+def loads(json: str) -> list[Component]:
+    """Parses jarkup JSON into a list of components."""
+
+def dumps(document: Document) -> str:
+    """Serializes a document to jarkup JSON."""
+
+def render_html(document: Document) -> str:
+    """Renders a document to an HTML fragment."""
+
+def to_markdown(document: Document) -> str:
+    """Renders a document to GitHub-flavored Markdown."""
+
+def to_text(document: Document) -> str:
+    """Extracts the plain text of a document."""
+
+def validate(document: Document) -> list[ValidationIssue]:
+    """Validates a document and returns every problem found, in document order."""
+
+def extract_assets(document: Document) -> Assets:
+    """Returns the icons, files and images a document references."""
@@ -0,0 +1,538 @@
+//! Python bindings for jarkup.
+//!
+//! Documents are parsed into `Component` objects holding the `type`, `id`,
+//! `props` and `slots` of the JSON form, where `props` is a `dict` with the
+//! JSON keys and `slots` maps slot names to lists of components. Parsed
+//! components are instances of the subclass named after their type, e.g.
+//! `Heading`, which reads its props through typed accessors such as `level`.
+//! Every
+//! function taking a document accepts either a list of components or a jarkup
+//! JSON string, and raises `JarkupError` when it is not a valid document.
+
+use jarkup_rs::{
+    Assets as ModelAssets, Component as Model, html, markdown, plain,
+    validate::{self, ValidationErrorKind},
+};
+use pyo3::{
+    IntoPyObjectExt, create_exception,
+    exceptions::{PyTypeError, PyValueError},
+    prelude::*,
+    types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple},
+};
+use serde_json::{Map, Number, Value};
+
+create_exception!(
+    jarkup,
+    JarkupError,
+    PyValueError,
+    "Raised when a value is not a valid jarkup document."
+);
+
+/// A component, inline or block-level.
+#[pyclass(module = "jarkup", subclass)]
+pub struct Component {
+    #[pyo3(get, set, name = "type")]
+    type_name: String,
+    #[pyo3(get, set)]
+    id: Option<String>,
+    #[pyo3(get, set)]
+    props: Option<Py<PyDict>>,
+    #[pyo3(get, set)]
+    slots: Option<Py<PyDict>>,
+}
+
+#[pymethods]
+impl Component {
+    #[new]
+    #[pyo3(signature = (r#type, props = None, slots = None, id = None))]
+    fn new(
+        r#type: String,
+        props: Option<Py<PyDict>>,
+        slots: Option<Py<PyDict>>,
+        id: Option<String>,
+    ) -> Self {
+        Component {
+            type_name: r#type,
+            id,
+            props,
+            slots,
+        }
+    }
+
+    /// Parses a single component from jarkup JSON.
+    #[staticmethod]
+    fn from_json(py: Python<'_>, json: &str) -> PyResult<Py<PyAny>> {
+        let component = serde_json::from_str::<Model>(json).map_err(invalid)?;
+        from_model(py, &component)
+    }
+
+    /// Serializes the component to jarkup JSON.
+    fn to_json(&self, py: Python<'_>) -> PyResult<String> {
+        let component = serde_json::from_value::<Model>(self.to_value(py)?).map_err(invalid)?;
+        serde_json::to_string(&component).map_err(invalid)
+    }
+
+    /// Returns the component as plain dicts and lists, as `json.loads` would.
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_python(py, &self.to_value(py)?)
+    }
+
+    fn __eq__(&self, py: Python<'_>, other: &Bound<'_, PyAny>) -> PyResult<bool> {
+        match other.extract::<PyRef<'_, Component>>() {
+            Ok(other) => Ok(self.to_value(py)? == other.to_value(py)?),
+            Err(_) => Ok(false),
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        match &self.id {
+            Some(id) => format!("Component(type={:?}, id={:?})", self.type_name, id),
+            None => format!("Component(type={:?})", self.type_name),
+        }
+    }
+}
+
+impl Component {
+    fn to_value(&self, py: Python<'_>) -> PyResult<Value> {
+        let mut object = Map::new();
+        object.insert("type".to_string(), Value::String(self.type_name.clone()));
+        if let Some(id) = &self.id {
+            object.insert("id".to_string(), Value::String(id.clone()));
+        }
+        if let Some(props) = &self.props {
+            object.insert("props".to_string(), from_python(props.bind(py).as_any())?);
+        }
+        if let Some(slots) = &self.slots {
+            object.insert("slots".to_string(), from_python(slots.bind(py).as_any())?);
+        }
+        Ok(Value::Object(object))
+    }
+
+    /// Reads a prop, or `None` when it is absent.
+    fn prop<'py, T: FromPyObjectOwned<'py>>(
+        &self,
+        py: Python<'py>,
+        key: &str,
+    ) -> PyResult<Option<T>>
+    where
+        for<'a> <T as FromPyObject<'a, 'py>>::Error: Into<PyErr>,
+    {
+        let Some(props) = &self.props else {
+            return Ok(None);
+        };
+        match props.bind(py).get_item(key)? {
+            Some(value) if !value.is_none() => value.extract().map(Some).map_err(Into::into),
+            _ => Ok(None),
+        }
+    }
+}
+
+// Component types # -------------------------------------------------- #
+
+/// Declares a `Component` subclass per type, with a read-only accessor for
+/// each prop, named in snake case after its JSON key.
+macro_rules! components {
+    ($($name:ident { $($field:ident: $ty:ty = $key:literal),* $(,)? })*) => {
+        $(
+            #[doc = concat!("A `", stringify!($name), "` component.")]
+            #[pyclass(module = "jarkup", extends = Component)]
+            pub struct $name;
+
+            #[pymethods]
+            impl $name {
+                #[new]
+                #[pyo3(signature = (props = None, slots = None, id = None))]
+                fn new(
+                    props: Option<Py<PyDict>>,
+                    slots: Option<Py<PyDict>>,
+                    id: Option<String>,
+                ) -> (Self, Component) {
+                    let type_name = stringify!($name).to_string();
+                    (
+                        $name,
+                        Component {
+                            type_name,
+                            id,
+                            props,
+                            slots,
+                        },
+                    )
+                }
+
+                $(
+                    #[getter]
+                    fn $field(slf: PyRef<'_, Self>) -> PyResult<Option<$ty>> {
+                        slf.as_super().prop(slf.py(), $key)
+                    }
+                )*
+            }
+        )*
+
+        /// Wraps a component in the subclass of its type.
+        fn instantiate(py: Python<'_>, component: Component) -> PyResult<Py<PyAny>> {
+            match component.type_name.as_str() {
+                $(stringify!($name) => {
+                    let initializer = PyClassInitializer::from(component).add_subclass($name);
+                    Ok(Py::new(py, initializer)?.into_any())
+                })*
+                _ => Ok(Py::new(py, component)?.into_any()),
+            }
+        }
+
+        fn add_components(m: &Bound<'_, PyModule>) -> PyResult<()> {
+            $(m.add_class::<$name>()?;)*
+            Ok(())
+        }
+    };
+}
+
+components! {
+    Text {
+        text: String = "text",
+        color: String = "color",
+        background_color: String = "backgroundColor",
+        bold: bool = "bold",
+        italic: bool = "italic",
+        underline: bool = "underline",
+        strikethrough: bool = "strikethrough",
+        katex: bool = "katex",
+        code: bool = "code",
+        kbd: bool = "kbd",
+        ruby: String = "ruby",
+        href: String = "href",
+        favicon: String = "favicon",
+    }
+    Icon {
+        src: String = "src",
+        alt: String = "alt",
+    }
+    Fragment {}
+    Heading {
+        level: u8 = "level",
+    }
+    Paragraph {
+        color: String = "color",
+        background_color: String = "backgroundColor",
+    }
+    ListItem {}
+    List {
+        list_style: String = "listStyle",
+    }
+    BlockQuote {
+        cite: String = "cite",
+    }
+    Callout {
+        callout_type: String = "type",
+    }
+    Divider {}
+    Toggle {}
+    Bookmark {
+        url: String = "url",
+        title: String = "title",
+        description: String = "description",
+        image: String = "image",
+    }
+    File {
+        src: String = "src",
+        name: String = "name",
+    }
+    Image {
+        src: String = "src",
+        alt: String = "alt",
+        width: u32 = "width",
+        height: u32 = "height",
+        srcset: String = "srcset",
+        sizes: String = "sizes",
+        mime_type: String = "mimeType",
+    }
+    CodeBlock {
+        code: String = "code",
+        language: String = "language",
+    }
+    Katex {
+        expression: String = "expression",
+    }
+    Mermaid {
+        code: String = "code",
+    }
+    Tab {
+        title: String = "title",
+    }
+    Tabs {
+        title: String = "title",
+    }
+    Table {
+        has_column_header: bool = "hasColumnHeader",
+        has_row_header: bool = "hasRowHeader",
+        caption: String = "caption",
+    }
+    TableRow {}
+    TableCell {
+        is_header: bool = "isHeader",
+    }
+    ColumnList {}
+    Column {
+        width_ratio: f64 = "widthRatio",
+    }
+    Unsupported {
+        details: String = "details",
+    }
+}
+
+/// A problem found by `validate`.
+#[pyclass(module = "jarkup", frozen, get_all)]
+pub struct ValidationIssue {
+    /// Path of the offending node, e.g. `/0/slots/default/2`.
+    path: String,
+    kind: String,
+    message: String,
+}
+
+#[pymethods]
+impl ValidationIssue {
+    fn __repr__(&self) -> String {
+        format!(
+            "ValidationIssue(path={:?}, kind={:?}, message={:?})",
+            self.path, self.kind, self.message
+        )
+    }
+}
+
+/// The icons, files and images a document references.
+#[pyclass(module = "jarkup", frozen, get_all)]
+pub struct Assets {
+    icons: Vec<String>,
+    files: Vec<String>,
+    images: Vec<String>,
+}
+
+#[pymethods]
+impl Assets {
+    fn __repr__(&self) -> String {
+        format!(
+            "Assets(icons={:?}, files={:?}, images={:?})",
+            self.icons, self.files, self.images
+        )
+    }
+}
+
+// Functions # -------------------------------------------------- #
+
+/// Parses jarkup JSON into a list of components.
+#[pyfunction]
+fn loads(py: Python<'_>, json: &str) -> PyResult<Vec<Py<PyAny>>> {
+    let components = serde_json::from_str::<Vec<Model>>(json).map_err(invalid)?;
+    components
+        .iter()
+        .map(|component| from_model(py, component))
+        .collect()
+}
+
+/// Serializes a document to jarkup JSON.
+#[pyfunction]
+fn dumps(document: &Bound<'_, PyAny>) -> PyResult<String> {
+    serde_json::to_string(&to_model(document)?).map_err(invalid)
+}
+
+/// Renders a document to an HTML fragment.
+#[pyfunction]
+fn render_html(document: &Bound<'_, PyAny>) -> PyResult<String> {
+    Ok(html::to_string(&to_model(document)?))
+}
+
+/// Renders a document to GitHub-flavored Markdown.
+#[pyfunction]
+fn to_markdown(document: &Bound<'_, PyAny>) -> PyResult<String> {
+    Ok(markdown::to_string(&to_model(document)?))
+}
+
+/// Extracts the plain text of a document.
+#[pyfunction]
+fn to_text(document: &Bound<'_, PyAny>) -> PyResult<String> {
+    Ok(plain::to_string(&to_model(document)?))
+}
+
+/// Validates a document and returns every problem found, in document order.
+#[pyfunction(name = "validate")]
+fn validate_document(document: &Bound<'_, PyAny>) -> PyResult<Vec<ValidationIssue>> {
+    let issues = validate::validate(&to_model(document)?)
+        .into_iter()
+        .map(|error| {
+            let (kind, message) = match &error.kind {
+                ValidationErrorKind::Mermaid(mermaid) => ("mermaid", mermaid.to_string()),
+            };
+            ValidationIssue {
+                path: error.path,
+                kind: kind.to_string(),
+                message,
+            }
+        })
+        .collect();
+    Ok(issues)
+}
+
+/// Returns the icons, files and images a document references.
+#[pyfunction]
+fn extract_assets(document: &Bound<'_, PyAny>) -> PyResult<Assets> {
+    let assets = to_model(document)?
+        .into_iter()
+        .map(Model::extract_assets)
+        .fold(ModelAssets::default(), |assets, more| assets + more);
+    Ok(Assets {
+        icons: assets.icons,
+        files: assets.files,
+        images: assets.images,
+    })
+}
+
+#[pymodule]
+fn jarkup(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("JarkupError", m.py().get_type::<JarkupError>())?;
+    m.add_class::<Component>()?;
+    add_components(m)?;
+    m.add_class::<ValidationIssue>()?;
+    m.add_class::<Assets>()?;
+    m.add_function(wrap_pyfunction!(loads, m)?)?;
+    m.add_function(wrap_pyfunction!(dumps, m)?)?;
+    m.add_function(wrap_pyfunction!(render_html, m)?)?;
+    m.add_function(wrap_pyfunction!(to_markdown, m)?)?;
+    m.add_function(wrap_pyfunction!(to_text, m)?)?;
+    m.add_function(wrap_pyfunction!(validate_document, m)?)?;
+    m.add_function(wrap_pyfunction!(extract_assets, m)?)?;
+    Ok(())
+}
+
+// Conversion # -------------------------------------------------- #
+
+fn invalid(error: serde_json::Error) -> PyErr {
+    JarkupError::new_err(format!("invalid document: {}", error))
+}
+
+/// Reads a document given as a JSON string or a list of components.
+fn to_model(document: &Bound<'_, PyAny>) -> PyResult<Vec<Model>> {
+    if let Ok(json) = document.cast::<PyString>() {
+        return serde_json::from_str(&json.to_cow()?).map_err(invalid);
+    }
+    serde_json::from_value(from_python(document)?).map_err(invalid)
+}
+
+fn from_model(py: Python<'_>, component: &Model) -> PyResult<Py<PyAny>> {
+    from_value(py, serde_json::to_value(component).map_err(invalid)?)
+}
+
+/// Builds a component from the JSON of a valid one.
+fn from_value(py: Python<'_>, value: Value) -> PyResult<Py<PyAny>> {
+    let Value::Object(mut object) = value else {
+        return Err(JarkupError::new_err(
+            "invalid document: expected a component",
+        ));
+    };
+    let type_name = match object.remove("type") {
+        Some(Value::String(type_name)) => type_name,
+        _ => return Err(JarkupError::new_err("invalid document: missing type")),
+    };
+    let id = match object.remove("id") {
+        Some(Value::String(id)) => Some(id),
+        _ => None,
+    };
+    let props = match object.remove("props") {
+        Some(props @ Value::Object(_)) => {
+            Some(to_python(py, &props)?.cast_into::<PyDict>()?.unbind())
+        }
+        _ => None,
+    };
+    let slots = match object.remove("slots") {
+        Some(Value::Object(slots)) => {
+            let dict = PyDict::new(py);
+            for (name, children) in slots {
+                let Value::Array(children) = children else {
+                    continue;
+                };
+                let children = children
+                    .into_iter()
+                    .map(|child| from_value(py, child))
+                    .collect::<PyResult<Vec<_>>>()?;
+                dict.set_item(name, children)?;
+            }
+            Some(dict.unbind())
+        }
+        _ => None,
+    };
+    instantiate(
+        py,
+        Component {
+            type_name,
+            id,
+            props,
+            slots,
+        },
+    )
+}
+
+fn to_python<'py>(py: Python<'py>, value: &Value) -> PyResult<Bound<'py, PyAny>> {
+    match value {
+        Value::Null => Ok(py.None().into_bound(py)),
+        Value::Bool(boolean) => boolean.into_bound_py_any(py),
+        Value::Number(number) => match (number.as_i64(), number.as_u64(), number.as_f64()) {
+            (Some(integer), _, _) => integer.into_bound_py_any(py),
+            (_, Some(integer), _) => integer.into_bound_py_any(py),
+            (_, _, float) => float.unwrap_or(f64::NAN).into_bound_py_any(py),
+        },
+        Value::String(string) => string.into_bound_py_any(py),
+        Value::Array(values) => {
+            let list = PyList::empty(py);
+            for value in values {
+                list.append(to_python(py, value)?)?;
+            }
+            Ok(list.into_any())
+        }
+        Value::Object(object) => {
+            let dict = PyDict::new(py);
+            for (key, value) in object {
+                dict.set_item(key, to_python(py, value)?)?;
+            }
+            Ok(dict.into_any())
+        }
+    }
+}
+
+fn from_python(object: &Bound<'_, PyAny>) -> PyResult<Value> {
+    if object.is_none() {
+        Ok(Value::Null)
+    } else if let Ok(component) = object.extract::<PyRef<'_, Component>>() {
+        component.to_value(object.py())
+    } else if object.is_instance_of::<PyBool>() {
+        Ok(Value::Bool(object.extract()?))
+    } else if object.is_instance_of::<PyInt>() {
+        match object.extract::<i64>() {
+            Ok(integer) => Ok(Value::from(integer)),
+            Err(_) => Ok(Value::from(object.extract::<u64>()?)),
+        }
+    } else if object.is_instance_of::<PyFloat>() {
+        Number::from_f64(object.extract()?)
+            .map(Value::Number)
+            .ok_or_else(|| JarkupError::new_err("invalid document: non-finite number"))
+    } else if let Ok(string) = object.cast::<PyString>() {
+        Ok(Value::String(string.to_cow()?.into_owned()))
+    } else if object.is_instance_of::<PyList>() || object.is_instance_of::<PyTuple>() {
+        object
+            .try_iter()?
+            .map(|item| from_python(&item?))
+            .collect::<PyResult<Vec<_>>>()
+            .map(Value::Array)
+    } else if let Ok(dict) = object.cast::<PyDict>() {
+        let mut map = Map::new();
+        for (key, value) in dict.iter() {
+            let key = key
+                .cast::<PyString>()
+                .map_err(|_| PyTypeError::new_err("dict keys must be strings"))?;
+            map.insert(key.to_cow()?.into_owned(), from_python(&value)?);
+        }
+        Ok(Value::Object(map))
+    } else {
+        Err(PyTypeError::new_err(format!(
+            "cannot convert {} to jarkup",
+            object.get_type().name()?
+        )))
+    }
+}
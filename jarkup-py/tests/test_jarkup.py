@@ -0,0 +1,106 @@
+import json
+import unittest
+
+import jarkup
+
+DOCUMENT = json.dumps(
+    [
+        {
+            "type": "Heading",
+            "id": "intro",
+            "props": {"level": 1},
+            "slots": {"default": [{"type": "Text", "props": {"text": "Intro"}}]},
+        },
+        {
+            "type": "Paragraph",
+            "slots": {
+                "default": [
+                    {"type": "Text", "props": {"text": "Hello "}},
+                    {"type": "Text", "props": {"text": "world", "bold": True}},
+                    {"type": "Icon", "props": {"src": "/a.svg"}},
+                ]
+            },
+        },
+        {"type": "Mermaid", "props": {"code": "flowchart LR\n  A[Start --> B"}},
+        {"type": "Image", "props": {"src": "/cat.png"}},
+    ]
+)
+
+
+class TestModel(unittest.TestCase):
+    def test_loads(self):
+        components = jarkup.loads(DOCUMENT)
+        heading = components[0]
+        self.assertEqual(heading.type, "Heading")
+        self.assertEqual(heading.id, "intro")
+        self.assertEqual(heading.props, {"level": 1})
+        self.assertEqual(heading.slots["default"][0].props["text"], "Intro")
+        self.assertIsNone(components[3].slots)
+
+    def test_types(self):
+        heading, paragraph, mermaid, image = jarkup.loads(DOCUMENT)
+        self.assertIsInstance(heading, jarkup.Heading)
+        self.assertIsInstance(heading, jarkup.Component)
+        self.assertEqual(heading.level, 1)
+        world = paragraph.slots["default"][1]
+        self.assertIsInstance(world, jarkup.Text)
+        self.assertEqual((world.text, world.bold, world.italic), ("world", True, None))
+        self.assertIsInstance(paragraph.slots["default"][2], jarkup.Icon)
+        self.assertEqual(mermaid.code, "flowchart LR\n  A[Start --> B")
+        self.assertIsNone(image.mime_type)
+        callout = jarkup.Component.from_json('{"type":"Callout","props":{"type":"tip"},"slots":{"default":[]}}')
+        self.assertEqual(callout.callout_type, "tip")
+        heading.props["level"] = "one"
+        with self.assertRaises(TypeError):
+            heading.level
+
+    def test_roundtrip(self):
+        components = jarkup.loads(DOCUMENT)
+        self.assertEqual(jarkup.dumps(components), jarkup.dumps(DOCUMENT))
+        self.assertEqual(jarkup.loads(jarkup.dumps(components)), components)
+        self.assertEqual(
+            jarkup.Component.from_json(components[1].to_json()), components[1]
+        )
+        self.assertEqual(components[0].to_dict(), json.loads(DOCUMENT)[0])
+
+    def test_build(self):
+        text = jarkup.Component("Text", props={"text": "Hi"})
+        paragraph = jarkup.Component("Paragraph", slots={"default": [text]})
+        self.assertEqual(jarkup.render_html([paragraph]), '<p class="jarkup-paragraph">Hi</p>')
+        paragraph.slots["default"][0].props["italic"] = True
+        self.assertIn("<em", jarkup.render_html([paragraph]))
+        heading = jarkup.Heading(props={"level": 2}, slots={"default": [jarkup.Text(props={"text": "Hi"})]})
+        self.assertEqual(heading.type, "Heading")
+        self.assertEqual(heading.level, 2)
+        self.assertEqual(jarkup.loads(jarkup.dumps([heading])), [heading])
+
+    def test_invalid(self):
+        with self.assertRaises(jarkup.JarkupError):
+            jarkup.loads('[{"type":"Nope"}]')
+        with self.assertRaises(jarkup.JarkupError):
+            jarkup.render_html([jarkup.Component("Heading", props={"level": 9})])
+        with self.assertRaises(TypeError):
+            jarkup.render_html([object()])
+
+
+class TestFunctions(unittest.TestCase):
+    def test_render(self):
+        self.assertIn(">Intro</h1>", jarkup.render_html(DOCUMENT))
+        self.assertTrue(jarkup.to_markdown(DOCUMENT).startswith("# Intro\n"))
+        self.assertEqual(jarkup.to_text(DOCUMENT), "Intro\n\nHello world\n")
+
+    def test_validate(self):
+        (issue,) = jarkup.validate(DOCUMENT)
+        self.assertEqual(issue.path, "/2")
+        self.assertEqual(issue.kind, "mermaid")
+        self.assertEqual(jarkup.validate("[]"), [])
+
+    def test_extract_assets(self):
+        assets = jarkup.extract_assets(jarkup.loads(DOCUMENT))
+        self.assertEqual(assets.icons, ["/a.svg"])
+        self.assertEqual(assets.files, [])
+        self.assertEqual(assets.images, ["/cat.png"])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,655 @@
+//! An arena-backed document with parent and sibling links.
+//!
+//! [`Arena`] stores every node of a document in a flat list and links each one
+//! to its parent, its siblings and the first and last child of each of its
+//! slots. Navigating up or sideways is constant time, and structural edits
+//! relink nodes instead of cloning subtrees.
+//!
+//! Components held by the arena have empty slots; their children are reached
+//! through the links. Converting back with [`Arena::into_components`] refills
+//! the slots, so the round trip is lossless.
+
+use std::{fmt, mem};
+
+use crate::{Component, node::SlotMut, path::NodePath};
+
+/// Handle to a node of an [`Arena`].
+///
+/// Ids are never reused, so the id of a removed node stays invalid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(usize);
+
+/// Where to place a node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Position<'a> {
+    Before(NodeId),
+    After(NodeId),
+    /// After the last child in the named slot of a node.
+    LastChild(NodeId, &'a str),
+    /// After the last top-level node.
+    LastRoot,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArenaError {
+    /// The node has been removed.
+    Removed(NodeId),
+    NoSlot {
+        node: NodeId,
+        slot: String,
+    },
+    /// A block component was placed in a slot that only holds inline ones.
+    NotInline {
+        node: NodeId,
+        slot: String,
+    },
+    /// A node was moved next to or into itself.
+    InvalidMove(NodeId),
+}
+
+impl fmt::Display for ArenaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArenaError::Removed(node) => write!(f, "node {} has been removed", node.0),
+            ArenaError::NoSlot { node, slot } => {
+                write!(f, "node {} has no slot `{}`", node.0, slot)
+            }
+            ArenaError::NotInline { node, slot } => write!(
+                f,
+                "slot `{}` of node {} only accepts inline components",
+                slot, node.0
+            ),
+            ArenaError::InvalidMove(node) => {
+                write!(f, "node {} cannot be moved next to or into itself", node.0)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ArenaError {}
+
+#[derive(Debug, Clone, Default)]
+pub struct Arena {
+    nodes: Vec<Option<Node>>,
+    first: Option<NodeId>,
+    last: Option<NodeId>,
+    len: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    /// The component with its slots emptied.
+    component: Component,
+    parent: Option<NodeId>,
+    /// Name of the slot of `parent` holding this node.
+    slot: Option<&'static str>,
+    previous: Option<NodeId>,
+    next: Option<NodeId>,
+    slots: Vec<SlotLinks>,
+}
+
+#[derive(Debug, Clone)]
+struct SlotLinks {
+    name: &'static str,
+    inline: bool,
+    first: Option<NodeId>,
+    last: Option<NodeId>,
+}
+
+/// Where a node sits: its parent and slot, and its neighbours.
+#[derive(Debug, Clone, Copy)]
+struct Place {
+    parent: Option<NodeId>,
+    slot: Option<&'static str>,
+    inline: bool,
+    previous: Option<NodeId>,
+    next: Option<NodeId>,
+}
+
+impl From<Vec<Component>> for Arena {
+    fn from(components: Vec<Component>) -> Self {
+        let mut arena = Arena::default();
+        for component in components {
+            arena
+                .add(component, Position::LastRoot)
+                .expect("roots accept any component");
+        }
+        arena
+    }
+}
+
+impl From<Arena> for Vec<Component> {
+    fn from(arena: Arena) -> Self {
+        arena.into_components()
+    }
+}
+
+impl Arena {
+    pub fn new() -> Self {
+        Arena::default()
+    }
+
+    /// Number of nodes, including inline ones.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn into_components(mut self) -> Vec<Component> {
+        let roots = self.roots().collect::<Vec<_>>();
+        roots.into_iter().map(|id| self.take(id)).collect()
+    }
+
+    pub fn to_components(&self) -> Vec<Component> {
+        self.clone().into_components()
+    }
+
+    // Navigation # -------------------------------------------------- #
+
+    /// The component at `id`, with empty slots.
+    pub fn component(&self, id: NodeId) -> Option<&Component> {
+        self.node(id).ok().map(|node| &node.component)
+    }
+
+    /// The component at `id`, with empty slots.
+    ///
+    /// Its props may be edited freely. Children added to its slots are kept
+    /// ahead of the linked ones when converting back.
+    pub fn component_mut(&mut self, id: NodeId) -> Option<&mut Component> {
+        self.node_mut(id).ok().map(|node| &mut node.component)
+    }
+
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id).ok()?.parent
+    }
+
+    /// Name of the slot of the parent holding `id`, if it is not top-level.
+    pub fn slot(&self, id: NodeId) -> Option<&'static str> {
+        self.node(id).ok()?.slot
+    }
+
+    pub fn previous_sibling(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id).ok()?.previous
+    }
+
+    pub fn next_sibling(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id).ok()?.next
+    }
+
+    /// Names of the slots of `id`, in declaration order.
+    pub fn slots(&self, id: NodeId) -> Vec<&'static str> {
+        self.node(id)
+            .map(|node| node.slots.iter().map(|slot| slot.name).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn first_child(&self, id: NodeId, slot: &str) -> Option<NodeId> {
+        self.slot_links(id, slot).ok()?.first
+    }
+
+    pub fn last_child(&self, id: NodeId, slot: &str) -> Option<NodeId> {
+        self.slot_links(id, slot).ok()?.last
+    }
+
+    pub fn roots(&self) -> Siblings<'_> {
+        Siblings {
+            arena: self,
+            next: self.first,
+        }
+    }
+
+    pub fn children(&self, id: NodeId, slot: &str) -> Siblings<'_> {
+        Siblings {
+            arena: self,
+            next: self.first_child(id, slot),
+        }
+    }
+
+    /// The parent of `id`, then its parent, up to the top level.
+    pub fn ancestors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        std::iter::successors(self.parent(id), |id| self.parent(*id))
+    }
+
+    /// The path of `id` in the equivalent component tree.
+    pub fn path(&self, id: NodeId) -> Option<NodePath> {
+        let index = |id: NodeId| {
+            std::iter::successors(Some(id), |id| self.previous_sibling(*id)).count() - 1
+        };
+        let node = self.node(id).ok()?;
+        let Some(parent) = node.parent else {
+            return Some(NodePath::new(index(id)));
+        };
+        Some(self.path(parent)?.child(node.slot?, index(id)))
+    }
+
+    // Edits # -------------------------------------------------- #
+
+    /// Adds `component` and its children at `position`.
+    pub fn insert(
+        &mut self,
+        position: Position<'_>,
+        component: Component,
+    ) -> Result<NodeId, ArenaError> {
+        let place = self.place(position)?;
+        self.check_inline(&place, &component)?;
+        self.add(component, position)
+    }
+
+    /// Removes `id` and its descendants, returning them as a component.
+    pub fn remove(&mut self, id: NodeId) -> Result<Component, ArenaError> {
+        self.node(id)?;
+        self.unlink(id);
+        Ok(self.take(id))
+    }
+
+    /// Moves `id` and its descendants to `position` without copying them.
+    pub fn move_node(&mut self, id: NodeId, position: Position<'_>) -> Result<(), ArenaError> {
+        self.node(id)?;
+        let anchor = match position {
+            Position::Before(anchor) | Position::After(anchor) => Some(anchor),
+            Position::LastChild(parent, _) => Some(parent),
+            Position::LastRoot => None,
+        };
+        if let Some(anchor) = anchor
+            && (anchor == id || self.ancestors(anchor).any(|ancestor| ancestor == id))
+        {
+            return Err(ArenaError::InvalidMove(id));
+        }
+
+        let place = self.place(position)?;
+        self.check_inline(&place, &self.node(id)?.component)?;
+        self.unlink(id);
+        let place = self.place(position)?;
+        self.link(id, place);
+        Ok(())
+    }
+
+    // Internals # -------------------------------------------------- #
+
+    fn node(&self, id: NodeId) -> Result<&Node, ArenaError> {
+        self.nodes
+            .get(id.0)
+            .and_then(Option::as_ref)
+            .ok_or(ArenaError::Removed(id))
+    }
+
+    fn node_mut(&mut self, id: NodeId) -> Result<&mut Node, ArenaError> {
+        self.nodes
+            .get_mut(id.0)
+            .and_then(Option::as_mut)
+            .ok_or(ArenaError::Removed(id))
+    }
+
+    fn slot_links(&self, id: NodeId, slot: &str) -> Result<&SlotLinks, ArenaError> {
+        self.node(id)?
+            .slots
+            .iter()
+            .find(|links| links.name == slot)
+            .ok_or_else(|| ArenaError::NoSlot {
+                node: id,
+                slot: slot.to_string(),
+            })
+    }
+
+    /// The first and last child of a slot, or of the top level.
+    fn ends_mut(
+        &mut self,
+        parent: Option<NodeId>,
+        slot: Option<&str>,
+    ) -> (&mut Option<NodeId>, &mut Option<NodeId>) {
+        let Some(parent) = parent else {
+            return (&mut self.first, &mut self.last);
+        };
+        let links = self
+            .node_mut(parent)
+            .expect("parents outlive their children")
+            .slots
+            .iter_mut()
+            .find(|links| Some(links.name) == slot)
+            .expect("children sit in a slot of their parent");
+        (&mut links.first, &mut links.last)
+    }
+
+    fn place(&self, position: Position<'_>) -> Result<Place, ArenaError> {
+        let sibling = |id: NodeId| -> Result<Place, ArenaError> {
+            let node = self.node(id)?;
+            let inline = match (node.parent, node.slot) {
+                (Some(parent), Some(slot)) => self.slot_links(parent, slot)?.inline,
+                _ => false,
+            };
+            Ok(Place {
+                parent: node.parent,
+                slot: node.slot,
+                inline,
+                previous: node.previous,
+                next: node.next,
+            })
+        };
+
+        match position {
+            Position::Before(id) => Ok(Place {
+                next: Some(id),
+                ..sibling(id)?
+            }),
+            Position::After(id) => Ok(Place {
+                previous: Some(id),
+                ..sibling(id)?
+            }),
+            Position::LastChild(parent, slot) => {
+                let links = self.slot_links(parent, slot)?;
+                Ok(Place {
+                    parent: Some(parent),
+                    slot: Some(links.name),
+                    inline: links.inline,
+                    previous: links.last,
+                    next: None,
+                })
+            }
+            Position::LastRoot => Ok(Place {
+                parent: None,
+                slot: None,
+                inline: false,
+                previous: self.last,
+                next: None,
+            }),
+        }
+    }
+
+    fn check_inline(&self, place: &Place, component: &Component) -> Result<(), ArenaError> {
+        match (place, component) {
+            (
+                Place {
+                    parent: Some(parent),
+                    slot: Some(slot),
+                    inline: true,
+                    ..
+                },
+                Component::BlockComponent(_),
+            ) => Err(ArenaError::NotInline {
+                node: *parent,
+                slot: slot.to_string(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Stores `component` and, recursively, the children of its slots.
+    fn add(
+        &mut self,
+        mut component: Component,
+        position: Position<'_>,
+    ) -> Result<NodeId, ArenaError> {
+        let place = self.place(position)?;
+        let mut children = Vec::new();
+        let slots = component
+            .slots_mut()
+            .into_iter()
+            .map(|(name, slot)| {
+                let inline = matches!(slot, SlotMut::Inlines(_));
+                children.push((name, take_slot(slot)));
+                SlotLinks {
+                    name,
+                    inline,
+                    first: None,
+                    last: None,
+                }
+            })
+            .collect();
+
+        let id = NodeId(self.nodes.len());
+        self.nodes.push(Some(Node {
+            component,
+            parent: None,
+            slot: None,
+            previous: None,
+            next: None,
+            slots,
+        }));
+        self.len += 1;
+        self.link(id, place);
+
+        for (name, slot) in children {
+            for child in slot {
+                self.add(child, Position::LastChild(id, name))?;
+            }
+        }
+        Ok(id)
+    }
+
+    fn link(&mut self, id: NodeId, place: Place) {
+        let node = self.node_mut(id).expect("linked nodes exist");
+        node.parent = place.parent;
+        node.slot = place.slot;
+        node.previous = place.previous;
+        node.next = place.next;
+
+        let (first, last) = self.ends_mut(place.parent, place.slot);
+        if place.previous.is_none() {
+            *first = Some(id);
+        }
+        if place.next.is_none() {
+            *last = Some(id);
+        }
+        if let Some(previous) = place.previous {
+            self.node_mut(previous).expect("siblings exist").next = Some(id);
+        }
+        if let Some(next) = place.next {
+            self.node_mut(next).expect("siblings exist").previous = Some(id);
+        }
+    }
+
+    fn unlink(&mut self, id: NodeId) {
+        let node = self.node_mut(id).expect("unlinked nodes exist");
+        let (parent, slot) = (node.parent.take(), node.slot.take());
+        let (previous, next) = (node.previous.take(), node.next.take());
+
+        let (first, last) = self.ends_mut(parent, slot);
+        if previous.is_none() {
+            *first = next;
+        }
+        if next.is_none() {
+            *last = previous;
+        }
+        if let Some(previous) = previous {
+            self.node_mut(previous).expect("siblings exist").next = next;
+        }
+        if let Some(next) = next {
+            self.node_mut(next).expect("siblings exist").previous = previous;
+        }
+    }
+
+    /// Removes an unlinked node and its descendants from storage, refilling
+    /// the slots of its component.
+    fn take(&mut self, id: NodeId) -> Component {
+        let node = self.nodes[id.0].take().expect("taken nodes exist");
+        self.len -= 1;
+
+        let mut children = node
+            .slots
+            .iter()
+            .map(|links| {
+                let mut slot = Vec::new();
+                let mut next = links.first;
+                while let Some(child) = next {
+                    next = self.nodes[child.0].as_ref().and_then(|child| child.next);
+                    slot.push(self.take(child));
+                }
+                (links.name, slot)
+            })
+            .collect::<Vec<_>>();
+
+        let mut component = node.component;
+        for (name, slot) in component.slots_mut() {
+            let Some((_, children)) = children
+                .iter_mut()
+                .find(|(slot_name, _)| *slot_name == name)
+            else {
+                continue;
+            };
+            let children = mem::take(children);
+            match slot {
+                SlotMut::Components(components) => components.extend(children),
+                SlotMut::Inlines(inlines) => {
+                    inlines.extend(children.into_iter().filter_map(|child| match child {
+                        Component::InlineComponent(inline_component) => Some(inline_component),
+                        Component::BlockComponent(_) => None,
+                    }))
+                }
+            }
+        }
+        component
+    }
+}
+
+/// Moves the children out of a slot, wrapping inline ones as components.
+fn take_slot(slot: SlotMut<'_>) -> Vec<Component> {
+    match slot {
+        SlotMut::Components(components) => mem::take(components),
+        SlotMut::Inlines(inlines) => mem::take(inlines)
+            .into_iter()
+            .map(Component::InlineComponent)
+            .collect(),
+    }
+}
+
+/// Iterator over a node and the siblings following it.
+#[derive(Debug, Clone)]
+pub struct Siblings<'a> {
+    arena: &'a Arena,
+    next: Option<NodeId>,
+}
+
+impl Iterator for Siblings<'_> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let id = self.next?;
+        self.next = self.arena.next_sibling(id);
+        Some(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{Arena, ArenaError, Position};
+    use crate::Component;
+
+    fn document() -> Vec<Component> {
+        serde_json::from_str(
+            r#"[
+                {"type":"Heading","props":{"level":1},"slots":{"default":[{"type":"Text","props":{"text":"Title"}}]}},
+                {"type":"Toggle","slots":{
+                    "default":[{"type":"Divider"},{"type":"Paragraph","slots":{"default":[]}}],
+                    "summary":[{"type":"Text","props":{"text":"More"}}]
+                }},
+                {"type":"Table","props":{},"slots":{"body":[]}}
+            ]"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let slice = include_bytes!("./jarkup.json");
+        let components = serde_json::from_slice::<Vec<Component>>(slice).unwrap();
+        let arena = Arena::from(components.clone());
+        assert_eq!(arena.to_components(), components);
+        assert_eq!(arena.into_components(), components);
+
+        let arena = Arena::from(document());
+        assert_eq!(arena.len(), 7);
+        assert_eq!(Vec::<Component>::from(arena), document());
+    }
+
+    #[test]
+    fn test_navigation() {
+        let arena = Arena::from(document());
+        let roots = arena.roots().collect::<Vec<_>>();
+        let toggle = roots[1];
+        assert_eq!(arena.slots(toggle), vec!["default", "summary"]);
+        assert_eq!(arena.slots(roots[2]), vec!["body"]);
+
+        let children = arena.children(toggle, "default").collect::<Vec<_>>();
+        assert_eq!(children.len(), 2);
+        assert_eq!(arena.parent(children[1]), Some(toggle));
+        assert_eq!(arena.slot(children[1]), Some("default"));
+        assert_eq!(arena.next_sibling(children[0]), Some(children[1]));
+        assert_eq!(arena.previous_sibling(children[1]), Some(children[0]));
+        assert_eq!(arena.last_child(toggle, "default"), Some(children[1]));
+        assert_eq!(arena.next_sibling(toggle), Some(roots[2]));
+
+        let summary = arena.first_child(toggle, "summary").unwrap();
+        assert_eq!(arena.component(summary).unwrap().type_name(), "Text");
+        assert_eq!(arena.ancestors(summary).collect::<Vec<_>>(), vec![toggle]);
+        assert_eq!(
+            arena.path(summary).unwrap().to_string(),
+            "/1/slots/summary/0"
+        );
+        assert_eq!(
+            arena.path(children[1]).unwrap().to_string(),
+            "/1/slots/default/1"
+        );
+    }
+
+    #[test]
+    fn test_edits() {
+        let mut arena = Arena::from(document());
+        let roots = arena.roots().collect::<Vec<_>>();
+        let (heading, toggle) = (roots[0], roots[1]);
+        let title = arena.first_child(heading, "default").unwrap();
+        let divider = arena.first_child(toggle, "default").unwrap();
+
+        // Moving the heading relinks its subtree in place.
+        arena
+            .move_node(heading, Position::LastChild(toggle, "default"))
+            .unwrap();
+        assert_eq!(arena.parent(title), Some(heading));
+        assert_eq!(
+            arena.path(title).unwrap().to_string(),
+            "/0/slots/default/2/slots/default/0"
+        );
+        arena
+            .move_node(
+                title,
+                Position::Before(arena.first_child(toggle, "summary").unwrap()),
+            )
+            .unwrap();
+        assert_eq!(arena.first_child(heading, "default"), None);
+
+        let removed = arena.remove(divider).unwrap();
+        assert_eq!(removed.type_name(), "Divider");
+        assert_eq!(arena.remove(divider), Err(ArenaError::Removed(divider)));
+
+        let inserted = arena
+            .insert(Position::After(toggle), removed.clone())
+            .unwrap();
+        assert_eq!(arena.path(inserted).unwrap().to_string(), "/1");
+        assert_eq!(
+            arena.insert(Position::Before(title), removed.clone()),
+            Err(ArenaError::NotInline {
+                node: toggle,
+                slot: "summary".to_string()
+            })
+        );
+        assert!(matches!(
+            arena.insert(Position::LastChild(inserted, "default"), removed),
+            Err(ArenaError::NoSlot { .. })
+        ));
+        assert_eq!(
+            arena.move_node(toggle, Position::LastChild(heading, "default")),
+            Err(ArenaError::InvalidMove(toggle))
+        );
+
+        let json = serde_json::to_value(arena.into_components()).unwrap();
+        assert_eq!(json[0]["type"], "Toggle");
+        assert_eq!(json[0]["slots"]["summary"][0]["props"]["text"], "Title");
+        assert_eq!(json[0]["slots"]["summary"][1]["props"]["text"], "More");
+        assert_eq!(json[0]["slots"]["default"][1]["type"], "Heading");
+        assert_eq!(json[1]["type"], "Divider");
+        assert_eq!(json[2]["type"], "Table");
+    }
+}
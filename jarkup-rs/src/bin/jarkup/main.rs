@@ -0,0 +1,579 @@
+//! The `jarkup` command-line tool.
+//!
+//! Every command but `serve` reads the files it is given, or standard input
+//! when there are none or the file is `-`. Documents are jarkup JSON unless `--from` or
+//! the file extension says otherwise. Problems are reported on standard error
+//! as one JSON object per line, and the exit code is 1 when any was found and
+//! 2 for invalid arguments.
+
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    io::{self, Read, Write},
+    path::Path,
+    process::ExitCode,
+};
+
+use jarkup_rs::{
+    Assets, Component, InlineComponent, html,
+    limits::{self, LimitError, Limits},
+    markdown,
+    node::{NodeRef, Slot},
+    parse, plain,
+    site::{self, SiteConfig, SiteError},
+    syntax, toc,
+    validate::{self, ValidationErrorKind},
+};
+use serde_json::{Map, Value, json};
+
+mod serve;
+
+const USAGE: &str = "\
+Usage: jarkup <COMMAND> [OPTIONS] [FILE]...
+
+Commands:
+  convert   Convert a document to another format
+  validate  Report every problem found in documents
+  fmt       Re-serialize documents canonically
+  stats     Count components, words and characters
+  assets    List the icons, files and images a document references
+  toc       List the headings of a document
+  serve     Preview the documents of a directory, reloading on change
+  build     Build a static site from the documents of a directory
+
+Options:
+  --from <FORMAT>      Input format: json, html, markdown or text
+                       [default: from the file extension, else json]
+  --to <FORMAT>        Output format of `convert`: json, html, markdown,
+                       plain or text
+                       [default: from --output, else json]
+  -o, --output <FILE>  Write to FILE instead of standard output, or the
+                       output directory of `build`
+  --check              `fmt`: report files that are not formatted
+  --write              `fmt`: rewrite files in place
+  --port <PORT>        `serve`: port to listen on [default: 8000]
+  --title <TITLE>      `build`: name of the site
+  --layout <FILE>      `build`: HTML template of every page
+  --base-url <URL>     `build`: URL the site is published at, for sitemap.xml
+  -h, --help           Print this help
+";
+
+// Errors # -------------------------------------------------- #
+
+/// A problem reported on standard error.
+#[derive(Debug, Default)]
+struct Error {
+    kind: &'static str,
+    message: String,
+    file: Option<String>,
+    line: Option<usize>,
+    column: Option<usize>,
+    /// Path of the offending node, e.g. `/0/slots/default/2`.
+    path: Option<String>,
+}
+
+impl Error {
+    fn new(kind: &'static str, message: impl Into<String>) -> Self {
+        Error {
+            kind,
+            message: message.into(),
+            ..Default::default()
+        }
+    }
+
+    fn usage(message: impl Into<String>) -> Self {
+        Error::new("usage", message)
+    }
+
+    fn io(file: &str, error: io::Error) -> Self {
+        Error {
+            file: Some(file.to_string()),
+            ..Error::new("io", error.to_string())
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        let mut object = Map::new();
+        object.insert("kind".to_string(), Value::from(self.kind));
+        object.insert("message".to_string(), Value::from(self.message.as_str()));
+        if let Some(file) = &self.file {
+            object.insert("file".to_string(), Value::from(file.as_str()));
+        }
+        if let Some(line) = self.line {
+            object.insert("line".to_string(), Value::from(line));
+        }
+        if let Some(column) = self.column {
+            object.insert("column".to_string(), Value::from(column));
+        }
+        if let Some(path) = &self.path {
+            object.insert("path".to_string(), Value::from(path.as_str()));
+        }
+        Value::Object(object)
+    }
+}
+
+impl From<SiteError> for Error {
+    fn from(error: SiteError) -> Self {
+        match error {
+            SiteError::Io { path, error } => Error::io(&path.display().to_string(), error),
+            SiteError::Parse { path, message } => Error {
+                file: Some(path.display().to_string()),
+                ..Error::new("parse", message)
+            },
+            SiteError::BrokenLink { document, href } => Error {
+                file: Some(document),
+                ..Error::new("link", format!("broken link `{}`", href))
+            },
+            SiteError::MissingAsset { document, src } => Error {
+                file: Some(document),
+                ..Error::new("asset", format!("missing asset `{}`", src))
+            },
+            SiteError::DuplicateId { id, first, second } => Error {
+                file: Some(second.display().to_string()),
+                ..Error::new(
+                    "duplicate",
+                    format!("same page `{}` as {}", id, first.display()),
+                )
+            },
+        }
+    }
+}
+
+// Formats # -------------------------------------------------- #
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Json,
+    Html,
+    Markdown,
+    /// Text without markup, which can only be written.
+    Plain,
+    /// The text syntax of [`syntax`].
+    Text,
+}
+
+impl Format {
+    fn from_name(name: &str) -> Result<Self, Error> {
+        match name {
+            "json" => Ok(Format::Json),
+            "html" => Ok(Format::Html),
+            "markdown" => Ok(Format::Markdown),
+            "plain" => Ok(Format::Plain),
+            "text" => Ok(Format::Text),
+            _ => Err(Error::usage(format!(
+                "unknown format `{}`; expected json, html, markdown, plain or text",
+                name
+            ))),
+        }
+    }
+
+    fn from_path(path: &str) -> Option<Self> {
+        match Path::new(path).extension()?.to_str()? {
+            "json" => Some(Format::Json),
+            "html" | "htm" => Some(Format::Html),
+            "md" | "markdown" => Some(Format::Markdown),
+            "txt" => Some(Format::Plain),
+            "jarkup" => Some(Format::Text),
+            _ => None,
+        }
+    }
+
+    fn import(self, input: &Input) -> Result<Vec<Component>, Error> {
+        match self {
+            Format::Json => {
+                limits::from_str(&input.source, &Limits::default()).map_err(|error| match error {
+                    LimitError::Json(error) => Error {
+                        file: Some(input.name.clone()),
+                        line: Some(error.line()),
+                        column: Some(error.column()),
+                        ..Error::new("parse", error.to_string())
+                    },
+                    error => Error {
+                        file: Some(input.name.clone()),
+                        ..Error::new("limit", error.to_string())
+                    },
+                })
+            }
+            Format::Html => Ok(parse::html::parse(&input.source)),
+            Format::Markdown => Ok(parse::markdown::parse(&input.source)),
+            Format::Plain => Err(Error {
+                file: Some(input.name.clone()),
+                ..Error::usage("plain text cannot be read as a document")
+            }),
+            Format::Text => syntax::parse(&input.source).map_err(|error| Error {
+                file: Some(input.name.clone()),
+                line: Some(error.line),
+                column: Some(error.column),
+                ..Error::new("parse", error.message)
+            }),
+        }
+    }
+
+    fn export(self, components: &[Component]) -> String {
+        match self {
+            Format::Json => to_json(&components),
+            Format::Html => html::to_string(components) + "\n",
+            Format::Markdown => markdown::to_string(components),
+            Format::Plain => plain::to_string(components),
+            Format::Text => syntax::to_string(components),
+        }
+    }
+}
+
+fn to_json<T>(value: &T) -> String
+where
+    T: serde::Serialize + ?Sized,
+{
+    serde_json::to_string_pretty(value).expect("documents serialize to JSON") + "\n"
+}
+
+// Arguments # -------------------------------------------------- #
+
+#[derive(Debug, Default)]
+struct Options {
+    from: Option<Format>,
+    to: Option<Format>,
+    output: Option<String>,
+    check: bool,
+    write: bool,
+    port: Option<u16>,
+    title: Option<String>,
+    layout: Option<String>,
+    base_url: Option<String>,
+    help: bool,
+    files: Vec<String>,
+}
+
+impl Options {
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, Error> {
+        let mut options = Options::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (name, inline_value) = match arg.split_once('=') {
+                Some((name, value)) if name.starts_with("--") => (name, Some(value.to_string())),
+                _ => (arg.as_str(), None),
+            };
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| Error::usage(format!("`{}` expects a value", name)))
+            };
+            match name {
+                "--from" => options.from = Some(Format::from_name(&value()?)?),
+                "--to" => options.to = Some(Format::from_name(&value()?)?),
+                "-o" | "--output" => options.output = Some(value()?),
+                "--check" => options.check = true,
+                "--write" => options.write = true,
+                "--title" => options.title = Some(value()?),
+                "--layout" => options.layout = Some(value()?),
+                "--base-url" => options.base_url = Some(value()?),
+                "--port" => {
+                    let port = value()?;
+                    options.port = Some(
+                        port.parse()
+                            .map_err(|_| Error::usage(format!("invalid port `{}`", port)))?,
+                    );
+                }
+                "-h" | "--help" => options.help = true,
+                "--" => {
+                    options.files.extend(args);
+                    break;
+                }
+                _ if name.starts_with('-') && name != "-" => {
+                    return Err(Error::usage(format!("unknown option `{}`", name)));
+                }
+                _ => options.files.push(arg),
+            }
+        }
+        Ok(options)
+    }
+
+    /// The only input of commands that take a single document.
+    fn single_input(&self) -> Result<Input, Error> {
+        match self.files.as_slice() {
+            [] => Input::read("-"),
+            [file] => Input::read(file),
+            _ => Err(Error::usage("expected at most one file")),
+        }
+    }
+
+    /// Every input, or standard input when no file was given.
+    fn inputs(&self) -> Vec<String> {
+        if self.files.is_empty() {
+            vec!["-".to_string()]
+        } else {
+            self.files.clone()
+        }
+    }
+
+    fn format_of(&self, input: &Input) -> Format {
+        self.from
+            .or_else(|| Format::from_path(&input.name))
+            .unwrap_or(Format::Json)
+    }
+
+    fn emit(&self, output: &str) -> Result<(), Error> {
+        match &self.output {
+            Some(file) => fs::write(file, output).map_err(|error| Error::io(file, error)),
+            None => io::stdout()
+                .lock()
+                .write_all(output.as_bytes())
+                .map_err(|error| Error::io("-", error)),
+        }
+    }
+}
+
+struct Input {
+    /// File name, or `-` for standard input.
+    name: String,
+    source: String,
+}
+
+impl Input {
+    fn read(name: &str) -> Result<Self, Error> {
+        let source = if name == "-" {
+            let mut source = String::new();
+            io::stdin().read_to_string(&mut source).map(|_| source)
+        } else {
+            fs::read_to_string(name)
+        };
+        Ok(Input {
+            name: name.to_string(),
+            source: source.map_err(|error| Error::io(name, error))?,
+        })
+    }
+}
+
+// Commands # -------------------------------------------------- #
+
+fn main() -> ExitCode {
+    let mut args = env::args().skip(1);
+    let command = args.next().unwrap_or_default();
+    let result = Options::parse(args).and_then(|options| {
+        if options.help || matches!(command.as_str(), "" | "help" | "-h" | "--help") {
+            print!("{}", USAGE);
+            return Ok(Vec::new());
+        }
+        match command.as_str() {
+            "convert" => convert(&options),
+            "validate" => Ok(validate(&options)),
+            "fmt" => fmt(&options),
+            "stats" => stats(&options),
+            "assets" => assets(&options),
+            "toc" => table_of_contents(&options),
+            "serve" => serve::serve(&options),
+            "build" => build(&options),
+            _ => Err(Error::usage(format!("unknown command `{}`", command))),
+        }
+    });
+
+    let (errors, code) = match result {
+        Ok(errors) if errors.is_empty() => return ExitCode::SUCCESS,
+        Ok(errors) => (errors, 1),
+        Err(error) if error.kind == "usage" => (vec![error], 2),
+        Err(error) => (vec![error], 1),
+    };
+    for error in errors {
+        eprintln!("{}", error.to_json());
+    }
+    ExitCode::from(code)
+}
+
+/// Commands return the problems found, or the error that stopped them.
+type Outcome = Result<Vec<Error>, Error>;
+
+fn convert(options: &Options) -> Outcome {
+    let input = options.single_input()?;
+    let components = options.format_of(&input).import(&input)?;
+    let to = options
+        .to
+        .or_else(|| options.output.as_deref().and_then(Format::from_path))
+        .unwrap_or(Format::Json);
+    options.emit(&to.export(&components))?;
+    Ok(Vec::new())
+}
+
+fn validate(options: &Options) -> Vec<Error> {
+    let mut errors = Vec::new();
+    for name in options.inputs() {
+        let components =
+            Input::read(&name).and_then(|input| options.format_of(&input).import(&input));
+        let components = match components {
+            Ok(components) => components,
+            Err(error) => {
+                errors.push(error);
+                continue;
+            }
+        };
+        for error in validate::validate(&components) {
+            let message = match &error.kind {
+                ValidationErrorKind::Mermaid(error) => {
+                    format!("invalid mermaid diagram: {}", error)
+                }
+            };
+            errors.push(Error {
+                file: Some(name.clone()),
+                path: Some(error.path),
+                ..Error::new("validation", message)
+            });
+        }
+    }
+    errors
+}
+
+fn fmt(options: &Options) -> Outcome {
+    if options.check && options.write {
+        return Err(Error::usage("`--check` and `--write` cannot be combined"));
+    }
+    if options.output.is_some() && (options.check || options.write) {
+        return Err(Error::usage(
+            "`--output` cannot be combined with `--check` or `--write`",
+        ));
+    }
+
+    let mut errors = Vec::new();
+    let mut output = String::new();
+    for name in options.inputs() {
+        if options.write && name == "-" {
+            return Err(Error::usage("`--write` needs files to rewrite"));
+        }
+        let input = match Input::read(&name) {
+            Ok(input) => input,
+            Err(error) => {
+                errors.push(error);
+                continue;
+            }
+        };
+        let format = options.format_of(&input);
+        if matches!(format, Format::Html | Format::Markdown | Format::Plain) {
+            errors.push(Error {
+                file: Some(name),
+                ..Error::new("usage", "only json and text documents can be formatted")
+            });
+            continue;
+        }
+        let formatted = match format.import(&input) {
+            Ok(components) => format.export(&components),
+            Err(error) => {
+                errors.push(error);
+                continue;
+            }
+        };
+
+        if options.check {
+            if formatted != input.source {
+                errors.push(Error {
+                    file: Some(name),
+                    ..Error::new("unformatted", "document is not formatted")
+                });
+            }
+        } else if options.write {
+            if formatted != input.source
+                && let Err(error) = fs::write(&name, formatted)
+            {
+                errors.push(Error::io(&name, error));
+            }
+        } else {
+            output.push_str(&formatted);
+        }
+    }
+    if !output.is_empty() {
+        options.emit(&output)?;
+    }
+    Ok(errors)
+}
+
+fn stats(options: &Options) -> Outcome {
+    #[derive(Default)]
+    struct Stats {
+        components: usize,
+        max_depth: usize,
+        words: usize,
+        characters: usize,
+        types: BTreeMap<&'static str, usize>,
+    }
+
+    fn visit(node: NodeRef<'_>, depth: usize, stats: &mut Stats) {
+        stats.components += 1;
+        stats.max_depth = stats.max_depth.max(depth);
+        *stats.types.entry(node.type_name()).or_default() += 1;
+        for (_, slot) in node.slots() {
+            if let Slot::Inlines(inlines) = slot {
+                // Runs may split words, so count them over the whole slot.
+                let text = inlines
+                    .iter()
+                    .filter_map(|inline_component| match inline_component {
+                        InlineComponent::Text(text) => Some(text.props.text.as_str()),
+                        InlineComponent::Icon(_) => None,
+                    })
+                    .collect::<String>();
+                stats.words += text.split_whitespace().count();
+                stats.characters += text.chars().count();
+            }
+            for child in slot.iter() {
+                visit(child, depth + 1, stats);
+            }
+        }
+    }
+
+    let input = options.single_input()?;
+    let components = options.format_of(&input).import(&input)?;
+    let mut stats = Stats::default();
+    for component in &components {
+        visit(NodeRef::Component(component), 1, &mut stats);
+    }
+    options.emit(&to_json(&json!({
+        "topLevel": components.len(),
+        "components": stats.components,
+        "maxDepth": stats.max_depth,
+        "words": stats.words,
+        "characters": stats.characters,
+        "types": stats.types,
+    })))?;
+    Ok(Vec::new())
+}
+
+fn assets(options: &Options) -> Outcome {
+    let input = options.single_input()?;
+    let components = options.format_of(&input).import(&input)?;
+    let assets = components
+        .into_iter()
+        .map(Component::extract_assets)
+        .fold(Assets::default(), |assets, more| assets + more);
+    options.emit(&to_json(&assets))?;
+    Ok(Vec::new())
+}
+
+fn table_of_contents(options: &Options) -> Outcome {
+    let input = options.single_input()?;
+    let components = options.format_of(&input).import(&input)?;
+    options.emit(&to_json(&toc::toc(&components)))?;
+    Ok(Vec::new())
+}
+
+fn build(options: &Options) -> Outcome {
+    let source = match options.files.as_slice() {
+        [] => ".",
+        [directory] => directory.as_str(),
+        _ => return Err(Error::usage("expected at most one directory")),
+    };
+    let Some(output) = &options.output else {
+        return Err(Error::usage("`build` needs an `--output` directory"));
+    };
+
+    let mut config = SiteConfig {
+        base_url: options.base_url.clone(),
+        ..Default::default()
+    };
+    if let Some(title) = &options.title {
+        config.title = title.clone();
+    }
+    if let Some(layout) = &options.layout {
+        config.layout = fs::read_to_string(layout).map_err(|error| Error::io(layout, error))?;
+    }
+    match site::build(Path::new(source), Path::new(output), &config) {
+        Ok(_) => Ok(Vec::new()),
+        Err(errors) => Ok(errors.into_iter().map(Error::from).collect()),
+    }
+}
@@ -0,0 +1,409 @@
+//! `jarkup serve`, a live preview of a directory of documents.
+//!
+//! A watcher thread polls the directory, re-renders every jarkup JSON, jarkup
+//! text or Markdown document whose modification time or size changed, and
+//! bumps a version that `/_events` streams to open pages as a server-sent
+//! `reload` event. A document at `docs/intro.jarkup` is served at
+//! `/docs/intro.html`, and any other file is served as it is.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    path::{Component as PathComponent, Path, PathBuf},
+    sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use jarkup_rs::{html, toc};
+
+use crate::{Error, Format, Input, Options, Outcome};
+
+const DEFAULT_PORT: u16 = 8000;
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// How often idle event streams are written to, to notice closed pages.
+const HEARTBEAT: Duration = Duration::from_secs(15);
+const RELOAD_SCRIPT: &str = r#"<script>new EventSource("/_events").addEventListener("reload", () => location.reload());</script>"#;
+
+struct Page {
+    /// Modification time and size of the source when it was rendered.
+    stamp: (SystemTime, u64),
+    html: String,
+}
+
+struct Site {
+    root: PathBuf,
+    /// Rendered pages by URL path.
+    pages: Mutex<BTreeMap<String, Page>>,
+    version: Mutex<u64>,
+    changed: Condvar,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+pub fn serve(options: &Options) -> Outcome {
+    let root = match options.files.as_slice() {
+        [] => PathBuf::from("."),
+        [directory] => PathBuf::from(directory),
+        _ => return Err(Error::usage("expected at most one directory")),
+    };
+    if !root.is_dir() {
+        return Err(Error {
+            file: Some(root.display().to_string()),
+            ..Error::new("io", "not a directory")
+        });
+    }
+
+    let address = ("127.0.0.1", options.port.unwrap_or(DEFAULT_PORT));
+    let listener = TcpListener::bind(address).map_err(|error| {
+        Error::new(
+            "io",
+            format!("cannot listen on port {}: {}", address.1, error),
+        )
+    })?;
+    let site = Arc::new(Site {
+        root,
+        pages: Mutex::new(BTreeMap::new()),
+        version: Mutex::new(0),
+        changed: Condvar::new(),
+    });
+    site.scan();
+
+    let local_address = listener
+        .local_addr()
+        .map_err(|error| Error::new("io", error.to_string()))?;
+    println!(
+        "Serving {} at http://{}",
+        site.root.display(),
+        local_address
+    );
+    let _ = io::stdout().flush();
+
+    let watcher = Arc::clone(&site);
+    thread::spawn(move || {
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            if watcher.scan() {
+                *lock(&watcher.version) += 1;
+                watcher.changed.notify_all();
+            }
+        }
+    });
+
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let site = Arc::clone(&site);
+        thread::spawn(move || {
+            // Failures only mean that the page went away.
+            let _ = handle(&site, stream);
+        });
+    }
+    Ok(Vec::new())
+}
+
+impl Site {
+    /// Re-renders changed documents and drops deleted ones, returning whether
+    /// anything changed.
+    fn scan(&self) -> bool {
+        let mut sources = Vec::new();
+        collect_sources(&self.root, &mut sources);
+
+        let mut pages = lock(&self.pages);
+        let mut changed = false;
+        let mut seen = Vec::new();
+        for (source, format) in sources {
+            let Ok(metadata) = fs::metadata(&source) else {
+                continue;
+            };
+            let stamp = (
+                metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                metadata.len(),
+            );
+            let url = page_url(&self.root, &source);
+            seen.push(url.clone());
+            if pages.get(&url).is_some_and(|page| page.stamp == stamp) {
+                continue;
+            }
+            let html = render(&self.root, &source, format);
+            pages.insert(url, Page { stamp, html });
+            changed = true;
+        }
+
+        let count = pages.len();
+        pages.retain(|url, _| seen.contains(url));
+        changed || pages.len() != count
+    }
+}
+
+/// Finds jarkup and Markdown documents below `directory`, skipping hidden
+/// entries.
+fn collect_sources(directory: &Path, sources: &mut Vec<(PathBuf, Format)>) {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            collect_sources(&path, sources);
+        } else if let Some(format @ (Format::Json | Format::Markdown | Format::Text)) =
+            Format::from_path(&path.to_string_lossy())
+        {
+            sources.push((path, format));
+        }
+    }
+}
+
+fn page_url(root: &Path, source: &Path) -> String {
+    let relative = source.strip_prefix(root).unwrap_or(source);
+    let segments = relative
+        .with_extension("html")
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    format!("/{}", segments.join("/"))
+}
+
+fn render(root: &Path, source: &Path, format: Format) -> String {
+    let name = source
+        .strip_prefix(root)
+        .unwrap_or(source)
+        .display()
+        .to_string();
+    let components = fs::read_to_string(source)
+        .map_err(|error| Error::io(&name, error))
+        .and_then(|source| {
+            format.import(&Input {
+                name: name.clone(),
+                source,
+            })
+        });
+    match components {
+        Ok(components) => {
+            let title = toc::toc(&components)
+                .into_iter()
+                .next()
+                .map_or_else(|| name.clone(), |entry| entry.text);
+            layout(&title, &html::to_string(&components))
+        }
+        Err(error) => {
+            let location = match (error.line, error.column) {
+                (Some(line), Some(column)) => format!("{}:{}:{}", name, line, column),
+                _ => name.clone(),
+            };
+            let body = format!(
+                "<pre>{}: {}</pre>",
+                escape(&location),
+                escape(&error.message)
+            );
+            layout(&name, &body)
+        }
+    }
+}
+
+fn layout(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n{}\n{}\n</body>\n</html>\n",
+        escape(title),
+        body,
+        RELOAD_SCRIPT
+    )
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// HTTP # -------------------------------------------------- #
+
+fn handle(site: &Site, mut stream: TcpStream) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let target = parts.next().unwrap_or("/");
+    let head = method == "HEAD";
+    if method != "GET" && !head {
+        return respond(
+            &mut stream,
+            "405 Method Not Allowed",
+            "text/plain",
+            b"method not allowed\n",
+            head,
+        );
+    }
+
+    let path = percent_decode(target.split(['?', '#']).next().unwrap_or("/"));
+    if path == "/_events" {
+        return events(site, stream);
+    }
+    if path == "/" {
+        let index = index(&lock(&site.pages));
+        return respond(
+            &mut stream,
+            "200 OK",
+            "text/html; charset=utf-8",
+            index.as_bytes(),
+            head,
+        );
+    }
+    if let Some(page) = lock(&site.pages).get(&path) {
+        return respond(
+            &mut stream,
+            "200 OK",
+            "text/html; charset=utf-8",
+            page.html.as_bytes(),
+            head,
+        );
+    }
+
+    // Only plain segments, so that requests cannot leave the directory.
+    let relative = Path::new(path.trim_start_matches('/'));
+    let file = relative
+        .components()
+        .all(|component| matches!(component, PathComponent::Normal(_)))
+        .then(|| site.root.join(relative))
+        .filter(|file| file.is_file());
+    match file.map(fs::read) {
+        Some(Ok(contents)) => respond(
+            &mut stream,
+            "200 OK",
+            content_type(relative),
+            &contents,
+            head,
+        ),
+        _ => respond(
+            &mut stream,
+            "404 Not Found",
+            "text/plain",
+            b"not found\n",
+            head,
+        ),
+    }
+}
+
+fn respond(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &[u8],
+    head: bool,
+) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    )?;
+    if !head {
+        stream.write_all(body)?;
+    }
+    stream.flush()
+}
+
+/// Streams a `reload` event every time the site changes.
+fn events(site: &Site, mut stream: TcpStream) -> io::Result<()> {
+    stream.write_all(
+        b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n",
+    )?;
+    stream.flush()?;
+
+    let mut seen = *lock(&site.version);
+    loop {
+        let version = {
+            let version = lock(&site.version);
+            let (version, _) = site
+                .changed
+                .wait_timeout_while(version, HEARTBEAT, |version| *version == seen)
+                .unwrap_or_else(PoisonError::into_inner);
+            *version
+        };
+        if version == seen {
+            stream.write_all(b": ping\n\n")?;
+        } else {
+            write!(stream, "event: reload\ndata: {}\n\n", version)?;
+            seen = version;
+        }
+        stream.flush()?;
+    }
+}
+
+fn index(pages: &BTreeMap<String, Page>) -> String {
+    let mut body = String::from("<ul>\n");
+    for url in pages.keys() {
+        body.push_str(&format!(
+            "<li><a href=\"{}\">{}</a></li>\n",
+            escape(url),
+            escape(url.trim_start_matches('/'))
+        ));
+    }
+    body.push_str("</ul>");
+    layout("Index", &body)
+}
+
+fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("html" | "htm") => "text/html; charset=utf-8",
+        Some("css") => "text/css",
+        Some("js") => "text/javascript",
+        Some("json") => "application/json",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("pdf") => "application/pdf",
+        Some("txt" | "jarkup") => "text/plain; charset=utf-8",
+        _ => "application/octet-stream",
+    }
+}
+
+fn percent_decode(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let hex = bytes
+            .get(index + 1..index + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[index], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
@@ -0,0 +1,109 @@
+//! Binary encodings.
+//!
+//! `cbor` (behind the `cbor` feature) and `msgpack` (behind the `msgpack`
+//! feature) encode documents either with the same keys as the JSON form, or in
+//! the [`compact`](crate::compact) form with integer type tags and short keys.
+//! Both decode back to exactly the components that were encoded.
+
+use std::fmt;
+
+use crate::compact::CompactError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BinaryError {
+    Encode(String),
+    Decode(String),
+    Compact(CompactError),
+}
+
+impl fmt::Display for BinaryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BinaryError::Encode(message) => write!(f, "cannot encode document: {}", message),
+            BinaryError::Decode(message) => write!(f, "cannot decode document: {}", message),
+            BinaryError::Compact(error) => write!(f, "cannot decode document: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for BinaryError {}
+
+impl From<CompactError> for BinaryError {
+    fn from(error: CompactError) -> Self {
+        BinaryError::Compact(error)
+    }
+}
+
+/// CBOR ([RFC 8949](https://www.rfc-editor.org/rfc/rfc8949)).
+#[cfg(feature = "cbor")]
+pub mod cbor {
+    use serde_json::Value;
+
+    use super::BinaryError;
+    use crate::{Component, compact};
+
+    /// Encodes `components` with the keys of the JSON form.
+    pub fn to_vec(components: &[Component]) -> Result<Vec<u8>, BinaryError> {
+        encode(&components)
+    }
+
+    /// Decodes components encoded by [`to_vec`].
+    pub fn from_slice(bytes: &[u8]) -> Result<Vec<Component>, BinaryError> {
+        ciborium::from_reader(bytes).map_err(|error| BinaryError::Decode(error.to_string()))
+    }
+
+    /// Encodes `components` in the compact form.
+    pub fn to_vec_compact(components: &[Component]) -> Result<Vec<u8>, BinaryError> {
+        encode(&compact::to_value(components))
+    }
+
+    /// Decodes components encoded by [`to_vec_compact`].
+    pub fn from_slice_compact(bytes: &[u8]) -> Result<Vec<Component>, BinaryError> {
+        let value = ciborium::from_reader::<Value, _>(bytes)
+            .map_err(|error| BinaryError::Decode(error.to_string()))?;
+        Ok(compact::from_value(value)?)
+    }
+
+    fn encode<T>(value: &T) -> Result<Vec<u8>, BinaryError>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(value, &mut bytes)
+            .map_err(|error| BinaryError::Encode(error.to_string()))?;
+        Ok(bytes)
+    }
+}
+
+/// MessagePack.
+#[cfg(feature = "msgpack")]
+pub mod msgpack {
+    use serde_json::Value;
+
+    use super::BinaryError;
+    use crate::{Component, compact};
+
+    /// Encodes `components` with the keys of the JSON form.
+    pub fn to_vec(components: &[Component]) -> Result<Vec<u8>, BinaryError> {
+        // Structs must be maps rather than arrays, as props are optional.
+        rmp_serde::to_vec_named(components).map_err(|error| BinaryError::Encode(error.to_string()))
+    }
+
+    /// Decodes components encoded by [`to_vec`].
+    pub fn from_slice(bytes: &[u8]) -> Result<Vec<Component>, BinaryError> {
+        rmp_serde::from_slice(bytes).map_err(|error| BinaryError::Decode(error.to_string()))
+    }
+
+    /// Encodes `components` in the compact form.
+    pub fn to_vec_compact(components: &[Component]) -> Result<Vec<u8>, BinaryError> {
+        rmp_serde::to_vec_named(&compact::to_value(components))
+            .map_err(|error| BinaryError::Encode(error.to_string()))
+    }
+
+    /// Decodes components encoded by [`to_vec_compact`].
+    pub fn from_slice_compact(bytes: &[u8]) -> Result<Vec<Component>, BinaryError> {
+        let value = rmp_serde::from_slice::<Value>(bytes)
+            .map_err(|error| BinaryError::Decode(error.to_string()))?;
+        Ok(compact::from_value(value)?)
+    }
+}
@@ -0,0 +1,1039 @@
+//! Borrowed variant of the model.
+//!
+//! Deserializing [`Component`] from a `&'a str` borrows every string that
+//! needs no unescaping straight from the input instead of allocating it, which
+//! suits read-only paths such as parsing a document only to render it with
+//! [`html::render_borrowed`](crate::html::render_borrowed). The types mirror
+//! the owned ones field for field, serialize identically, and convert with
+//! [`Component::into_owned`] and `From<&crate::Component>`. Props and slots
+//! that hold no strings are the owned types themselves.
+
+use std::borrow::Cow;
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::{
+    BookmarkSlots, CalloutProps, ColumnListProps, ColumnProps, DividerProps, DividerSlots,
+    FileSlots, FragmentProps, HeadingProps, IconSlots, ImageSlots, KatexSlots, ListItemProps,
+    ListProps, MermaidSlots, TableCellProps, TableRowProps, TextSlots, ToggleProps,
+    UnsupportedSlots,
+};
+
+/// Deserializes an optional string, borrowing it when possible.
+///
+/// `#[serde(borrow)]` only reaches a `Cow` directly, not one inside an
+/// `Option`.
+fn option<'de: 'a, 'a, D>(deserializer: D) -> Result<Option<Cow<'a, str>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Borrowed<'a>(#[serde(borrow)] Cow<'a, str>);
+
+    Ok(Option::<Borrowed<'a>>::deserialize(deserializer)?.map(|borrowed| borrowed.0))
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Component<'a> {
+    InlineComponent(#[serde(borrow)] InlineComponent<'a>),
+    BlockComponent(#[serde(borrow)] BlockComponent<'a>),
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum InlineComponent<'a> {
+    Text(#[serde(borrow)] Text<'a>),
+    Icon(#[serde(borrow)] Icon<'a>),
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum BlockComponent<'a> {
+    Fragment(#[serde(borrow)] Fragment<'a>),
+    Heading(#[serde(borrow)] Heading<'a>),
+    Paragraph(#[serde(borrow)] Paragraph<'a>),
+    ListItem(#[serde(borrow)] ListItem<'a>),
+    List(#[serde(borrow)] List<'a>),
+    BlockQuote(#[serde(borrow)] BlockQuote<'a>),
+    Callout(#[serde(borrow)] Callout<'a>),
+    Divider(#[serde(borrow)] Divider<'a>),
+    Toggle(#[serde(borrow)] Toggle<'a>),
+    Bookmark(#[serde(borrow)] Bookmark<'a>),
+    File(#[serde(borrow)] File<'a>),
+    Image(#[serde(borrow)] Image<'a>),
+    CodeBlock(#[serde(borrow)] CodeBlock<'a>),
+    Katex(#[serde(borrow)] Katex<'a>),
+    Mermaid(#[serde(borrow)] Mermaid<'a>),
+    Tab(#[serde(borrow)] Tab<'a>),
+    Tabs(#[serde(borrow)] Tabs<'a>),
+    Table(#[serde(borrow)] Table<'a>),
+    TableRow(#[serde(borrow)] TableRow<'a>),
+    TableCell(#[serde(borrow)] TableCell<'a>),
+    ColumnList(#[serde(borrow)] ColumnList<'a>),
+    Column(#[serde(borrow)] Column<'a>),
+    Unsupported(#[serde(borrow)] Unsupported<'a>),
+}
+
+impl Component<'_> {
+    /// Copies every borrowed string, detaching the component from its input.
+    pub fn into_owned(self) -> crate::Component {
+        Convert::into_owned(self)
+    }
+}
+
+impl InlineComponent<'_> {
+    pub fn into_owned(self) -> crate::InlineComponent {
+        Convert::into_owned(self)
+    }
+}
+
+impl BlockComponent<'_> {
+    pub fn into_owned(self) -> crate::BlockComponent {
+        Convert::into_owned(self)
+    }
+}
+
+impl<'a> From<&'a crate::Component> for Component<'a> {
+    fn from(component: &'a crate::Component) -> Self {
+        Convert::borrow(component)
+    }
+}
+
+impl<'a> From<&'a crate::InlineComponent> for InlineComponent<'a> {
+    fn from(component: &'a crate::InlineComponent) -> Self {
+        Convert::borrow(component)
+    }
+}
+
+impl<'a> From<&'a crate::BlockComponent> for BlockComponent<'a> {
+    fn from(component: &'a crate::BlockComponent) -> Self {
+        Convert::borrow(component)
+    }
+}
+
+// Text # -------------------------------------------------- #
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Text<'a> {
+    #[serde(borrow, default, deserialize_with = "option")]
+    pub id: Option<Cow<'a, str>>,
+
+    #[serde(borrow)]
+    pub props: TextProps<'a>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slots: Option<TextSlots>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TextProps<'a> {
+    #[serde(borrow)]
+    pub text: Cow<'a, str>,
+
+    #[serde(borrow, default, deserialize_with = "option")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<Cow<'a, str>>,
+
+    #[serde(borrow, default, deserialize_with = "option")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background_color: Option<Cow<'a, str>>,
+
+    #[serde(skip_serializing_if = "crate::skip_fn::option_false")]
+    pub bold: Option<bool>,
+
+    #[serde(skip_serializing_if = "crate::skip_fn::option_false")]
+    pub italic: Option<bool>,
+
+    #[serde(skip_serializing_if = "crate::skip_fn::option_false")]
+    pub underline: Option<bool>,
+
+    #[serde(skip_serializing_if = "crate::skip_fn::option_false")]
+    pub strikethrough: Option<bool>,
+
+    #[serde(skip_serializing_if = "crate::skip_fn::option_false")]
+    pub katex: Option<bool>,
+
+    #[serde(skip_serializing_if = "crate::skip_fn::option_false")]
+    pub code: Option<bool>,
+
+    #[serde(skip_serializing_if = "crate::skip_fn::option_false")]
+    pub kbd: Option<bool>,
+
+    #[serde(borrow, default, deserialize_with = "option")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ruby: Option<Cow<'a, str>>,
+
+    #[serde(borrow, default, deserialize_with = "option")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub href: Option<Cow<'a, str>>,
+
+    #[serde(borrow, default, deserialize_with = "option")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub favicon: Option<Cow<'a, str>>,
+}
+
+// Icon # -------------------------------------------------- #
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Icon<'a> {
+    #[serde(borrow, default, deserialize_with = "option")]
+    pub id: Option<Cow<'a, str>>,
+
+    #[serde(borrow)]
+    pub props: IconProps<'a>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slots: Option<IconSlots>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct IconProps<'a> {
+    #[serde(borrow)]
+    pub src: Cow<'a, str>,
+
+    #[serde(borrow, default, deserialize_with = "option")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alt: Option<Cow<'a, str>>,
+}
+
+// Fragment # -------------------------------------------------- #
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Fragment<'a> {
+    #[serde(borrow, default, deserialize_with = "option")]
+    pub id: Option<Cow<'a, str>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub props: Option<FragmentProps>,
+
+    #[serde(borrow)]
+    pub slots: FragmentSlots<'a>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FragmentSlots<'a> {
+    #[serde(borrow)]
+    pub default: Vec<Component<'a>>,
+}
+
+// Heading # -------------------------------------------------- #
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Heading<'a> {
+    #[serde(borrow, default, deserialize_with = "option")]
+    pub id: Option<Cow<'a, str>>,
+
+    pub props: HeadingProps,
+
+    #[serde(borrow)]
+    pub slots: HeadingSlots<'a>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HeadingSlots<'a> {
+    #[serde(borrow)]
+    pub default: Vec<InlineComponent<'a>>,
+}
+
+// Paragraph # -------------------------------------------------- #
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Paragraph<'a> {
+    #[serde(borrow, default, deserialize_with = "option")]
+    pub id: Option<Cow<'a, str>>,
+
+    #[serde(borrow)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub props: Option<ParagraphProps<'a>>,
+
+    #[serde(borrow)]
+    pub slots: ParagraphSlots<'a>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ParagraphProps<'a> {
+    #[serde(borrow, default, deserialize_with = "option")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<Cow<'a, str>>,
+
+    #[serde(borrow, default, deserialize_with = "option")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background_color: Option<Cow<'a, str>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ParagraphSlots<'a> {
+    #[serde(borrow)]
+    pub default: Vec<InlineComponent<'a>>,
+}
+
+// ListItem # -------------------------------------------------- #
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ListItem<'a> {
+    #[serde(borrow, default, deserialize_with = "option")]
+    pub id: Option<Cow<'a, str>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub props: Option<ListItemProps>,
+
+    #[serde(borrow)]
+    pub slots: ListItemSlots<'a>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ListItemSlots<'a> {
+    #[serde(borrow)]
+    pub default: Vec<Component<'a>>,
+}
+
+// List # -------------------------------------------------- #
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct List<'a> {
+    #[serde(borrow, default, deserialize_with = "option")]
+    pub id: Option<Cow<'a, str>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub props: Option<ListProps>,
+
+    #[serde(borrow)]
+    pub slots: ListSlots<'a>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ListSlots<'a> {
+    #[serde(borrow)]
+    pub default: Vec<Component<'a>>,
+}
+
+// BlockQuote # -------------------------------------------------- #
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockQuote<'a> {
+    #[serde(borrow, default, deserialize_with = "option")]
+    pub id: Option<Cow<'a, str>>,
+
+    #[serde(borrow)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub props: Option<BlockQuoteProps<'a>>,
+
+    #[serde(borrow)]
+    pub slots: BlockQuoteSlots<'a>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockQuoteProps<'a> {
+    #[serde(borrow, default, deserialize_with = "option")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cite: Option<Cow<'a, str>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockQuoteSlots<'a> {
+    #[serde(borrow)]
+    pub default: Vec<Component<'a>>,
+}
+
+// Callout # -------------------------------------------------- #
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Callout<'a> {
+    #[serde(borrow, default, deserialize_with = "option")]
+    pub id: Option<Cow<'a, str>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub props: Option<CalloutProps>,
+
+    #[serde(borrow)]
+    pub slots: CalloutSlots<'a>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CalloutSlots<'a> {
+    #[serde(borrow)]
+    pub default: Vec<Component<'a>>,
+}
+
+// Divider # -------------------------------------------------- #
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Divider<'a> {
+    #[serde(borrow, default, deserialize_with = "option")]
+    pub id: Option<Cow<'a, str>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub props: Option<DividerProps>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slots: Option<DividerSlots>,
+}
+
+// Toggle # -------------------------------------------------- #
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Toggle<'a> {
+    #[serde(borrow, default, deserialize_with = "option")]
+    pub id: Option<Cow<'a, str>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub props: Option<ToggleProps>,
+
+    #[serde(borrow)]
+    pub slots: ToggleSlots<'a>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ToggleSlots<'a> {
+    #[serde(borrow)]
+    pub default: Vec<Component<'a>>,
+
+    #[serde(borrow)]
+    pub summary: Vec<InlineComponent<'a>>,
+}
+
+// Bookmark # -------------------------------------------------- #
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Bookmark<'a> {
+    #[serde(borrow, default, deserialize_with = "option")]
+    pub id: Option<Cow<'a, str>>,
+
+    #[serde(borrow)]
+    pub props: BookmarkProps<'a>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slots: Option<BookmarkSlots>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BookmarkProps<'a> {
+    #[serde(borrow)]
+    pub url: Cow<'a, str>,
+
+    #[serde(borrow, default, deserialize_with = "option")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<Cow<'a, str>>,
+
+    #[serde(borrow, default, deserialize_with = "option")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<Cow<'a, str>>,
+
+    #[serde(borrow, default, deserialize_with = "option")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<Cow<'a, str>>,
+}
+
+// File # -------------------------------------------------- #
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct File<'a> {
+    #[serde(borrow, default, deserialize_with = "option")]
+    pub id: Option<Cow<'a, str>>,
+
+    #[serde(borrow)]
+    pub props: FileProps<'a>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slots: Option<FileSlots>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FileProps<'a> {
+    #[serde(borrow)]
+    pub src: Cow<'a, str>,
+
+    #[serde(borrow, default, deserialize_with = "option")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<Cow<'a, str>>,
+}
+
+// Image # -------------------------------------------------- #
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Image<'a> {
+    #[serde(borrow, default, deserialize_with = "option")]
+    pub id: Option<Cow<'a, str>>,
+
+    #[serde(borrow)]
+    pub props: ImageProps<'a>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slots: Option<ImageSlots>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageProps<'a> {
+    #[serde(borrow)]
+    pub src: Cow<'a, str>,
+
+    #[serde(borrow, default, deserialize_with = "option")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alt: Option<Cow<'a, str>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+
+    #[serde(borrow, default, deserialize_with = "option")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub srcset: Option<Cow<'a, str>>,
+
+    #[serde(borrow, default, deserialize_with = "option")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sizes: Option<Cow<'a, str>>,
+
+    #[serde(borrow, default, deserialize_with = "option")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<Cow<'a, str>>,
+}
+
+// CodeBlock # -------------------------------------------------- #
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeBlock<'a> {
+    #[serde(borrow, default, deserialize_with = "option")]
+    pub id: Option<Cow<'a, str>>,
+
+    #[serde(borrow)]
+    pub props: CodeBlockProps<'a>,
+
+    #[serde(borrow)]
+    pub slots: Option<CodeBlockSlots<'a>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeBlockProps<'a> {
+    #[serde(borrow)]
+    pub code: Cow<'a, str>,
+
+    #[serde(borrow)]
+    pub language: Cow<'a, str>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeBlockSlots<'a> {
+    #[serde(borrow)]
+    pub default: Vec<InlineComponent<'a>>,
+}
+
+// Katex # -------------------------------------------------- #
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Katex<'a> {
+    #[serde(borrow, default, deserialize_with = "option")]
+    pub id: Option<Cow<'a, str>>,
+
+    #[serde(borrow)]
+    pub props: KatexProps<'a>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slots: Option<KatexSlots>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct KatexProps<'a> {
+    #[serde(borrow)]
+    pub expression: Cow<'a, str>,
+}
+
+// Mermaid # -------------------------------------------------- #
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Mermaid<'a> {
+    #[serde(borrow, default, deserialize_with = "option")]
+    pub id: Option<Cow<'a, str>>,
+
+    #[serde(borrow)]
+    pub props: MermaidProps<'a>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slots: Option<MermaidSlots>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MermaidProps<'a> {
+    #[serde(borrow)]
+    pub code: Cow<'a, str>,
+}
+
+// Tab # -------------------------------------------------- #
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Tab<'a> {
+    #[serde(borrow, default, deserialize_with = "option")]
+    pub id: Option<Cow<'a, str>>,
+
+    #[serde(borrow)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub props: Option<TabProps<'a>>,
+
+    #[serde(borrow)]
+    pub slots: TabSlots<'a>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TabProps<'a> {
+    #[serde(borrow, default, deserialize_with = "option")]
+    pub title: Option<Cow<'a, str>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TabSlots<'a> {
+    #[serde(borrow)]
+    pub labels: Vec<InlineComponent<'a>>,
+
+    #[serde(borrow)]
+    pub contents: Vec<Component<'a>>,
+}
+
+// Tabs # -------------------------------------------------- #
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Tabs<'a> {
+    #[serde(borrow, default, deserialize_with = "option")]
+    pub id: Option<Cow<'a, str>>,
+
+    #[serde(borrow)]
+    pub props: Option<TabsProps<'a>>,
+
+    #[serde(borrow)]
+    pub slots: TabsSlots<'a>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TabsProps<'a> {
+    #[serde(borrow, default, deserialize_with = "option")]
+    pub title: Option<Cow<'a, str>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TabsSlots<'a> {
+    #[serde(borrow)]
+    pub default: Vec<Component<'a>>,
+}
+
+// Table # -------------------------------------------------- #
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Table<'a> {
+    #[serde(borrow, default, deserialize_with = "option")]
+    pub id: Option<Cow<'a, str>>,
+
+    #[serde(borrow)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub props: Option<TableProps<'a>>,
+
+    #[serde(borrow)]
+    pub slots: TableSlots<'a>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TableProps<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub has_column_header: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub has_row_header: Option<bool>,
+
+    #[serde(borrow, default, deserialize_with = "option")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub caption: Option<Cow<'a, str>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TableSlots<'a> {
+    #[serde(borrow)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub header: Option<Vec<Component<'a>>>,
+
+    #[serde(borrow)]
+    pub body: Vec<Component<'a>>,
+}
+
+// TableRow # -------------------------------------------------- #
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TableRow<'a> {
+    #[serde(borrow, default, deserialize_with = "option")]
+    pub id: Option<Cow<'a, str>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub props: Option<TableRowProps>,
+
+    #[serde(borrow)]
+    pub slots: TableRowSlots<'a>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TableRowSlots<'a> {
+    #[serde(borrow)]
+    pub default: Vec<Component<'a>>,
+}
+
+// TableCell # -------------------------------------------------- #
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TableCell<'a> {
+    #[serde(borrow, default, deserialize_with = "option")]
+    pub id: Option<Cow<'a, str>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub props: Option<TableCellProps>,
+
+    #[serde(borrow)]
+    pub slots: TableCellSlots<'a>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TableCellSlots<'a> {
+    #[serde(borrow)]
+    pub default: Vec<InlineComponent<'a>>,
+}
+
+// ColumnList # -------------------------------------------------- #
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnList<'a> {
+    #[serde(borrow, default, deserialize_with = "option")]
+    pub id: Option<Cow<'a, str>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub props: Option<ColumnListProps>,
+
+    #[serde(borrow)]
+    pub slots: ColumnListSlots<'a>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnListSlots<'a> {
+    #[serde(borrow)]
+    pub default: Vec<Component<'a>>,
+}
+
+// Column # -------------------------------------------------- #
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Column<'a> {
+    #[serde(borrow, default, deserialize_with = "option")]
+    pub id: Option<Cow<'a, str>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub props: Option<ColumnProps>,
+
+    #[serde(borrow)]
+    pub slots: ColumnSlots<'a>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnSlots<'a> {
+    #[serde(borrow)]
+    pub default: Vec<Component<'a>>,
+}
+
+// Unsupported # -------------------------------------------------- #
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Unsupported<'a> {
+    #[serde(borrow, default, deserialize_with = "option")]
+    pub id: Option<Cow<'a, str>>,
+
+    #[serde(borrow)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub props: Option<UnsupportedProps<'a>>,
+
+    pub slots: Option<UnsupportedSlots>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsupportedProps<'a> {
+    #[serde(borrow)]
+    pub details: Cow<'a, str>,
+}
+
+// Conversion # -------------------------------------------------- #
+
+/// Conversion between a borrowed type and its owned counterpart.
+trait Convert<'a> {
+    type Owned: 'a;
+
+    fn into_owned(self) -> Self::Owned;
+
+    fn borrow(owned: &'a Self::Owned) -> Self;
+}
+
+impl<'a> Convert<'a> for Cow<'a, str> {
+    type Owned = String;
+
+    fn into_owned(self) -> String {
+        Cow::into_owned(self)
+    }
+
+    fn borrow(owned: &'a String) -> Self {
+        Cow::Borrowed(owned)
+    }
+}
+
+impl<'a, T: Convert<'a>> Convert<'a> for Option<T> {
+    type Owned = Option<T::Owned>;
+
+    fn into_owned(self) -> Self::Owned {
+        self.map(T::into_owned)
+    }
+
+    fn borrow(owned: &'a Self::Owned) -> Self {
+        owned.as_ref().map(T::borrow)
+    }
+}
+
+impl<'a, T: Convert<'a>> Convert<'a> for Vec<T> {
+    type Owned = Vec<T::Owned>;
+
+    fn into_owned(self) -> Self::Owned {
+        self.into_iter().map(T::into_owned).collect()
+    }
+
+    fn borrow(owned: &'a Self::Owned) -> Self {
+        owned.iter().map(T::borrow).collect()
+    }
+}
+
+/// Implements [`Convert`] for owned types shared by both models.
+macro_rules! shared {
+    ($($name:ty),* $(,)?) => {$(
+        impl<'a> Convert<'a> for $name {
+            type Owned = $name;
+
+            fn into_owned(self) -> Self {
+                self
+            }
+
+            fn borrow(owned: &'a Self) -> Self {
+                owned.clone()
+            }
+        }
+    )*};
+}
+
+shared!(
+    bool,
+    u32,
+    BookmarkSlots,
+    CalloutProps,
+    ColumnListProps,
+    ColumnProps,
+    DividerProps,
+    DividerSlots,
+    FileSlots,
+    FragmentProps,
+    HeadingProps,
+    IconSlots,
+    ImageSlots,
+    KatexSlots,
+    ListItemProps,
+    ListProps,
+    MermaidSlots,
+    TableCellProps,
+    TableRowProps,
+    TextSlots,
+    ToggleProps,
+    UnsupportedSlots,
+);
+
+/// Implements [`Convert`] field by field for structs of the same name.
+macro_rules! structs {
+    ($($name:ident { $($field:ident),* $(,)? })*) => {$(
+        impl<'a> Convert<'a> for $name<'a> {
+            type Owned = crate::$name;
+
+            fn into_owned(self) -> crate::$name {
+                crate::$name {
+                    $($field: Convert::into_owned(self.$field),)*
+                }
+            }
+
+            fn borrow(owned: &'a crate::$name) -> Self {
+                $name {
+                    $($field: Convert::borrow(&owned.$field),)*
+                }
+            }
+        }
+    )*};
+}
+
+structs! {
+    Text { id, props, slots }
+    TextProps {
+        text, color, background_color, bold, italic, underline, strikethrough, katex, code, kbd,
+        ruby, href, favicon,
+    }
+    Icon { id, props, slots }
+    IconProps { src, alt }
+    Fragment { id, props, slots }
+    FragmentSlots { default }
+    Heading { id, props, slots }
+    HeadingSlots { default }
+    Paragraph { id, props, slots }
+    ParagraphProps { color, background_color }
+    ParagraphSlots { default }
+    ListItem { id, props, slots }
+    ListItemSlots { default }
+    List { id, props, slots }
+    ListSlots { default }
+    BlockQuote { id, props, slots }
+    BlockQuoteProps { cite }
+    BlockQuoteSlots { default }
+    Callout { id, props, slots }
+    CalloutSlots { default }
+    Divider { id, props, slots }
+    Toggle { id, props, slots }
+    ToggleSlots { default, summary }
+    Bookmark { id, props, slots }
+    BookmarkProps { url, title, description, image }
+    File { id, props, slots }
+    FileProps { src, name }
+    Image { id, props, slots }
+    ImageProps { src, alt, width, height, srcset, sizes, mime_type }
+    CodeBlock { id, props, slots }
+    CodeBlockProps { code, language }
+    CodeBlockSlots { default }
+    Katex { id, props, slots }
+    KatexProps { expression }
+    Mermaid { id, props, slots }
+    MermaidProps { code }
+    Tab { id, props, slots }
+    TabProps { title }
+    TabSlots { labels, contents }
+    Tabs { id, props, slots }
+    TabsProps { title }
+    TabsSlots { default }
+    Table { id, props, slots }
+    TableProps { has_column_header, has_row_header, caption }
+    TableSlots { header, body }
+    TableRow { id, props, slots }
+    TableRowSlots { default }
+    TableCell { id, props, slots }
+    TableCellSlots { default }
+    ColumnList { id, props, slots }
+    ColumnListSlots { default }
+    Column { id, props, slots }
+    ColumnSlots { default }
+    Unsupported { id, props, slots }
+    UnsupportedProps { details }
+}
+
+/// Implements [`Convert`] variant by variant for enums of the same name.
+macro_rules! enums {
+    ($($name:ident { $($variant:ident),* $(,)? })*) => {$(
+        impl<'a> Convert<'a> for $name<'a> {
+            type Owned = crate::$name;
+
+            fn into_owned(self) -> crate::$name {
+                match self {
+                    $($name::$variant(inner) => crate::$name::$variant(Convert::into_owned(inner)),)*
+                }
+            }
+
+            fn borrow(owned: &'a crate::$name) -> Self {
+                match owned {
+                    $(crate::$name::$variant(inner) => $name::$variant(Convert::borrow(inner)),)*
+                }
+            }
+        }
+    )*};
+}
+
+enums! {
+    Component { InlineComponent, BlockComponent }
+    InlineComponent { Text, Icon }
+    BlockComponent {
+        Fragment, Heading, Paragraph, ListItem, List, BlockQuote, Callout, Divider, Toggle,
+        Bookmark, File, Image, CodeBlock, Katex, Mermaid, Tab, Tabs, Table, TableRow, TableCell,
+        ColumnList, Column, Unsupported,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::borrow::Cow;
+
+    use super::{BlockComponent, Component, InlineComponent};
+
+    #[test]
+    fn test_borrow() {
+        let json = r#"[{"type":"Paragraph","slots":{"default":[
+            {"type":"Text","props":{"text":"plain","href":"https://example.com"}},
+            {"type":"Text","props":{"text":"line\nbreak"}}
+        ]}}]"#;
+        let components = serde_json::from_str::<Vec<Component>>(json).unwrap();
+
+        let Component::BlockComponent(BlockComponent::Paragraph(paragraph)) = &components[0] else {
+            panic!("expected a paragraph");
+        };
+        let [InlineComponent::Text(plain), InlineComponent::Text(escaped)] =
+            paragraph.slots.default.as_slice()
+        else {
+            panic!("expected two texts");
+        };
+        assert!(matches!(plain.props.text, Cow::Borrowed("plain")));
+        assert!(matches!(plain.props.href, Some(Cow::Borrowed(_))));
+        assert!(matches!(escaped.props.text, Cow::Owned(_)));
+    }
+
+    #[test]
+    fn test_into_owned() {
+        let json = std::str::from_utf8(include_bytes!("./jarkup.json")).unwrap();
+        let owned = serde_json::from_str::<Vec<crate::Component>>(json).unwrap();
+        let borrowed = serde_json::from_str::<Vec<Component>>(json).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&borrowed).unwrap(),
+            serde_json::to_value(&owned).unwrap()
+        );
+        assert_eq!(
+            owned.iter().map(Component::from).collect::<Vec<_>>(),
+            borrowed
+        );
+        assert_eq!(
+            borrowed
+                .into_iter()
+                .map(Component::into_owned)
+                .collect::<Vec<_>>(),
+            owned
+        );
+    }
+}
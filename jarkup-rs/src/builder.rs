@@ -0,0 +1,677 @@
+//! Ergonomic constructors for building documents in Rust.
+//!
+//! ```
+//! use jarkup_rs::{Component, Heading, List, Paragraph, Table, builder::text};
+//!
+//! let document: Vec<Component> = vec![
+//!     Heading::h2(["Getting started"]).into(),
+//!     Paragraph::new([text("Hello").bold(), text("world").href("https://example.com")]).into(),
+//!     List::ordered(["Install", "Run"]).into(),
+//!     Table::from_rows([["Name", "Value"], ["a", "1"]]).with_header().into(),
+//! ];
+//! ```
+
+use crate::{
+    BlockQuote, BlockQuoteProps, BlockQuoteSlots, Bookmark, BookmarkProps, Callout, CalloutProps,
+    CalloutSlots, CalloutType, CodeBlock, CodeBlockProps, Column, ColumnList, ColumnListSlots,
+    ColumnProps, ColumnSlots, Component, Divider, File, FileProps, Fragment, FragmentSlots,
+    Heading, HeadingLevel, HeadingProps, HeadingSlots, Icon, IconProps, Image, ImageProps,
+    InlineComponent, Katex, KatexProps, List, ListItem, ListItemSlots, ListProps, ListSlots,
+    ListStyle, Mermaid, MermaidProps, Paragraph, ParagraphSlots, Tab, TabSlots, Table, TableCell,
+    TableCellSlots, TableProps, TableRow, TableRowSlots, TableSlots, Tabs, TabsSlots, Text,
+    TextProps, Toggle, ToggleSlots,
+};
+
+/// A plain text run, to be styled with the [`Text`] builder methods.
+pub fn text(text: impl Into<String>) -> Text {
+    Text::new(text)
+}
+
+pub fn icon(src: impl Into<String>) -> Icon {
+    Icon::new(src)
+}
+
+fn inlines<I>(children: I) -> Vec<InlineComponent>
+where
+    I: IntoIterator,
+    I::Item: Into<InlineComponent>,
+{
+    children.into_iter().map(Into::into).collect()
+}
+
+fn components<I>(children: I) -> Vec<Component>
+where
+    I: IntoIterator,
+    I::Item: Into<Component>,
+{
+    children.into_iter().map(Into::into).collect()
+}
+
+macro_rules! with_id {
+    ($($name:ident),* $(,)?) => {
+        $(
+            impl $name {
+                pub fn with_id(mut self, id: impl Into<String>) -> Self {
+                    self.id = Some(id.into());
+                    self
+                }
+            }
+        )*
+    };
+}
+
+with_id!(
+    Text, Icon, Fragment, Heading, Paragraph, ListItem, List, BlockQuote, Callout, Divider, Toggle,
+    Bookmark, File, Image, CodeBlock, Katex, Mermaid, Tab, Tabs, Table, TableRow, TableCell,
+    ColumnList, Column,
+);
+
+// Inline # -------------------------------------------------- #
+
+macro_rules! text_flags {
+    ($($flag:ident),* $(,)?) => {
+        $(
+            pub fn $flag(mut self) -> Self {
+                self.props.$flag = Some(true);
+                self
+            }
+        )*
+    };
+}
+
+macro_rules! text_strings {
+    ($($prop:ident),* $(,)?) => {
+        $(
+            pub fn $prop(mut self, $prop: impl Into<String>) -> Self {
+                self.props.$prop = Some($prop.into());
+                self
+            }
+        )*
+    };
+}
+
+impl Text {
+    pub fn new(text: impl Into<String>) -> Self {
+        Text {
+            id: None,
+            props: TextProps {
+                text: text.into(),
+                ..Default::default()
+            },
+            slots: None,
+        }
+    }
+
+    text_flags!(bold, italic, underline, strikethrough, katex, code, kbd);
+
+    text_strings!(color, background_color, ruby, href, favicon);
+}
+
+impl From<&str> for Text {
+    fn from(text: &str) -> Self {
+        Text::new(text)
+    }
+}
+
+impl From<String> for Text {
+    fn from(text: String) -> Self {
+        Text::new(text)
+    }
+}
+
+impl From<&str> for InlineComponent {
+    fn from(text: &str) -> Self {
+        Text::new(text).into()
+    }
+}
+
+impl From<String> for InlineComponent {
+    fn from(text: String) -> Self {
+        Text::new(text).into()
+    }
+}
+
+impl Icon {
+    pub fn new(src: impl Into<String>) -> Self {
+        Icon {
+            id: None,
+            props: IconProps {
+                src: src.into(),
+                alt: None,
+            },
+            slots: None,
+        }
+    }
+
+    pub fn alt(mut self, alt: impl Into<String>) -> Self {
+        self.props.alt = Some(alt.into());
+        self
+    }
+}
+
+// Text blocks # -------------------------------------------------- #
+
+impl Heading {
+    pub fn new<I>(level: HeadingLevel, children: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<InlineComponent>,
+    {
+        Heading {
+            id: None,
+            props: HeadingProps { level },
+            slots: HeadingSlots {
+                default: inlines(children),
+            },
+        }
+    }
+}
+
+macro_rules! heading_levels {
+    ($($name:ident => $level:ident),* $(,)?) => {
+        impl Heading {
+            $(
+                pub fn $name<I>(children: I) -> Self
+                where
+                    I: IntoIterator,
+                    I::Item: Into<InlineComponent>,
+                {
+                    Heading::new(HeadingLevel::$level, children)
+                }
+            )*
+        }
+    };
+}
+
+heading_levels!(h1 => H1, h2 => H2, h3 => H3, h4 => H4, h5 => H5, h6 => H6);
+
+impl Paragraph {
+    pub fn new<I>(children: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<InlineComponent>,
+    {
+        Paragraph {
+            id: None,
+            props: None,
+            slots: ParagraphSlots {
+                default: inlines(children),
+            },
+        }
+    }
+}
+
+// Containers # -------------------------------------------------- #
+
+impl Fragment {
+    pub fn new<I>(children: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Component>,
+    {
+        Fragment {
+            id: None,
+            props: None,
+            slots: FragmentSlots {
+                default: components(children),
+            },
+        }
+    }
+}
+
+impl ListItem {
+    pub fn new<I>(children: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Component>,
+    {
+        ListItem {
+            id: None,
+            props: None,
+            slots: ListItemSlots {
+                default: components(children),
+            },
+        }
+    }
+}
+
+impl From<Text> for ListItem {
+    fn from(text: Text) -> Self {
+        ListItem::new([text])
+    }
+}
+
+impl From<&str> for ListItem {
+    fn from(text: &str) -> Self {
+        ListItem::new([Text::new(text)])
+    }
+}
+
+impl From<String> for ListItem {
+    fn from(text: String) -> Self {
+        ListItem::new([Text::new(text)])
+    }
+}
+
+impl List {
+    pub fn new<I>(style: ListStyle, items: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<ListItem>,
+    {
+        List {
+            id: None,
+            props: Some(ListProps {
+                list_style: Some(style),
+            }),
+            slots: ListSlots {
+                default: items.into_iter().map(|item| item.into().into()).collect(),
+            },
+        }
+    }
+
+    pub fn unordered<I>(items: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<ListItem>,
+    {
+        List::new(ListStyle::Unordered, items)
+    }
+
+    pub fn ordered<I>(items: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<ListItem>,
+    {
+        List::new(ListStyle::Ordered, items)
+    }
+}
+
+impl BlockQuote {
+    pub fn new<I>(children: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Component>,
+    {
+        BlockQuote {
+            id: None,
+            props: None,
+            slots: BlockQuoteSlots {
+                default: components(children),
+            },
+        }
+    }
+
+    pub fn cite(mut self, cite: impl Into<String>) -> Self {
+        self.props = Some(BlockQuoteProps {
+            cite: Some(cite.into()),
+        });
+        self
+    }
+}
+
+impl Callout {
+    pub fn new<I>(r#type: CalloutType, children: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Component>,
+    {
+        Callout {
+            id: None,
+            props: Some(CalloutProps {
+                r#type: Some(r#type),
+            }),
+            slots: CalloutSlots {
+                default: components(children),
+            },
+        }
+    }
+}
+
+impl Toggle {
+    pub fn new<S, I>(summary: S, children: I) -> Self
+    where
+        S: IntoIterator,
+        S::Item: Into<InlineComponent>,
+        I: IntoIterator,
+        I::Item: Into<Component>,
+    {
+        Toggle {
+            id: None,
+            props: None,
+            slots: ToggleSlots {
+                default: components(children),
+                summary: inlines(summary),
+            },
+        }
+    }
+}
+
+impl Tab {
+    pub fn new<L, I>(labels: L, contents: I) -> Self
+    where
+        L: IntoIterator,
+        L::Item: Into<InlineComponent>,
+        I: IntoIterator,
+        I::Item: Into<Component>,
+    {
+        Tab {
+            id: None,
+            props: None,
+            slots: TabSlots {
+                labels: inlines(labels),
+                contents: components(contents),
+            },
+        }
+    }
+}
+
+impl Tabs {
+    pub fn new(tabs: impl IntoIterator<Item = Tab>) -> Self {
+        Tabs {
+            id: None,
+            props: None,
+            slots: TabsSlots {
+                default: components(tabs),
+            },
+        }
+    }
+}
+
+impl Column {
+    pub fn new<I>(children: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Component>,
+    {
+        Column {
+            id: None,
+            props: None,
+            slots: ColumnSlots {
+                default: components(children),
+            },
+        }
+    }
+
+    pub fn width_ratio(mut self, width_ratio: f32) -> Self {
+        self.props = Some(ColumnProps {
+            width_ratio: Some(width_ratio),
+        });
+        self
+    }
+}
+
+impl ColumnList {
+    pub fn new(columns: impl IntoIterator<Item = Column>) -> Self {
+        ColumnList {
+            id: None,
+            props: None,
+            slots: ColumnListSlots {
+                default: components(columns),
+            },
+        }
+    }
+}
+
+// Tables # -------------------------------------------------- #
+
+impl TableCell {
+    pub fn new<I>(children: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<InlineComponent>,
+    {
+        TableCell {
+            id: None,
+            props: None,
+            slots: TableCellSlots {
+                default: inlines(children),
+            },
+        }
+    }
+}
+
+impl From<Text> for TableCell {
+    fn from(text: Text) -> Self {
+        TableCell::new([text])
+    }
+}
+
+impl From<&str> for TableCell {
+    fn from(text: &str) -> Self {
+        TableCell::new([text])
+    }
+}
+
+impl From<String> for TableCell {
+    fn from(text: String) -> Self {
+        TableCell::new([text])
+    }
+}
+
+impl TableRow {
+    pub fn new<I>(cells: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<TableCell>,
+    {
+        TableRow {
+            id: None,
+            props: None,
+            slots: TableRowSlots {
+                default: cells.into_iter().map(|cell| cell.into().into()).collect(),
+            },
+        }
+    }
+}
+
+impl Table {
+    /// A table whose rows all go in the body.
+    pub fn from_rows<R>(rows: R) -> Self
+    where
+        R: IntoIterator,
+        R::Item: IntoIterator,
+        <R::Item as IntoIterator>::Item: Into<TableCell>,
+    {
+        Table {
+            id: None,
+            props: None,
+            slots: TableSlots {
+                header: None,
+                body: rows
+                    .into_iter()
+                    .map(|row| TableRow::new(row).into())
+                    .collect(),
+            },
+        }
+    }
+
+    /// Moves the first body row to the header.
+    pub fn with_header(mut self) -> Self {
+        if !self.slots.body.is_empty() {
+            let row = self.slots.body.remove(0);
+            self.slots.header.get_or_insert_with(Vec::new).push(row);
+            self.props
+                .get_or_insert_with(TableProps::default)
+                .has_column_header = Some(true);
+        }
+        self
+    }
+
+    pub fn caption(mut self, caption: impl Into<String>) -> Self {
+        self.props.get_or_insert_with(TableProps::default).caption = Some(caption.into());
+        self
+    }
+}
+
+// Media # -------------------------------------------------- #
+
+impl Divider {
+    pub fn new() -> Self {
+        Divider::default()
+    }
+}
+
+impl Bookmark {
+    pub fn new(url: impl Into<String>) -> Self {
+        Bookmark {
+            id: None,
+            props: BookmarkProps {
+                url: url.into(),
+                ..Default::default()
+            },
+            slots: None,
+        }
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.props.title = Some(title.into());
+        self
+    }
+}
+
+impl File {
+    pub fn new(src: impl Into<String>) -> Self {
+        File {
+            id: None,
+            props: FileProps {
+                src: src.into(),
+                name: None,
+            },
+            slots: None,
+        }
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.props.name = Some(name.into());
+        self
+    }
+}
+
+impl Image {
+    pub fn new(src: impl Into<String>) -> Self {
+        Image {
+            id: None,
+            props: ImageProps {
+                src: src.into(),
+                ..Default::default()
+            },
+            slots: None,
+        }
+    }
+
+    pub fn alt(mut self, alt: impl Into<String>) -> Self {
+        self.props.alt = Some(alt.into());
+        self
+    }
+
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.props.width = Some(width);
+        self.props.height = Some(height);
+        self
+    }
+}
+
+impl CodeBlock {
+    pub fn new(code: impl Into<String>, language: impl Into<String>) -> Self {
+        CodeBlock {
+            id: None,
+            props: CodeBlockProps {
+                code: code.into(),
+                language: language.into(),
+            },
+            slots: None,
+        }
+    }
+}
+
+impl Katex {
+    pub fn new(expression: impl Into<String>) -> Self {
+        Katex {
+            id: None,
+            props: KatexProps {
+                expression: expression.into(),
+            },
+            slots: None,
+        }
+    }
+}
+
+impl Mermaid {
+    pub fn new(code: impl Into<String>) -> Self {
+        Mermaid {
+            id: None,
+            props: MermaidProps { code: code.into() },
+            slots: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::text;
+    use crate::{
+        Callout, CalloutType, Component, Heading, InlineComponent, List, Paragraph, Table, Toggle,
+    };
+
+    #[test]
+    fn test_builders_match_json() {
+        let built: Vec<Component> = vec![
+            Heading::h2(["Title"]).with_id("title").into(),
+            Paragraph::new([
+                text("Hello").bold(),
+                text("world").href("https://example.com"),
+            ])
+            .into(),
+            List::ordered(["One", "Two"]).into(),
+            Callout::new(CalloutType::Warning, [Paragraph::new(["Careful"])]).into(),
+            Toggle::new([text("More").italic()], [Component::from(text("Body"))]).into(),
+            Table::from_rows([["Name", "Value"], ["a", "1"]])
+                .with_header()
+                .into(),
+        ];
+
+        let expected = serde_json::from_str::<Vec<Component>>(
+            r#"[
+                {"type":"Heading","id":"title","props":{"level":2},"slots":{"default":[{"type":"Text","props":{"text":"Title"}}]}},
+                {"type":"Paragraph","slots":{"default":[
+                    {"type":"Text","props":{"text":"Hello","bold":true}},
+                    {"type":"Text","props":{"text":"world","href":"https://example.com"}}
+                ]}},
+                {"type":"List","props":{"listStyle":"ordered"},"slots":{"default":[
+                    {"type":"ListItem","slots":{"default":[{"type":"Text","props":{"text":"One"}}]}},
+                    {"type":"ListItem","slots":{"default":[{"type":"Text","props":{"text":"Two"}}]}}
+                ]}},
+                {"type":"Callout","props":{"type":"warning"},"slots":{"default":[
+                    {"type":"Paragraph","slots":{"default":[{"type":"Text","props":{"text":"Careful"}}]}}
+                ]}},
+                {"type":"Toggle","slots":{
+                    "default":[{"type":"Text","props":{"text":"Body"}}],
+                    "summary":[{"type":"Text","props":{"text":"More","italic":true}}]
+                }},
+                {"type":"Table","props":{"hasColumnHeader":true},"slots":{
+                    "header":[{"type":"TableRow","slots":{"default":[
+                        {"type":"TableCell","slots":{"default":[{"type":"Text","props":{"text":"Name"}}]}},
+                        {"type":"TableCell","slots":{"default":[{"type":"Text","props":{"text":"Value"}}]}}
+                    ]}}],
+                    "body":[{"type":"TableRow","slots":{"default":[
+                        {"type":"TableCell","slots":{"default":[{"type":"Text","props":{"text":"a"}}]}},
+                        {"type":"TableCell","slots":{"default":[{"type":"Text","props":{"text":"1"}}]}}
+                    ]}}]
+                }}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(built, expected);
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(InlineComponent::from("Hi"), text("Hi").into());
+        assert_eq!(
+            Paragraph::new(["Hi", "there"]),
+            Paragraph::new([text("Hi"), text("there")])
+        );
+    }
+}
@@ -0,0 +1,303 @@
+//! Compact form of the JSON model.
+//!
+//! The compact form has the same shape as the JSON form, but component types
+//! are small integers and the keys of components, props and slots are one or
+//! two letters long, e.g. `{"t":4,"s":{"d":[{"t":0,"p":{"x":"Hi"}}]}}` for a
+//! paragraph. `null` values are left out. [`from_value`] reverses
+//! [`to_value`] exactly, so documents survive a round trip unchanged.
+//!
+//! The tables below are part of the format: entries may be added, but never
+//! changed or reordered.
+
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+use crate::Component;
+
+/// Component types, tagged by their index.
+const TYPES: &[&str] = &[
+    "Text",
+    "Icon",
+    "Fragment",
+    "Heading",
+    "Paragraph",
+    "ListItem",
+    "List",
+    "BlockQuote",
+    "Callout",
+    "Divider",
+    "Toggle",
+    "Bookmark",
+    "File",
+    "Image",
+    "CodeBlock",
+    "Katex",
+    "Mermaid",
+    "Tab",
+    "Tabs",
+    "Table",
+    "TableRow",
+    "TableCell",
+    "ColumnList",
+    "Column",
+    "Unsupported",
+];
+
+const COMPONENT_KEYS: &[(&str, &str)] =
+    &[("type", "t"), ("id", "i"), ("props", "p"), ("slots", "s")];
+
+const PROP_KEYS: &[(&str, &str)] = &[
+    ("text", "x"),
+    ("color", "c"),
+    ("backgroundColor", "bc"),
+    ("bold", "b"),
+    ("italic", "it"),
+    ("underline", "u"),
+    ("strikethrough", "st"),
+    ("katex", "k"),
+    ("code", "cd"),
+    ("kbd", "kb"),
+    ("ruby", "rb"),
+    ("href", "h"),
+    ("favicon", "fv"),
+    ("src", "sr"),
+    ("alt", "a"),
+    ("level", "l"),
+    ("listStyle", "ls"),
+    ("cite", "ci"),
+    ("type", "ty"),
+    ("url", "ur"),
+    ("title", "ti"),
+    ("description", "de"),
+    ("image", "im"),
+    ("name", "n"),
+    ("width", "w"),
+    ("height", "he"),
+    ("srcset", "ss"),
+    ("sizes", "sz"),
+    ("mimeType", "mt"),
+    ("language", "la"),
+    ("expression", "e"),
+    ("hasColumnHeader", "hc"),
+    ("hasRowHeader", "hr"),
+    ("caption", "ca"),
+    ("isHeader", "ih"),
+    ("widthRatio", "wr"),
+    ("details", "dt"),
+];
+
+const SLOT_KEYS: &[(&str, &str)] = &[
+    ("default", "d"),
+    ("summary", "su"),
+    ("labels", "lb"),
+    ("contents", "co"),
+    ("header", "hd"),
+    ("body", "bo"),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompactError {
+    /// A component had a type tag missing from the table.
+    UnknownTag(Value),
+    /// A component, props or slots object had a key missing from the table.
+    UnknownKey(String),
+    /// The value did not have the shape of a compact document.
+    Malformed(String),
+    /// The expanded document did not deserialize.
+    Invalid(String),
+}
+
+impl fmt::Display for CompactError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompactError::UnknownTag(tag) => write!(f, "unknown type tag {}", tag),
+            CompactError::UnknownKey(key) => write!(f, "unknown key `{}`", key),
+            CompactError::Malformed(message) => {
+                write!(f, "malformed compact document: {}", message)
+            }
+            CompactError::Invalid(message) => write!(f, "invalid document: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for CompactError {}
+
+/// Converts `components` to the compact form.
+pub fn to_value(components: &[Component]) -> Value {
+    // Serializing the model into a `Value` never fails.
+    let value = serde_json::to_value(components).unwrap_or_default();
+    match value {
+        Value::Array(components) => Value::Array(components.into_iter().map(shorten).collect()),
+        value => value,
+    }
+}
+
+/// Converts a document in the compact form back to components.
+pub fn from_value(value: Value) -> Result<Vec<Component>, CompactError> {
+    let Value::Array(components) = value else {
+        return Err(CompactError::Malformed("expected an array".to_string()));
+    };
+    let components = components
+        .into_iter()
+        .map(expand)
+        .collect::<Result<Vec<_>, _>>()?;
+    serde_json::from_value(Value::Array(components))
+        .map_err(|error| CompactError::Invalid(error.to_string()))
+}
+
+fn lookup<'a>(table: &[(&'a str, &'a str)], key: &str, short: bool) -> Option<&'a str> {
+    table.iter().find_map(|&(long, abbreviation)| match short {
+        true => (abbreviation == key).then_some(long),
+        false => (long == key).then_some(abbreviation),
+    })
+}
+
+/// Renames the keys of `object` using `table`, dropping `null` values.
+fn rename(
+    object: Map<String, Value>,
+    table: &[(&str, &str)],
+    short: bool,
+    mut value: impl FnMut(&str, Value) -> Result<Value, CompactError>,
+) -> Result<Map<String, Value>, CompactError> {
+    let mut renamed = Map::new();
+    for (key, item) in object {
+        if item.is_null() {
+            continue;
+        }
+        // Keys outside the tables cannot come from the model.
+        let Some(name) = lookup(table, &key, short) else {
+            return Err(CompactError::UnknownKey(key));
+        };
+        let long = if short { name } else { key.as_str() };
+        let item = value(long, item)?;
+        renamed.insert(name.to_string(), item);
+    }
+    Ok(renamed)
+}
+
+fn shorten(component: Value) -> Value {
+    let Value::Object(object) = component else {
+        return component;
+    };
+    let shortened = rename(object, COMPONENT_KEYS, false, |key, item| {
+        Ok(match (key, item) {
+            ("type", Value::String(name)) => {
+                match TYPES.iter().position(|&type_name| type_name == name) {
+                    Some(tag) => Value::from(tag),
+                    None => Value::String(name),
+                }
+            }
+            ("props", Value::Object(props)) => {
+                Value::Object(rename(props, PROP_KEYS, false, |_, prop| Ok(prop))?)
+            }
+            ("slots", Value::Object(slots)) => {
+                Value::Object(rename(slots, SLOT_KEYS, false, |_, slot| {
+                    Ok(match slot {
+                        Value::Array(children) => {
+                            Value::Array(children.into_iter().map(shorten).collect())
+                        }
+                        slot => slot,
+                    })
+                })?)
+            }
+            (_, item) => item,
+        })
+    });
+    // Components serialized from the model only carry known keys.
+    shortened.map(Value::Object).unwrap_or_default()
+}
+
+fn expand(component: Value) -> Result<Value, CompactError> {
+    let Value::Object(object) = component else {
+        return Err(CompactError::Malformed(
+            "expected a component object".to_string(),
+        ));
+    };
+    let expanded = rename(object, COMPONENT_KEYS, true, |key, item| {
+        Ok(match (key, item) {
+            ("type", tag) => tag
+                .as_u64()
+                .and_then(|tag| TYPES.get(usize::try_from(tag).ok()?))
+                .map(|&name| Value::from(name))
+                .ok_or(CompactError::UnknownTag(tag))?,
+            ("props", Value::Object(props)) => {
+                Value::Object(rename(props, PROP_KEYS, true, |_, prop| Ok(prop))?)
+            }
+            ("slots", Value::Object(slots)) => {
+                Value::Object(rename(slots, SLOT_KEYS, true, |_, slot| match slot {
+                    Value::Array(children) => children
+                        .into_iter()
+                        .map(expand)
+                        .collect::<Result<Vec<_>, _>>()
+                        .map(Value::Array),
+                    _ => Err(CompactError::Malformed(
+                        "expected an array of components".to_string(),
+                    )),
+                })?)
+            }
+            (_, item) => item,
+        })
+    })?;
+    Ok(Value::Object(expanded))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::collections::HashSet;
+
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let components =
+            serde_json::from_slice::<Vec<Component>>(include_bytes!("./jarkup.json")).unwrap();
+        let compact = to_value(&components);
+        assert_eq!(from_value(compact.clone()).unwrap(), components);
+        let json = serde_json::to_string(&components).unwrap();
+        assert!(compact.to_string().len() * 4 < json.len() * 3);
+
+        let paragraph = serde_json::from_value::<Vec<Component>>(json!([
+            {"type":"Paragraph","slots":{"default":[{"type":"Text","props":{"text":"Hi"}}]}}
+        ]))
+        .unwrap();
+        assert_eq!(
+            to_value(&paragraph),
+            json!([{"t":4,"s":{"d":[{"t":0,"p":{"x":"Hi"}}]}}])
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            from_value(json!([{"t":99}])),
+            Err(CompactError::UnknownTag(json!(99)))
+        );
+        assert_eq!(
+            from_value(json!([{"t":4,"q":1}])),
+            Err(CompactError::UnknownKey("q".to_string()))
+        );
+        assert!(matches!(
+            from_value(json!({})),
+            Err(CompactError::Malformed(_))
+        ));
+        assert!(matches!(
+            from_value(json!([{"t":3}])),
+            Err(CompactError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_tables() {
+        for table in [COMPONENT_KEYS, PROP_KEYS, SLOT_KEYS] {
+            let longs = table.iter().map(|(long, _)| long).collect::<HashSet<_>>();
+            let shorts = table.iter().map(|(_, short)| short).collect::<HashSet<_>>();
+            assert_eq!(longs.len(), table.len());
+            assert_eq!(shorts.len(), table.len());
+        }
+        assert_eq!(TYPES.iter().collect::<HashSet<_>>().len(), TYPES.len());
+    }
+}
@@ -0,0 +1,1081 @@
+//! Structural diff between two revisions of a document.
+//!
+//! Children are matched by `id` when present, and otherwise by type and
+//! content similarity. Nodes with an `id` are followed across parents, so a
+//! block dragged into another container shows up as a move rather than a
+//! deletion plus an insertion.
+//!
+//! Paths of deleted nodes and of move sources refer to the old document; all
+//! other paths refer to the new one. The operations describe the two
+//! revisions and are not meant to be applied in sequence.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    Component, InlineComponent, Text, TextProps,
+    html::{self, Render},
+    node::{NodeRef, SlotMut},
+};
+
+/// Minimum similarity for two children without ids to count as the same node.
+const THRESHOLD: f64 = 0.5;
+
+/// Character diffs and similarity matching above this many table cells fall
+/// back to a replacement and to positional pairing respectively.
+const MAX_CELLS: usize = 400_000;
+
+/// First private-use code point tried for marking slots when annotating HTML.
+const SENTINEL: u32 = 0xE000;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "camelCase")]
+pub enum Operation {
+    Insert {
+        path: String,
+        component: Component,
+    },
+    Delete {
+        path: String,
+    },
+    Move {
+        from: String,
+        to: String,
+    },
+    UpdateProps {
+        path: String,
+        changes: Vec<PropChange>,
+    },
+    /// Character-level changes to the `text` prop of a `Text` run.
+    EditText {
+        path: String,
+        edits: Vec<TextEdit>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PropChange {
+    pub name: String,
+    /// `None` when the prop was added.
+    pub old: Option<Value>,
+    /// `None` when the prop was removed.
+    pub new: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TextEdit {
+    Equal(String),
+    Insert(String),
+    Delete(String),
+}
+
+/// Returns the operations turning `old` into `new`, in document order.
+pub fn diff(old: &[Component], new: &[Component]) -> Vec<Operation> {
+    let mut operations = Vec::new();
+    collect(&align(old, new), &mut operations);
+    operations
+}
+
+/// Returns a character-level diff of two strings.
+pub fn diff_text(old: &str, new: &str) -> Vec<TextEdit> {
+    let old = old.chars().collect::<Vec<_>>();
+    let new = new.chars().collect::<Vec<_>>();
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let a = &old[prefix..old.len() - suffix];
+    let b = &new[prefix..new.len() - suffix];
+
+    let mut edits = Vec::new();
+    push(&mut edits, TextEdit::Equal(old[..prefix].iter().collect()));
+    if a.len() * b.len() > MAX_CELLS {
+        push(&mut edits, TextEdit::Delete(a.iter().collect()));
+        push(&mut edits, TextEdit::Insert(b.iter().collect()));
+    } else {
+        // Longest common subsequence of the remaining middle parts.
+        let width = b.len() + 1;
+        let mut table = vec![0u32; (a.len() + 1) * width];
+        for i in (0..a.len()).rev() {
+            for j in (0..b.len()).rev() {
+                table[i * width + j] = if a[i] == b[j] {
+                    table[(i + 1) * width + j + 1] + 1
+                } else {
+                    table[(i + 1) * width + j].max(table[i * width + j + 1])
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+        while i < a.len() || j < b.len() {
+            if i < a.len() && j < b.len() && a[i] == b[j] {
+                push(&mut edits, TextEdit::Equal(a[i].to_string()));
+                i += 1;
+                j += 1;
+            } else if i < a.len()
+                && (j == b.len() || table[(i + 1) * width + j] >= table[i * width + j + 1])
+            {
+                push(&mut edits, TextEdit::Delete(a[i].to_string()));
+                i += 1;
+            } else {
+                push(&mut edits, TextEdit::Insert(b[j].to_string()));
+                j += 1;
+            }
+        }
+    }
+    push(
+        &mut edits,
+        TextEdit::Equal(old[old.len() - suffix..].iter().collect()),
+    );
+    edits
+}
+
+/// Appends `edit`, merging it into the previous edit of the same kind.
+fn push(edits: &mut Vec<TextEdit>, edit: TextEdit) {
+    let (TextEdit::Equal(text) | TextEdit::Insert(text) | TextEdit::Delete(text)) = &edit;
+    if text.is_empty() {
+        return;
+    }
+    match (edits.last_mut(), edit) {
+        (Some(TextEdit::Equal(last)), TextEdit::Equal(text))
+        | (Some(TextEdit::Insert(last)), TextEdit::Insert(text))
+        | (Some(TextEdit::Delete(last)), TextEdit::Delete(text)) => last.push_str(&text),
+        (_, edit) => edits.push(edit),
+    }
+}
+
+/// Renders `new` as HTML with the changes from `old` annotated.
+///
+/// Insertions and deletions are wrapped in `<ins class="jarkup-diff-insert">`
+/// and `<del class="jarkup-diff-delete">`. Moved and updated nodes are wrapped
+/// in an element with the `jarkup-diff-move` and `jarkup-diff-update` classes
+/// and carry `data-from` and `data-props` attributes.
+pub fn to_html(old: &[Component], new: &[Component]) -> String {
+    let mut html = String::new();
+    // Writing into a `String` never fails.
+    let _ = render_entries(&align(old, new), &mut html);
+    html
+}
+
+// Alignment # -------------------------------------------------- #
+
+enum Entry<'a> {
+    Inserted {
+        node: NodeRef<'a>,
+        path: String,
+    },
+    Deleted {
+        node: NodeRef<'a>,
+        path: String,
+    },
+    /// An old child whose id appears elsewhere in the new document. It
+    /// becomes a deletion unless the new occurrence was matched to it.
+    Departed {
+        node: NodeRef<'a>,
+        path: String,
+        id: &'a str,
+    },
+    Matched(Box<Match<'a>>),
+}
+
+struct Match<'a> {
+    new: NodeRef<'a>,
+    old_path: String,
+    new_path: String,
+    moved: bool,
+    props: Vec<PropChange>,
+    /// Empty unless the text of a `Text` run changed.
+    text: Vec<TextEdit>,
+    slots: Vec<(&'static str, Vec<Entry<'a>>)>,
+}
+
+impl Entry<'_> {
+    fn changed(&self) -> bool {
+        match self {
+            Entry::Inserted { .. } | Entry::Deleted { .. } => true,
+            Entry::Departed { .. } => false,
+            Entry::Matched(matched) => matched.changed(),
+        }
+    }
+}
+
+impl Match<'_> {
+    fn changed(&self) -> bool {
+        self.moved || !self.props.is_empty() || !self.text.is_empty() || self.slots_changed()
+    }
+
+    fn slots_changed(&self) -> bool {
+        self.slots
+            .iter()
+            .any(|(_, entries)| entries.iter().any(Entry::changed))
+    }
+}
+
+type IdIndex<'a> = HashMap<&'a str, (NodeRef<'a>, String)>;
+
+struct Aligner<'a> {
+    old_ids: IdIndex<'a>,
+    new_ids: IdIndex<'a>,
+    /// Old ids that have been matched to a node of the new document.
+    claimed: HashSet<&'a str>,
+}
+
+fn align<'a>(old: &'a [Component], new: &'a [Component]) -> Vec<Entry<'a>> {
+    let old = old.iter().map(NodeRef::Component).collect::<Vec<_>>();
+    let new = new.iter().map(NodeRef::Component).collect::<Vec<_>>();
+
+    let mut aligner = Aligner {
+        old_ids: HashMap::new(),
+        new_ids: HashMap::new(),
+        claimed: HashSet::new(),
+    };
+    index_ids(&old, "", &mut aligner.old_ids);
+    index_ids(&new, "", &mut aligner.new_ids);
+
+    let mut entries = aligner.slot(&old, "", &new, "");
+    resolve(&mut entries, &aligner.claimed);
+    entries
+}
+
+/// Records the first node carrying each id, with its path.
+fn index_ids<'a>(nodes: &[NodeRef<'a>], parent: &str, ids: &mut IdIndex<'a>) {
+    for (index, node) in nodes.iter().enumerate() {
+        let path = format!("{}/{}", parent, index);
+        if let Some(id) = node.id() {
+            ids.entry(id).or_insert_with(|| (*node, path.clone()));
+        }
+        for (name, slot) in node.slots() {
+            let children = slot.iter().collect::<Vec<_>>();
+            index_ids(&children, &format!("{}/slots/{}", path, name), ids);
+        }
+    }
+}
+
+/// Turns departed nodes that were never claimed into deletions.
+fn resolve(entries: &mut Vec<Entry<'_>>, claimed: &HashSet<&str>) {
+    entries.retain(|entry| !matches!(entry, Entry::Departed { id, .. } if claimed.contains(id)));
+    for entry in entries {
+        match entry {
+            Entry::Departed { node, path, .. } => {
+                *entry = Entry::Deleted {
+                    node: *node,
+                    path: std::mem::take(path),
+                };
+            }
+            Entry::Matched(matched) => {
+                for (_, entries) in &mut matched.slots {
+                    resolve(entries, claimed);
+                }
+            }
+            Entry::Inserted { .. } | Entry::Deleted { .. } => {}
+        }
+    }
+}
+
+enum Origin<'a> {
+    /// Matched to the old child at this index of the same slot.
+    Local(usize),
+    /// Matched by id to an old node under another parent.
+    Remote(NodeRef<'a>, String),
+    Inserted,
+}
+
+impl<'a> Aligner<'a> {
+    fn slot(
+        &mut self,
+        old: &[NodeRef<'a>],
+        old_path: &str,
+        new: &[NodeRef<'a>],
+        new_path: &str,
+    ) -> Vec<Entry<'a>> {
+        let pairs = match_children(old, new);
+        let mut old_taken = vec![false; old.len()];
+        let mut origins = Vec::with_capacity(new.len());
+        for (j, pair) in pairs.into_iter().enumerate() {
+            let id = new[j].id().filter(|id| !self.claimed.contains(id));
+            let origin = match (pair, id) {
+                (Some(i), _) => {
+                    old_taken[i] = true;
+                    Origin::Local(i)
+                }
+                // Followed across parents.
+                (None, Some(id)) => match self.old_ids.get(id) {
+                    Some((node, path)) => Origin::Remote(*node, path.clone()),
+                    None => Origin::Inserted,
+                },
+                (None, None) => Origin::Inserted,
+            };
+            if let (Origin::Local(_) | Origin::Remote(..), Some(id)) = (&origin, id) {
+                self.claimed.insert(id);
+            }
+            origins.push(origin);
+        }
+
+        // Children matched out of order were moved within the slot.
+        let local_pairs = origins
+            .iter()
+            .filter_map(|origin| match origin {
+                Origin::Local(i) => Some(*i),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let in_order = longest_increasing(&local_pairs);
+
+        let mut entries = Vec::new();
+        let mut pending = (0..old.len()).filter(|&i| !old_taken[i]).peekable();
+        for (j, origin) in origins.into_iter().enumerate() {
+            let path = format!("{}/{}", new_path, j);
+            match origin {
+                Origin::Local(i) => {
+                    let moved = !in_order.contains(&i);
+                    if !moved {
+                        while let Some(removed) = pending.next_if(|&removed| removed < i) {
+                            entries.push(self.removed(old[removed], old_path, removed));
+                        }
+                    }
+                    let from = format!("{}/{}", old_path, i);
+                    self.compare(old[i], from, new[j], path, moved, &mut entries);
+                }
+                Origin::Remote(node, from) => {
+                    self.compare(node, from, new[j], path, true, &mut entries);
+                }
+                Origin::Inserted => entries.push(Entry::Inserted { node: new[j], path }),
+            }
+        }
+        for removed in pending {
+            entries.push(self.removed(old[removed], old_path, removed));
+        }
+        entries
+    }
+
+    fn removed(&self, node: NodeRef<'a>, parent: &str, index: usize) -> Entry<'a> {
+        let path = format!("{}/{}", parent, index);
+        match node.id() {
+            Some(id) if self.new_ids.contains_key(id) => Entry::Departed { node, path, id },
+            _ => Entry::Deleted { node, path },
+        }
+    }
+
+    fn compare(
+        &mut self,
+        old: NodeRef<'a>,
+        old_path: String,
+        new: NodeRef<'a>,
+        new_path: String,
+        moved: bool,
+        entries: &mut Vec<Entry<'a>>,
+    ) {
+        if old.type_name() != new.type_name() {
+            entries.push(Entry::Deleted {
+                node: old,
+                path: old_path,
+            });
+            entries.push(Entry::Inserted {
+                node: new,
+                path: new_path,
+            });
+            return;
+        }
+
+        let mut old_props = old.props();
+        let mut new_props = new.props();
+        let mut text = Vec::new();
+        if old.type_name() == "Text" {
+            let old_text = old_props.remove("text");
+            let new_text = new_props.remove("text");
+            if old_text != new_text {
+                text = diff_text(
+                    old_text
+                        .as_ref()
+                        .and_then(Value::as_str)
+                        .unwrap_or_default(),
+                    new_text
+                        .as_ref()
+                        .and_then(Value::as_str)
+                        .unwrap_or_default(),
+                );
+            }
+        }
+        let props = old_props
+            .keys()
+            .chain(new_props.keys().filter(|key| !old_props.contains_key(*key)))
+            .filter(|key| old_props.get(*key) != new_props.get(*key))
+            .map(|key| PropChange {
+                name: key.clone(),
+                old: old_props.get(key).cloned(),
+                new: new_props.get(key).cloned(),
+            })
+            .collect();
+
+        let old_slots = old.slots();
+        let new_slots = new.slots();
+        let names = new_slots
+            .iter()
+            .map(|(name, _)| *name)
+            .chain(
+                old_slots
+                    .iter()
+                    .map(|(name, _)| *name)
+                    .filter(|name| new_slots.iter().all(|(new_name, _)| new_name != name)),
+            )
+            .collect::<Vec<_>>();
+        let children = |slots: &[(&'static str, crate::node::Slot<'a>)], name: &str| {
+            slots
+                .iter()
+                .find(|(slot_name, _)| *slot_name == name)
+                .map(|(_, slot)| slot.iter().collect::<Vec<_>>())
+                .unwrap_or_default()
+        };
+        let slots = names
+            .into_iter()
+            .map(|name| {
+                let entries = self.slot(
+                    &children(&old_slots, name),
+                    &format!("{}/slots/{}", old_path, name),
+                    &children(&new_slots, name),
+                    &format!("{}/slots/{}", new_path, name),
+                );
+                (name, entries)
+            })
+            .collect();
+
+        entries.push(Entry::Matched(Box::new(Match {
+            new,
+            old_path,
+            new_path,
+            moved,
+            props,
+            text,
+            slots,
+        })));
+    }
+}
+
+// Matching # -------------------------------------------------- #
+
+/// Pairs the children of one slot across two revisions and returns, for each
+/// new child, the index of its old counterpart.
+///
+/// Children are paired by id, then by similarity. Finally, when as many
+/// unpaired children of a type sit between the same two matched neighbours in
+/// both revisions, they are paired in order, however much they changed.
+pub(crate) fn match_children(old: &[NodeRef<'_>], new: &[NodeRef<'_>]) -> Vec<Option<usize>> {
+    let mut pairs = vec![None; new.len()];
+    let mut old_taken = vec![false; old.len()];
+
+    let ids = old
+        .iter()
+        .enumerate()
+        .filter_map(|(index, node)| Some((node.id()?, index)))
+        .collect::<HashMap<_, _>>();
+    for (j, node) in new.iter().enumerate() {
+        if let Some(&i) = node.id().and_then(|id| ids.get(id))
+            && !old_taken[i]
+        {
+            pairs[j] = Some(i);
+            old_taken[i] = true;
+        }
+    }
+
+    let old_candidates = (0..old.len())
+        .filter(|&i| !old_taken[i] && old[i].id().is_none())
+        .collect::<Vec<_>>();
+    let new_candidates = (0..new.len())
+        .filter(|&j| pairs[j].is_none() && new[j].id().is_none())
+        .collect::<Vec<_>>();
+    for (i, j) in match_similar(old, &old_candidates, new, &new_candidates) {
+        pairs[j] = Some(i);
+        old_taken[i] = true;
+    }
+
+    for &j in &new_candidates {
+        if pairs[j].is_some() {
+            continue;
+        }
+        let before = (0..j).rev().find_map(|j| pairs[j].map(|i| (i, j)));
+        let after = (j + 1..new.len()).find_map(|j| pairs[j].map(|i| (i, j)));
+        let (old_start, new_start) = before.map_or((0, 0), |(i, j)| (i + 1, j + 1));
+        let (old_end, new_end) = after.unwrap_or((old.len(), new.len()));
+        if old_start > old_end {
+            continue;
+        }
+
+        let type_name = new[j].type_name();
+        let old_gap = (old_start..old_end)
+            .filter(|&i| !old_taken[i] && old[i].id().is_none())
+            .filter(|&i| old[i].type_name() == type_name)
+            .collect::<Vec<_>>();
+        let new_gap = (new_start..new_end)
+            .filter(|&j| pairs[j].is_none() && new[j].id().is_none())
+            .filter(|&j| new[j].type_name() == type_name)
+            .collect::<Vec<_>>();
+        if old_gap.len() == new_gap.len() {
+            for (i, j) in old_gap.into_iter().zip(new_gap) {
+                pairs[j] = Some(i);
+                old_taken[i] = true;
+            }
+        }
+    }
+
+    pairs
+}
+
+// Similarity # -------------------------------------------------- #
+
+struct Fingerprint {
+    type_name: &'static str,
+    content: String,
+    bigrams: HashMap<(char, char), usize>,
+}
+
+impl Fingerprint {
+    fn new(node: NodeRef<'_>) -> Self {
+        let mut content = String::new();
+        collect_content(node, &mut content);
+        let chars = content.chars().collect::<Vec<_>>();
+        let mut bigrams = HashMap::new();
+        for pair in chars.windows(2) {
+            *bigrams.entry((pair[0], pair[1])).or_insert(0) += 1;
+        }
+        Fingerprint {
+            type_name: node.type_name(),
+            content,
+            bigrams,
+        }
+    }
+
+    /// Dice coefficient over character bigrams, `0.0` across types.
+    fn similarity(&self, other: &Fingerprint) -> f64 {
+        if self.type_name != other.type_name {
+            return 0.0;
+        }
+        if self.content == other.content {
+            return 1.0;
+        }
+        let total = self.bigrams.values().sum::<usize>() + other.bigrams.values().sum::<usize>();
+        if total == 0 {
+            return 0.0;
+        }
+        let common = self
+            .bigrams
+            .iter()
+            .map(|(bigram, count)| {
+                other
+                    .bigrams
+                    .get(bigram)
+                    .map_or(0, |other| *other.min(count))
+            })
+            .sum::<usize>();
+        2.0 * common as f64 / total as f64
+    }
+}
+
+/// Appends every string prop of `node` and its descendants to `content`.
+fn collect_content(node: NodeRef<'_>, content: &mut String) {
+    fn strings(value: &Value, content: &mut String) {
+        match value {
+            Value::String(string) => {
+                content.push_str(string);
+                content.push('\n');
+            }
+            Value::Array(items) => items.iter().for_each(|item| strings(item, content)),
+            Value::Object(object) => object.values().for_each(|item| strings(item, content)),
+            _ => {}
+        }
+    }
+
+    for value in node.props().values() {
+        strings(value, content);
+    }
+    for (_, slot) in node.slots() {
+        for child in slot.iter() {
+            collect_content(child, content);
+        }
+    }
+}
+
+/// Pairs similar candidates, preferring an in-order alignment and then
+/// pairing what is left regardless of order.
+fn match_similar(
+    old: &[NodeRef<'_>],
+    old_candidates: &[usize],
+    new: &[NodeRef<'_>],
+    new_candidates: &[usize],
+) -> Vec<(usize, usize)> {
+    if old_candidates.len().saturating_mul(new_candidates.len()) > MAX_CELLS {
+        return match_positions(old, old_candidates, new, new_candidates);
+    }
+
+    let old_prints = old_candidates
+        .iter()
+        .map(|&i| Fingerprint::new(old[i]))
+        .collect::<Vec<_>>();
+    let new_prints = new_candidates
+        .iter()
+        .map(|&j| Fingerprint::new(new[j]))
+        .collect::<Vec<_>>();
+    let (n, m) = (old_prints.len(), new_prints.len());
+    let similarity = old_prints
+        .iter()
+        .map(|old| {
+            new_prints
+                .iter()
+                .map(|new| old.similarity(new))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    // Alignment maximizing the total similarity of in-order pairs.
+    let mut score = vec![vec![0.0_f64; m + 1]; n + 1];
+    for a in (0..n).rev() {
+        for b in (0..m).rev() {
+            let mut best = score[a + 1][b].max(score[a][b + 1]);
+            if similarity[a][b] >= THRESHOLD {
+                best = best.max(similarity[a][b] + score[a + 1][b + 1]);
+            }
+            score[a][b] = best;
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let mut old_used = vec![false; n];
+    let mut new_used = vec![false; m];
+    let (mut a, mut b) = (0, 0);
+    while a < n && b < m {
+        if similarity[a][b] >= THRESHOLD && score[a][b] == similarity[a][b] + score[a + 1][b + 1] {
+            pairs.push((a, b));
+            old_used[a] = true;
+            new_used[b] = true;
+            a += 1;
+            b += 1;
+        } else if score[a][b] == score[a + 1][b] {
+            a += 1;
+        } else {
+            b += 1;
+        }
+    }
+
+    for b in 0..m {
+        if new_used[b] {
+            continue;
+        }
+        let best = (0..n)
+            .filter(|&a| !old_used[a] && similarity[a][b] >= THRESHOLD)
+            .max_by(|&x, &y| similarity[x][b].total_cmp(&similarity[y][b]));
+        if let Some(a) = best {
+            pairs.push((a, b));
+            old_used[a] = true;
+            new_used[b] = true;
+        }
+    }
+
+    pairs
+        .into_iter()
+        .map(|(a, b)| (old_candidates[a], new_candidates[b]))
+        .collect()
+}
+
+/// Pairs the candidates of each type in order of appearance.
+fn match_positions(
+    old: &[NodeRef<'_>],
+    old_candidates: &[usize],
+    new: &[NodeRef<'_>],
+    new_candidates: &[usize],
+) -> Vec<(usize, usize)> {
+    let mut by_type = HashMap::<&str, Vec<usize>>::new();
+    for &i in old_candidates.iter().rev() {
+        by_type.entry(old[i].type_name()).or_default().push(i);
+    }
+    new_candidates
+        .iter()
+        .filter_map(|&j| Some((by_type.get_mut(new[j].type_name())?.pop()?, j)))
+        .collect()
+}
+
+/// Returns the values forming a longest increasing subsequence of `values`.
+fn longest_increasing(values: &[usize]) -> HashSet<usize> {
+    // `tails[k]` is the index of the smallest tail of an increasing
+    // subsequence of length `k + 1`.
+    let mut tails: Vec<usize> = Vec::new();
+    let mut previous = vec![None; values.len()];
+    for (index, value) in values.iter().enumerate() {
+        let position = tails.partition_point(|&tail| values[tail] < *value);
+        previous[index] = position.checked_sub(1).map(|position| tails[position]);
+        if position == tails.len() {
+            tails.push(index);
+        } else {
+            tails[position] = index;
+        }
+    }
+
+    let mut sequence = HashSet::new();
+    let mut current = tails.last().copied();
+    while let Some(index) = current {
+        sequence.insert(values[index]);
+        current = previous[index];
+    }
+    sequence
+}
+
+// Output # -------------------------------------------------- #
+
+fn collect(entries: &[Entry<'_>], operations: &mut Vec<Operation>) {
+    for entry in entries {
+        match entry {
+            Entry::Inserted { node, path } => operations.push(Operation::Insert {
+                path: path.clone(),
+                component: node.to_component(),
+            }),
+            Entry::Deleted { path, .. } => {
+                operations.push(Operation::Delete { path: path.clone() })
+            }
+            Entry::Departed { .. } => {}
+            Entry::Matched(matched) => {
+                if matched.moved {
+                    operations.push(Operation::Move {
+                        from: matched.old_path.clone(),
+                        to: matched.new_path.clone(),
+                    });
+                }
+                if !matched.props.is_empty() {
+                    operations.push(Operation::UpdateProps {
+                        path: matched.new_path.clone(),
+                        changes: matched.props.clone(),
+                    });
+                }
+                if !matched.text.is_empty() {
+                    operations.push(Operation::EditText {
+                        path: matched.new_path.clone(),
+                        edits: matched.text.clone(),
+                    });
+                }
+                for (_, entries) in &matched.slots {
+                    collect(entries, operations);
+                }
+            }
+        }
+    }
+}
+
+fn render_node<W>(node: NodeRef<'_>, sink: &mut W) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    match node {
+        NodeRef::Component(component) => component.render(&html::Options::default(), sink),
+        NodeRef::Inline(inline_component) => {
+            inline_component.render(&html::Options::default(), sink)
+        }
+    }
+}
+
+fn render_entries<W>(entries: &[Entry<'_>], sink: &mut W) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    for entry in entries {
+        match entry {
+            Entry::Inserted { node, .. } => {
+                sink.write_str("<ins class=\"jarkup-diff-insert\">")?;
+                render_node(*node, sink)?;
+                sink.write_str("</ins>")?;
+            }
+            Entry::Deleted { node, .. } => {
+                sink.write_str("<del class=\"jarkup-diff-delete\">")?;
+                render_node(*node, sink)?;
+                sink.write_str("</del>")?;
+            }
+            Entry::Departed { .. } => {}
+            Entry::Matched(matched) => render_match(matched, sink)?,
+        }
+    }
+    Ok(())
+}
+
+fn render_match<W>(matched: &Match<'_>, sink: &mut W) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    let tag = if matched.new.as_inline().is_some() {
+        "span"
+    } else {
+        "div"
+    };
+    let wrapped = matched.moved || !matched.props.is_empty();
+    if wrapped {
+        let mut classes = Vec::new();
+        if matched.moved {
+            classes.push("jarkup-diff-move");
+        }
+        if !matched.props.is_empty() {
+            classes.push("jarkup-diff-update");
+        }
+        write!(sink, "<{} class=\"{}\"", tag, classes.join(" "))?;
+        if matched.moved {
+            sink.write_str(" data-from=\"")?;
+            html::escape(&matched.old_path, sink)?;
+            sink.write_char('"')?;
+        }
+        if !matched.props.is_empty() {
+            let names = matched
+                .props
+                .iter()
+                .map(|change| change.name.as_str())
+                .collect::<Vec<_>>();
+            sink.write_str(" data-props=\"")?;
+            html::escape(&names.join(","), sink)?;
+            sink.write_char('"')?;
+        }
+        sink.write_char('>')?;
+    }
+
+    if let Some(InlineComponent::Text(text)) = matched.new.as_inline()
+        && !matched.text.is_empty()
+    {
+        render_text_edits(text, &matched.text, sink)?;
+    } else if matched.slots_changed() {
+        render_shell(matched, sink)?;
+    } else {
+        render_node(matched.new, sink)?;
+    }
+
+    if wrapped {
+        write!(sink, "</{}>", tag)?;
+    }
+    Ok(())
+}
+
+fn render_text_edits<W>(text: &Text, edits: &[TextEdit], sink: &mut W) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    for edit in edits {
+        let (segment, wrapper) = match edit {
+            TextEdit::Equal(segment) => (segment, None),
+            TextEdit::Insert(segment) => (segment, Some(("ins", "jarkup-diff-insert"))),
+            TextEdit::Delete(segment) => (segment, Some(("del", "jarkup-diff-delete"))),
+        };
+        if let Some((tag, class)) = wrapper {
+            write!(sink, "<{} class=\"{}\">", tag, class)?;
+        }
+        let run = InlineComponent::Text(Text {
+            id: None,
+            props: TextProps {
+                text: segment.clone(),
+                ..text.props.clone()
+            },
+            slots: None,
+        });
+        run.render(&html::Options::default(), sink)?;
+        if let Some((tag, _)) = wrapper {
+            write!(sink, "</{}>", tag)?;
+        }
+    }
+    Ok(())
+}
+
+/// Renders the new node with each slot replaced by its annotated entries.
+///
+/// The node is rendered once with a private-use sentinel character in place
+/// of each slot, and the output is split around the sentinels. The sentinels
+/// are chosen above every code point in the props of the node, so they cannot
+/// clash with its content.
+fn render_shell<W>(matched: &Match<'_>, sink: &mut W) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    let mut shell = matched.new.to_component();
+    let mut names = Vec::new();
+    for (name, slot) in shell.slots_mut() {
+        match slot {
+            SlotMut::Components(children) => children.clear(),
+            SlotMut::Inlines(children) => children.clear(),
+        }
+        names.push(name);
+    }
+
+    let mut content = String::new();
+    collect_content(NodeRef::Component(&shell), &mut content);
+    let mut base = SENTINEL;
+    while let Some(used) = content
+        .chars()
+        .map(u32::from)
+        .filter(|&code| code >= base && code < base + names.len() as u32)
+        .max()
+    {
+        base = used + 1;
+    }
+
+    for (index, (_, slot)) in shell.slots_mut().into_iter().enumerate() {
+        let sentinel = InlineComponent::Text(Text {
+            id: None,
+            props: TextProps {
+                text: sentinel(base, index).to_string(),
+                ..Default::default()
+            },
+            slots: None,
+        });
+        match slot {
+            SlotMut::Components(children) => children.push(Component::InlineComponent(sentinel)),
+            SlotMut::Inlines(children) => children.push(sentinel),
+        }
+    }
+
+    let mut rendered = String::new();
+    shell.render(&html::Options::default(), &mut rendered)?;
+    let mut rest = rendered.as_str();
+    for (index, name) in names.into_iter().enumerate() {
+        let Some((before, after)) = rest.split_once(sentinel(base, index)) else {
+            continue;
+        };
+        sink.write_str(before)?;
+        if let Some((_, entries)) = matched.slots.iter().find(|(slot, _)| *slot == name) {
+            render_entries(entries, sink)?;
+        }
+        rest = after;
+    }
+    sink.write_str(rest)
+}
+
+fn sentinel(base: u32, index: usize) -> char {
+    char::from_u32(base + index as u32).unwrap_or(char::REPLACEMENT_CHARACTER)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{Operation, TextEdit, diff, diff_text, to_html};
+    use crate::Component;
+
+    fn parse(json: &str) -> Vec<Component> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_diff_text() {
+        assert_eq!(
+            diff_text("Hello world", "Hello brave world!"),
+            vec![
+                TextEdit::Equal("Hello ".to_string()),
+                TextEdit::Insert("brave ".to_string()),
+                TextEdit::Equal("world".to_string()),
+                TextEdit::Insert("!".to_string()),
+            ]
+        );
+        assert_eq!(
+            diff_text("same", "same"),
+            vec![TextEdit::Equal("same".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_diff_operations() {
+        let old = parse(
+            r#"[
+                {"type":"Heading","id":"title","props":{"level":1},"slots":{"default":[{"type":"Text","props":{"text":"Title"}}]}},
+                {"type":"Paragraph","slots":{"default":[{"type":"Text","props":{"text":"The quick brown fox"}}]}},
+                {"type":"Callout","slots":{"default":[{"type":"Paragraph","id":"note","slots":{"default":[{"type":"Text","props":{"text":"Note"}}]}}]}},
+                {"type":"Divider"}
+            ]"#,
+        );
+        let new = parse(
+            r#"[
+                {"type":"Paragraph","id":"note","slots":{"default":[{"type":"Text","props":{"text":"Note"}}]}},
+                {"type":"Heading","id":"title","props":{"level":2},"slots":{"default":[{"type":"Text","props":{"text":"Title"}}]}},
+                {"type":"Paragraph","slots":{"default":[{"type":"Text","props":{"text":"The quick red fox"}}]}},
+                {"type":"Callout","slots":{"default":[]}},
+                {"type":"Image","props":{"src":"a.png"}}
+            ]"#,
+        );
+
+        let operations = diff(&old, &new);
+        let summary = operations
+            .iter()
+            .map(|operation| match operation {
+                Operation::Insert { path, .. } => format!("insert {}", path),
+                Operation::Delete { path } => format!("delete {}", path),
+                Operation::Move { from, to } => format!("move {} {}", from, to),
+                Operation::UpdateProps { path, changes } => {
+                    format!("update {} {}", path, changes[0].name)
+                }
+                Operation::EditText { path, .. } => format!("edit {}", path),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                "move /2/slots/default/0 /0",
+                "update /1 level",
+                "edit /2/slots/default/0",
+                "insert /4",
+                "delete /3",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_match_positions_above_cap() {
+        let paragraphs = |prefix: &str| {
+            let paragraphs = (0..2100)
+                .map(|index| {
+                    format!(
+                        r#"{{"type":"Paragraph","slots":{{"default":[{{"type":"Text","props":{{"text":"{} {}"}}}}]}}}}"#,
+                        prefix, index
+                    )
+                })
+                .collect::<Vec<_>>();
+            parse(&format!("[{}]", paragraphs.join(",")))
+        };
+
+        let operations = diff(&paragraphs("old"), &paragraphs("new"));
+        assert_eq!(operations.len(), 2100);
+        assert!(
+            operations
+                .iter()
+                .all(|operation| matches!(operation, Operation::EditText { .. }))
+        );
+    }
+
+    #[test]
+    fn test_to_html() {
+        let old = parse(
+            r#"[{"type":"Paragraph","slots":{"default":[{"type":"Text","props":{"text":"Hello world"}}]}}]"#,
+        );
+        let new = parse(
+            r#"[{"type":"Paragraph","slots":{"default":[{"type":"Text","props":{"text":"Hello there world"}}]}},{"type":"Divider"}]"#,
+        );
+
+        assert_eq!(
+            to_html(&old, &new),
+            "<p class=\"jarkup-paragraph\">Hello <ins class=\"jarkup-diff-insert\">there </ins>world</p>\
+             <ins class=\"jarkup-diff-insert\"><hr class=\"jarkup-divider\"></ins>"
+        );
+    }
+
+    #[test]
+    fn test_to_html_private_use_content() {
+        let table = |text: &str| {
+            parse(&format!(
+                r#"[{{"type":"Table","props":{{"hasColumnHeader":false,"hasRowHeader":false,"caption":"\ue000\ue001"}},"slots":{{"body":[
+                    {{"type":"TableRow","slots":{{"default":[
+                        {{"type":"TableCell","slots":{{"default":[{{"type":"Text","props":{{"text":"{}"}}}}]}}}}
+                    ]}}}}
+                ]}}}}]"#,
+                text
+            ))
+        };
+
+        let html = to_html(&table("a"), &table("a b"));
+        assert_eq!(html.matches("\u{e000}\u{e001}").count(), 1);
+        assert!(html.contains("a<ins class=\"jarkup-diff-insert\"> b</ins>"));
+    }
+}
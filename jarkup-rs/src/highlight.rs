@@ -0,0 +1,557 @@
+//! Server-side syntax highlighting for [`CodeBlock`]s.
+//!
+//! [`highlight`] tokenizes `props.code` according to `props.language` and
+//! fills `slots.default` with colored [`Text`] runs, so renderers only have to
+//! handle `Text`. Languages without a known grammar produce a single uncolored
+//! run. The HTML renderer also colors blocks without runs while rendering,
+//! with the theme in its [`Options`](crate::html::Options).
+
+use crate::{
+    BlockComponent, CodeBlock, CodeBlockSlots, Component, InlineComponent, Text, TextProps,
+    node::SlotMut,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Plain,
+    Keyword,
+    String,
+    Number,
+    Comment,
+    Function,
+    Type,
+}
+
+/// Colors applied to each token kind. `None` leaves the run uncolored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    pub keyword: Option<String>,
+    pub string: Option<String>,
+    pub number: Option<String>,
+    pub comment: Option<String>,
+    pub function: Option<String>,
+    pub r#type: Option<String>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            keyword: Some("#cf222e".to_string()),
+            string: Some("#0a3069".to_string()),
+            number: Some("#0550ae".to_string()),
+            comment: Some("#6e7781".to_string()),
+            function: Some("#8250df".to_string()),
+            r#type: Some("#953800".to_string()),
+        }
+    }
+}
+
+impl Theme {
+    /// A palette for dark backgrounds.
+    pub fn dark() -> Self {
+        Self {
+            keyword: Some("#ff7b72".to_string()),
+            string: Some("#a5d6ff".to_string()),
+            number: Some("#79c0ff".to_string()),
+            comment: Some("#8b949e".to_string()),
+            function: Some("#d2a8ff".to_string()),
+            r#type: Some("#ffa657".to_string()),
+        }
+    }
+
+    pub(crate) fn color(&self, kind: TokenKind) -> Option<&String> {
+        match kind {
+            TokenKind::Plain => None,
+            TokenKind::Keyword => self.keyword.as_ref(),
+            TokenKind::String => self.string.as_ref(),
+            TokenKind::Number => self.number.as_ref(),
+            TokenKind::Comment => self.comment.as_ref(),
+            TokenKind::Function => self.function.as_ref(),
+            TokenKind::Type => self.r#type.as_ref(),
+        }
+    }
+}
+
+struct Syntax {
+    keywords: &'static [&'static str],
+    case_insensitive: bool,
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+    quotes: &'static [char],
+    /// Whether capitalized identifiers are highlighted as types.
+    types: bool,
+}
+
+const C_COMMENTS: &[&str] = &["//"];
+const HASH_COMMENTS: &[&str] = &["#"];
+const C_BLOCK: Option<(&str, &str)> = Some(("/*", "*/"));
+
+const RUST: Syntax = Syntax {
+    keywords: &[
+        "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+        "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+        "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait",
+        "true", "type", "unsafe", "use", "where", "while",
+    ],
+    case_insensitive: false,
+    line_comments: C_COMMENTS,
+    block_comment: C_BLOCK,
+    quotes: &['"'],
+    types: true,
+};
+
+const JAVASCRIPT: Syntax = Syntax {
+    keywords: &[
+        "abstract",
+        "as",
+        "async",
+        "await",
+        "break",
+        "case",
+        "catch",
+        "class",
+        "const",
+        "continue",
+        "default",
+        "delete",
+        "do",
+        "else",
+        "enum",
+        "export",
+        "extends",
+        "false",
+        "finally",
+        "for",
+        "from",
+        "function",
+        "if",
+        "implements",
+        "import",
+        "in",
+        "instanceof",
+        "interface",
+        "let",
+        "new",
+        "null",
+        "of",
+        "private",
+        "protected",
+        "public",
+        "readonly",
+        "return",
+        "static",
+        "super",
+        "switch",
+        "this",
+        "throw",
+        "true",
+        "try",
+        "type",
+        "typeof",
+        "undefined",
+        "var",
+        "void",
+        "while",
+        "yield",
+    ],
+    case_insensitive: false,
+    line_comments: C_COMMENTS,
+    block_comment: C_BLOCK,
+    quotes: &['"', '\'', '`'],
+    types: true,
+};
+
+const PYTHON: Syntax = Syntax {
+    keywords: &[
+        "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del",
+        "elif", "else", "except", "False", "finally", "for", "from", "global", "if", "import",
+        "in", "is", "lambda", "None", "nonlocal", "not", "or", "pass", "raise", "return", "True",
+        "try", "while", "with", "yield",
+    ],
+    case_insensitive: false,
+    line_comments: HASH_COMMENTS,
+    block_comment: None,
+    quotes: &['"', '\''],
+    types: true,
+};
+
+const GO: Syntax = Syntax {
+    keywords: &[
+        "break",
+        "case",
+        "chan",
+        "const",
+        "continue",
+        "default",
+        "defer",
+        "else",
+        "fallthrough",
+        "false",
+        "for",
+        "func",
+        "go",
+        "goto",
+        "if",
+        "import",
+        "interface",
+        "map",
+        "nil",
+        "package",
+        "range",
+        "return",
+        "select",
+        "struct",
+        "switch",
+        "true",
+        "type",
+        "var",
+    ],
+    case_insensitive: false,
+    line_comments: C_COMMENTS,
+    block_comment: C_BLOCK,
+    quotes: &['"', '`'],
+    types: true,
+};
+
+const C_FAMILY: Syntax = Syntax {
+    keywords: &[
+        "auto",
+        "bool",
+        "break",
+        "case",
+        "catch",
+        "char",
+        "class",
+        "const",
+        "continue",
+        "default",
+        "delete",
+        "do",
+        "double",
+        "else",
+        "enum",
+        "extends",
+        "extern",
+        "false",
+        "final",
+        "float",
+        "for",
+        "if",
+        "implements",
+        "import",
+        "int",
+        "interface",
+        "long",
+        "namespace",
+        "new",
+        "null",
+        "nullptr",
+        "package",
+        "private",
+        "protected",
+        "public",
+        "return",
+        "short",
+        "signed",
+        "sizeof",
+        "static",
+        "struct",
+        "switch",
+        "template",
+        "this",
+        "throw",
+        "true",
+        "try",
+        "typedef",
+        "union",
+        "unsigned",
+        "using",
+        "virtual",
+        "void",
+        "volatile",
+        "while",
+    ],
+    case_insensitive: false,
+    line_comments: C_COMMENTS,
+    block_comment: C_BLOCK,
+    quotes: &['"'],
+    types: true,
+};
+
+const SHELL: Syntax = Syntax {
+    keywords: &[
+        "case", "do", "done", "elif", "else", "esac", "export", "fi", "for", "function", "if",
+        "in", "local", "return", "then", "until", "while",
+    ],
+    case_insensitive: false,
+    line_comments: HASH_COMMENTS,
+    block_comment: None,
+    quotes: &['"', '\''],
+    types: false,
+};
+
+const JSON: Syntax = Syntax {
+    keywords: &["false", "null", "true"],
+    case_insensitive: false,
+    line_comments: &[],
+    block_comment: None,
+    quotes: &['"'],
+    types: false,
+};
+
+const TOML: Syntax = Syntax {
+    keywords: &["false", "true"],
+    case_insensitive: false,
+    line_comments: HASH_COMMENTS,
+    block_comment: None,
+    quotes: &['"', '\''],
+    types: false,
+};
+
+const YAML: Syntax = Syntax {
+    keywords: &["false", "no", "null", "true", "yes"],
+    case_insensitive: false,
+    line_comments: HASH_COMMENTS,
+    block_comment: None,
+    quotes: &['"', '\''],
+    types: false,
+};
+
+const SQL: Syntax = Syntax {
+    keywords: &[
+        "and", "as", "by", "create", "delete", "desc", "distinct", "drop", "from", "group",
+        "having", "in", "index", "insert", "into", "is", "join", "left", "limit", "not", "null",
+        "on", "or", "order", "right", "select", "set", "table", "update", "values", "where",
+    ],
+    case_insensitive: true,
+    line_comments: &["--"],
+    block_comment: C_BLOCK,
+    quotes: &['\''],
+    types: false,
+};
+
+const CSS: Syntax = Syntax {
+    keywords: &["important", "inherit", "initial", "none"],
+    case_insensitive: false,
+    line_comments: &[],
+    block_comment: C_BLOCK,
+    quotes: &['"', '\''],
+    types: false,
+};
+
+fn syntax(language: &str) -> Option<&'static Syntax> {
+    let syntax = match language.to_ascii_lowercase().as_str() {
+        "rust" | "rs" => &RUST,
+        "javascript" | "js" | "jsx" | "mjs" | "typescript" | "ts" | "tsx" => &JAVASCRIPT,
+        "python" | "py" => &PYTHON,
+        "go" | "golang" => &GO,
+        "c" | "h" | "cpp" | "c++" | "cc" | "hpp" | "java" | "csharp" | "cs" | "c#" | "kotlin"
+        | "kt" => &C_FAMILY,
+        "shell" | "sh" | "bash" | "zsh" | "console" => &SHELL,
+        "json" | "jsonc" => &JSON,
+        "toml" => &TOML,
+        "yaml" | "yml" => &YAML,
+        "sql" => &SQL,
+        "css" | "scss" => &CSS,
+        _ => return None,
+    };
+    Some(syntax)
+}
+
+/// Splits `code` into tokens. Concatenating the token texts yields `code`.
+pub fn tokenize<'a>(code: &'a str, language: &str) -> Vec<(TokenKind, &'a str)> {
+    let Some(syntax) = syntax(language) else {
+        return vec![(TokenKind::Plain, code)];
+    };
+
+    let mut spans: Vec<(TokenKind, usize, usize)> = Vec::new();
+    let mut push = |kind: TokenKind, start: usize, len: usize| {
+        // Merge adjacent runs of the same kind so the output stays compact.
+        match spans.last_mut() {
+            Some((last_kind, _, end)) if *last_kind == kind => *end += len,
+            _ => spans.push((kind, start, start + len)),
+        }
+    };
+
+    let mut index = 0;
+    while index < code.len() {
+        let rest = &code[index..];
+        let c = rest.chars().next().unwrap_or_default();
+
+        if let Some(prefix) = syntax
+            .line_comments
+            .iter()
+            .find(|prefix| rest.starts_with(**prefix))
+        {
+            let end = rest[prefix.len()..]
+                .find('\n')
+                .map_or(rest.len(), |end| prefix.len() + end);
+            push(TokenKind::Comment, index, end);
+            index += end;
+        } else if let Some((open, close)) = syntax
+            .block_comment
+            .filter(|(open, _)| rest.starts_with(*open))
+        {
+            let end = rest[open.len()..]
+                .find(close)
+                .map_or(rest.len(), |end| open.len() + end + close.len());
+            push(TokenKind::Comment, index, end);
+            index += end;
+        } else if syntax.quotes.contains(&c) {
+            let mut end = rest.len();
+            let mut escaped = false;
+            for (offset, next) in rest.char_indices().skip(1) {
+                if escaped {
+                    escaped = false;
+                } else if next == '\\' {
+                    escaped = true;
+                } else if next == c {
+                    end = offset + next.len_utf8();
+                    break;
+                } else if next == '\n' && c != '`' {
+                    end = offset;
+                    break;
+                }
+            }
+            push(TokenKind::String, index, end);
+            index += end;
+        } else if c.is_ascii_digit() {
+            let end = rest
+                .find(|next: char| !(next.is_ascii_alphanumeric() || next == '.' || next == '_'))
+                .unwrap_or(rest.len());
+            push(TokenKind::Number, index, end);
+            index += end;
+        } else if c.is_alphabetic() || c == '_' {
+            let end = rest
+                .char_indices()
+                .skip(1)
+                .find(|(_, next)| !(next.is_alphanumeric() || *next == '_'))
+                .map_or(rest.len(), |(offset, _)| offset);
+            let word = &rest[..end];
+            let is_keyword = if syntax.case_insensitive {
+                syntax
+                    .keywords
+                    .iter()
+                    .any(|keyword| keyword.eq_ignore_ascii_case(word))
+            } else {
+                syntax.keywords.contains(&word)
+            };
+            let kind = if is_keyword {
+                TokenKind::Keyword
+            } else if rest[end..].trim_start_matches([' ', '\t']).starts_with('(') {
+                TokenKind::Function
+            } else if syntax.types && word.starts_with(|first: char| first.is_uppercase()) {
+                TokenKind::Type
+            } else {
+                TokenKind::Plain
+            };
+            push(kind, index, end);
+            index += end;
+        } else {
+            push(TokenKind::Plain, index, c.len_utf8());
+            index += c.len_utf8();
+        }
+    }
+
+    spans
+        .into_iter()
+        .map(|(kind, start, end)| (kind, &code[start..end]))
+        .collect()
+}
+
+/// Replaces `slots.default` of `code_block` with its code as colored runs.
+pub fn highlight(code_block: &mut CodeBlock, theme: &Theme) {
+    let runs = tokenize(&code_block.props.code, &code_block.props.language)
+        .into_iter()
+        .map(|(kind, text)| {
+            InlineComponent::Text(Text {
+                props: TextProps {
+                    text: text.to_string(),
+                    color: theme.color(kind).cloned(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+        })
+        .collect();
+
+    code_block.slots = Some(CodeBlockSlots { default: runs });
+}
+
+/// Highlights every code block in `components`, including nested ones.
+pub fn highlight_all(components: &mut [Component], theme: &Theme) {
+    for component in components {
+        if let Component::BlockComponent(BlockComponent::CodeBlock(code_block)) = component {
+            highlight(code_block, theme);
+            continue;
+        }
+        for (_, slot) in component.slots_mut() {
+            if let SlotMut::Components(children) = slot {
+                highlight_all(children, theme);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{Theme, TokenKind, highlight_all, tokenize};
+    use crate::{BlockComponent, Component, InlineComponent};
+
+    #[test]
+    fn test_tokenize_rust() {
+        let code = "fn main() {\n    // greet\n    println!(\"hi\", 42);\n}";
+        let tokens = tokenize(code, "rust");
+
+        assert_eq!(
+            tokens.iter().map(|(_, text)| *text).collect::<String>(),
+            code
+        );
+        assert!(tokens.contains(&(TokenKind::Keyword, "fn")));
+        assert!(tokens.contains(&(TokenKind::Function, "main")));
+        assert!(tokens.contains(&(TokenKind::Comment, "// greet")));
+        assert!(tokens.contains(&(TokenKind::String, "\"hi\"")));
+        assert!(tokens.contains(&(TokenKind::Number, "42")));
+    }
+
+    #[test]
+    fn test_unknown_language_is_plain() {
+        assert_eq!(
+            tokenize("a <- b", "brainfuck"),
+            vec![(TokenKind::Plain, "a <- b")]
+        );
+    }
+
+    #[test]
+    fn test_highlight_all() {
+        let json = r#"[{"type":"Callout","slots":{"default":[
+            {"type":"CodeBlock","props":{"code":"let x = 1;","language":"rust"}}
+        ]}}]"#;
+        let mut components = serde_json::from_str::<Vec<Component>>(json).unwrap();
+        highlight_all(&mut components, &Theme::dark());
+
+        let Component::BlockComponent(BlockComponent::Callout(callout)) = &components[0] else {
+            panic!("expected a callout");
+        };
+        let Component::BlockComponent(BlockComponent::CodeBlock(code_block)) =
+            &callout.slots.default[0]
+        else {
+            panic!("expected a code block");
+        };
+        let runs = &code_block.slots.as_ref().unwrap().default;
+        let InlineComponent::Text(first) = &runs[0] else {
+            panic!("expected a text run");
+        };
+        assert_eq!(first.props.text, "let");
+        assert_eq!(first.props.color.as_deref(), Some("#ff7b72"));
+        let code = runs
+            .iter()
+            .map(|run| match run {
+                InlineComponent::Text(text) => text.props.text.as_str(),
+                InlineComponent::Icon(_) => panic!("expected a text run"),
+            })
+            .collect::<String>();
+        assert_eq!(code, "let x = 1;");
+    }
+}
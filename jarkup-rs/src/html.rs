@@ -0,0 +1,871 @@
+//! HTML rendering.
+//!
+//! Output is written straight into a [`fmt::Write`] or [`io::Write`] sink, so a
+//! document is never buffered as a whole. [`chunks`] renders one top-level
+//! component at a time for servers that send the page incrementally.
+//!
+//! The renderer walks the owned model and the [`borrowed`] one alike, so
+//! documents parsed only to be rendered can skip the owned model with
+//! [`render_borrowed`]. [`render_with`] and [`to_string_with`] take
+//! [`Options`], such as the theme code blocks are colored with.
+
+#[cfg(not(feature = "highlight"))]
+use std::marker::PhantomData;
+use std::{fmt, io};
+
+use crate::borrowed;
+#[cfg(feature = "highlight")]
+use crate::highlight::Theme;
+
+/// Rendering options.
+#[derive(Debug, Clone, Copy)]
+pub struct Options<'a> {
+    /// Theme code blocks without highlighted runs are colored with, or `None`
+    /// to leave them plain. Defaults to [`Theme::default`].
+    #[cfg(feature = "highlight")]
+    pub theme: Option<&'a Theme>,
+    #[cfg(not(feature = "highlight"))]
+    marker: PhantomData<&'a ()>,
+}
+
+impl Default for Options<'_> {
+    fn default() -> Self {
+        #[cfg(feature = "highlight")]
+        {
+            static THEME: std::sync::LazyLock<Theme> = std::sync::LazyLock::new(Theme::default);
+            Options {
+                theme: Some(&THEME),
+            }
+        }
+
+        #[cfg(not(feature = "highlight"))]
+        Options {
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Renders `components` into a `fmt::Write` sink such as a `String`.
+pub fn render<W>(components: &[crate::Component], sink: &mut W) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    render_with(components, &Options::default(), sink)
+}
+
+/// Renders `components` with `options` into a `fmt::Write` sink.
+pub fn render_with<W>(
+    components: &[crate::Component],
+    options: &Options<'_>,
+    sink: &mut W,
+) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    children(components, options, sink)
+}
+
+/// Renders borrowed `components` into a `fmt::Write` sink.
+pub fn render_borrowed<W>(components: &[borrowed::Component<'_>], sink: &mut W) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    children(components, &Options::default(), sink)
+}
+
+/// Renders `components` into an `io::Write` sink such as a socket or file.
+pub fn write<W>(components: &[crate::Component], sink: &mut W) -> io::Result<()>
+where
+    W: io::Write,
+{
+    let mut adapter = IoAdapter {
+        inner: sink,
+        error: None,
+    };
+    match render(components, &mut adapter) {
+        Ok(()) => Ok(()),
+        Err(fmt::Error) => Err(adapter
+            .error
+            .unwrap_or_else(|| io::Error::other("formatter error"))),
+    }
+}
+
+/// Renders `components` into a new `String`.
+pub fn to_string(components: &[crate::Component]) -> String {
+    let mut html = String::new();
+    // Writing into a `String` never fails.
+    let _ = render(components, &mut html);
+    html
+}
+
+/// Renders `components` with `options` into a new `String`.
+pub fn to_string_with(components: &[crate::Component], options: &Options<'_>) -> String {
+    let mut html = String::new();
+    let _ = render_with(components, options, &mut html);
+    html
+}
+
+/// Renders borrowed `components` into a new `String`.
+pub fn to_string_borrowed(components: &[borrowed::Component<'_>]) -> String {
+    let mut html = String::new();
+    let _ = render_borrowed(components, &mut html);
+    html
+}
+
+/// Returns an iterator yielding the HTML of each top-level component in turn.
+pub fn chunks(components: &[crate::Component]) -> Chunks<'_> {
+    Chunks {
+        components: components.iter(),
+    }
+}
+
+pub struct Chunks<'a> {
+    components: std::slice::Iter<'a, crate::Component>,
+}
+
+impl Iterator for Chunks<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let component = self.components.next()?;
+        let mut html = String::new();
+        let _ = component.render(&Options::default(), &mut html);
+        Some(html)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.components.size_hint()
+    }
+}
+
+struct IoAdapter<'a, W> {
+    inner: &'a mut W,
+    error: Option<io::Error>,
+}
+
+impl<W> fmt::Write for IoAdapter<'_, W>
+where
+    W: io::Write,
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|error| {
+            self.error = Some(error);
+            fmt::Error
+        })
+    }
+}
+
+/// Writes `text` with `&`, `<`, `>`, `"` and `'` escaped.
+pub(crate) fn escape<W>(text: &str, sink: &mut W) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    let mut last = 0;
+    for (index, byte) in text.bytes().enumerate() {
+        let entity = match byte {
+            b'&' => "&amp;",
+            b'<' => "&lt;",
+            b'>' => "&gt;",
+            b'"' => "&quot;",
+            b'\'' => "&#39;",
+            _ => continue,
+        };
+        sink.write_str(&text[last..index])?;
+        sink.write_str(entity)?;
+        last = index + 1;
+    }
+    sink.write_str(&text[last..])
+}
+
+fn attribute<W>(name: &str, value: &str, sink: &mut W) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    write!(sink, " {}=\"", name)?;
+    escape(value, sink)?;
+    sink.write_char('"')
+}
+
+/// What a URL attribute points at, which decides the schemes it may use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Url {
+    /// A link target: `http`, `https` or `mailto`.
+    Link,
+    /// An image source: `http`, `https` or a `data:image/` URL.
+    Source,
+}
+
+/// Returns whether `url` is a relative reference or uses a scheme allowed for
+/// `kind`, so that `javascript:` and similar URLs in third-party documents
+/// never reach the output.
+fn is_safe_url(url: &str, kind: Url) -> bool {
+    // Browsers skip leading control characters and spaces, and drop tabs and
+    // newlines anywhere in a URL.
+    let url = url
+        .trim_start_matches(|c: char| c <= ' ')
+        .replace(['\t', '\n', '\r'], "")
+        .to_ascii_lowercase();
+    let Some(colon) = url
+        .find([':', '/', '?', '#'])
+        .filter(|end| url[*end..].starts_with(':'))
+    else {
+        return true;
+    };
+    match (&url[..colon], kind) {
+        ("http" | "https", _) | ("mailto", Url::Link) => true,
+        ("data", Url::Source) => url[colon + 1..].starts_with("image/"),
+        _ => false,
+    }
+}
+
+/// Writes a URL attribute, or nothing when `url` is not safe for `kind`.
+fn url_attribute<W>(name: &str, url: &str, kind: Url, sink: &mut W) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    if is_safe_url(url, kind) {
+        attribute(name, url, sink)?;
+    }
+    Ok(())
+}
+
+fn optional_attribute<W>(name: &str, value: Option<&str>, sink: &mut W) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    match value {
+        Some(value) => attribute(name, value, sink),
+        None => Ok(()),
+    }
+}
+
+fn style<W>(color: Option<&str>, background_color: Option<&str>, sink: &mut W) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    if color.is_none() && background_color.is_none() {
+        return Ok(());
+    }
+    sink.write_str(" style=\"")?;
+    if let Some(color) = color {
+        sink.write_str("color:")?;
+        escape(color, sink)?;
+        sink.write_char(';')?;
+    }
+    if let Some(background_color) = background_color {
+        sink.write_str("background-color:")?;
+        escape(background_color, sink)?;
+        sink.write_char(';')?;
+    }
+    sink.write_char('"')
+}
+
+fn open<W>(tag: &str, class: &str, id: Option<&str>, sink: &mut W) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    write!(sink, "<{} class=\"{}\"", tag, class)?;
+    optional_attribute("id", id, sink)
+}
+
+/// A component the renderer can write, from either model.
+pub(crate) trait Render {
+    fn render<W>(&self, options: &Options<'_>, sink: &mut W) -> fmt::Result
+    where
+        W: fmt::Write;
+}
+
+fn children<C, W>(components: &[C], options: &Options<'_>, sink: &mut W) -> fmt::Result
+where
+    C: Render,
+    W: fmt::Write,
+{
+    for component in components {
+        component.render(options, sink)?;
+    }
+    Ok(())
+}
+
+fn render_inline_katex<W>(expression: &str, sink: &mut W) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    #[cfg(feature = "math")]
+    return render_mathml(expression, false, sink);
+
+    #[cfg(not(feature = "math"))]
+    {
+        sink.write_str("<span class=\"jarkup-katex\">")?;
+        escape(expression, sink)?;
+        sink.write_str("</span>")
+    }
+}
+
+/// Writes `expression` as MathML, or as raw TeX in a `<code>` when it cannot
+/// be converted.
+#[cfg(feature = "math")]
+fn render_mathml<W>(expression: &str, display: bool, sink: &mut W) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    match crate::math::parse(expression, display) {
+        Ok(expression) => write!(sink, "{}", expression),
+        Err(error) => {
+            sink.write_str("<code class=\"jarkup-katex-error\"")?;
+            attribute("title", &error.to_string(), sink)?;
+            sink.write_char('>')?;
+            escape(expression, sink)?;
+            sink.write_str("</code>")
+        }
+    }
+}
+
+/// Writes `code`, colored with the theme in `options` when the `highlight`
+/// feature is enabled.
+fn render_code<W>(code: &str, language: &str, options: &Options<'_>, sink: &mut W) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    #[cfg(feature = "highlight")]
+    if let Some(theme) = options.theme {
+        for (kind, text) in crate::highlight::tokenize(code, language) {
+            match theme.color(kind) {
+                Some(color) => {
+                    sink.write_str("<span")?;
+                    style(Some(color), None, sink)?;
+                    sink.write_char('>')?;
+                    escape(text, sink)?;
+                    sink.write_str("</span>")?;
+                }
+                None => escape(text, sink)?,
+            }
+        }
+        return Ok(());
+    }
+
+    #[cfg(not(feature = "highlight"))]
+    let _ = (language, options);
+    escape(code, sink)
+}
+
+/// Implements [`Render`] for the model at `$model`.
+///
+/// The owned and borrowed types share their field names, and their strings
+/// both dereference to `str`, so one body renders either without converting.
+macro_rules! renderer {
+    ($module:ident, $($model:ident)::+ $(, $lifetime:lifetime)?) => {
+        mod $module {
+            use std::fmt;
+
+            #[cfg(feature = "math")]
+            use super::render_mathml;
+            use super::{
+                Options, Url, attribute, children, escape, is_safe_url, open, optional_attribute,
+                render_code, render_inline_katex, style, url_attribute,
+            };
+            use crate::{CalloutType, ListStyle};
+            use $($model)::+::{BlockComponent, Component, InlineComponent, Text};
+
+            pub(super) fn render_component<W>(
+                component: &Component,
+                options: &Options<'_>,
+                sink: &mut W,
+            ) -> fmt::Result
+            where
+                W: fmt::Write,
+            {
+                match component {
+                    Component::InlineComponent(inline_component) => {
+                        render_inline(inline_component, sink)
+                    }
+                    Component::BlockComponent(block_component) => {
+                        render_block(block_component, options, sink)
+                    }
+                }
+            }
+
+            pub(super) fn render_inline<W>(component: &InlineComponent, sink: &mut W) -> fmt::Result
+            where
+                W: fmt::Write,
+            {
+                match component {
+                    InlineComponent::Text(text) => render_text(text, sink),
+                    InlineComponent::Icon(icon) => {
+                        sink.write_str("<img class=\"jarkup-icon\"")?;
+                        url_attribute("src", &icon.props.src, Url::Source, sink)?;
+                        attribute("alt", icon.props.alt.as_deref().unwrap_or(""), sink)?;
+                        sink.write_char('>')
+                    }
+                }
+            }
+
+            fn render_text<W>(text: &Text, sink: &mut W) -> fmt::Result
+            where
+                W: fmt::Write,
+            {
+                let props = &text.props;
+                let flag = |value: Option<bool>| value.unwrap_or(false);
+
+                if let Some(href) = &props.href {
+                    sink.write_str("<a class=\"jarkup-link\"")?;
+                    url_attribute("href", href, Url::Link, sink)?;
+                    sink.write_char('>')?;
+                    if let Some(favicon) = &props.favicon {
+                        sink.write_str("<img class=\"jarkup-favicon\"")?;
+                        url_attribute("src", favicon, Url::Source, sink)?;
+                        sink.write_str(" alt=\"\">")?;
+                    }
+                }
+                if props.ruby.is_some() {
+                    sink.write_str("<ruby>")?;
+                }
+                let styled = props.color.is_some() || props.background_color.is_some();
+                if styled {
+                    sink.write_str("<span")?;
+                    style(
+                        props.color.as_deref(),
+                        props.background_color.as_deref(),
+                        sink,
+                    )?;
+                    sink.write_char('>')?;
+                }
+
+                let marks = [
+                    (flag(props.strikethrough), "s"),
+                    (flag(props.underline), "u"),
+                    (flag(props.italic), "em"),
+                    (flag(props.bold), "strong"),
+                ];
+                for (_, tag) in marks.iter().filter(|(enabled, _)| *enabled) {
+                    write!(sink, "<{}>", tag)?;
+                }
+
+                if flag(props.katex) {
+                    render_inline_katex(&props.text, sink)?;
+                } else if flag(props.code) {
+                    sink.write_str("<code>")?;
+                    escape(&props.text, sink)?;
+                    sink.write_str("</code>")?;
+                } else if flag(props.kbd) {
+                    sink.write_str("<kbd>")?;
+                    escape(&props.text, sink)?;
+                    sink.write_str("</kbd>")?;
+                } else {
+                    escape(&props.text, sink)?;
+                }
+
+                for (_, tag) in marks.iter().rev().filter(|(enabled, _)| *enabled) {
+                    write!(sink, "</{}>", tag)?;
+                }
+
+                if styled {
+                    sink.write_str("</span>")?;
+                }
+                if let Some(ruby) = &props.ruby {
+                    sink.write_str("<rp>(</rp><rt>")?;
+                    escape(ruby, sink)?;
+                    sink.write_str("</rt><rp>)</rp></ruby>")?;
+                }
+                if props.href.is_some() {
+                    sink.write_str("</a>")?;
+                }
+                Ok(())
+            }
+
+            fn render_block<W>(
+                component: &BlockComponent,
+                options: &Options<'_>,
+                sink: &mut W,
+            ) -> fmt::Result
+            where
+                W: fmt::Write,
+            {
+                match component {
+                    BlockComponent::Fragment(fragment) => children(&fragment.slots.default, options, sink),
+                    BlockComponent::Heading(heading) => {
+                        let level = u8::from(heading.props.level.clone());
+                        write!(sink, "<h{} class=\"jarkup-heading\"", level)?;
+                        optional_attribute("id", heading.id.as_deref(), sink)?;
+                        sink.write_char('>')?;
+                        children(&heading.slots.default, options, sink)?;
+                        write!(sink, "</h{}>", level)
+                    }
+                    BlockComponent::Paragraph(paragraph) => {
+                        open("p", "jarkup-paragraph", paragraph.id.as_deref(), sink)?;
+                        if let Some(props) = &paragraph.props {
+                            style(
+                                props.color.as_deref(),
+                                props.background_color.as_deref(),
+                                sink,
+                            )?;
+                        }
+                        sink.write_char('>')?;
+                        children(&paragraph.slots.default, options, sink)?;
+                        sink.write_str("</p>")
+                    }
+                    BlockComponent::ListItem(list_item) => {
+                        open("li", "jarkup-list-item", list_item.id.as_deref(), sink)?;
+                        sink.write_char('>')?;
+                        children(&list_item.slots.default, options, sink)?;
+                        sink.write_str("</li>")
+                    }
+                    BlockComponent::List(list) => {
+                        let tag = match list
+                            .props
+                            .as_ref()
+                            .and_then(|props| props.list_style.as_ref())
+                        {
+                            Some(ListStyle::Ordered) => "ol",
+                            Some(ListStyle::Unordered) | None => "ul",
+                        };
+                        open(tag, "jarkup-list", list.id.as_deref(), sink)?;
+                        sink.write_char('>')?;
+                        children(&list.slots.default, options, sink)?;
+                        write!(sink, "</{}>", tag)
+                    }
+                    BlockComponent::BlockQuote(block_quote) => {
+                        open(
+                            "blockquote",
+                            "jarkup-block-quote",
+                            block_quote.id.as_deref(),
+                            sink,
+                        )?;
+                        let cite = block_quote
+                            .props
+                            .as_ref()
+                            .and_then(|props| props.cite.as_deref());
+                        optional_attribute("cite", cite, sink)?;
+                        sink.write_char('>')?;
+                        children(&block_quote.slots.default, options, sink)?;
+                        sink.write_str("</blockquote>")
+                    }
+                    BlockComponent::Callout(callout) => {
+                        let r#type = match callout
+                            .props
+                            .as_ref()
+                            .and_then(|props| props.r#type.as_ref())
+                        {
+                            Some(CalloutType::Note) | None => "note",
+                            Some(CalloutType::Tip) => "tip",
+                            Some(CalloutType::Important) => "important",
+                            Some(CalloutType::Warning) => "warning",
+                            Some(CalloutType::Caution) => "caution",
+                        };
+                        write!(
+                            sink,
+                            "<aside class=\"jarkup-callout jarkup-callout-{}\"",
+                            r#type
+                        )?;
+                        optional_attribute("id", callout.id.as_deref(), sink)?;
+                        sink.write_char('>')?;
+                        children(&callout.slots.default, options, sink)?;
+                        sink.write_str("</aside>")
+                    }
+                    BlockComponent::Divider(divider) => {
+                        open("hr", "jarkup-divider", divider.id.as_deref(), sink)?;
+                        sink.write_char('>')
+                    }
+                    BlockComponent::Toggle(toggle) => {
+                        open("details", "jarkup-toggle", toggle.id.as_deref(), sink)?;
+                        sink.write_str("><summary>")?;
+                        children(&toggle.slots.summary, options, sink)?;
+                        sink.write_str("</summary>")?;
+                        children(&toggle.slots.default, options, sink)?;
+                        sink.write_str("</details>")
+                    }
+                    BlockComponent::Bookmark(bookmark) => {
+                        let props = &bookmark.props;
+                        open("a", "jarkup-bookmark", bookmark.id.as_deref(), sink)?;
+                        url_attribute("href", &props.url, Url::Link, sink)?;
+                        sink.write_char('>')?;
+                        if let Some(image) = &props.image {
+                            sink.write_str("<img class=\"jarkup-bookmark-image\"")?;
+                            url_attribute("src", image, Url::Source, sink)?;
+                            sink.write_str(" alt=\"\">")?;
+                        }
+                        sink.write_str("<span class=\"jarkup-bookmark-title\">")?;
+                        escape(props.title.as_deref().unwrap_or(&props.url), sink)?;
+                        sink.write_str("</span>")?;
+                        if let Some(description) = &props.description {
+                            sink.write_str("<span class=\"jarkup-bookmark-description\">")?;
+                            escape(description, sink)?;
+                            sink.write_str("</span>")?;
+                        }
+                        sink.write_str("</a>")
+                    }
+                    BlockComponent::File(file) => {
+                        let props = &file.props;
+                        open("a", "jarkup-file", file.id.as_deref(), sink)?;
+                        url_attribute("href", &props.src, Url::Link, sink)?;
+                        sink.write_str(" download>")?;
+                        escape(props.name.as_deref().unwrap_or(&props.src), sink)?;
+                        sink.write_str("</a>")
+                    }
+                    BlockComponent::Image(image) => {
+                        let props = &image.props;
+                        open("img", "jarkup-image", image.id.as_deref(), sink)?;
+                        url_attribute("src", &props.src, Url::Source, sink)?;
+                        attribute("alt", props.alt.as_deref().unwrap_or(""), sink)?;
+                        if let Some(width) = props.width {
+                            write!(sink, " width=\"{}\"", width)?;
+                        }
+                        if let Some(height) = props.height {
+                            write!(sink, " height=\"{}\"", height)?;
+                        }
+                        let srcset = props.srcset.as_deref().filter(|srcset| {
+                            srcset.split(',').all(|candidate| {
+                                let url = candidate.split_whitespace().next().unwrap_or("");
+                                is_safe_url(url, Url::Source)
+                            })
+                        });
+                        optional_attribute("srcset", srcset, sink)?;
+                        optional_attribute("sizes", props.sizes.as_deref(), sink)?;
+                        optional_attribute("type", props.mime_type.as_deref(), sink)?;
+                        sink.write_char('>')
+                    }
+                    BlockComponent::CodeBlock(code_block) => {
+                        open("pre", "jarkup-code-block", code_block.id.as_deref(), sink)?;
+                        sink.write_str("><code class=\"language-")?;
+                        escape(&code_block.props.language, sink)?;
+                        sink.write_str("\">")?;
+                        match &code_block.slots {
+                            Some(slots) if !slots.default.is_empty() => {
+                                children(&slots.default, options, sink)?
+                            }
+                            _ => {
+                                let props = &code_block.props;
+                                render_code(&props.code, &props.language, options, sink)?
+                            }
+                        }
+                        sink.write_str("</code></pre>")
+                    }
+                    BlockComponent::Katex(katex) => {
+                        open("div", "jarkup-katex", katex.id.as_deref(), sink)?;
+                        sink.write_char('>')?;
+                        #[cfg(feature = "math")]
+                        render_mathml(&katex.props.expression, true, sink)?;
+                        #[cfg(not(feature = "math"))]
+                        escape(&katex.props.expression, sink)?;
+                        sink.write_str("</div>")
+                    }
+                    BlockComponent::Mermaid(mermaid) => {
+                        open("pre", "jarkup-mermaid mermaid", mermaid.id.as_deref(), sink)?;
+                        sink.write_char('>')?;
+                        escape(&mermaid.props.code, sink)?;
+                        sink.write_str("</pre>")
+                    }
+                    BlockComponent::Tab(tab) => {
+                        open("div", "jarkup-tab", tab.id.as_deref(), sink)?;
+                        let title = tab.props.as_ref().and_then(|props| props.title.as_deref());
+                        optional_attribute("title", title, sink)?;
+                        sink.write_str("><div class=\"jarkup-tab-label\">")?;
+                        children(&tab.slots.labels, options, sink)?;
+                        sink.write_str("</div><div class=\"jarkup-tab-content\">")?;
+                        children(&tab.slots.contents, options, sink)?;
+                        sink.write_str("</div></div>")
+                    }
+                    BlockComponent::Tabs(tabs) => {
+                        open("div", "jarkup-tabs", tabs.id.as_deref(), sink)?;
+                        let title = tabs.props.as_ref().and_then(|props| props.title.as_deref());
+                        optional_attribute("title", title, sink)?;
+                        sink.write_char('>')?;
+                        children(&tabs.slots.default, options, sink)?;
+                        sink.write_str("</div>")
+                    }
+                    BlockComponent::Table(table) => {
+                        open("table", "jarkup-table", table.id.as_deref(), sink)?;
+                        sink.write_char('>')?;
+                        if let Some(caption) = table
+                            .props
+                            .as_ref()
+                            .and_then(|props| props.caption.as_ref())
+                        {
+                            sink.write_str("<caption>")?;
+                            escape(caption, sink)?;
+                            sink.write_str("</caption>")?;
+                        }
+                        if let Some(header) = &table.slots.header {
+                            sink.write_str("<thead>")?;
+                            children(header, options, sink)?;
+                            sink.write_str("</thead>")?;
+                        }
+                        sink.write_str("<tbody>")?;
+                        children(&table.slots.body, options, sink)?;
+                        sink.write_str("</tbody></table>")
+                    }
+                    BlockComponent::TableRow(table_row) => {
+                        open("tr", "jarkup-table-row", table_row.id.as_deref(), sink)?;
+                        sink.write_char('>')?;
+                        children(&table_row.slots.default, options, sink)?;
+                        sink.write_str("</tr>")
+                    }
+                    BlockComponent::TableCell(table_cell) => {
+                        let is_header = table_cell
+                            .props
+                            .as_ref()
+                            .and_then(|props| props.is_header)
+                            .unwrap_or(false);
+                        let tag = if is_header { "th" } else { "td" };
+                        open(tag, "jarkup-table-cell", table_cell.id.as_deref(), sink)?;
+                        sink.write_char('>')?;
+                        children(&table_cell.slots.default, options, sink)?;
+                        write!(sink, "</{}>", tag)
+                    }
+                    BlockComponent::ColumnList(column_list) => {
+                        open("div", "jarkup-column-list", column_list.id.as_deref(), sink)?;
+                        sink.write_char('>')?;
+                        children(&column_list.slots.default, options, sink)?;
+                        sink.write_str("</div>")
+                    }
+                    BlockComponent::Column(column) => {
+                        open("div", "jarkup-column", column.id.as_deref(), sink)?;
+                        let ratio = column.props.as_ref().and_then(|props| props.width_ratio);
+                        if let Some(ratio) = ratio {
+                            write!(sink, " style=\"flex:{};\"", ratio)?;
+                        }
+                        sink.write_char('>')?;
+                        children(&column.slots.default, options, sink)?;
+                        sink.write_str("</div>")
+                    }
+                    BlockComponent::Unsupported(unsupported) => {
+                        open("div", "jarkup-unsupported", unsupported.id.as_deref(), sink)?;
+                        let details = unsupported.props.as_ref().map(|props| &*props.details);
+                        optional_attribute("data-details", details, sink)?;
+                        sink.write_str("></div>")
+                    }
+                }
+            }
+        }
+
+        impl Render for $($model)::+::Component$(<$lifetime>)? {
+            fn render<W>(&self, options: &Options<'_>, sink: &mut W) -> fmt::Result
+            where
+                W: fmt::Write,
+            {
+                $module::render_component(self, options, sink)
+            }
+        }
+
+        impl Render for $($model)::+::InlineComponent$(<$lifetime>)? {
+            fn render<W>(&self, _options: &Options<'_>, sink: &mut W) -> fmt::Result
+            where
+                W: fmt::Write,
+            {
+                $module::render_inline(self, sink)
+            }
+        }
+    };
+}
+
+renderer!(owned, crate);
+renderer!(borrowed_model, crate::borrowed, '_);
+
+#[cfg(test)]
+mod tests {
+
+    use super::{chunks, to_string, to_string_borrowed, write};
+    use crate::Component;
+
+    fn seed() -> Vec<Component> {
+        let slice = include_bytes!("./jarkup.json");
+        serde_json::from_slice::<Vec<Component>>(slice).unwrap()
+    }
+
+    #[test]
+    fn test_render_paragraph() {
+        let json = r#"[{"type":"Paragraph","slots":{"default":[
+            {"type":"Text","props":{"text":"<a> & b","bold":true,"href":"https://example.com/?a=1&b=2"}}
+        ]}}]"#;
+        let components = serde_json::from_str::<Vec<Component>>(json).unwrap();
+
+        assert_eq!(
+            to_string(&components),
+            "<p class=\"jarkup-paragraph\"><a class=\"jarkup-link\" href=\"https://example.com/?a=1&amp;b=2\"><strong>&lt;a&gt; &amp; b</strong></a></p>"
+        );
+    }
+
+    #[test]
+    fn test_render_unsafe_urls() {
+        let json = r#"[
+            {"type":"Paragraph","slots":{"default":[
+                {"type":"Text","props":{"text":"a","href":"javascript:alert(1)"}},
+                {"type":"Text","props":{"text":"b","href":" JaVa\tScript:alert(1)"}},
+                {"type":"Text","props":{"text":"c","href":"mailto:a@example.com"}},
+                {"type":"Text","props":{"text":"d","href":"/docs?x=a:b"}},
+                {"type":"Icon","props":{"src":"data:text/html,<script>"}}
+            ]}},
+            {"type":"Image","props":{"src":"javascript:alert(1)","srcset":"a.png 1x, vbscript:x 2x"}},
+            {"type":"Image","props":{"src":"data:image/png;base64,AAAA"}},
+            {"type":"File","props":{"src":"mailto:a@example.com"}}
+        ]"#;
+        let html = to_string(&serde_json::from_str::<Vec<Component>>(json).unwrap());
+
+        assert!(!html.to_ascii_lowercase().contains("script:"));
+        assert!(!html.contains("data:text"));
+        assert!(html.contains("<a class=\"jarkup-link\">a</a><a class=\"jarkup-link\">b</a>"));
+        assert!(html.contains("href=\"mailto:a@example.com\">c</a>"));
+        assert!(html.contains("href=\"/docs?x=a:b\">d</a>"));
+        assert!(html.contains("<img class=\"jarkup-image\" alt=\"\">"));
+        assert!(html.contains("src=\"data:image/png;base64,AAAA\""));
+        assert!(html.contains("href=\"mailto:a@example.com\" download>"));
+    }
+
+    #[test]
+    fn test_sinks_agree() {
+        let components = seed();
+        let html = to_string(&components);
+
+        let mut bytes = Vec::new();
+        write(&components, &mut bytes).unwrap();
+        assert_eq!(String::from_utf8(bytes).unwrap(), html);
+
+        assert_eq!(chunks(&components).collect::<String>(), html);
+
+        let json = std::str::from_utf8(include_bytes!("./jarkup.json")).unwrap();
+        let borrowed = serde_json::from_str::<Vec<crate::borrowed::Component>>(json).unwrap();
+        assert_eq!(to_string_borrowed(&borrowed), html);
+    }
+
+    #[cfg(feature = "highlight")]
+    #[test]
+    fn test_render_highlighted() {
+        use super::{Options, to_string_with};
+
+        let json = r#"[{"type":"CodeBlock","props":{"code":"let x = 1;","language":"rust"}}]"#;
+        let components = serde_json::from_str::<Vec<Component>>(json).unwrap();
+
+        let html = to_string(&components);
+        assert!(html.contains("<code class=\"language-rust\"><span style=\"color:#cf222e"));
+
+        let html = to_string_with(&components, &Options { theme: None });
+        assert!(html.contains("<code class=\"language-rust\">let x = 1;</code>"));
+
+        let dark = crate::highlight::Theme::dark();
+        let html = to_string_with(&components, &Options { theme: Some(&dark) });
+        assert!(html.contains("<code class=\"language-rust\"><span style=\"color:#ff7b72"));
+
+        let mut components = components;
+        crate::highlight::highlight_all(&mut components, &dark);
+        assert_eq!(to_string_with(&components, &Options { theme: None }), html);
+    }
+
+    #[cfg(feature = "math")]
+    #[test]
+    fn test_render_math() {
+        let json = r#"[
+            {"type":"Katex","props":{"expression":"x^2"}},
+            {"type":"Paragraph","slots":{"default":[
+                {"type":"Text","props":{"text":"\\begin{matrix}","katex":true}}
+            ]}}
+        ]"#;
+        let html = to_string(&serde_json::from_str::<Vec<Component>>(json).unwrap());
+
+        assert!(
+            html.contains("<math xmlns=\"http://www.w3.org/1998/Math/MathML\" display=\"block\">")
+        );
+        assert!(html.contains("<msup><mi>x</mi><mn>2</mn></msup>"));
+        assert!(html.contains(
+            "<code class=\"jarkup-katex-error\" title=\"unsupported macro: \\begin\">\\begin{matrix}</code>"
+        ));
+    }
+}
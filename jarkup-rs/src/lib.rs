@@ -1,25 +1,51 @@
+pub mod arena;
+pub mod binary;
+pub mod borrowed;
+pub mod builder;
+pub mod compact;
+pub mod diff;
+#[cfg(feature = "highlight")]
+pub mod highlight;
+pub mod html;
+pub mod limits;
 mod r#macro;
+pub mod markdown;
+#[cfg(feature = "math")]
+pub mod math;
+pub mod merge;
+pub mod mermaid;
+pub mod node;
+pub mod parse;
+pub mod patch;
+pub mod path;
+pub mod plain;
+pub mod select;
+pub mod site;
 mod skip_fn;
+pub mod stream;
+pub mod syntax;
+pub mod toc;
+pub mod validate;
 
 use std::ops::{Add, AddAssign};
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 #[serde(untagged)]
 pub enum Component {
     InlineComponent(InlineComponent),
     BlockComponent(BlockComponent),
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 #[serde(tag = "type")]
 pub enum InlineComponent {
     Text(Text),
     Icon(Icon),
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 #[serde(tag = "type")]
 pub enum BlockComponent {
     Fragment(Fragment),
@@ -47,7 +73,7 @@ pub enum BlockComponent {
     Unsupported(Unsupported),
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 pub struct Assets {
     pub icons: Vec<String>,
     pub files: Vec<String>,
@@ -79,12 +105,11 @@ impl Component {
 
     fn extract_assets_recursive(self, mut assets: Assets) -> Assets {
         match self {
-            Component::InlineComponent(inline_component) => match inline_component {
-                InlineComponent::Icon(icon) => {
+            Component::InlineComponent(inline_component) => {
+                if let InlineComponent::Icon(icon) = inline_component {
                     assets.icons.push(icon.props.src);
                 }
-                _ => {}
-            },
+            }
             Component::BlockComponent(block_component) => match block_component {
                 BlockComponent::File(file) => {
                     assets.files.push(file.props.src);
@@ -218,7 +243,7 @@ mod tests {
 }
 
 // Text # -------------------------------------------------- #
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Text {
     pub id: Option<String>,
@@ -229,7 +254,7 @@ pub struct Text {
     pub slots: Option<TextSlots>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct TextProps {
     pub text: String,
@@ -271,14 +296,14 @@ pub struct TextProps {
     pub favicon: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct TextSlots;
 
 crate::to_inline_component!(Text);
 
 // Icon # -------------------------------------------------- #
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Icon {
     pub id: Option<String>,
@@ -290,7 +315,7 @@ pub struct Icon {
     pub slots: Option<IconSlots>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct IconProps {
     pub src: String,
@@ -299,14 +324,14 @@ pub struct IconProps {
     pub alt: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct IconSlots;
 
 crate::to_inline_component!(Icon);
 
 // Fragment # -------------------------------------------------- #
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Fragment {
     pub id: Option<String>,
@@ -317,18 +342,18 @@ pub struct Fragment {
     pub slots: FragmentSlots,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct FragmentProps;
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct FragmentSlots {
     pub default: Vec<Component>,
 }
 
 // Heading # -------------------------------------------------- #
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Heading {
     pub id: Option<String>,
@@ -339,7 +364,7 @@ pub struct Heading {
     pub slots: HeadingSlots,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 #[serde(try_from = "u8", into = "u8")]
 pub enum HeadingLevel {
@@ -381,13 +406,13 @@ impl TryFrom<u8> for HeadingLevel {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct HeadingProps {
     pub level: HeadingLevel,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct HeadingSlots {
     pub default: Vec<InlineComponent>,
@@ -396,7 +421,7 @@ pub struct HeadingSlots {
 crate::to_block_component!(Heading);
 
 // Paragraph # -------------------------------------------------- #
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Paragraph {
     pub id: Option<String>,
@@ -407,7 +432,7 @@ pub struct Paragraph {
     pub slots: ParagraphSlots,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ParagraphProps {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -417,7 +442,7 @@ pub struct ParagraphProps {
     pub background_color: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ParagraphSlots {
     pub default: Vec<InlineComponent>,
@@ -426,7 +451,7 @@ pub struct ParagraphSlots {
 crate::to_block_component!(Paragraph);
 
 // ListItem # -------------------------------------------------- #
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ListItem {
     pub id: Option<String>,
@@ -437,11 +462,11 @@ pub struct ListItem {
     pub slots: ListItemSlots,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ListItemProps;
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ListItemSlots {
     pub default: Vec<Component>,
@@ -450,7 +475,7 @@ pub struct ListItemSlots {
 crate::to_block_component!(ListItem);
 
 // List # -------------------------------------------------- #
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct List {
     pub id: Option<String>,
@@ -460,14 +485,14 @@ pub struct List {
     pub slots: ListSlots,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ListProps {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub list_style: Option<ListStyle>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub enum ListStyle {
     #[default]
@@ -475,7 +500,7 @@ pub enum ListStyle {
     Ordered,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ListSlots {
     pub default: Vec<Component>,
@@ -484,7 +509,7 @@ pub struct ListSlots {
 crate::to_block_component!(List);
 
 // BlockQuote # -------------------------------------------------- #
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct BlockQuote {
     pub id: Option<String>,
@@ -494,14 +519,14 @@ pub struct BlockQuote {
     pub slots: BlockQuoteSlots,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct BlockQuoteProps {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cite: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct BlockQuoteSlots {
     pub default: Vec<Component>,
@@ -510,7 +535,7 @@ pub struct BlockQuoteSlots {
 crate::to_block_component!(BlockQuote);
 
 // Callout # -------------------------------------------------- #
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Callout {
     pub id: Option<String>,
@@ -520,7 +545,7 @@ pub struct Callout {
     pub slots: CalloutSlots,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub enum CalloutType {
     #[default]
@@ -531,14 +556,14 @@ pub enum CalloutType {
     Caution,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct CalloutProps {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub r#type: Option<CalloutType>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct CalloutSlots {
     pub default: Vec<Component>,
@@ -547,7 +572,7 @@ pub struct CalloutSlots {
 crate::to_block_component!(Callout);
 
 // Divider # -------------------------------------------------- #
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Divider {
     pub id: Option<String>,
@@ -558,18 +583,18 @@ pub struct Divider {
     pub slots: Option<DividerSlots>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct DividerProps;
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct DividerSlots;
 
 crate::to_block_component!(Divider);
 
 // Toggle # -------------------------------------------------- #
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Toggle {
     pub id: Option<String>,
@@ -579,11 +604,11 @@ pub struct Toggle {
     pub slots: ToggleSlots,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ToggleProps;
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ToggleSlots {
     pub default: Vec<Component>,
@@ -593,7 +618,7 @@ pub struct ToggleSlots {
 crate::to_block_component!(Toggle);
 
 // Bookmark # -------------------------------------------------- #
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Bookmark {
     pub id: Option<String>,
@@ -603,7 +628,7 @@ pub struct Bookmark {
     pub slots: Option<BookmarkSlots>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct BookmarkProps {
     pub url: String,
@@ -615,14 +640,14 @@ pub struct BookmarkProps {
     pub image: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct BookmarkSlots;
 
 crate::to_block_component!(Bookmark);
 
 // File # -------------------------------------------------- #
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct File {
     pub id: Option<String>,
@@ -632,7 +657,7 @@ pub struct File {
     pub slots: Option<FileSlots>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct FileProps {
     pub src: String,
@@ -640,14 +665,14 @@ pub struct FileProps {
     pub name: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct FileSlots;
 
 crate::to_block_component!(File);
 
 // Image # -------------------------------------------------- #
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Image {
     pub id: Option<String>,
@@ -657,7 +682,7 @@ pub struct Image {
     pub slots: Option<ImageSlots>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ImageProps {
     pub src: String,
@@ -675,14 +700,14 @@ pub struct ImageProps {
     pub mime_type: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ImageSlots;
 
 crate::to_block_component!(Image);
 
 // CodeBlock # -------------------------------------------------- #
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct CodeBlock {
     pub id: Option<String>,
@@ -691,14 +716,14 @@ pub struct CodeBlock {
     pub slots: Option<CodeBlockSlots>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct CodeBlockProps {
     pub code: String,
     pub language: String,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct CodeBlockSlots {
     pub default: Vec<InlineComponent>,
@@ -707,7 +732,7 @@ pub struct CodeBlockSlots {
 crate::to_block_component!(CodeBlock);
 
 // Katex # -------------------------------------------------- #
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Katex {
     pub id: Option<String>,
@@ -717,20 +742,20 @@ pub struct Katex {
     pub slots: Option<KatexSlots>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct KatexProps {
     pub expression: String,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct KatexSlots;
 
 crate::to_block_component!(Katex);
 
 // Mermaid # -------------------------------------------------- #
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Mermaid {
     pub id: Option<String>,
@@ -740,20 +765,20 @@ pub struct Mermaid {
     pub slots: Option<MermaidSlots>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct MermaidProps {
     pub code: String,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct MermaidSlots;
 
 crate::to_block_component!(Mermaid);
 
 // Tab # -------------------------------------------------- #
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Tab {
     pub id: Option<String>,
@@ -763,13 +788,13 @@ pub struct Tab {
     pub slots: TabSlots,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct TabProps {
     pub title: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct TabSlots {
     pub labels: Vec<InlineComponent>,
@@ -779,7 +804,7 @@ pub struct TabSlots {
 crate::to_block_component!(Tab);
 
 // Tabs # -------------------------------------------------- #
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Tabs {
     pub id: Option<String>,
@@ -787,13 +812,13 @@ pub struct Tabs {
     pub slots: TabsSlots,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct TabsProps {
     pub title: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct TabsSlots {
     pub default: Vec<Component>,
@@ -802,7 +827,7 @@ pub struct TabsSlots {
 crate::to_block_component!(Tabs);
 
 // Table # -------------------------------------------------- #
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Table {
     pub id: Option<String>,
@@ -812,7 +837,7 @@ pub struct Table {
     pub slots: TableSlots,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct TableProps {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -823,7 +848,7 @@ pub struct TableProps {
     pub caption: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct TableSlots {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -834,7 +859,7 @@ pub struct TableSlots {
 crate::to_block_component!(Table);
 
 // TableRow # -------------------------------------------------- #
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct TableRow {
     pub id: Option<String>,
@@ -844,11 +869,11 @@ pub struct TableRow {
     pub slots: TableRowSlots,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct TableRowProps;
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct TableRowSlots {
     pub default: Vec<Component>,
@@ -857,7 +882,7 @@ pub struct TableRowSlots {
 crate::to_block_component!(TableRow);
 
 // TableCell # -------------------------------------------------- #
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct TableCell {
     pub id: Option<String>,
@@ -867,14 +892,14 @@ pub struct TableCell {
     pub slots: TableCellSlots,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct TableCellProps {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub is_header: Option<bool>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct TableCellSlots {
     pub default: Vec<InlineComponent>,
@@ -883,7 +908,7 @@ pub struct TableCellSlots {
 crate::to_block_component!(TableCell);
 
 // ColumnList # -------------------------------------------------- #
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ColumnList {
     pub id: Option<String>,
@@ -893,11 +918,11 @@ pub struct ColumnList {
     pub slots: ColumnListSlots,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ColumnListProps {}
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ColumnListSlots {
     pub default: Vec<Component>,
@@ -906,7 +931,7 @@ pub struct ColumnListSlots {
 crate::to_block_component!(ColumnList);
 
 // Column # -------------------------------------------------- #
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Column {
     pub id: Option<String>,
@@ -916,13 +941,13 @@ pub struct Column {
     pub slots: ColumnSlots,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ColumnProps {
     pub width_ratio: Option<f32>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ColumnSlots {
     pub default: Vec<Component>,
@@ -931,7 +956,7 @@ pub struct ColumnSlots {
 crate::to_block_component!(Column);
 
 // Unsupported # -------------------------------------------------- #
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Unsupported {
     pub id: Option<String>,
@@ -941,13 +966,13 @@ pub struct Unsupported {
     pub slots: Option<UnsupportedSlots>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct UnsupportedProps {
     pub details: String,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct UnsupportedSlots {}
 
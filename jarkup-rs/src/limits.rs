@@ -0,0 +1,249 @@
+//! Guarded loading of untrusted jarkup JSON.
+//!
+//! The input is scanned once without building any values, and rejected as soon
+//! as it exceeds one of the configured [`Limits`]. Only documents that pass the
+//! scan are handed to `serde_json` for deserialization.
+
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// Maximum nesting depth of components. Top-level components have depth 1.
+    pub max_depth: usize,
+
+    /// Maximum number of components in the whole document, inline ones included.
+    pub max_nodes: usize,
+
+    /// Maximum length in bytes of any JSON string, measured in its escaped form.
+    pub max_string_length: usize,
+
+    /// Maximum size in bytes of the input.
+    pub max_document_size: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_depth: 32,
+            max_nodes: 100_000,
+            max_string_length: 1024 * 1024,
+            max_document_size: 16 * 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum LimitError {
+    DocumentTooLarge { size: usize, limit: usize },
+    TooDeep { offset: usize, limit: usize },
+    TooManyNodes { offset: usize, limit: usize },
+    StringTooLong { offset: usize, limit: usize },
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for LimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LimitError::DocumentTooLarge { size, limit } => write!(
+                f,
+                "document is {} bytes, exceeding the limit of {} bytes",
+                size, limit
+            ),
+            LimitError::TooDeep { offset, limit } => write!(
+                f,
+                "components nested deeper than {} levels at byte {}",
+                limit, offset
+            ),
+            LimitError::TooManyNodes { offset, limit } => write!(
+                f,
+                "document contains more than {} components (at byte {})",
+                limit, offset
+            ),
+            LimitError::StringTooLong { offset, limit } => write!(
+                f,
+                "string starting at byte {} is longer than {} bytes",
+                offset, limit
+            ),
+            LimitError::Json(error) => write!(f, "invalid document: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for LimitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LimitError::Json(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for LimitError {
+    fn from(error: serde_json::Error) -> Self {
+        LimitError::Json(error)
+    }
+}
+
+enum Frame {
+    Array,
+    Object,
+    /// An object that is a top-level value or an array element, i.e. a component.
+    Node,
+}
+
+impl Limits {
+    /// Scans `slice` and reports the first limit it exceeds.
+    ///
+    /// Syntax errors are not reported here; they surface when the document is
+    /// deserialized.
+    pub fn check(&self, slice: &[u8]) -> Result<(), LimitError> {
+        if slice.len() > self.max_document_size {
+            return Err(LimitError::DocumentTooLarge {
+                size: slice.len(),
+                limit: self.max_document_size,
+            });
+        }
+
+        let mut stack: Vec<Frame> = Vec::new();
+        let mut depth = 0;
+        let mut nodes = 0;
+        let mut string_start: Option<usize> = None;
+        let mut escaped = false;
+
+        for (offset, byte) in slice.iter().enumerate() {
+            if let Some(start) = string_start {
+                if offset - start > self.max_string_length + 1 {
+                    return Err(LimitError::StringTooLong {
+                        offset: start,
+                        limit: self.max_string_length,
+                    });
+                }
+                if escaped {
+                    escaped = false;
+                } else if *byte == b'\\' {
+                    escaped = true;
+                } else if *byte == b'"' {
+                    string_start = None;
+                }
+                continue;
+            }
+
+            match byte {
+                b'"' => string_start = Some(offset),
+                b'[' => stack.push(Frame::Array),
+                b'{' => {
+                    if matches!(stack.last(), None | Some(Frame::Array)) {
+                        nodes += 1;
+                        depth += 1;
+                        if nodes > self.max_nodes {
+                            return Err(LimitError::TooManyNodes {
+                                offset,
+                                limit: self.max_nodes,
+                            });
+                        }
+                        if depth > self.max_depth {
+                            return Err(LimitError::TooDeep {
+                                offset,
+                                limit: self.max_depth,
+                            });
+                        }
+                        stack.push(Frame::Node);
+                    } else {
+                        stack.push(Frame::Object);
+                    }
+                }
+                b']' | b'}' => {
+                    if let Some(Frame::Node) = stack.pop() {
+                        depth -= 1;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Deserializes `slice` after checking it against `limits`.
+pub fn from_slice<T>(slice: &[u8], limits: &Limits) -> Result<T, LimitError>
+where
+    T: DeserializeOwned,
+{
+    limits.check(slice)?;
+    Ok(serde_json::from_slice(slice)?)
+}
+
+/// Deserializes `s` after checking it against `limits`.
+pub fn from_str<T>(s: &str, limits: &Limits) -> Result<T, LimitError>
+where
+    T: DeserializeOwned,
+{
+    from_slice(s.as_bytes(), limits)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{LimitError, Limits, from_slice, from_str};
+    use crate::Component;
+
+    fn nested_list_items(depth: usize) -> String {
+        let mut json = String::new();
+        for _ in 0..depth {
+            json.push_str(r#"{"type":"ListItem","id":null,"slots":{"default":["#);
+        }
+        for _ in 0..depth {
+            json.push_str("]}}");
+        }
+        format!("[{}]", json)
+    }
+
+    #[test]
+    fn test_accepts_seed() {
+        let slice = include_bytes!("./jarkup.json");
+        from_slice::<Vec<Component>>(slice, &Limits::default()).unwrap();
+    }
+
+    #[test]
+    fn test_rejects_deep_nesting() {
+        let limits = Limits {
+            max_depth: 4,
+            ..Default::default()
+        };
+
+        from_str::<Vec<Component>>(&nested_list_items(4), &limits).unwrap();
+
+        let error = from_str::<Vec<Component>>(&nested_list_items(5), &limits).unwrap_err();
+        assert!(matches!(error, LimitError::TooDeep { limit: 4, .. }));
+    }
+
+    #[test]
+    fn test_rejects_too_many_nodes_and_long_strings() {
+        let limits = Limits {
+            max_nodes: 2,
+            max_string_length: 8,
+            ..Default::default()
+        };
+
+        let nodes = r#"[{"type":"Divider"},{"type":"Divider"},{"type":"Divider"}]"#;
+        let error = from_str::<Vec<Component>>(nodes, &limits).unwrap_err();
+        assert!(matches!(error, LimitError::TooManyNodes { limit: 2, .. }));
+
+        let string = r#"[{"type":"Katex","props":{"expression":"\"escaped\" and long"}}]"#;
+        let error = from_str::<Vec<Component>>(string, &limits).unwrap_err();
+        assert!(matches!(error, LimitError::StringTooLong { limit: 8, .. }));
+
+        let size = Limits {
+            max_document_size: 4,
+            ..Default::default()
+        };
+        let error = from_str::<Vec<Component>>(nodes, &size).unwrap_err();
+        assert!(matches!(
+            error,
+            LimitError::DocumentTooLarge { limit: 4, .. }
+        ));
+    }
+}
@@ -31,3 +31,272 @@ macro_rules! to_block_component {
         }
     };
 }
+
+/// Builds a `Vec<Component>` from a compact DSL.
+///
+/// ```
+/// use jarkup_rs::jarkup;
+///
+/// let document = jarkup![
+///     h1["Title"],
+///     p["Hello ", b["world"]],
+///     callout(warning)[p["Careful"]],
+///     ul["One", li[p["Two"]]],
+/// ];
+/// assert_eq!(document.len(), 4);
+/// ```
+///
+/// Block nodes are `h1`–`h6`, `p`, `ul`/`ol` of `li`, `quote`, `callout(kind)`,
+/// `toggle[summary][body]`, `tabs` of `tab[label][body]`, `columns` of
+/// `column`, `fragment`, `table` or `table(header)` of `[cells]` rows,
+/// `code(language)[source]`, `katex[..]`, `mermaid[..]`, `img(src)`,
+/// `bookmark(url)` and `hr`. Inline nodes are string literals, `b`, `i`, `u`,
+/// `s`, `code`, `kbd`, `math`, `a(href)` and `icon(src)`. `{ expr }` inserts
+/// any value convertible to the expected kind.
+///
+/// Placing a block where only inline nodes are allowed fails to compile:
+///
+/// ```compile_fail
+/// let document = jarkup_rs::jarkup![p[h1["Title"]]];
+/// ```
+#[macro_export]
+macro_rules! jarkup {
+    ($($body:tt)*) => {
+        $crate::__jarkup_blocks!($($body)*)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __jarkup_blocks {
+    (@push $blocks:ident;) => {};
+    (@push $blocks:ident; , $($rest:tt)*) => {
+        $crate::__jarkup_blocks!(@push $blocks; $($rest)*);
+    };
+    (@push $blocks:ident; $text:literal $($rest:tt)*) => {
+        $blocks.push($crate::Component::from($crate::Text::new($text)));
+        $crate::__jarkup_blocks!(@push $blocks; $($rest)*);
+    };
+    (@push $blocks:ident; { $value:expr } $($rest:tt)*) => {
+        $blocks.push(::core::convert::Into::<$crate::Component>::into($value));
+        $crate::__jarkup_blocks!(@push $blocks; $($rest)*);
+    };
+    (@push $blocks:ident; hr $($rest:tt)*) => {
+        $blocks.push($crate::Component::from($crate::Divider::new()));
+        $crate::__jarkup_blocks!(@push $blocks; $($rest)*);
+    };
+    (@push $blocks:ident; toggle [$($summary:tt)*] [$($inner:tt)*] $($rest:tt)*) => {
+        $blocks.push($crate::Component::from($crate::Toggle::new(
+            $crate::__jarkup_inlines!($($summary)*),
+            $crate::__jarkup_blocks!($($inner)*),
+        )));
+        $crate::__jarkup_blocks!(@push $blocks; $($rest)*);
+    };
+    (@push $blocks:ident; $level:ident [$($inner:tt)*] $($rest:tt)*) => {
+        $blocks.push($crate::__jarkup_block!($level [$($inner)*]));
+        $crate::__jarkup_blocks!(@push $blocks; $($rest)*);
+    };
+    (@push $blocks:ident; $name:ident ($($args:tt)*) [$($inner:tt)*] $($rest:tt)*) => {
+        $blocks.push($crate::__jarkup_block!($name ($($args)*) [$($inner)*]));
+        $crate::__jarkup_blocks!(@push $blocks; $($rest)*);
+    };
+    (@push $blocks:ident; $name:ident ($($args:tt)*) $($rest:tt)*) => {
+        $blocks.push($crate::__jarkup_block!($name ($($args)*)));
+        $crate::__jarkup_blocks!(@push $blocks; $($rest)*);
+    };
+    ($($body:tt)*) => {{
+        #[allow(unused_mut)]
+        let mut blocks = ::std::vec::Vec::<$crate::Component>::new();
+        $crate::__jarkup_blocks!(@push blocks; $($body)*);
+        blocks
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __jarkup_block {
+    (h1 [$($inner:tt)*]) => { $crate::__jarkup_block!(@heading h1 $($inner)*) };
+    (h2 [$($inner:tt)*]) => { $crate::__jarkup_block!(@heading h2 $($inner)*) };
+    (h3 [$($inner:tt)*]) => { $crate::__jarkup_block!(@heading h3 $($inner)*) };
+    (h4 [$($inner:tt)*]) => { $crate::__jarkup_block!(@heading h4 $($inner)*) };
+    (h5 [$($inner:tt)*]) => { $crate::__jarkup_block!(@heading h5 $($inner)*) };
+    (h6 [$($inner:tt)*]) => { $crate::__jarkup_block!(@heading h6 $($inner)*) };
+    (@heading $level:ident $($inner:tt)*) => {
+        $crate::Component::from($crate::Heading::$level($crate::__jarkup_inlines!($($inner)*)))
+    };
+    (p [$($inner:tt)*]) => {
+        $crate::Component::from($crate::Paragraph::new($crate::__jarkup_inlines!($($inner)*)))
+    };
+    (ul [$($inner:tt)*]) => {
+        $crate::Component::from($crate::List::unordered($crate::__jarkup_items!($($inner)*)))
+    };
+    (ol [$($inner:tt)*]) => {
+        $crate::Component::from($crate::List::ordered($crate::__jarkup_items!($($inner)*)))
+    };
+    (quote [$($inner:tt)*]) => {
+        $crate::Component::from($crate::BlockQuote::new($crate::__jarkup_blocks!($($inner)*)))
+    };
+    (fragment [$($inner:tt)*]) => {
+        $crate::Component::from($crate::Fragment::new($crate::__jarkup_blocks!($($inner)*)))
+    };
+    (tabs [$($inner:tt)*]) => {
+        $crate::Component::from($crate::Tabs::new($crate::__jarkup_tabs!($($inner)*)))
+    };
+    (columns [$($inner:tt)*]) => {
+        $crate::Component::from($crate::ColumnList::new($crate::__jarkup_columns!($($inner)*)))
+    };
+    (table [$([$($cell:expr),* $(,)?]),* $(,)?]) => {
+        $crate::Component::from($crate::Table::from_rows(::std::vec![
+            $(::std::vec![$(::core::convert::Into::<$crate::TableCell>::into($cell)),*]),*
+        ]))
+    };
+    (table (header) [$($inner:tt)*]) => {
+        match $crate::__jarkup_block!(table [$($inner)*]) {
+            $crate::Component::BlockComponent($crate::BlockComponent::Table(table)) => {
+                $crate::Component::from(table.with_header())
+            }
+            _ => unreachable!(),
+        }
+    };
+    (katex [$expression:expr]) => {
+        $crate::Component::from($crate::Katex::new($expression))
+    };
+    (mermaid [$code:expr]) => {
+        $crate::Component::from($crate::Mermaid::new($code))
+    };
+    (callout ($kind:ident) [$($inner:tt)*]) => {
+        $crate::Component::from($crate::Callout::new(
+            $crate::__jarkup_callout_type!($kind),
+            $crate::__jarkup_blocks!($($inner)*),
+        ))
+    };
+    (code ($language:ident) [$code:expr]) => {
+        $crate::Component::from($crate::CodeBlock::new($code, stringify!($language)))
+    };
+    (code ($language:literal) [$code:expr]) => {
+        $crate::Component::from($crate::CodeBlock::new($code, $language))
+    };
+    (img ($src:expr $(, $alt:expr)?)) => {
+        $crate::Component::from($crate::Image::new($src)$(.alt($alt))?)
+    };
+    (bookmark ($url:expr)) => {
+        $crate::Component::from($crate::Bookmark::new($url))
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __jarkup_callout_type {
+    (note) => {
+        $crate::CalloutType::Note
+    };
+    (tip) => {
+        $crate::CalloutType::Tip
+    };
+    (important) => {
+        $crate::CalloutType::Important
+    };
+    (warning) => {
+        $crate::CalloutType::Warning
+    };
+    (caution) => {
+        $crate::CalloutType::Caution
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __jarkup_items {
+    (@push $items:ident;) => {};
+    (@push $items:ident; , $($rest:tt)*) => {
+        $crate::__jarkup_items!(@push $items; $($rest)*);
+    };
+    (@push $items:ident; $text:literal $($rest:tt)*) => {
+        $items.push($crate::ListItem::from($text));
+        $crate::__jarkup_items!(@push $items; $($rest)*);
+    };
+    (@push $items:ident; { $value:expr } $($rest:tt)*) => {
+        $items.push(::core::convert::Into::<$crate::ListItem>::into($value));
+        $crate::__jarkup_items!(@push $items; $($rest)*);
+    };
+    (@push $items:ident; li [$($inner:tt)*] $($rest:tt)*) => {
+        $items.push($crate::ListItem::new($crate::__jarkup_blocks!($($inner)*)));
+        $crate::__jarkup_items!(@push $items; $($rest)*);
+    };
+    ($($body:tt)*) => {{
+        #[allow(unused_mut)]
+        let mut items = ::std::vec::Vec::<$crate::ListItem>::new();
+        $crate::__jarkup_items!(@push items; $($body)*);
+        items
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __jarkup_tabs {
+    ($(tab [$($label:tt)*] [$($inner:tt)*]),* $(,)?) => {
+        ::std::vec![$($crate::Tab::new(
+            $crate::__jarkup_inlines!($($label)*),
+            $crate::__jarkup_blocks!($($inner)*),
+        )),*]
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __jarkup_columns {
+    ($(column [$($inner:tt)*]),* $(,)?) => {
+        ::std::vec![$($crate::Column::new($crate::__jarkup_blocks!($($inner)*))),*]
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __jarkup_inlines {
+    (@push $inlines:ident;) => {};
+    (@push $inlines:ident; , $($rest:tt)*) => {
+        $crate::__jarkup_inlines!(@push $inlines; $($rest)*);
+    };
+    (@push $inlines:ident; $text:literal $($rest:tt)*) => {
+        $inlines.push($crate::InlineComponent::from($crate::Text::new($text)));
+        $crate::__jarkup_inlines!(@push $inlines; $($rest)*);
+    };
+    (@push $inlines:ident; { $value:expr } $($rest:tt)*) => {
+        $inlines.push(::core::convert::Into::<$crate::InlineComponent>::into($value));
+        $crate::__jarkup_inlines!(@push $inlines; $($rest)*);
+    };
+    (@push $inlines:ident; icon ($src:expr) $($rest:tt)*) => {
+        $inlines.push($crate::InlineComponent::from($crate::Icon::new($src)));
+        $crate::__jarkup_inlines!(@push $inlines; $($rest)*);
+    };
+    (@push $inlines:ident; a ($href:expr) [$($inner:tt)*] $($rest:tt)*) => {
+        let href: ::std::string::String = ::core::convert::Into::into($href);
+        $inlines.extend($crate::__jarkup_inlines!(@map [text => text.href(href.clone())] $($inner)*));
+        $crate::__jarkup_inlines!(@push $inlines; $($rest)*);
+    };
+    (@push $inlines:ident; $mark:ident [$($inner:tt)*] $($rest:tt)*) => {
+        $inlines.extend($crate::__jarkup_inlines!(@mark $mark; $($inner)*));
+        $crate::__jarkup_inlines!(@push $inlines; $($rest)*);
+    };
+    (@mark b; $($inner:tt)*) => { $crate::__jarkup_inlines!(@map [text => text.bold()] $($inner)*) };
+    (@mark i; $($inner:tt)*) => { $crate::__jarkup_inlines!(@map [text => text.italic()] $($inner)*) };
+    (@mark u; $($inner:tt)*) => { $crate::__jarkup_inlines!(@map [text => text.underline()] $($inner)*) };
+    (@mark s; $($inner:tt)*) => { $crate::__jarkup_inlines!(@map [text => text.strikethrough()] $($inner)*) };
+    (@mark code; $($inner:tt)*) => { $crate::__jarkup_inlines!(@map [text => text.code()] $($inner)*) };
+    (@mark kbd; $($inner:tt)*) => { $crate::__jarkup_inlines!(@map [text => text.kbd()] $($inner)*) };
+    (@mark math; $($inner:tt)*) => { $crate::__jarkup_inlines!(@map [text => text.katex()] $($inner)*) };
+    (@map [$text:ident => $style:expr] $($inner:tt)*) => {
+        $crate::__jarkup_inlines!($($inner)*)
+            .into_iter()
+            .map(|inline_component| match inline_component {
+                $crate::InlineComponent::Text($text) => $crate::InlineComponent::Text($style),
+                other => other,
+            })
+    };
+    ($($body:tt)*) => {{
+        #[allow(unused_mut)]
+        let mut inlines = ::std::vec::Vec::<$crate::InlineComponent>::new();
+        $crate::__jarkup_inlines!(@push inlines; $($body)*);
+        inlines
+    }};
+}
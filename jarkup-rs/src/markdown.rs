@@ -0,0 +1,537 @@
+//! Markdown rendering.
+//!
+//! Output follows CommonMark with the GitHub extensions for tables,
+//! strikethrough and alerts, which callouts map to. Components Markdown has no
+//! syntax for fall back to inline HTML (`<details>` for toggles, `<kbd>`,
+//! `<u>` and `<ruby>`), to `$…$` and `$$…$$` for KaTeX, and to a fenced block
+//! for Mermaid. Tabs are flattened into their labels in bold followed by their
+//! contents, column lists into their columns in order, and colors as well as
+//! [`Unsupported`](crate::Unsupported) components are dropped.
+
+use std::fmt;
+
+use crate::{BlockComponent, CalloutType, Component, InlineComponent, ListStyle, Text};
+
+/// Renders `components` into a `fmt::Write` sink such as a `String`.
+pub fn render<W>(components: &[Component], sink: &mut W) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    let mut first = true;
+    for block in group(components) {
+        let markdown = render_group(&block);
+        if markdown.is_empty() {
+            continue;
+        }
+        if !first {
+            sink.write_char('\n')?;
+        }
+        first = false;
+        sink.write_str(&markdown)?;
+        sink.write_char('\n')?;
+    }
+    Ok(())
+}
+
+/// Renders `components` into a new `String`.
+pub fn to_string(components: &[Component]) -> String {
+    let mut markdown = String::new();
+    // Writing into a `String` never fails.
+    let _ = render(components, &mut markdown);
+    markdown
+}
+
+/// A block, or consecutive inline components rendered as a paragraph.
+enum Group<'a> {
+    Block(&'a BlockComponent),
+    Inlines(Vec<&'a InlineComponent>),
+}
+
+fn group(components: &[Component]) -> Vec<Group<'_>> {
+    let mut groups = Vec::new();
+    for component in components {
+        match component {
+            Component::BlockComponent(block) => groups.push(Group::Block(block)),
+            Component::InlineComponent(inline_component) => match groups.last_mut() {
+                Some(Group::Inlines(inlines)) => inlines.push(inline_component),
+                _ => groups.push(Group::Inlines(vec![inline_component])),
+            },
+        }
+    }
+    groups
+}
+
+fn render_group(group: &Group<'_>) -> String {
+    match group {
+        Group::Block(block) => render_block(block),
+        Group::Inlines(inlines) => paragraph(inlines),
+    }
+}
+
+/// Renders blocks separated by blank lines, without a trailing newline.
+fn blocks(components: &[Component]) -> String {
+    group(components)
+        .iter()
+        .map(render_group)
+        .filter(|markdown| !markdown.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Renders the contents of a list item, keeping text followed by nested
+/// lists tight.
+fn list_item(components: &[Component]) -> String {
+    let groups = group(components);
+    let tight = groups.iter().all(|group| {
+        matches!(
+            group,
+            Group::Inlines(_) | Group::Block(BlockComponent::List(_))
+        )
+    });
+    groups
+        .iter()
+        .map(render_group)
+        .filter(|markdown| !markdown.is_empty())
+        .collect::<Vec<_>>()
+        .join(if tight { "\n" } else { "\n\n" })
+}
+
+/// Prefixes the first line of `text` with `first` and the others with `rest`,
+/// leaving blank lines without trailing spaces.
+fn prefix_lines(text: &str, first: &str, rest: &str) -> String {
+    text.split('\n')
+        .enumerate()
+        .map(|(index, line)| {
+            let prefix = if index == 0 { first } else { rest };
+            if line.is_empty() {
+                prefix.trim_end().to_string()
+            } else {
+                format!("{}{}", prefix, line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn fence(code: &str, info: &str) -> String {
+    let longest = code.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat((longest + 1).max(3));
+    format!("{}{}\n{}\n{}", fence, info, code, fence)
+}
+
+fn render_block(block: &BlockComponent) -> String {
+    match block {
+        BlockComponent::Fragment(fragment) => blocks(&fragment.slots.default),
+        BlockComponent::Heading(heading) => {
+            let level = u8::from(heading.props.level.clone()) as usize;
+            format!(
+                "{} {}",
+                "#".repeat(level),
+                inlines(&heading.slots.default.iter().collect::<Vec<_>>())
+            )
+        }
+        BlockComponent::Paragraph(block) => {
+            paragraph(&block.slots.default.iter().collect::<Vec<_>>())
+        }
+        BlockComponent::ListItem(item) => prefix_lines(&list_item(&item.slots.default), "- ", "  "),
+        BlockComponent::List(list) => {
+            let ordered = list
+                .props
+                .as_ref()
+                .is_some_and(|props| props.list_style == Some(ListStyle::Ordered));
+            let mut number = 0;
+            let mut items = Vec::new();
+            for item in &list.slots.default {
+                let content = match item {
+                    Component::BlockComponent(BlockComponent::ListItem(item)) => {
+                        list_item(&item.slots.default)
+                    }
+                    item => blocks(std::slice::from_ref(item)),
+                };
+                number += 1;
+                let marker = if ordered {
+                    format!("{}. ", number)
+                } else {
+                    "- ".to_string()
+                };
+                items.push(prefix_lines(&content, &marker, &" ".repeat(marker.len())));
+            }
+            items.join("\n")
+        }
+        BlockComponent::BlockQuote(block_quote) => {
+            let mut content = blocks(&block_quote.slots.default);
+            if let Some(cite) = block_quote
+                .props
+                .as_ref()
+                .and_then(|props| props.cite.as_ref())
+            {
+                content.push_str("\n\n— ");
+                content.push_str(&escape(cite));
+            }
+            prefix_lines(&content, "> ", "> ")
+        }
+        BlockComponent::Callout(callout) => {
+            let kind = match callout
+                .props
+                .as_ref()
+                .and_then(|props| props.r#type.clone())
+                .unwrap_or_default()
+            {
+                CalloutType::Note => "NOTE",
+                CalloutType::Tip => "TIP",
+                CalloutType::Important => "IMPORTANT",
+                CalloutType::Warning => "WARNING",
+                CalloutType::Caution => "CAUTION",
+            };
+            let content = blocks(&callout.slots.default);
+            prefix_lines(&format!("[!{}]\n{}", kind, content), "> ", "> ")
+        }
+        BlockComponent::Divider(_) => "---".to_string(),
+        BlockComponent::Toggle(toggle) => {
+            let summary = inlines(&toggle.slots.summary.iter().collect::<Vec<_>>());
+            let content = blocks(&toggle.slots.default);
+            if content.is_empty() {
+                format!("<details>\n<summary>{}</summary>\n</details>", summary)
+            } else {
+                format!(
+                    "<details>\n<summary>{}</summary>\n\n{}\n\n</details>",
+                    summary, content
+                )
+            }
+        }
+        BlockComponent::Bookmark(bookmark) => {
+            let title = bookmark
+                .props
+                .title
+                .as_deref()
+                .unwrap_or(&bookmark.props.url);
+            format!("[{}]({})", escape(title), destination(&bookmark.props.url))
+        }
+        BlockComponent::File(file) => {
+            let name = file.props.name.as_deref().unwrap_or(&file.props.src);
+            format!("[{}]({})", escape(name), destination(&file.props.src))
+        }
+        BlockComponent::Image(image) => format!(
+            "![{}]({})",
+            escape(image.props.alt.as_deref().unwrap_or("")),
+            destination(&image.props.src)
+        ),
+        BlockComponent::CodeBlock(code_block) => {
+            fence(&code_block.props.code, &code_block.props.language)
+        }
+        BlockComponent::Katex(katex) => format!("$$\n{}\n$$", katex.props.expression),
+        BlockComponent::Mermaid(mermaid) => fence(&mermaid.props.code, "mermaid"),
+        BlockComponent::Tab(tab) => {
+            let label = inlines(&tab.slots.labels.iter().collect::<Vec<_>>());
+            let label = match label.trim() {
+                "" => String::new(),
+                label => format!("**{}**", label),
+            };
+            [label, blocks(&tab.slots.contents)]
+                .into_iter()
+                .filter(|markdown| !markdown.is_empty())
+                .collect::<Vec<_>>()
+                .join("\n\n")
+        }
+        BlockComponent::Tabs(tabs) => blocks(&tabs.slots.default),
+        BlockComponent::Table(table) => render_table(table),
+        BlockComponent::TableRow(table_row) => {
+            format!("| {} |", cells(&table_row.slots.default).join(" | "))
+        }
+        BlockComponent::TableCell(table_cell) => {
+            paragraph(&table_cell.slots.default.iter().collect::<Vec<_>>())
+        }
+        BlockComponent::ColumnList(column_list) => blocks(&column_list.slots.default),
+        BlockComponent::Column(column) => blocks(&column.slots.default),
+        BlockComponent::Unsupported(_) => String::new(),
+    }
+}
+
+fn render_table(table: &crate::Table) -> String {
+    let rows = |components: &[Component]| {
+        components
+            .iter()
+            .filter_map(|row| match row {
+                Component::BlockComponent(BlockComponent::TableRow(table_row)) => {
+                    Some(cells(&table_row.slots.default))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+    let mut body = rows(&table.slots.body);
+    let mut header = table.slots.header.as_deref().map(rows).unwrap_or_default();
+    let has_column_header = table
+        .props
+        .as_ref()
+        .and_then(|props| props.has_column_header)
+        .unwrap_or(false);
+    if header.is_empty() && has_column_header && !body.is_empty() {
+        header.push(body.remove(0));
+    }
+    // Tables need exactly one header row; extra ones join the body.
+    let mut header_rows = header.into_iter();
+    let first = header_rows.next();
+    let body = header_rows.chain(body).collect::<Vec<_>>();
+
+    let columns = first
+        .iter()
+        .chain(&body)
+        .map(Vec::len)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let line = |cells: &[String]| {
+        let mut cells = cells.to_vec();
+        cells.resize(columns, String::new());
+        format!("| {} |", cells.join(" | "))
+    };
+
+    let mut lines = vec![
+        line(&first.unwrap_or_default()),
+        format!("|{}", " --- |".repeat(columns)),
+    ];
+    lines.extend(body.iter().map(|row| line(row)));
+    let mut markdown = lines.join("\n");
+    if let Some(caption) = table
+        .props
+        .as_ref()
+        .and_then(|props| props.caption.as_ref())
+    {
+        markdown.push_str("\n\n");
+        markdown.push_str(&escape(caption));
+    }
+    markdown
+}
+
+fn cells(components: &[Component]) -> Vec<String> {
+    components
+        .iter()
+        .map(|cell| match cell {
+            Component::BlockComponent(BlockComponent::TableCell(table_cell)) => {
+                inlines(&table_cell.slots.default.iter().collect::<Vec<_>>())
+                    .replace("\\\n", "<br>")
+            }
+            cell => blocks(std::slice::from_ref(cell)).replace('\n', " "),
+        })
+        .collect()
+}
+
+/// Renders inline components as a paragraph, escaping what would otherwise
+/// start another block.
+fn paragraph(components: &[&InlineComponent]) -> String {
+    let markdown = inlines(components);
+    let digits = markdown.bytes().take_while(u8::is_ascii_digit).count();
+    let starts_block = markdown.starts_with(['#', '>', '-', '+', '=', '|'])
+        || (digits > 0 && markdown[digits..].starts_with(['.', ')']));
+    if starts_block {
+        format!("{}\\{}", &markdown[..digits], &markdown[digits..])
+    } else {
+        markdown
+    }
+}
+
+fn inlines(components: &[&InlineComponent]) -> String {
+    let mut markdown = String::new();
+    for component in components {
+        match component {
+            InlineComponent::Text(text) => render_text(text, &mut markdown),
+            InlineComponent::Icon(icon) => markdown.push_str(&format!(
+                "![{}]({})",
+                escape(icon.props.alt.as_deref().unwrap_or("")),
+                destination(&icon.props.src)
+            )),
+        }
+    }
+    markdown
+}
+
+fn render_text(text: &Text, markdown: &mut String) {
+    let props = &text.props;
+    let flag = |value: Option<bool>| value.unwrap_or(false);
+
+    // Emphasis cannot start or end with whitespace, so it stays outside.
+    let content = props.text.as_str();
+    let trimmed = content.trim();
+    let (leading, trailing) = match content.find(trimmed) {
+        Some(start) if !trimmed.is_empty() => {
+            (&content[..start], &content[start + trimmed.len()..])
+        }
+        _ => (content, ""),
+    };
+    if trimmed.is_empty() {
+        markdown.push_str(&escape(content));
+        return;
+    }
+
+    let mut inner = if flag(props.katex) {
+        format!("${}$", trimmed)
+    } else if flag(props.code) {
+        code_span(trimmed)
+    } else if flag(props.kbd) {
+        format!("<kbd>{}</kbd>", escape_html(trimmed))
+    } else {
+        escape(trimmed)
+    };
+    if let Some(ruby) = &props.ruby {
+        inner = format!("<ruby>{}<rt>{}</rt></ruby>", inner, escape_html(ruby));
+    }
+    if flag(props.underline) {
+        inner = format!("<u>{}</u>", inner);
+    }
+    if flag(props.italic) {
+        inner = format!("*{}*", inner);
+    }
+    if flag(props.bold) {
+        inner = format!("**{}**", inner);
+    }
+    if flag(props.strikethrough) {
+        inner = format!("~~{}~~", inner);
+    }
+    if let Some(href) = &props.href {
+        inner = format!("[{}]({})", inner, destination(href));
+    }
+
+    markdown.push_str(&escape(leading));
+    markdown.push_str(&inner);
+    markdown.push_str(&escape(trailing));
+}
+
+fn code_span(code: &str) -> String {
+    let longest = code.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let ticks = "`".repeat(longest + 1);
+    if code.starts_with('`') || code.ends_with('`') {
+        format!("{} {} {}", ticks, code, ticks)
+    } else {
+        format!("{}{}{}", ticks, code, ticks)
+    }
+}
+
+/// Escapes Markdown punctuation, and turns line breaks into hard breaks.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '~' | '|' | '$' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\\n"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::new();
+    // Writing into a `String` never fails.
+    let _ = crate::html::escape(text, &mut escaped);
+    escaped
+}
+
+/// Formats a link destination, in angle brackets when it would otherwise be
+/// cut short.
+fn destination(url: &str) -> String {
+    if url.contains([' ', '(', ')', '<', '>']) || url.is_empty() {
+        format!("<{}>", url.replace('<', "%3C").replace('>', "%3E"))
+    } else {
+        url.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::to_string;
+    use crate::Component;
+
+    #[test]
+    fn test_render() {
+        let components = serde_json::from_str::<Vec<Component>>(
+            r##"[
+                {"type":"Heading","props":{"level":2},"slots":{"default":[{"type":"Text","props":{"text":"Intro"}}]}},
+                {"type":"Paragraph","slots":{"default":[
+                    {"type":"Text","props":{"text":"Press "}},
+                    {"type":"Text","props":{"text":"Ctrl","kbd":true}},
+                    {"type":"Text","props":{"text":" to "}},
+                    {"type":"Text","props":{"text":"copy ","bold":true,"italic":true}},
+                    {"type":"Text","props":{"text":"a*b","href":"https://example.com/a b"}},
+                    {"type":"Text","props":{"text":"x`y","code":true}}
+                ]}},
+                {"type":"Paragraph","slots":{"default":[{"type":"Text","props":{"text":"# not a heading"}}]}},
+                {"type":"List","props":{"listStyle":"ordered"},"slots":{"default":[
+                    {"type":"ListItem","slots":{"default":[{"type":"Text","props":{"text":"One"}}]}},
+                    {"type":"ListItem","slots":{"default":[
+                        {"type":"Text","props":{"text":"Two"}},
+                        {"type":"List","slots":{"default":[
+                            {"type":"ListItem","slots":{"default":[{"type":"Text","props":{"text":"Nested"}}]}}
+                        ]}}
+                    ]}}
+                ]}},
+                {"type":"Callout","props":{"type":"warning"},"slots":{"default":[
+                    {"type":"Paragraph","slots":{"default":[{"type":"Text","props":{"text":"Hot"}}]}}
+                ]}},
+                {"type":"Toggle","slots":{"summary":[{"type":"Text","props":{"text":"More"}}],"default":[{"type":"Divider"}]}},
+                {"type":"CodeBlock","props":{"code":"let a = 1;","language":"rust"}},
+                {"type":"Table","props":{"hasColumnHeader":true},"slots":{"body":[
+                    {"type":"TableRow","slots":{"default":[
+                        {"type":"TableCell","slots":{"default":[{"type":"Text","props":{"text":"Name"}}]}},
+                        {"type":"TableCell","slots":{"default":[{"type":"Text","props":{"text":"Value"}}]}}
+                    ]}},
+                    {"type":"TableRow","slots":{"default":[
+                        {"type":"TableCell","slots":{"default":[{"type":"Text","props":{"text":"a|b"}}]}}
+                    ]}}
+                ]}},
+                {"type":"Tabs","slots":{"default":[
+                    {"type":"Tab","slots":{"labels":[{"type":"Text","props":{"text":"Rust"}}],"contents":[
+                        {"type":"Katex","props":{"expression":"x^2"}}
+                    ]}}
+                ]}},
+                {"type":"Image","props":{"src":"/cat.png","alt":"A cat"}},
+                {"type":"Unsupported","props":{"details":"embed"},"slots":null}
+            ]"##,
+        )
+        .unwrap();
+
+        assert_eq!(
+            to_string(&components),
+            r##"## Intro
+
+Press <kbd>Ctrl</kbd> to ***copy*** [a\*b](<https://example.com/a b>)``x`y``
+
+\# not a heading
+
+1. One
+2. Two
+   - Nested
+
+> [!WARNING]
+> Hot
+
+<details>
+<summary>More</summary>
+
+---
+
+</details>
+
+```rust
+let a = 1;
+```
+
+| Name | Value |
+| --- | --- |
+| a\|b |  |
+
+**Rust**
+
+$$
+x^2
+$$
+
+![A cat](/cat.png)
+"##
+        );
+    }
+}
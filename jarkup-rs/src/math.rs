@@ -0,0 +1,600 @@
+//! Conversion of TeX expressions into MathML.
+//!
+//! Covers the subset of KaTeX syntax that appears in prose: scripts, fractions,
+//! roots, accents, `\left`/`\right` delimiters, `\text`, font commands, greek
+//! letters and common operators. Anything else is reported as a [`MathError`]
+//! so the caller can fall back to the raw TeX.
+
+use std::fmt;
+
+use crate::html::escape;
+
+/// Braces and scripts nested deeper than this are rejected.
+const MAX_DEPTH: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MathError {
+    UnsupportedMacro(String),
+    MissingArgument(String),
+    UnbalancedBraces,
+    UnexpectedCharacter(char),
+    TooDeep,
+}
+
+impl fmt::Display for MathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MathError::UnsupportedMacro(name) => write!(f, "unsupported macro: \\{}", name),
+            MathError::MissingArgument(name) => write!(f, "missing argument for {}", name),
+            MathError::UnbalancedBraces => write!(f, "unbalanced braces"),
+            MathError::UnexpectedCharacter(c) => write!(f, "unexpected character: {}", c),
+            MathError::TooDeep => write!(f, "expression nested too deeply"),
+        }
+    }
+}
+
+impl std::error::Error for MathError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Identifier(String),
+    Number(String),
+    Operator(String),
+    Text(String),
+    Space(&'static str),
+    Row(Vec<Node>),
+    Fraction(Box<Node>, Box<Node>),
+    Sqrt(Box<Node>),
+    Root(Box<Node>, Box<Node>),
+    Sub(Box<Node>, Box<Node>),
+    Sup(Box<Node>, Box<Node>),
+    SubSup(Box<Node>, Box<Node>, Box<Node>),
+    Accent(Box<Node>, &'static str),
+    Variant(&'static str, Box<Node>),
+}
+
+fn symbol(name: &str) -> Option<Node> {
+    let identifier = |s: &str| Some(Node::Identifier(s.to_string()));
+    let operator = |s: &str| Some(Node::Operator(s.to_string()));
+    match name {
+        "alpha" => identifier("α"),
+        "beta" => identifier("β"),
+        "gamma" => identifier("γ"),
+        "delta" => identifier("δ"),
+        "epsilon" => identifier("ϵ"),
+        "varepsilon" => identifier("ε"),
+        "zeta" => identifier("ζ"),
+        "eta" => identifier("η"),
+        "theta" => identifier("θ"),
+        "vartheta" => identifier("ϑ"),
+        "iota" => identifier("ι"),
+        "kappa" => identifier("κ"),
+        "lambda" => identifier("λ"),
+        "mu" => identifier("μ"),
+        "nu" => identifier("ν"),
+        "xi" => identifier("ξ"),
+        "pi" => identifier("π"),
+        "rho" => identifier("ρ"),
+        "sigma" => identifier("σ"),
+        "tau" => identifier("τ"),
+        "upsilon" => identifier("υ"),
+        "phi" => identifier("ϕ"),
+        "varphi" => identifier("φ"),
+        "chi" => identifier("χ"),
+        "psi" => identifier("ψ"),
+        "omega" => identifier("ω"),
+        "Gamma" => identifier("Γ"),
+        "Delta" => identifier("Δ"),
+        "Theta" => identifier("Θ"),
+        "Lambda" => identifier("Λ"),
+        "Xi" => identifier("Ξ"),
+        "Pi" => identifier("Π"),
+        "Sigma" => identifier("Σ"),
+        "Upsilon" => identifier("Υ"),
+        "Phi" => identifier("Φ"),
+        "Psi" => identifier("Ψ"),
+        "Omega" => identifier("Ω"),
+        "infty" => identifier("∞"),
+        "partial" => identifier("∂"),
+        "nabla" => identifier("∇"),
+        "hbar" => identifier("ℏ"),
+        "ell" => identifier("ℓ"),
+        "emptyset" => identifier("∅"),
+        "pm" => operator("±"),
+        "mp" => operator("∓"),
+        "times" => operator("×"),
+        "div" => operator("÷"),
+        "cdot" => operator("⋅"),
+        "circ" => operator("∘"),
+        "ast" => operator("∗"),
+        "leq" | "le" => operator("≤"),
+        "geq" | "ge" => operator("≥"),
+        "neq" | "ne" => operator("≠"),
+        "approx" => operator("≈"),
+        "equiv" => operator("≡"),
+        "sim" => operator("∼"),
+        "simeq" => operator("≃"),
+        "propto" => operator("∝"),
+        "ll" => operator("≪"),
+        "gg" => operator("≫"),
+        "in" => operator("∈"),
+        "notin" => operator("∉"),
+        "ni" => operator("∋"),
+        "subset" => operator("⊂"),
+        "subseteq" => operator("⊆"),
+        "supset" => operator("⊃"),
+        "supseteq" => operator("⊇"),
+        "cup" => operator("∪"),
+        "cap" => operator("∩"),
+        "setminus" => operator("∖"),
+        "wedge" | "land" => operator("∧"),
+        "vee" | "lor" => operator("∨"),
+        "neg" | "lnot" => operator("¬"),
+        "forall" => operator("∀"),
+        "exists" => operator("∃"),
+        "to" | "rightarrow" => operator("→"),
+        "leftarrow" | "gets" => operator("←"),
+        "leftrightarrow" => operator("↔"),
+        "Rightarrow" | "implies" => operator("⇒"),
+        "Leftarrow" => operator("⇐"),
+        "Leftrightarrow" | "iff" => operator("⇔"),
+        "mapsto" => operator("↦"),
+        "sum" => operator("∑"),
+        "prod" => operator("∏"),
+        "int" => operator("∫"),
+        "iint" => operator("∬"),
+        "oint" => operator("∮"),
+        "ldots" | "dots" => operator("…"),
+        "cdots" => operator("⋯"),
+        "vdots" => operator("⋮"),
+        "ddots" => operator("⋱"),
+        "mid" => operator("∣"),
+        "parallel" => operator("∥"),
+        "perp" => operator("⊥"),
+        "angle" => operator("∠"),
+        "langle" => operator("⟨"),
+        "rangle" => operator("⟩"),
+        "lbrace" => operator("{"),
+        "rbrace" => operator("}"),
+        "lfloor" => operator("⌊"),
+        "rfloor" => operator("⌋"),
+        "lceil" => operator("⌈"),
+        "rceil" => operator("⌉"),
+        "{" | "}" | "%" | "$" | "#" | "&" | "_" | "|" => operator(name),
+        "sin" | "cos" | "tan" | "cot" | "sec" | "csc" | "arcsin" | "arccos" | "arctan" | "sinh"
+        | "cosh" | "tanh" | "log" | "ln" | "lg" | "exp" | "lim" | "max" | "min" | "sup" | "inf"
+        | "det" | "dim" | "gcd" | "arg" | "deg" | "ker" | "Pr" => identifier(name),
+        "," => Some(Node::Space("0.1667em")),
+        ":" | ">" => Some(Node::Space("0.2222em")),
+        ";" => Some(Node::Space("0.2778em")),
+        " " => Some(Node::Space("0.25em")),
+        "quad" => Some(Node::Space("1em")),
+        "qquad" => Some(Node::Space("2em")),
+        "!" => Some(Node::Space("-0.1667em")),
+        _ => None,
+    }
+}
+
+fn accent(name: &str) -> Option<&'static str> {
+    match name {
+        "hat" | "widehat" => Some("^"),
+        "bar" | "overline" => Some("¯"),
+        "vec" => Some("→"),
+        "dot" => Some("˙"),
+        "ddot" => Some("¨"),
+        "tilde" | "widetilde" => Some("~"),
+        _ => None,
+    }
+}
+
+fn variant(name: &str) -> Option<&'static str> {
+    match name {
+        "mathrm" | "rm" => Some("normal"),
+        "mathbf" | "bf" | "boldsymbol" => Some("bold"),
+        "mathit" => Some("italic"),
+        "mathbb" => Some("double-struck"),
+        "mathcal" => Some("script"),
+        "mathfrak" => Some("fraktur"),
+        "mathsf" => Some("sans-serif"),
+        "mathtt" => Some("monospace"),
+        _ => None,
+    }
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    position: usize,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<char> {
+        self.source[self.position..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += c.len_utf8();
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    fn command_name(&mut self) -> &'a str {
+        let start = self.position;
+        match self.peek() {
+            Some(c) if c.is_ascii_alphabetic() => {
+                while self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+                    self.bump();
+                }
+            }
+            Some(_) => {
+                self.bump();
+            }
+            None => {}
+        }
+        &self.source[start..self.position]
+    }
+
+    /// Whether the input continues with the `\right` command, as opposed to
+    /// a longer name such as `\rightarrow`.
+    fn at_right(&self) -> bool {
+        self.source[self.position..]
+            .strip_prefix("\\right")
+            .is_some_and(|rest| !rest.starts_with(|c: char| c.is_ascii_alphabetic()))
+    }
+
+    fn enter(&mut self) -> Result<(), MathError> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(MathError::TooDeep);
+        }
+        Ok(())
+    }
+
+    /// Parses atoms until end of input, `}` or `\right`.
+    fn row(&mut self) -> Result<Vec<Node>, MathError> {
+        let mut nodes = Vec::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                None | Some('}') => break,
+                Some('\\') if self.at_right() => break,
+                Some('^') | Some('_') => {
+                    let base = nodes.pop().unwrap_or(Node::Row(Vec::new()));
+                    nodes.push(self.scripts(base)?);
+                }
+                Some(_) => nodes.push(self.atom()?),
+            }
+        }
+        Ok(nodes)
+    }
+
+    fn scripts(&mut self, base: Node) -> Result<Node, MathError> {
+        let mut sub = None;
+        let mut sup = None;
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some('_') if sub.is_none() => {
+                    self.bump();
+                    sub = Some(self.argument("_")?);
+                }
+                Some('^') if sup.is_none() => {
+                    self.bump();
+                    sup = Some(self.argument("^")?);
+                }
+                _ => break,
+            }
+        }
+        let base = Box::new(base);
+        Ok(match (sub, sup) {
+            (Some(sub), Some(sup)) => Node::SubSup(base, Box::new(sub), Box::new(sup)),
+            (Some(sub), None) => Node::Sub(base, Box::new(sub)),
+            (None, Some(sup)) => Node::Sup(base, Box::new(sup)),
+            (None, None) => *base,
+        })
+    }
+
+    fn argument(&mut self, name: &str) -> Result<Node, MathError> {
+        self.skip_whitespace();
+        match self.peek() {
+            None | Some('}') | Some('^') | Some('_') => {
+                Err(MathError::MissingArgument(name.to_string()))
+            }
+            Some(_) => self.atom(),
+        }
+    }
+
+    fn group(&mut self) -> Result<Node, MathError> {
+        self.enter()?;
+        self.bump();
+        let nodes = self.row()?;
+        if self.bump() != Some('}') {
+            return Err(MathError::UnbalancedBraces);
+        }
+        self.depth -= 1;
+        Ok(Node::Row(nodes))
+    }
+
+    /// Reads the raw contents of a `{...}` group, as used by `\text`.
+    fn raw_group(&mut self, name: &str) -> Result<&'a str, MathError> {
+        self.skip_whitespace();
+        if self.peek() != Some('{') {
+            return Err(MathError::MissingArgument(format!("\\{}", name)));
+        }
+        self.bump();
+        let start = self.position;
+        let mut depth = 1;
+        while let Some(c) = self.bump() {
+            match c {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(&self.source[start..self.position - 1]);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Err(MathError::UnbalancedBraces)
+    }
+
+    fn delimiter(&mut self, name: &str) -> Result<Node, MathError> {
+        self.skip_whitespace();
+        match self.bump() {
+            Some('.') => Ok(Node::Row(Vec::new())),
+            Some('\\') => {
+                let command = self.command_name();
+                match symbol(command) {
+                    Some(node @ Node::Operator(_)) => Ok(node),
+                    _ => Err(MathError::UnsupportedMacro(command.to_string())),
+                }
+            }
+            Some(c) if "()[]|/<>".contains(c) => Ok(Node::Operator(c.to_string())),
+            _ => Err(MathError::MissingArgument(format!("\\{}", name))),
+        }
+    }
+
+    fn atom(&mut self) -> Result<Node, MathError> {
+        let Some(c) = self.peek() else {
+            return Err(MathError::MissingArgument("expression".to_string()));
+        };
+        match c {
+            '{' => self.group(),
+            '\\' => {
+                self.bump();
+                self.command()
+            }
+            '0'..='9' | '.' => {
+                let start = self.position;
+                while self.peek().is_some_and(|c| c.is_ascii_digit() || c == '.') {
+                    self.bump();
+                }
+                Ok(Node::Number(self.source[start..self.position].to_string()))
+            }
+            '~' => {
+                self.bump();
+                Ok(Node::Space("0.25em"))
+            }
+            '&' | '#' | '$' | '%' => Err(MathError::UnexpectedCharacter(c)),
+            c if c.is_alphabetic() => {
+                self.bump();
+                Ok(Node::Identifier(c.to_string()))
+            }
+            c => {
+                self.bump();
+                Ok(Node::Operator(c.to_string()))
+            }
+        }
+    }
+
+    fn command(&mut self) -> Result<Node, MathError> {
+        let name = self.command_name();
+        if let Some(node) = symbol(name) {
+            return Ok(node);
+        }
+        if let Some(accent) = accent(name) {
+            self.enter()?;
+            let body = self.argument(&format!("\\{}", name))?;
+            self.depth -= 1;
+            return Ok(Node::Accent(Box::new(body), accent));
+        }
+        if let Some(variant) = variant(name) {
+            self.enter()?;
+            let body = self.argument(&format!("\\{}", name))?;
+            self.depth -= 1;
+            return Ok(Node::Variant(variant, Box::new(body)));
+        }
+        match name {
+            "frac" | "dfrac" | "tfrac" => {
+                self.enter()?;
+                let numerator = self.argument("\\frac")?;
+                let denominator = self.argument("\\frac")?;
+                self.depth -= 1;
+                Ok(Node::Fraction(Box::new(numerator), Box::new(denominator)))
+            }
+            "sqrt" => {
+                self.enter()?;
+                self.skip_whitespace();
+                let index = if self.peek() == Some('[') {
+                    self.bump();
+                    let mut nodes = Vec::new();
+                    loop {
+                        self.skip_whitespace();
+                        match self.peek() {
+                            Some(']') => {
+                                self.bump();
+                                break;
+                            }
+                            None => return Err(MathError::MissingArgument("\\sqrt".to_string())),
+                            Some(_) => nodes.push(self.atom()?),
+                        }
+                    }
+                    Some(Node::Row(nodes))
+                } else {
+                    None
+                };
+                let radicand = self.argument("\\sqrt")?;
+                self.depth -= 1;
+                Ok(match index {
+                    Some(index) => Node::Root(Box::new(radicand), Box::new(index)),
+                    None => Node::Sqrt(Box::new(radicand)),
+                })
+            }
+            "text" | "textrm" | "mbox" => Ok(Node::Text(self.raw_group(name)?.to_string())),
+            "operatorname" => Ok(Node::Identifier(self.raw_group(name)?.to_string())),
+            "left" => {
+                self.enter()?;
+                let open = self.delimiter("left")?;
+                let mut nodes = vec![open];
+                nodes.extend(self.row()?);
+                if !self.at_right() {
+                    return Err(MathError::MissingArgument("\\right".to_string()));
+                }
+                self.position += "\\right".len();
+                nodes.push(self.delimiter("right")?);
+                self.depth -= 1;
+                Ok(Node::Row(nodes))
+            }
+            _ => Err(MathError::UnsupportedMacro(name.to_string())),
+        }
+    }
+}
+
+/// A parsed TeX expression. Its `Display` output is a `<math>` element.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expression<'a> {
+    source: &'a str,
+    display: bool,
+    node: Node,
+}
+
+/// Parses `expression`; `display` selects block rather than inline layout.
+pub fn parse(expression: &str, display: bool) -> Result<Expression<'_>, MathError> {
+    let mut parser = Parser {
+        source: expression,
+        position: 0,
+        depth: 0,
+    };
+    let nodes = parser.row()?;
+    if parser.position < expression.len() {
+        return Err(MathError::UnbalancedBraces);
+    }
+    Ok(Expression {
+        source: expression,
+        display,
+        node: Node::Row(nodes),
+    })
+}
+
+fn write_node<W>(node: &Node, sink: &mut W) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    let element = |tag: &str, text: &str, sink: &mut W| -> fmt::Result {
+        write!(sink, "<{}>", tag)?;
+        escape(text, sink)?;
+        write!(sink, "</{}>", tag)
+    };
+    let children = |tag: &str, nodes: &[&Node], sink: &mut W| -> fmt::Result {
+        write!(sink, "<{}>", tag)?;
+        for node in nodes {
+            write_node(node, sink)?;
+        }
+        write!(sink, "</{}>", tag)
+    };
+    match node {
+        Node::Identifier(text) => element("mi", text, sink),
+        Node::Number(text) => element("mn", text, sink),
+        Node::Operator(text) => element("mo", text, sink),
+        Node::Text(text) => element("mtext", text, sink),
+        Node::Space(width) => write!(sink, "<mspace width=\"{}\"></mspace>", width),
+        Node::Row(nodes) => {
+            sink.write_str("<mrow>")?;
+            for node in nodes {
+                write_node(node, sink)?;
+            }
+            sink.write_str("</mrow>")
+        }
+        Node::Fraction(numerator, denominator) => {
+            children("mfrac", &[numerator, denominator], sink)
+        }
+        Node::Sqrt(radicand) => children("msqrt", &[radicand], sink),
+        Node::Root(radicand, index) => children("mroot", &[radicand, index], sink),
+        Node::Sub(base, sub) => children("msub", &[base, sub], sink),
+        Node::Sup(base, sup) => children("msup", &[base, sup], sink),
+        Node::SubSup(base, sub, sup) => children("msubsup", &[base, sub, sup], sink),
+        Node::Accent(base, accent) => {
+            sink.write_str("<mover accent=\"true\">")?;
+            write_node(base, sink)?;
+            element("mo", accent, sink)?;
+            sink.write_str("</mover>")
+        }
+        Node::Variant(variant, body) => {
+            write!(sink, "<mstyle mathvariant=\"{}\">", variant)?;
+            write_node(body, sink)?;
+            sink.write_str("</mstyle>")
+        }
+    }
+}
+
+impl fmt::Display for Expression<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("<math xmlns=\"http://www.w3.org/1998/Math/MathML\"")?;
+        if self.display {
+            f.write_str(" display=\"block\"")?;
+        }
+        f.write_str("><semantics>")?;
+        write_node(&self.node, f)?;
+        f.write_str("<annotation encoding=\"application/x-tex\">")?;
+        escape(self.source, f)?;
+        f.write_str("</annotation></semantics></math>")
+    }
+}
+
+/// Converts `expression` into a `<math>` element.
+pub fn to_mathml(expression: &str, display: bool) -> Result<String, MathError> {
+    Ok(parse(expression, display)?.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{MathError, to_mathml};
+
+    #[test]
+    fn test_fraction_and_scripts() {
+        let mathml = to_mathml(r"\frac{a^2}{b_i} + \sqrt{x}", false).unwrap();
+        assert!(mathml.contains(
+            "<mfrac><mrow><msup><mi>a</mi><mn>2</mn></msup></mrow><mrow><msub><mi>b</mi><mi>i</mi></msub></mrow></mfrac>"
+        ));
+        assert!(mathml.contains("<msqrt><mrow><mi>x</mi></mrow></msqrt>"));
+        assert!(mathml.contains("<annotation encoding=\"application/x-tex\">"));
+    }
+
+    #[test]
+    fn test_right_prefixed_commands() {
+        let mathml = to_mathml(r"a \rightarrow b", false).unwrap();
+        assert!(mathml.contains("<mi>a</mi><mo>→</mo><mi>b</mi>"));
+
+        let mathml = to_mathml(r"\left( a \rightarrow b \right)", false).unwrap();
+        assert!(mathml.contains("<mo>(</mo><mi>a</mi><mo>→</mo><mi>b</mi><mo>)</mo>"));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            to_mathml(r"\begin{pmatrix}", true),
+            Err(MathError::UnsupportedMacro("begin".to_string()))
+        );
+        assert_eq!(to_mathml("{x", false), Err(MathError::UnbalancedBraces));
+        assert_eq!(to_mathml("x}", false), Err(MathError::UnbalancedBraces));
+        assert_eq!(to_mathml(&"{".repeat(100), false), Err(MathError::TooDeep));
+    }
+}
@@ -0,0 +1,488 @@
+//! Three-way merge of concurrent edits.
+//!
+//! [`merge`] combines the changes made in `ours` and `theirs` since `base`.
+//! Children are paired across revisions as in [`crate::diff`], and changes to
+//! different nodes, slots or props are merged automatically. Wherever both
+//! sides made incompatible changes, the merged document keeps our version,
+//! or both insertions, and a [`Conflict`] records what happened.
+
+use std::{collections::HashMap, fmt};
+
+use serde_json::{Map, Value};
+
+use crate::{
+    Component,
+    diff::match_children,
+    node::{NodeRef, SlotMut},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConflictKind {
+    /// Both sides set the prop to different values. `None` means unset.
+    Prop {
+        name: String,
+        base: Option<Value>,
+        ours: Option<Value>,
+        theirs: Option<Value>,
+    },
+    /// Both sides replaced the node with components of different types.
+    Node {
+        base: Component,
+        ours: Component,
+        theirs: Component,
+    },
+    /// One side deleted a node that the other changed. The deleting side is
+    /// `None`.
+    Delete {
+        ours: Option<Component>,
+        theirs: Option<Component>,
+    },
+    /// Both sides inserted different components at the same position.
+    Insert {
+        ours: Vec<Component>,
+        theirs: Vec<Component>,
+    },
+    /// Both sides reordered the children of the slot differently.
+    Order,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    /// Path in the merged document, e.g. `/0/slots/default/2`, of the node or,
+    /// for [`ConflictKind::Order`], of the slot.
+    pub path: String,
+    pub kind: ConflictKind,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ConflictKind::Prop { name, .. } => {
+                write!(f, "{}: both sides changed prop `{}`", self.path, name)
+            }
+            ConflictKind::Node { .. } => write!(f, "{}: both sides replaced the node", self.path),
+            ConflictKind::Delete { ours: None, .. } => {
+                write!(f, "{}: deleted by us but changed by them", self.path)
+            }
+            ConflictKind::Delete { .. } => {
+                write!(f, "{}: changed by us but deleted by them", self.path)
+            }
+            ConflictKind::Insert { .. } => {
+                write!(f, "{}: both sides inserted content here", self.path)
+            }
+            ConflictKind::Order => write!(f, "{}: both sides reordered the slot", self.path),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Merged {
+    pub components: Vec<Component>,
+    /// Empty when the merge was clean.
+    pub conflicts: Vec<Conflict>,
+}
+
+/// Merges the changes from `base` to `ours` and from `base` to `theirs`.
+pub fn merge(base: &[Component], ours: &[Component], theirs: &[Component]) -> Merged {
+    let mut conflicts = Vec::new();
+    let components = merge_slot(
+        &nodes(base),
+        &nodes(ours),
+        &nodes(theirs),
+        "",
+        &mut conflicts,
+    );
+    Merged {
+        components,
+        conflicts,
+    }
+}
+
+fn nodes(components: &[Component]) -> Vec<NodeRef<'_>> {
+    components.iter().map(NodeRef::Component).collect()
+}
+
+fn children<'a>(node: NodeRef<'a>, name: &str) -> Vec<NodeRef<'a>> {
+    node.slots()
+        .into_iter()
+        .find(|(slot_name, _)| *slot_name == name)
+        .map(|(_, slot)| slot.iter().collect())
+        .unwrap_or_default()
+}
+
+fn equal(a: NodeRef<'_>, b: NodeRef<'_>) -> bool {
+    match (a, b) {
+        (NodeRef::Component(a), NodeRef::Component(b)) => a == b,
+        _ => match (a.as_inline(), b.as_inline()) {
+            (Some(a), Some(b)) => a == b,
+            _ => false,
+        },
+    }
+}
+
+/// Children inserted by one side, keyed by the base index of the preceding
+/// matched child, or `None` at the start of the slot.
+fn insertions<'a>(
+    side: &[NodeRef<'a>],
+    pairs: &[Option<usize>],
+) -> HashMap<Option<usize>, Vec<NodeRef<'a>>> {
+    let mut insertions = HashMap::<_, Vec<_>>::new();
+    let mut anchor = None;
+    for (node, pair) in side.iter().zip(pairs) {
+        match pair {
+            Some(index) => anchor = Some(*index),
+            None => insertions.entry(anchor).or_default().push(*node),
+        }
+    }
+    insertions
+}
+
+fn merge_slot(
+    base: &[NodeRef<'_>],
+    ours: &[NodeRef<'_>],
+    theirs: &[NodeRef<'_>],
+    path: &str,
+    conflicts: &mut Vec<Conflict>,
+) -> Vec<Component> {
+    let ours_pairs = match_children(base, ours);
+    let theirs_pairs = match_children(base, theirs);
+    let mut in_ours = vec![None; base.len()];
+    let mut in_theirs = vec![None; base.len()];
+    for (index, pair) in ours_pairs.iter().enumerate() {
+        if let Some(base_index) = pair {
+            in_ours[*base_index] = Some(index);
+        }
+    }
+    for (index, pair) in theirs_pairs.iter().enumerate() {
+        if let Some(base_index) = pair {
+            in_theirs[*base_index] = Some(index);
+        }
+    }
+
+    // Follow the order of whichever side moved children around.
+    let ours_order = ours_pairs.iter().flatten().copied().collect::<Vec<_>>();
+    let theirs_order = theirs_pairs.iter().flatten().copied().collect::<Vec<_>>();
+    let sorted = |order: &[usize]| order.windows(2).all(|pair| pair[0] < pair[1]);
+    let order = match (sorted(&ours_order), sorted(&theirs_order)) {
+        (true, true) => (0..base.len()).collect(),
+        (false, true) => ours_order,
+        (true, false) => theirs_order,
+        (false, false) => {
+            let common = |order: &[usize], other: &[Option<usize>]| {
+                order
+                    .iter()
+                    .copied()
+                    .filter(|index| other[*index].is_some())
+                    .collect::<Vec<_>>()
+            };
+            if common(&ours_order, &in_theirs) != common(&theirs_order, &in_ours) {
+                conflicts.push(Conflict {
+                    path: path.to_string(),
+                    kind: ConflictKind::Order,
+                });
+            }
+            ours_order
+        }
+    };
+    // Children dropped by the chosen side go after their base predecessor.
+    let mut sequence = Vec::with_capacity(base.len());
+    let mut listed = vec![false; base.len()];
+    for index in order {
+        if !listed[index] {
+            listed[index] = true;
+            sequence.push(index);
+        }
+    }
+    for index in 0..base.len() {
+        if !listed[index] {
+            let position = (0..index)
+                .rev()
+                .find_map(|previous| sequence.iter().position(|&listed| listed == previous))
+                .map_or(0, |position| position + 1);
+            sequence.insert(position, index);
+        }
+    }
+
+    let mut ours_insertions = insertions(ours, &ours_pairs);
+    let mut theirs_insertions = insertions(theirs, &theirs_pairs);
+    let mut merged = Vec::new();
+    let mut insert = |anchor: Option<usize>, merged: &mut Vec<Component>| {
+        let ours = ours_insertions.remove(&anchor).unwrap_or_default();
+        let theirs = theirs_insertions.remove(&anchor).unwrap_or_default();
+        let identical = ours.len() == theirs.len()
+            && ours
+                .iter()
+                .zip(&theirs)
+                .all(|(ours, theirs)| equal(*ours, *theirs));
+        if !ours.is_empty() && !theirs.is_empty() && !identical {
+            conflicts.push(Conflict {
+                path: format!("{}/{}", path, merged.len()),
+                kind: ConflictKind::Insert {
+                    ours: ours.iter().map(NodeRef::to_component).collect(),
+                    theirs: theirs.iter().map(NodeRef::to_component).collect(),
+                },
+            });
+        }
+        merged.extend(ours.iter().map(NodeRef::to_component));
+        if !identical {
+            merged.extend(theirs.iter().map(NodeRef::to_component));
+        }
+    };
+
+    insert(None, &mut merged);
+    let mut node_conflicts = Vec::new();
+    for index in sequence {
+        let child_path = format!("{}/{}", path, merged.len());
+        let original = base[index];
+        match (in_ours[index], in_theirs[index]) {
+            (Some(o), Some(t)) => merged.push(merge_node(
+                original,
+                ours[o],
+                theirs[t],
+                &child_path,
+                &mut node_conflicts,
+            )),
+            (Some(o), None) if !equal(ours[o], original) => {
+                node_conflicts.push(Conflict {
+                    path: child_path,
+                    kind: ConflictKind::Delete {
+                        ours: Some(ours[o].to_component()),
+                        theirs: None,
+                    },
+                });
+                merged.push(ours[o].to_component());
+            }
+            (None, Some(t)) if !equal(theirs[t], original) => {
+                node_conflicts.push(Conflict {
+                    path: child_path,
+                    kind: ConflictKind::Delete {
+                        ours: None,
+                        theirs: Some(theirs[t].to_component()),
+                    },
+                });
+                merged.push(theirs[t].to_component());
+            }
+            _ => {}
+        }
+        insert(Some(index), &mut merged);
+    }
+    conflicts.extend(node_conflicts);
+    merged
+}
+
+fn merge_node(
+    base: NodeRef<'_>,
+    ours: NodeRef<'_>,
+    theirs: NodeRef<'_>,
+    path: &str,
+    conflicts: &mut Vec<Conflict>,
+) -> Component {
+    if equal(ours, base) {
+        return theirs.to_component();
+    }
+    if equal(theirs, base) || equal(ours, theirs) {
+        return ours.to_component();
+    }
+    let type_name = base.type_name();
+    if ours.type_name() != type_name || theirs.type_name() != type_name {
+        conflicts.push(Conflict {
+            path: path.to_string(),
+            kind: ConflictKind::Node {
+                base: base.to_component(),
+                ours: ours.to_component(),
+                theirs: theirs.to_component(),
+            },
+        });
+        return ours.to_component();
+    }
+
+    let mut merged = merge_props(base, ours, theirs, path, conflicts);
+
+    for (name, slot) in merged.slots_mut() {
+        let children = merge_slot(
+            &children(base, name),
+            &children(ours, name),
+            &children(theirs, name),
+            &format!("{}/slots/{}", path, name),
+            conflicts,
+        );
+        match slot {
+            SlotMut::Components(slot) => *slot = children,
+            SlotMut::Inlines(slot) => {
+                *slot = children
+                    .into_iter()
+                    .filter_map(|child| match child {
+                        Component::InlineComponent(inline_component) => Some(inline_component),
+                        Component::BlockComponent(_) => None,
+                    })
+                    .collect();
+            }
+        }
+    }
+    merged
+}
+
+/// Returns `ours` with the props merged prop by prop.
+fn merge_props(
+    base: NodeRef<'_>,
+    ours: NodeRef<'_>,
+    theirs: NodeRef<'_>,
+    path: &str,
+    conflicts: &mut Vec<Conflict>,
+) -> Component {
+    let (base_props, ours_props, theirs_props) = (base.props(), ours.props(), theirs.props());
+    if ours_props == theirs_props || theirs_props == base_props {
+        return ours.to_component();
+    }
+
+    let mut merged = Map::new();
+    let names = base_props
+        .keys()
+        .chain(ours_props.keys())
+        .chain(theirs_props.keys())
+        .fold(Vec::new(), |mut names, name| {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+            names
+        });
+    for name in names {
+        let (b, o, t) = (
+            base_props.get(&name),
+            ours_props.get(&name),
+            theirs_props.get(&name),
+        );
+        let value = if o == t || t == b {
+            o
+        } else if o == b {
+            t
+        } else {
+            conflicts.push(Conflict {
+                path: path.to_string(),
+                kind: ConflictKind::Prop {
+                    name: name.clone(),
+                    base: b.cloned(),
+                    ours: o.cloned(),
+                    theirs: t.cloned(),
+                },
+            });
+            o
+        };
+        if let Some(value) = value {
+            merged.insert(name, value.clone());
+        }
+    }
+
+    let mut json = match ours {
+        NodeRef::Component(component) => serde_json::to_value(component),
+        NodeRef::Inline(inline_component) => serde_json::to_value(inline_component),
+    }
+    .unwrap_or_default();
+    json["props"] = if merged.is_empty() {
+        Value::Null
+    } else {
+        Value::Object(merged)
+    };
+    // Props merged from valid revisions are valid unless they depend on
+    // each other; fall back to ours rather than produce an invalid node.
+    serde_json::from_value(json).unwrap_or_else(|_| ours.to_component())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{ConflictKind, merge};
+    use crate::Component;
+
+    fn parse(json: &str) -> Vec<Component> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn paragraph(text: &str) -> String {
+        format!(
+            r#"{{"type":"Paragraph","slots":{{"default":[{{"type":"Text","props":{{"text":"{}"}}}}]}}}}"#,
+            text
+        )
+    }
+
+    #[test]
+    fn test_merge_non_overlapping() {
+        let base = parse(&format!(
+            r#"[{{"type":"Heading","id":"h","props":{{"level":1}},"slots":{{"default":[{{"type":"Text","props":{{"text":"Title"}}}}]}}}},{},{},{}]"#,
+            paragraph("First paragraph"),
+            paragraph("Second paragraph"),
+            paragraph("Third paragraph")
+        ));
+        // Ours renames the title and drops the second paragraph.
+        let ours = parse(&format!(
+            r#"[{{"type":"Heading","id":"h","props":{{"level":1}},"slots":{{"default":[{{"type":"Text","props":{{"text":"New title"}}}}]}}}},{},{}]"#,
+            paragraph("First paragraph"),
+            paragraph("Third paragraph")
+        ));
+        // Theirs changes the heading level and appends a divider.
+        let theirs = parse(&format!(
+            r#"[{{"type":"Heading","id":"h","props":{{"level":2}},"slots":{{"default":[{{"type":"Text","props":{{"text":"Title"}}}}]}}}},{},{},{},{{"type":"Divider"}}]"#,
+            paragraph("First paragraph"),
+            paragraph("Second paragraph"),
+            paragraph("Third paragraph")
+        ));
+
+        let merged = merge(&base, &ours, &theirs);
+        assert_eq!(merged.conflicts, Vec::new());
+        assert_eq!(
+            merged.components,
+            parse(&format!(
+                r#"[{{"type":"Heading","id":"h","props":{{"level":2}},"slots":{{"default":[{{"type":"Text","props":{{"text":"New title"}}}}]}}}},{},{},{{"type":"Divider"}}]"#,
+                paragraph("First paragraph"),
+                paragraph("Third paragraph")
+            ))
+        );
+    }
+
+    #[test]
+    fn test_merge_conflicts() {
+        let base = parse(&format!("[{},{}]", paragraph("Hello"), paragraph("Bye")));
+        let ours = parse(&format!(
+            "[{},{}]",
+            paragraph("Hello, world"),
+            paragraph("Bye now")
+        ));
+        let theirs = parse(&format!("[{}]", paragraph("Hello there")));
+
+        let merged = merge(&base, &ours, &theirs);
+        assert_eq!(merged.components, ours);
+        assert_eq!(
+            merged
+                .conflicts
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "/0/slots/default/0: both sides changed prop `text`",
+                "/1: changed by us but deleted by them",
+            ]
+        );
+        assert!(matches!(
+            &merged.conflicts[0].kind,
+            ConflictKind::Prop { ours: Some(ours), theirs: Some(theirs), .. }
+                if ours == "Hello, world" && theirs == "Hello there"
+        ));
+    }
+
+    #[test]
+    fn test_merge_props() {
+        let text = |props: &str| {
+            parse(&format!(
+                r#"[{{"type":"Paragraph","slots":{{"default":[{{"type":"Text","props":{{"text":"Hi"{}}}}}]}}}}]"#,
+                props
+            ))
+        };
+        let base = text("");
+        let ours = text(r#","bold":true"#);
+        let theirs = text(r#","italic":true"#);
+
+        let merged = merge(&base, &ours, &theirs);
+        assert_eq!(merged.conflicts, Vec::new());
+        assert_eq!(merged.components, text(r#","bold":true,"italic":true"#));
+    }
+}
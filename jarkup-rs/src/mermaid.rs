@@ -0,0 +1,434 @@
+//! Lightweight checks for Mermaid diagram sources.
+//!
+//! This is not a full Mermaid parser. It detects the diagram kind from the
+//! header line and catches the mistakes that most often break rendering:
+//! unknown headers, unbalanced brackets and quotes, unclosed blocks and
+//! malformed statements in sequence, gantt and pie diagrams.
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagramKind {
+    Flowchart,
+    Sequence,
+    Class,
+    State,
+    EntityRelationship,
+    Journey,
+    Gantt,
+    Pie,
+    Quadrant,
+    Requirement,
+    GitGraph,
+    Mindmap,
+    Timeline,
+    C4,
+    Sankey,
+    XyChart,
+    Block,
+    Packet,
+    Architecture,
+    Kanban,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MermaidError {
+    /// 1-based line number within the diagram source.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for MermaidError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for MermaidError {}
+
+fn kind(keyword: &str) -> Option<DiagramKind> {
+    let kind = match keyword {
+        "graph" | "flowchart" | "flowchart-elk" => DiagramKind::Flowchart,
+        "sequenceDiagram" => DiagramKind::Sequence,
+        "classDiagram" | "classDiagram-v2" => DiagramKind::Class,
+        "stateDiagram" | "stateDiagram-v2" => DiagramKind::State,
+        "erDiagram" => DiagramKind::EntityRelationship,
+        "journey" => DiagramKind::Journey,
+        "gantt" => DiagramKind::Gantt,
+        "pie" => DiagramKind::Pie,
+        "quadrantChart" => DiagramKind::Quadrant,
+        "requirementDiagram" => DiagramKind::Requirement,
+        "gitGraph" => DiagramKind::GitGraph,
+        "mindmap" => DiagramKind::Mindmap,
+        "timeline" => DiagramKind::Timeline,
+        "C4Context" | "C4Container" | "C4Component" | "C4Dynamic" | "C4Deployment" => {
+            DiagramKind::C4
+        }
+        "sankey-beta" => DiagramKind::Sankey,
+        "xychart-beta" => DiagramKind::XyChart,
+        "block-beta" => DiagramKind::Block,
+        "packet-beta" => DiagramKind::Packet,
+        "architecture-beta" => DiagramKind::Architecture,
+        "kanban" => DiagramKind::Kanban,
+        _ => return None,
+    };
+    Some(kind)
+}
+
+/// Returns the significant lines of `code` with their 1-based line numbers,
+/// skipping blank lines, `%%` comments, directives and YAML front matter.
+fn statements(code: &str) -> Vec<(usize, &str)> {
+    let mut lines = code.lines().enumerate().peekable();
+    let mut statements = Vec::new();
+
+    while lines.peek().is_some_and(|(_, line)| line.trim().is_empty()) {
+        lines.next();
+    }
+    if lines.peek().is_some_and(|(_, line)| line.trim() == "---") {
+        lines.next();
+        for (_, line) in lines.by_ref() {
+            if line.trim() == "---" {
+                break;
+            }
+        }
+    }
+
+    for (index, line) in lines {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("%%") {
+            continue;
+        }
+        statements.push((index + 1, line));
+    }
+    statements
+}
+
+/// Detects the diagram kind from the header line of `code`.
+pub fn detect(code: &str) -> Option<DiagramKind> {
+    let (_, header) = statements(code).into_iter().next()?;
+    kind(header.split_whitespace().next()?)
+}
+
+/// Checks `code` and returns its diagram kind, or every problem found.
+pub fn validate(code: &str) -> Result<DiagramKind, Vec<MermaidError>> {
+    let statements = statements(code);
+    let Some(&(header_line, header)) = statements.first() else {
+        return Err(vec![MermaidError {
+            line: 1,
+            message: "diagram is empty".to_string(),
+        }]);
+    };
+
+    let mut words = header.split_whitespace();
+    let keyword = words.next().unwrap_or_default();
+    let Some(kind) = kind(keyword) else {
+        return Err(vec![MermaidError {
+            line: header_line,
+            message: format!("unknown diagram type `{}`", keyword),
+        }]);
+    };
+
+    let mut errors = Vec::new();
+    // Statements may be terminated by `;`, as in `graph TD;`.
+    if kind == DiagramKind::Flowchart
+        && let Some(direction) = words.next().and_then(|word| word.split(';').next())
+        && !matches!(direction, "" | "TB" | "TD" | "BT" | "RL" | "LR")
+    {
+        errors.push(MermaidError {
+            line: header_line,
+            message: format!("unknown flowchart direction `{}`", direction),
+        });
+    }
+
+    let body = &statements[1..];
+    match kind {
+        DiagramKind::Flowchart => {
+            check_brackets(body, true, &mut errors);
+            check_blocks(body, &["subgraph"], &mut errors);
+        }
+        DiagramKind::Sequence => check_sequence(body, &mut errors),
+        DiagramKind::Class | DiagramKind::State | DiagramKind::EntityRelationship => {
+            check_brackets(body, false, &mut errors);
+            check_braces(body, &mut errors);
+        }
+        DiagramKind::Gantt => check_gantt(body, &mut errors),
+        DiagramKind::Pie => check_pie(body, &mut errors),
+        _ => {}
+    }
+
+    if errors.is_empty() {
+        Ok(kind)
+    } else {
+        errors.sort_by_key(|error| error.line);
+        Err(errors)
+    }
+}
+
+/// Checks that `()`, `[]` and quotes are balanced within each line. Braces
+/// are excluded because class and state diagrams open blocks with them. With
+/// `asymmetric`, a `>` right after a node id opens the flowchart shape
+/// `id>text]`.
+fn check_brackets(statements: &[(usize, &str)], asymmetric: bool, errors: &mut Vec<MermaidError>) {
+    for &(line, statement) in statements {
+        let mut stack = Vec::new();
+        let mut quoted = false;
+        let mut previous = ' ';
+        for c in statement.chars() {
+            let after_id = previous.is_alphanumeric() || previous == '_';
+            previous = c;
+            if c == '"' {
+                quoted = !quoted;
+                continue;
+            }
+            if quoted {
+                continue;
+            }
+            match c {
+                '(' | '[' => stack.push(c),
+                '>' if asymmetric && after_id && stack.is_empty() => stack.push(c),
+                ')' | ']' => {
+                    let matched = match stack.pop() {
+                        Some('(') => c == ')',
+                        Some('[' | '>') => c == ']',
+                        _ => false,
+                    };
+                    if !matched {
+                        errors.push(MermaidError {
+                            line,
+                            message: format!("unexpected `{}`", c),
+                        });
+                        stack.clear();
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        if quoted {
+            errors.push(MermaidError {
+                line,
+                message: "unterminated string".to_string(),
+            });
+        } else if let Some(open) = stack.last() {
+            errors.push(MermaidError {
+                line,
+                message: format!("unclosed `{}`", open),
+            });
+        }
+    }
+}
+
+/// Checks that lines ending with `{` are matched by a line consisting of `}`.
+fn check_braces(statements: &[(usize, &str)], errors: &mut Vec<MermaidError>) {
+    let mut open = Vec::new();
+    for &(line, statement) in statements {
+        if statement.ends_with('{') {
+            open.push(line);
+        } else if statement == "}" && open.pop().is_none() {
+            errors.push(MermaidError {
+                line,
+                message: "`}` without a matching `{`".to_string(),
+            });
+        }
+    }
+    for line in open {
+        errors.push(MermaidError {
+            line,
+            message: "`{` is never closed".to_string(),
+        });
+    }
+}
+
+/// Checks that every statement starting with one of `openers` has an `end`.
+fn check_blocks(statements: &[(usize, &str)], openers: &[&str], errors: &mut Vec<MermaidError>) {
+    let mut open = Vec::new();
+    for &(line, statement) in statements {
+        let keyword = statement.split_whitespace().next().unwrap_or_default();
+        if openers.contains(&keyword) {
+            open.push((line, keyword));
+        } else if keyword == "end" && open.pop().is_none() {
+            errors.push(MermaidError {
+                line,
+                message: "`end` without a matching block".to_string(),
+            });
+        }
+    }
+    for (line, keyword) in open {
+        errors.push(MermaidError {
+            line,
+            message: format!("`{}` is never closed with `end`", keyword),
+        });
+    }
+}
+
+fn check_sequence(statements: &[(usize, &str)], errors: &mut Vec<MermaidError>) {
+    const BLOCKS: &[&str] = &[
+        "loop", "alt", "opt", "par", "critical", "break", "rect", "box",
+    ];
+    const KEYWORDS: &[&str] = &[
+        "participant",
+        "actor",
+        "create",
+        "destroy",
+        "note",
+        "Note",
+        "activate",
+        "deactivate",
+        "autonumber",
+        "title",
+        "else",
+        "and",
+        "option",
+        "links",
+        "link",
+        "properties",
+        "details",
+        "accTitle",
+        "accDescr",
+        "end",
+    ];
+    const ARROWS: &[&str] = &[
+        "-->>", "->>", "-->", "->", "--x", "-x", "--)", "-)", "<<->>", "<<-->>",
+    ];
+
+    check_blocks(statements, BLOCKS, errors);
+
+    for &(line, statement) in statements {
+        let keyword = statement
+            .split(|c: char| c.is_whitespace() || c == ':')
+            .next()
+            .unwrap_or_default();
+        if BLOCKS.contains(&keyword) || KEYWORDS.contains(&keyword) {
+            continue;
+        }
+        let head = statement.split(':').next().unwrap_or_default();
+        if !ARROWS.iter().any(|arrow| head.contains(arrow)) {
+            errors.push(MermaidError {
+                line,
+                message: format!(
+                    "expected a message like `A->>B: text`, found `{}`",
+                    statement
+                ),
+            });
+        } else if !statement.contains(':') {
+            errors.push(MermaidError {
+                line,
+                message: "message is missing `: text`".to_string(),
+            });
+        }
+    }
+}
+
+fn check_gantt(statements: &[(usize, &str)], errors: &mut Vec<MermaidError>) {
+    const KEYWORDS: &[&str] = &[
+        "dateFormat",
+        "axisFormat",
+        "tickInterval",
+        "title",
+        "section",
+        "excludes",
+        "includes",
+        "todayMarker",
+        "weekday",
+        "weekend",
+        "inclusiveEndDates",
+        "topAxis",
+        "displayMode",
+        "accTitle",
+        "accDescr",
+        "click",
+    ];
+
+    for &(line, statement) in statements {
+        let keyword = statement
+            .split(|c: char| c.is_whitespace() || c == ':')
+            .next()
+            .unwrap_or_default();
+        if KEYWORDS.contains(&keyword) {
+            continue;
+        }
+        match statement.split_once(':') {
+            Some((name, data)) if !name.trim().is_empty() && !data.trim().is_empty() => {}
+            _ => errors.push(MermaidError {
+                line,
+                message: format!("expected a task like `name : data`, found `{}`", statement),
+            }),
+        }
+    }
+}
+
+fn check_pie(statements: &[(usize, &str)], errors: &mut Vec<MermaidError>) {
+    for &(line, statement) in statements {
+        if statement.starts_with("title") || statement == "showData" {
+            continue;
+        }
+        let valid = statement.split_once(':').is_some_and(|(label, value)| {
+            let label = label.trim();
+            label.len() >= 2
+                && label.starts_with('"')
+                && label.ends_with('"')
+                && value.trim().parse::<f64>().is_ok()
+        });
+        if !valid {
+            errors.push(MermaidError {
+                line,
+                message: format!(
+                    "expected a slice like `\"label\" : 42`, found `{}`",
+                    statement
+                ),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{DiagramKind, detect, validate};
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(detect("graph TD\n  A-->B"), Some(DiagramKind::Flowchart));
+        assert_eq!(
+            detect("---\ntitle: Example\n---\n%% comment\nsequenceDiagram\n  A->>B: hi"),
+            Some(DiagramKind::Sequence)
+        );
+        assert_eq!(
+            detect("stateDiagram-v2\n  [*] --> S"),
+            Some(DiagramKind::State)
+        );
+        assert_eq!(detect("nonsense"), None);
+    }
+
+    #[test]
+    fn test_validate_reports_lines() {
+        assert_eq!(
+            validate("flowchart LR\n  A[Start] --> B{Decide}\n  B -->|yes| C(Done)"),
+            Ok(DiagramKind::Flowchart)
+        );
+
+        let errors = validate("flowchart LR\n  A[Start --> B\n  subgraph one\n  C").unwrap_err();
+        assert_eq!(
+            errors.iter().map(|error| error.line).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+
+        let errors = validate("sequenceDiagram\n  Alice->>Bob: Hello\n  Bob Alice\n  loop Every minute\n  Bob-->>Alice").unwrap_err();
+        assert_eq!(
+            errors.iter().map(|error| error.line).collect::<Vec<_>>(),
+            vec![3, 4, 5]
+        );
+
+        assert_eq!(
+            validate("graph TD;\n  A-->B;\n  id1>Flagged]-->B"),
+            Ok(DiagramKind::Flowchart)
+        );
+        assert_eq!(
+            validate("classDiagram\n  class Shape {\n    <<interface>>\n  }"),
+            Ok(DiagramKind::Class)
+        );
+
+        let errors = validate("pie title Pets\n  \"Dogs\" : 386\n  Cats : many").unwrap_err();
+        assert_eq!(errors[0].line, 3);
+    }
+}
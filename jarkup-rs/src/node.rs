@@ -0,0 +1,471 @@
+//! Uniform access to the type name and named slots of components.
+
+use serde::{
+    Deserialize,
+    de::{self, Deserializer, Visitor},
+    forward_to_deserialize_any,
+};
+use serde_json::{Map, Value};
+
+use crate::{BlockComponent, Component, InlineComponent};
+
+macro_rules! block_dispatch {
+    ($value:expr, $binding:ident => $body:expr) => {
+        match $value {
+            BlockComponent::Fragment($binding) => $body,
+            BlockComponent::Heading($binding) => $body,
+            BlockComponent::Paragraph($binding) => $body,
+            BlockComponent::ListItem($binding) => $body,
+            BlockComponent::List($binding) => $body,
+            BlockComponent::BlockQuote($binding) => $body,
+            BlockComponent::Callout($binding) => $body,
+            BlockComponent::Divider($binding) => $body,
+            BlockComponent::Toggle($binding) => $body,
+            BlockComponent::Bookmark($binding) => $body,
+            BlockComponent::File($binding) => $body,
+            BlockComponent::Image($binding) => $body,
+            BlockComponent::CodeBlock($binding) => $body,
+            BlockComponent::Katex($binding) => $body,
+            BlockComponent::Mermaid($binding) => $body,
+            BlockComponent::Tab($binding) => $body,
+            BlockComponent::Tabs($binding) => $body,
+            BlockComponent::Table($binding) => $body,
+            BlockComponent::TableRow($binding) => $body,
+            BlockComponent::TableCell($binding) => $body,
+            BlockComponent::ColumnList($binding) => $body,
+            BlockComponent::Column($binding) => $body,
+            BlockComponent::Unsupported($binding) => $body,
+        }
+    };
+}
+
+/// A borrowed slot, holding either block-level or inline children.
+#[derive(Debug, Clone, Copy)]
+pub enum Slot<'a> {
+    Components(&'a Vec<Component>),
+    Inlines(&'a Vec<InlineComponent>),
+}
+
+/// A mutably borrowed slot, holding either block-level or inline children.
+#[derive(Debug)]
+pub enum SlotMut<'a> {
+    Components(&'a mut Vec<Component>),
+    Inlines(&'a mut Vec<InlineComponent>),
+}
+
+impl Slot<'_> {
+    pub fn len(&self) -> usize {
+        match self {
+            Slot::Components(components) => components.len(),
+            Slot::Inlines(inlines) => inlines.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<'a> Slot<'a> {
+    pub fn get(&self, index: usize) -> Option<NodeRef<'a>> {
+        match *self {
+            Slot::Components(components) => components.get(index).map(NodeRef::Component),
+            Slot::Inlines(inlines) => inlines.get(index).map(NodeRef::Inline),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = NodeRef<'a>> + use<'a> {
+        let slot = *self;
+        (0..slot.len()).filter_map(move |index| slot.get(index))
+    }
+}
+
+/// A borrowed child of either kind of slot.
+#[derive(Debug, Clone, Copy)]
+pub enum NodeRef<'a> {
+    Component(&'a Component),
+    Inline(&'a InlineComponent),
+}
+
+impl<'a> NodeRef<'a> {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            NodeRef::Component(component) => component.type_name(),
+            NodeRef::Inline(inline_component) => inline_component.type_name(),
+        }
+    }
+
+    pub fn id(&self) -> Option<&'a str> {
+        match *self {
+            NodeRef::Component(component) => component.id(),
+            NodeRef::Inline(inline_component) => inline_component.id(),
+        }
+    }
+
+    pub fn slots(&self) -> Vec<(&'static str, Slot<'a>)> {
+        match *self {
+            NodeRef::Component(component) => component.slots(),
+            NodeRef::Inline(_) => Vec::new(),
+        }
+    }
+
+    /// Returns the non-null props of this node as a JSON object, without
+    /// serializing its slots.
+    pub(crate) fn props(&self) -> Map<String, Value> {
+        let props = match *self {
+            NodeRef::Component(Component::BlockComponent(block_component)) => {
+                block_dispatch!(block_component, component => serde_json::to_value(&component.props))
+            }
+            NodeRef::Component(Component::InlineComponent(inline_component))
+            | NodeRef::Inline(inline_component) => match inline_component {
+                InlineComponent::Text(text) => serde_json::to_value(&text.props),
+                InlineComponent::Icon(icon) => serde_json::to_value(&icon.props),
+            },
+        };
+        match props {
+            Ok(Value::Object(mut props)) => {
+                props.retain(|_, value| !value.is_null());
+                props
+            }
+            _ => Map::new(),
+        }
+    }
+
+    /// Returns the names of every prop this type of node accepts, whether
+    /// set or not, as they appear in JSON.
+    pub(crate) fn prop_names(&self) -> &'static [&'static str] {
+        match *self {
+            NodeRef::Component(Component::BlockComponent(block_component)) => {
+                block_dispatch!(block_component, component => fields(&component.props))
+            }
+            NodeRef::Component(Component::InlineComponent(inline_component))
+            | NodeRef::Inline(inline_component) => match inline_component {
+                InlineComponent::Text(text) => fields(&text.props),
+                InlineComponent::Icon(icon) => fields(&icon.props),
+            },
+        }
+    }
+
+    pub fn as_inline(&self) -> Option<&'a InlineComponent> {
+        match *self {
+            NodeRef::Component(Component::InlineComponent(inline_component)) => {
+                Some(inline_component)
+            }
+            NodeRef::Component(Component::BlockComponent(_)) => None,
+            NodeRef::Inline(inline_component) => Some(inline_component),
+        }
+    }
+
+    pub fn to_component(&self) -> Component {
+        match *self {
+            NodeRef::Component(component) => component.clone(),
+            NodeRef::Inline(inline_component) => {
+                Component::InlineComponent(inline_component.clone())
+            }
+        }
+    }
+}
+
+/// A mutably borrowed child of either kind of slot.
+#[derive(Debug)]
+pub enum NodeMut<'a> {
+    Component(&'a mut Component),
+    Inline(&'a mut InlineComponent),
+}
+
+impl NodeMut<'_> {
+    pub fn as_ref(&self) -> NodeRef<'_> {
+        match self {
+            NodeMut::Component(component) => NodeRef::Component(component),
+            NodeMut::Inline(inline_component) => NodeRef::Inline(inline_component),
+        }
+    }
+
+    pub fn slots_mut(&mut self) -> Vec<(&'static str, SlotMut<'_>)> {
+        match self {
+            NodeMut::Component(component) => component.slots_mut(),
+            NodeMut::Inline(_) => Vec::new(),
+        }
+    }
+}
+
+/// Returns the field names of the struct `T` deserializes from, looking
+/// through `Option`.
+fn fields<'de, T>(_: &T) -> &'static [&'static str]
+where
+    T: Deserialize<'de>,
+{
+    let mut fields = Fields(&[]);
+    let _ = T::deserialize(&mut fields);
+    fields.0
+}
+
+/// A deserializer that records the fields it is asked for and fails.
+struct Fields(&'static [&'static str]);
+
+impl<'de> Deserializer<'de> for &mut Fields {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V>(self, _: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(de::Error::custom("not a struct"))
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _: &'static str,
+        fields: &'static [&'static str],
+        _: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.0 = fields;
+        Err(de::Error::custom("fields recorded"))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct newtype_struct seq tuple tuple_struct
+        map enum identifier ignored_any
+    }
+}
+
+impl Component {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Component::InlineComponent(inline_component) => inline_component.type_name(),
+            Component::BlockComponent(block_component) => block_component.type_name(),
+        }
+    }
+
+    pub fn id(&self) -> Option<&str> {
+        match self {
+            Component::InlineComponent(inline_component) => inline_component.id(),
+            Component::BlockComponent(block_component) => block_component.id(),
+        }
+    }
+
+    /// Returns the slots present on this component, in declaration order.
+    pub fn slots(&self) -> Vec<(&'static str, Slot<'_>)> {
+        match self {
+            Component::InlineComponent(_) => Vec::new(),
+            Component::BlockComponent(block_component) => block_component.slots(),
+        }
+    }
+
+    /// Returns the slots present on this component, in declaration order.
+    pub fn slots_mut(&mut self) -> Vec<(&'static str, SlotMut<'_>)> {
+        match self {
+            Component::InlineComponent(_) => Vec::new(),
+            Component::BlockComponent(block_component) => block_component.slots_mut(),
+        }
+    }
+}
+
+impl InlineComponent {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            InlineComponent::Text(_) => "Text",
+            InlineComponent::Icon(_) => "Icon",
+        }
+    }
+
+    pub fn id(&self) -> Option<&str> {
+        match self {
+            InlineComponent::Text(text) => text.id.as_deref(),
+            InlineComponent::Icon(icon) => icon.id.as_deref(),
+        }
+    }
+}
+
+impl BlockComponent {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            BlockComponent::Fragment(_) => "Fragment",
+            BlockComponent::Heading(_) => "Heading",
+            BlockComponent::Paragraph(_) => "Paragraph",
+            BlockComponent::ListItem(_) => "ListItem",
+            BlockComponent::List(_) => "List",
+            BlockComponent::BlockQuote(_) => "BlockQuote",
+            BlockComponent::Callout(_) => "Callout",
+            BlockComponent::Divider(_) => "Divider",
+            BlockComponent::Toggle(_) => "Toggle",
+            BlockComponent::Bookmark(_) => "Bookmark",
+            BlockComponent::File(_) => "File",
+            BlockComponent::Image(_) => "Image",
+            BlockComponent::CodeBlock(_) => "CodeBlock",
+            BlockComponent::Katex(_) => "Katex",
+            BlockComponent::Mermaid(_) => "Mermaid",
+            BlockComponent::Tab(_) => "Tab",
+            BlockComponent::Tabs(_) => "Tabs",
+            BlockComponent::Table(_) => "Table",
+            BlockComponent::TableRow(_) => "TableRow",
+            BlockComponent::TableCell(_) => "TableCell",
+            BlockComponent::ColumnList(_) => "ColumnList",
+            BlockComponent::Column(_) => "Column",
+            BlockComponent::Unsupported(_) => "Unsupported",
+        }
+    }
+
+    pub fn id(&self) -> Option<&str> {
+        block_dispatch!(self, component => component.id.as_deref())
+    }
+
+    /// Returns the slots present on this component, in declaration order.
+    ///
+    /// Optional slots that are `None` are omitted.
+    pub fn slots(&self) -> Vec<(&'static str, Slot<'_>)> {
+        match self {
+            BlockComponent::Fragment(fragment) => {
+                vec![("default", Slot::Components(&fragment.slots.default))]
+            }
+            BlockComponent::Heading(heading) => {
+                vec![("default", Slot::Inlines(&heading.slots.default))]
+            }
+            BlockComponent::Paragraph(paragraph) => {
+                vec![("default", Slot::Inlines(&paragraph.slots.default))]
+            }
+            BlockComponent::ListItem(list_item) => {
+                vec![("default", Slot::Components(&list_item.slots.default))]
+            }
+            BlockComponent::List(list) => {
+                vec![("default", Slot::Components(&list.slots.default))]
+            }
+            BlockComponent::BlockQuote(block_quote) => {
+                vec![("default", Slot::Components(&block_quote.slots.default))]
+            }
+            BlockComponent::Callout(callout) => {
+                vec![("default", Slot::Components(&callout.slots.default))]
+            }
+            BlockComponent::Toggle(toggle) => vec![
+                ("default", Slot::Components(&toggle.slots.default)),
+                ("summary", Slot::Inlines(&toggle.slots.summary)),
+            ],
+            BlockComponent::CodeBlock(code_block) => match &code_block.slots {
+                Some(slots) => vec![("default", Slot::Inlines(&slots.default))],
+                None => Vec::new(),
+            },
+            BlockComponent::Tab(tab) => vec![
+                ("labels", Slot::Inlines(&tab.slots.labels)),
+                ("contents", Slot::Components(&tab.slots.contents)),
+            ],
+            BlockComponent::Tabs(tabs) => {
+                vec![("default", Slot::Components(&tabs.slots.default))]
+            }
+            BlockComponent::Table(table) => {
+                let mut slots = Vec::new();
+                if let Some(header) = &table.slots.header {
+                    slots.push(("header", Slot::Components(header)));
+                }
+                slots.push(("body", Slot::Components(&table.slots.body)));
+                slots
+            }
+            BlockComponent::TableRow(table_row) => {
+                vec![("default", Slot::Components(&table_row.slots.default))]
+            }
+            BlockComponent::TableCell(table_cell) => {
+                vec![("default", Slot::Inlines(&table_cell.slots.default))]
+            }
+            BlockComponent::ColumnList(column_list) => {
+                vec![("default", Slot::Components(&column_list.slots.default))]
+            }
+            BlockComponent::Column(column) => {
+                vec![("default", Slot::Components(&column.slots.default))]
+            }
+            BlockComponent::Divider(_)
+            | BlockComponent::Bookmark(_)
+            | BlockComponent::File(_)
+            | BlockComponent::Image(_)
+            | BlockComponent::Katex(_)
+            | BlockComponent::Mermaid(_)
+            | BlockComponent::Unsupported(_) => Vec::new(),
+        }
+    }
+
+    /// Returns the slots present on this component, in declaration order.
+    ///
+    /// Optional slots that are `None` are omitted.
+    pub fn slots_mut(&mut self) -> Vec<(&'static str, SlotMut<'_>)> {
+        match self {
+            BlockComponent::Fragment(fragment) => {
+                vec![("default", SlotMut::Components(&mut fragment.slots.default))]
+            }
+            BlockComponent::Heading(heading) => {
+                vec![("default", SlotMut::Inlines(&mut heading.slots.default))]
+            }
+            BlockComponent::Paragraph(paragraph) => {
+                vec![("default", SlotMut::Inlines(&mut paragraph.slots.default))]
+            }
+            BlockComponent::ListItem(list_item) => {
+                vec![("default", SlotMut::Components(&mut list_item.slots.default))]
+            }
+            BlockComponent::List(list) => {
+                vec![("default", SlotMut::Components(&mut list.slots.default))]
+            }
+            BlockComponent::BlockQuote(block_quote) => {
+                vec![(
+                    "default",
+                    SlotMut::Components(&mut block_quote.slots.default),
+                )]
+            }
+            BlockComponent::Callout(callout) => {
+                vec![("default", SlotMut::Components(&mut callout.slots.default))]
+            }
+            BlockComponent::Toggle(toggle) => vec![
+                ("default", SlotMut::Components(&mut toggle.slots.default)),
+                ("summary", SlotMut::Inlines(&mut toggle.slots.summary)),
+            ],
+            BlockComponent::CodeBlock(code_block) => match &mut code_block.slots {
+                Some(slots) => vec![("default", SlotMut::Inlines(&mut slots.default))],
+                None => Vec::new(),
+            },
+            BlockComponent::Tab(tab) => vec![
+                ("labels", SlotMut::Inlines(&mut tab.slots.labels)),
+                ("contents", SlotMut::Components(&mut tab.slots.contents)),
+            ],
+            BlockComponent::Tabs(tabs) => {
+                vec![("default", SlotMut::Components(&mut tabs.slots.default))]
+            }
+            BlockComponent::Table(table) => {
+                let mut slots = Vec::new();
+                if let Some(header) = &mut table.slots.header {
+                    slots.push(("header", SlotMut::Components(header)));
+                }
+                slots.push(("body", SlotMut::Components(&mut table.slots.body)));
+                slots
+            }
+            BlockComponent::TableRow(table_row) => {
+                vec![("default", SlotMut::Components(&mut table_row.slots.default))]
+            }
+            BlockComponent::TableCell(table_cell) => {
+                vec![("default", SlotMut::Inlines(&mut table_cell.slots.default))]
+            }
+            BlockComponent::ColumnList(column_list) => {
+                vec![(
+                    "default",
+                    SlotMut::Components(&mut column_list.slots.default),
+                )]
+            }
+            BlockComponent::Column(column) => {
+                vec![("default", SlotMut::Components(&mut column.slots.default))]
+            }
+            BlockComponent::Divider(_)
+            | BlockComponent::Bookmark(_)
+            | BlockComponent::File(_)
+            | BlockComponent::Image(_)
+            | BlockComponent::Katex(_)
+            | BlockComponent::Mermaid(_)
+            | BlockComponent::Unsupported(_) => Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,1276 @@
+//! HTML importer.
+//!
+//! [`parse`] accepts an HTML fragment (or a whole page) and converts it into
+//! jarkup components. Like readability tools, it drops scripts, styles,
+//! navigation, forms and other page chrome, unwraps layout containers such as
+//! `<div>` and `<section>`, collapses whitespace outside `<pre>` and discards
+//! empty paragraphs. Elements without a jarkup counterpart become
+//! [`Unsupported`] components whose details hold the original start tag.
+
+use crate::{
+    BlockComponent, BlockQuote, BlockQuoteProps, Callout, CalloutProps, CalloutSlots, CalloutType,
+    CodeBlock, CodeBlockProps, Component, Divider, Heading, HeadingLevel, HeadingProps,
+    HeadingSlots, Icon, IconProps, Image, ImageProps, InlineComponent, Katex, KatexProps, List,
+    ListItem, ListItemSlots, ListProps, ListSlots, ListStyle, Paragraph, ParagraphProps,
+    ParagraphSlots, Table, TableCell, TableCellProps, TableCellSlots, TableProps, TableRow,
+    TableRowSlots, TableSlots, Text, TextProps, Toggle, ToggleSlots, Unsupported, UnsupportedProps,
+};
+
+// DOM # -------------------------------------------------- #
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Element(Element),
+    Text(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Node>,
+}
+
+impl Element {
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn text_content(&self) -> String {
+        let mut text = String::new();
+        collect_text(&self.children, &mut text);
+        text
+    }
+
+    fn start_tag(&self) -> String {
+        let mut tag = format!("<{}", self.name);
+        for (name, value) in &self.attributes {
+            tag.push_str(&format!(" {}=\"{}\"", name, value));
+        }
+        tag.push('>');
+        tag
+    }
+}
+
+fn collect_text(nodes: &[Node], text: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(content) => text.push_str(content),
+            Node::Element(element) => collect_text(&element.children, text),
+        }
+    }
+}
+
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Elements nested deeper than this are dropped, keeping their content.
+const MAX_DEPTH: usize = 128;
+
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style", "textarea", "title"];
+
+/// Elements that may appear inside a paragraph without closing it.
+const PHRASING_ELEMENTS: &[&str] = &[
+    "a", "abbr", "b", "bdi", "bdo", "br", "cite", "code", "data", "del", "dfn", "em", "font", "i",
+    "img", "ins", "kbd", "label", "mark", "math", "q", "rp", "rt", "ruby", "s", "samp", "small",
+    "span", "strike", "strong", "sub", "sup", "time", "tt", "u", "var", "wbr",
+];
+
+pub(super) fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let entity = rest[1..]
+            .find(';')
+            .filter(|end| *end <= 32)
+            .map(|end| &rest[1..end + 1]);
+        let character = entity.and_then(|entity| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some('\u{a0}'),
+            "copy" => Some('©'),
+            "reg" => Some('®'),
+            "trade" => Some('™'),
+            "hellip" => Some('…'),
+            "mdash" => Some('—'),
+            "ndash" => Some('–'),
+            "lsquo" => Some('‘'),
+            "rsquo" => Some('’'),
+            "ldquo" => Some('“'),
+            "rdquo" => Some('”'),
+            "middot" => Some('·'),
+            "times" => Some('×'),
+            _ => {
+                let number = entity.strip_prefix('#')?;
+                let code = match number.strip_prefix(['x', 'X']) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                    None => number.parse().ok()?,
+                };
+                char::from_u32(code)
+            }
+        });
+
+        match (entity, character) {
+            (Some(entity), Some(character)) => {
+                decoded.push(character);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+struct TreeBuilder {
+    root: Vec<Node>,
+    stack: Vec<Element>,
+}
+
+impl TreeBuilder {
+    fn append(&mut self, node: Node) {
+        match self.stack.last_mut() {
+            Some(parent) => parent.children.push(node),
+            None => self.root.push(node),
+        }
+    }
+
+    /// Closes the open elements from the top of the stack down to `index`.
+    fn close(&mut self, index: usize) {
+        while self.stack.len() > index {
+            let element = self.stack.pop().expect("stack is non-empty");
+            self.append(Node::Element(element));
+        }
+    }
+
+    /// Returns the index of the nearest open `name`, stopping at `boundaries`.
+    fn find_open(&self, names: &[&str], boundaries: &[&str]) -> Option<usize> {
+        for (index, element) in self.stack.iter().enumerate().rev() {
+            if names.contains(&element.name.as_str()) {
+                return Some(index);
+            }
+            if boundaries.contains(&element.name.as_str()) {
+                return None;
+            }
+        }
+        None
+    }
+
+    fn open(&mut self, element: Element, self_closing: bool) {
+        let name = element.name.as_str();
+
+        // Close elements whose end tags are optional. Foreign content such
+        // as MathML has no optional end tags.
+        let foreign = self
+            .stack
+            .iter()
+            .any(|open| matches!(open.name.as_str(), "math" | "svg"));
+        let implied = match name {
+            _ if foreign => None,
+            "li" => self.find_open(&["li"], &["ul", "ol"]),
+            "dt" | "dd" => self.find_open(&["dt", "dd"], &["dl"]),
+            "tr" => self.find_open(&["tr"], &["table"]),
+            "td" | "th" => self.find_open(&["td", "th"], &["tr", "table"]),
+            "thead" | "tbody" | "tfoot" => self.find_open(&["thead", "tbody", "tfoot"], &["table"]),
+            "option" => self.find_open(&["option"], &["select"]),
+            _ if !PHRASING_ELEMENTS.contains(&name) => self
+                .stack
+                .iter()
+                .rposition(|open| !PHRASING_ELEMENTS.contains(&open.name.as_str()))
+                .filter(|index| self.stack[*index].name == "p"),
+            _ => None,
+        };
+        if let Some(index) = implied {
+            self.close(index);
+        }
+
+        if self_closing || VOID_ELEMENTS.contains(&name) {
+            self.append(Node::Element(element));
+        } else if self.stack.len() < MAX_DEPTH {
+            self.stack.push(element);
+        }
+    }
+
+    fn end(&mut self, name: &str) {
+        if let Some(index) = self.stack.iter().rposition(|open| open.name == name) {
+            self.close(index);
+        }
+    }
+
+    fn finish(mut self) -> Vec<Node> {
+        self.close(0);
+        self.root
+    }
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-' || c == ':' || c == '_'
+}
+
+/// Parses `html` into a DOM, tolerating unclosed and misnested tags.
+fn parse_dom(html: &str) -> Vec<Node> {
+    let mut builder = TreeBuilder {
+        root: Vec::new(),
+        stack: Vec::new(),
+    };
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            builder.append(Node::Text(decode_entities(rest)));
+            break;
+        };
+        if start > 0 {
+            builder.append(Node::Text(decode_entities(&rest[..start])));
+            rest = &rest[start..];
+        }
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment
+                .find("-->")
+                .map_or("", |end| &comment[end + "-->".len()..]);
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+        } else if let Some(tag) = rest
+            .strip_prefix("</")
+            .filter(|tag| tag.starts_with(|c: char| c.is_ascii_alphabetic()))
+        {
+            let end = tag.find('>').unwrap_or(tag.len());
+            let name = tag[..end]
+                .trim_end()
+                .trim_end_matches(|c: char| !is_name_char(c))
+                .to_ascii_lowercase();
+            builder.end(&name);
+            rest = tag.get(end + 1..).unwrap_or("");
+        } else if rest[1..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+            let (element, self_closing, remainder) = parse_start_tag(&rest[1..]);
+            rest = remainder;
+
+            if RAW_TEXT_ELEMENTS.contains(&element.name.as_str()) {
+                let close = format!("</{}", element.name);
+                let end = rest.to_ascii_lowercase().find(&close).unwrap_or(rest.len());
+                let mut element = element;
+                if end > 0 {
+                    element
+                        .children
+                        .push(Node::Text(decode_entities(&rest[..end])));
+                }
+                rest = rest[end..]
+                    .find('>')
+                    .map_or("", |close| &rest[end + close + 1..]);
+                builder.append(Node::Element(element));
+            } else {
+                builder.open(element, self_closing);
+            }
+        } else {
+            builder.append(Node::Text("<".to_string()));
+            rest = &rest[1..];
+        }
+    }
+
+    builder.finish()
+}
+
+/// Parses a start tag following its `<`, returning the element, whether it
+/// was self-closing and the remaining input.
+fn parse_start_tag(input: &str) -> (Element, bool, &str) {
+    let name_end = input
+        .find(|c: char| !is_name_char(c))
+        .unwrap_or(input.len());
+    let mut element = Element {
+        name: input[..name_end].to_ascii_lowercase(),
+        attributes: Vec::new(),
+        children: Vec::new(),
+    };
+    let mut rest = &input[name_end..];
+
+    loop {
+        rest = rest.trim_start();
+        if let Some(remainder) = rest.strip_prefix("/>") {
+            return (element, true, remainder);
+        }
+        if let Some(remainder) = rest.strip_prefix('>') {
+            return (element, false, remainder);
+        }
+        if rest.is_empty() {
+            return (element, false, rest);
+        }
+        if let Some(remainder) = rest.strip_prefix('/') {
+            rest = remainder;
+            continue;
+        }
+
+        let name_end = rest
+            .find(|c: char| c.is_whitespace() || c == '=' || c == '>' || c == '/')
+            .unwrap_or(rest.len())
+            .max(1);
+        let name = rest[..name_end].to_ascii_lowercase();
+        rest = rest[name_end..].trim_start();
+
+        let mut value = String::new();
+        if let Some(remainder) = rest.strip_prefix('=') {
+            rest = remainder.trim_start();
+            match rest.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let end = rest[1..].find(quote).map_or(rest.len(), |end| end + 1);
+                    value = decode_entities(&rest[1..end]);
+                    rest = rest.get(end + 1..).unwrap_or("");
+                }
+                _ => {
+                    let end = rest
+                        .find(|c: char| c.is_whitespace() || c == '>')
+                        .unwrap_or(rest.len());
+                    value = decode_entities(&rest[..end]);
+                    rest = &rest[end..];
+                }
+            }
+        }
+        element.attributes.push((name, value));
+    }
+}
+
+// Conversion # -------------------------------------------------- #
+
+/// Elements dropped together with their content.
+const BOILERPLATE_ELEMENTS: &[&str] = &[
+    "aside", "button", "canvas", "dialog", "footer", "form", "head", "iframe", "input", "link",
+    "menu", "meta", "nav", "noscript", "object", "rp", "script", "select", "style", "svg",
+    "template", "textarea", "title",
+];
+
+/// Elements replaced by their children.
+const CONTAINER_ELEMENTS: &[&str] = &[
+    "article", "body", "center", "dd", "div", "dl", "dt", "header", "hgroup", "html", "main",
+    "section",
+];
+
+/// Class names and ids that mark page chrome rather than content.
+const BOILERPLATE_HINTS: &[&str] = &[
+    "advert",
+    "banner",
+    "breadcrumb",
+    "comment",
+    "cookie",
+    "footer",
+    "masthead",
+    "menu",
+    "navbar",
+    "newsletter",
+    "popup",
+    "related",
+    "share",
+    "sidebar",
+    "social",
+    "sponsor",
+];
+
+fn is_boilerplate(element: &Element) -> bool {
+    // Markup produced by `crate::html` is content by definition.
+    if element
+        .attribute("class")
+        .is_some_and(|class| class.starts_with("jarkup-"))
+    {
+        return false;
+    }
+    if BOILERPLATE_ELEMENTS.contains(&element.name.as_str()) {
+        return true;
+    }
+    if element.attribute("hidden").is_some()
+        || element.attribute("aria-hidden") == Some("true")
+        || element.attribute("role") == Some("navigation")
+    {
+        return true;
+    }
+    element
+        .attribute("class")
+        .unwrap_or_default()
+        .split_whitespace()
+        .chain(element.attribute("id"))
+        .any(|name| {
+            BOILERPLATE_HINTS
+                .iter()
+                .any(|hint| name.eq_ignore_ascii_case(hint))
+        })
+}
+
+fn has_class(element: &Element, class: &str) -> bool {
+    element
+        .attribute("class")
+        .is_some_and(|classes| classes.split_whitespace().any(|name| name == class))
+}
+
+/// Reads `color` and `background-color` from an inline `style` attribute.
+fn style_colors(element: &Element) -> (Option<String>, Option<String>) {
+    let mut color = None;
+    let mut background_color = None;
+    for declaration in element.attribute("style").unwrap_or_default().split(';') {
+        if let Some((property, value)) = declaration.split_once(':') {
+            let value = Some(value.trim().to_string()).filter(|value| !value.is_empty());
+            match property.trim().to_ascii_lowercase().as_str() {
+                "color" => color = value,
+                "background-color" | "background" => background_color = value,
+                _ => {}
+            }
+        }
+    }
+    (color, background_color)
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Marks {
+    bold: bool,
+    italic: bool,
+    underline: bool,
+    strikethrough: bool,
+    code: bool,
+    kbd: bool,
+    katex: bool,
+    href: Option<String>,
+    favicon: Option<String>,
+    color: Option<String>,
+    background_color: Option<String>,
+}
+
+impl Marks {
+    fn props(&self, text: String) -> TextProps {
+        let flag = |value: bool| if value { Some(true) } else { None };
+        TextProps {
+            text,
+            color: self.color.clone(),
+            background_color: self.background_color.clone(),
+            bold: flag(self.bold),
+            italic: flag(self.italic),
+            underline: flag(self.underline),
+            strikethrough: flag(self.strikethrough),
+            code: flag(self.code),
+            kbd: flag(self.kbd),
+            katex: flag(self.katex),
+            href: self.href.clone(),
+            favicon: self.favicon.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Collects inline runs, merging adjacent text with identical marks.
+#[derive(Default)]
+struct Inlines {
+    items: Vec<InlineComponent>,
+    /// Marks of the last item when it is mergeable text.
+    last_marks: Option<Marks>,
+}
+
+impl Inlines {
+    fn push_text(&mut self, text: &str, marks: &Marks, collapse: bool) {
+        let mut text = if collapse {
+            let mut collapsed = String::with_capacity(text.len());
+            for c in text.chars() {
+                if c.is_ascii_whitespace() {
+                    if !collapsed.ends_with(' ') {
+                        collapsed.push(' ');
+                    }
+                } else {
+                    collapsed.push(c);
+                }
+            }
+            collapsed
+        } else {
+            text.to_string()
+        };
+
+        if text.starts_with(' ') && self.ends_with_whitespace() {
+            text.remove(0);
+        }
+        if text.is_empty() {
+            return;
+        }
+
+        if self.last_marks.as_ref() == Some(marks)
+            && let Some(InlineComponent::Text(last)) = self.items.last_mut()
+        {
+            last.props.text.push_str(&text);
+            return;
+        }
+
+        self.items.push(InlineComponent::Text(Text {
+            props: marks.props(text),
+            ..Default::default()
+        }));
+        self.last_marks = Some(marks.clone());
+    }
+
+    fn push(&mut self, component: InlineComponent) {
+        self.items.push(component);
+        self.last_marks = None;
+    }
+
+    fn ends_with_whitespace(&self) -> bool {
+        match self.items.last() {
+            Some(InlineComponent::Text(text)) => text.props.text.ends_with([' ', '\n']),
+            Some(InlineComponent::Icon(_)) => false,
+            None => true,
+        }
+    }
+
+    /// Trims whitespace at both ends and returns the runs.
+    fn take(&mut self) -> Vec<InlineComponent> {
+        self.last_marks = None;
+        let mut items = std::mem::take(&mut self.items);
+
+        if let Some(InlineComponent::Text(first)) = items.first_mut() {
+            first.props.text = first.props.text.trim_start_matches(' ').to_string();
+        }
+        if let Some(InlineComponent::Text(last)) = items.last_mut() {
+            last.props.text = last.props.text.trim_end().to_string();
+        }
+        items.retain(|item| match item {
+            InlineComponent::Text(text) => !text.props.text.is_empty(),
+            InlineComponent::Icon(_) => true,
+        });
+        items
+    }
+}
+
+/// Where inline content is being collected, and what happens to elements that
+/// cannot be expressed inline.
+trait InlineContext {
+    fn inlines(&mut self) -> &mut Inlines;
+
+    fn non_inline(&mut self, element: &Element, marks: &Marks);
+}
+
+/// An inline-only slot such as a heading or table cell: images become icons
+/// and block elements are flattened into their inline content.
+impl InlineContext for Inlines {
+    fn inlines(&mut self) -> &mut Inlines {
+        self
+    }
+
+    fn non_inline(&mut self, element: &Element, marks: &Marks) {
+        if element.name == "img" {
+            if let Some(src) = element.attribute("src") {
+                self.push(InlineComponent::Icon(Icon {
+                    props: IconProps {
+                        src: src.to_string(),
+                        alt: element.attribute("alt").map(str::to_string),
+                    },
+                    ..Default::default()
+                }));
+            }
+            return;
+        }
+        for child in &element.children {
+            inline(child, marks, self);
+        }
+    }
+}
+
+/// A slot holding block components. Loose inline content is flushed either
+/// into a `Paragraph` or, for list items, directly into the slot.
+struct Blocks {
+    output: Vec<Component>,
+    inlines: Inlines,
+    paragraph: Option<ParagraphProps>,
+    wrap: bool,
+}
+
+impl Blocks {
+    fn new(wrap: bool) -> Self {
+        Self {
+            output: Vec::new(),
+            inlines: Inlines::default(),
+            paragraph: None,
+            wrap,
+        }
+    }
+
+    fn flush(&mut self) {
+        let items = self.inlines.take();
+        if items.is_empty() {
+            return;
+        }
+        if self.wrap {
+            self.output.push(
+                Paragraph {
+                    props: self.paragraph.clone(),
+                    slots: ParagraphSlots { default: items },
+                    ..Default::default()
+                }
+                .into(),
+            );
+        } else {
+            self.output
+                .extend(items.into_iter().map(Component::InlineComponent));
+        }
+    }
+
+    fn push(&mut self, component: impl Into<Component>) {
+        self.flush();
+        self.output.push(component.into());
+    }
+
+    fn finish(mut self) -> Vec<Component> {
+        self.flush();
+        self.output
+    }
+}
+
+impl InlineContext for Blocks {
+    fn inlines(&mut self) -> &mut Inlines {
+        &mut self.inlines
+    }
+
+    fn non_inline(&mut self, element: &Element, _marks: &Marks) {
+        block(element, self);
+    }
+}
+
+fn blocks(nodes: &[Node], wrap: bool) -> Vec<Component> {
+    let mut context = Blocks::new(wrap);
+    for node in nodes {
+        inline(node, &Marks::default(), &mut context);
+    }
+    context.finish()
+}
+
+fn inlines(nodes: &[Node]) -> Vec<InlineComponent> {
+    let mut context = Inlines::default();
+    for node in nodes {
+        inline(node, &Marks::default(), &mut context);
+    }
+    context.take()
+}
+
+fn inline<C>(node: &Node, marks: &Marks, context: &mut C)
+where
+    C: InlineContext,
+{
+    let element = match node {
+        Node::Text(text) => {
+            context.inlines().push_text(text, marks, true);
+            return;
+        }
+        Node::Element(element) => element,
+    };
+    if is_boilerplate(element) {
+        return;
+    }
+
+    let mut marks = marks.clone();
+    match element.name.as_str() {
+        "br" => {
+            context.inlines().push_text("\n", &marks, false);
+            return;
+        }
+        "ruby" => {
+            let base = element
+                .children
+                .iter()
+                .filter(|child| !matches!(child, Node::Element(element) if element.name == "rt"))
+                .fold(String::new(), |mut base, child| {
+                    collect_text(std::slice::from_ref(child), &mut base);
+                    base
+                });
+            let ruby = element
+                .children
+                .iter()
+                .filter_map(|child| match child {
+                    Node::Element(element) if element.name == "rt" => Some(element.text_content()),
+                    _ => None,
+                })
+                .collect::<String>();
+            let mut props = marks.props(base.trim().to_string());
+            props.ruby = Some(ruby.trim().to_string()).filter(|ruby| !ruby.is_empty());
+            context.inlines().push(InlineComponent::Text(Text {
+                props,
+                ..Default::default()
+            }));
+            return;
+        }
+        "a" => {
+            marks.href = element.attribute("href").map(str::to_string);
+            marks.favicon = element.children.iter().find_map(|child| match child {
+                Node::Element(image) if has_class(image, "jarkup-favicon") => {
+                    image.attribute("src").map(str::to_string)
+                }
+                _ => None,
+            });
+        }
+        "img" if has_class(element, "jarkup-favicon") => return,
+        "img"
+            if ["icon", "emoji"].iter().any(|hint| {
+                element
+                    .attribute("class")
+                    .is_some_and(|class| class.contains(hint))
+            }) =>
+        {
+            if let Some(src) = element.attribute("src") {
+                context.inlines().push(InlineComponent::Icon(Icon {
+                    props: IconProps {
+                        src: src.to_string(),
+                        alt: element.attribute("alt").map(str::to_string),
+                    },
+                    ..Default::default()
+                }));
+            }
+            return;
+        }
+        "math" => {
+            let mut marks = marks.clone();
+            marks.katex = true;
+            context.inlines().push(InlineComponent::Text(Text {
+                props: marks.props(tex_source(element)),
+                ..Default::default()
+            }));
+            return;
+        }
+        "span" if has_class(element, "jarkup-katex") => marks.katex = true,
+        "code" if has_class(element, "jarkup-katex-error") => marks.katex = true,
+        "strong" | "b" => marks.bold = true,
+        "em" | "i" | "cite" | "dfn" | "var" => marks.italic = true,
+        "u" | "ins" => marks.underline = true,
+        "s" | "del" | "strike" => marks.strikethrough = true,
+        "code" | "samp" | "tt" => marks.code = true,
+        "kbd" => marks.kbd = true,
+        "span" | "font" | "mark" => {
+            let (color, background_color) = style_colors(element);
+            marks.color = color.or(marks.color);
+            marks.background_color = background_color.or(marks.background_color);
+        }
+        name if PHRASING_ELEMENTS.contains(&name) && name != "img" => {}
+        _ => {
+            context.non_inline(element, &marks);
+            return;
+        }
+    }
+
+    for child in &element.children {
+        inline(child, &marks, context);
+    }
+}
+
+fn block(element: &Element, context: &mut Blocks) {
+    if has_class(element, "jarkup-unsupported") {
+        context.push(Unsupported {
+            props: element
+                .attribute("data-details")
+                .map(|details| UnsupportedProps {
+                    details: details.to_string(),
+                }),
+            ..Default::default()
+        });
+        return;
+    }
+
+    match element.name.as_str() {
+        "div" if has_class(element, "jarkup-katex") => context.push(Katex {
+            props: KatexProps {
+                expression: tex_source(element),
+            },
+            ..Default::default()
+        }),
+        "aside" if has_class(element, "jarkup-callout") => {
+            let r#type = [
+                ("jarkup-callout-tip", CalloutType::Tip),
+                ("jarkup-callout-important", CalloutType::Important),
+                ("jarkup-callout-warning", CalloutType::Warning),
+                ("jarkup-callout-caution", CalloutType::Caution),
+            ]
+            .into_iter()
+            .find(|(class, _)| has_class(element, class))
+            .map(|(_, r#type)| r#type);
+            context.push(Callout {
+                props: r#type.map(|r#type| CalloutProps {
+                    r#type: Some(r#type),
+                }),
+                slots: CalloutSlots {
+                    default: blocks(&element.children, true),
+                },
+                ..Default::default()
+            });
+        }
+        name if CONTAINER_ELEMENTS.contains(&name) => {
+            if matches!(name, "div" | "section" | "article" | "dl" | "dt" | "dd") {
+                context.flush();
+            }
+            for child in &element.children {
+                inline(child, &Marks::default(), context);
+            }
+            context.flush();
+        }
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+            let level = match element.name.as_bytes()[1] {
+                b'1' => HeadingLevel::H1,
+                b'2' => HeadingLevel::H2,
+                b'3' => HeadingLevel::H3,
+                b'4' => HeadingLevel::H4,
+                b'5' => HeadingLevel::H5,
+                _ => HeadingLevel::H6,
+            };
+            let default = inlines(&element.children);
+            if !default.is_empty() {
+                context.push(Heading {
+                    id: element.attribute("id").map(str::to_string),
+                    props: HeadingProps { level },
+                    slots: HeadingSlots { default },
+                });
+            }
+        }
+        "p" => {
+            context.flush();
+            let (color, background_color) = style_colors(element);
+            let mut paragraph = Blocks::new(true);
+            if color.is_some() || background_color.is_some() {
+                paragraph.paragraph = Some(ParagraphProps {
+                    color,
+                    background_color,
+                });
+            }
+            for child in &element.children {
+                inline(child, &Marks::default(), &mut paragraph);
+            }
+            context.output.extend(paragraph.finish());
+        }
+        "ul" | "ol" => {
+            let list_style = if element.name == "ol" {
+                ListStyle::Ordered
+            } else {
+                ListStyle::Unordered
+            };
+            let mut items = Vec::new();
+            for child in &element.children {
+                match child {
+                    Node::Text(text) if text.trim().is_empty() => {}
+                    Node::Element(element) if element.name == "li" => {
+                        items.push(ListItem {
+                            slots: ListItemSlots {
+                                default: blocks(&element.children, false),
+                            },
+                            ..Default::default()
+                        });
+                    }
+                    other => items.push(ListItem {
+                        slots: ListItemSlots {
+                            default: blocks(std::slice::from_ref(other), false),
+                        },
+                        ..Default::default()
+                    }),
+                }
+            }
+            context.push(List {
+                props: Some(ListProps {
+                    list_style: Some(list_style),
+                }),
+                slots: ListSlots {
+                    default: items.into_iter().map(Component::from).collect(),
+                },
+                ..Default::default()
+            });
+        }
+        "li" => {
+            // A list item outside of a list.
+            context.push(ListItem {
+                slots: ListItemSlots {
+                    default: blocks(&element.children, false),
+                },
+                ..Default::default()
+            });
+        }
+        "blockquote" => {
+            context.push(BlockQuote {
+                props: element.attribute("cite").map(|cite| BlockQuoteProps {
+                    cite: Some(cite.to_string()),
+                }),
+                slots: crate::BlockQuoteSlots {
+                    default: blocks(&element.children, true),
+                },
+                ..Default::default()
+            });
+        }
+        "details" => {
+            let (summary, rest): (Vec<&Node>, Vec<&Node>) = element.children.iter().partition(
+                |child| matches!(child, Node::Element(element) if element.name == "summary"),
+            );
+            let summary = summary
+                .into_iter()
+                .flat_map(|node| match node {
+                    Node::Element(element) => inlines(&element.children),
+                    Node::Text(_) => Vec::new(),
+                })
+                .collect();
+            let rest = rest.into_iter().cloned().collect::<Vec<_>>();
+            context.push(Toggle {
+                slots: ToggleSlots {
+                    default: blocks(&rest, true),
+                    summary,
+                },
+                ..Default::default()
+            });
+        }
+        "figure" => {
+            let caption = find_element(&element.children, "figcaption")
+                .map(|caption| caption.text_content().trim().to_string());
+            match find_element(&element.children, "img") {
+                Some(image) => {
+                    if let Some(mut image) = convert_image(image) {
+                        if image.props.alt.as_deref().is_none_or(str::is_empty) {
+                            image.props.alt = caption;
+                        }
+                        context.push(image);
+                    }
+                }
+                None => {
+                    for child in &element.children {
+                        inline(child, &Marks::default(), context);
+                    }
+                    context.flush();
+                }
+            }
+        }
+        "figcaption" => {
+            for child in &element.children {
+                inline(child, &Marks::default(), context);
+            }
+            context.flush();
+        }
+        "img" => {
+            if let Some(image) = convert_image(element) {
+                context.push(image);
+            }
+        }
+        "pre" => context.push(convert_code_block(element)),
+        "table" => context.push(convert_table(element)),
+        "hr" => context.push(Divider::default()),
+        _ => context.push(Unsupported {
+            props: Some(UnsupportedProps {
+                details: format!("Unsupported HTML element: {}", element.start_tag()),
+            }),
+            ..Default::default()
+        }),
+    }
+}
+
+/// Recovers the TeX source of rendered math from its `application/x-tex`
+/// annotation, falling back to the text content.
+fn tex_source(element: &Element) -> String {
+    match find_element(&element.children, "annotation") {
+        Some(annotation) if annotation.attribute("encoding") == Some("application/x-tex") => {
+            annotation.text_content()
+        }
+        _ => element.text_content(),
+    }
+}
+
+fn find_element<'a>(nodes: &'a [Node], name: &str) -> Option<&'a Element> {
+    nodes.iter().find_map(|node| match node {
+        Node::Element(element) if element.name == name => Some(element),
+        Node::Element(element) => find_element(&element.children, name),
+        Node::Text(_) => None,
+    })
+}
+
+fn convert_code_block(pre: &Element) -> CodeBlock {
+    let code = find_element(&pre.children, "code");
+    let language = code
+        .and_then(|code| code.attribute("class"))
+        .or(pre.attribute("class"))
+        .and_then(|class| {
+            class.split_whitespace().find_map(|class| {
+                class
+                    .strip_prefix("language-")
+                    .or_else(|| class.strip_prefix("lang-"))
+            })
+        })
+        .unwrap_or("plaintext");
+    let text = pre.text_content();
+    let text = text.strip_prefix('\n').unwrap_or(&text);
+    CodeBlock {
+        props: CodeBlockProps {
+            code: text.strip_suffix('\n').unwrap_or(text).to_string(),
+            language: language.to_string(),
+        },
+        ..Default::default()
+    }
+}
+
+fn convert_image(element: &Element) -> Option<Image> {
+    let attribute = |name: &str| element.attribute(name).map(str::to_string);
+    let dimension = |name: &str| element.attribute(name).and_then(|value| value.parse().ok());
+    Some(Image {
+        props: ImageProps {
+            src: attribute("src")?,
+            alt: attribute("alt"),
+            width: dimension("width"),
+            height: dimension("height"),
+            srcset: attribute("srcset"),
+            sizes: attribute("sizes"),
+            mime_type: None,
+        },
+        ..Default::default()
+    })
+}
+
+/// Collects the `<tr>`s below `nodes`, without descending into nested tables.
+fn collect_rows<'a>(nodes: &'a [Node], rows: &mut Vec<&'a Element>) {
+    for node in nodes {
+        if let Node::Element(element) = node {
+            match element.name.as_str() {
+                "tr" => rows.push(element),
+                "table" => {}
+                _ => collect_rows(&element.children, rows),
+            }
+        }
+    }
+}
+
+fn convert_table(table: &Element) -> Table {
+    let mut header = Vec::new();
+    let mut body = Vec::new();
+    let mut caption = None;
+
+    for child in &table.children {
+        let Node::Element(element) = child else {
+            continue;
+        };
+        match element.name.as_str() {
+            "caption" => caption = Some(element.text_content().trim().to_string()),
+            "thead" => collect_rows(&element.children, &mut header),
+            "tr" => body.push(element),
+            _ => collect_rows(&element.children, &mut body),
+        }
+    }
+
+    let is_header_row = |row: &Element| {
+        let cells = row
+            .children
+            .iter()
+            .filter_map(|child| match child {
+                Node::Element(element) if matches!(element.name.as_str(), "td" | "th") => {
+                    Some(element.name.as_str())
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        !cells.is_empty() && cells.iter().all(|name| *name == "th")
+    };
+    if header.is_empty() && body.first().is_some_and(|row| is_header_row(row)) {
+        header.push(body.remove(0));
+    }
+    let has_row_header = !body.is_empty()
+        && body.iter().all(|row| {
+            row.children
+                .iter()
+                .find_map(|child| match child {
+                    Node::Element(element) if matches!(element.name.as_str(), "td" | "th") => {
+                        Some(element.name == "th")
+                    }
+                    _ => None,
+                })
+                .unwrap_or(false)
+        });
+
+    let convert_row = |row: &Element| -> Component {
+        let cells = row
+            .children
+            .iter()
+            .filter_map(|child| match child {
+                Node::Element(element) if matches!(element.name.as_str(), "td" | "th") => {
+                    Some(Component::from(TableCell {
+                        props: (element.name == "th").then_some(TableCellProps {
+                            is_header: Some(true),
+                        }),
+                        slots: TableCellSlots {
+                            default: inlines(&element.children),
+                        },
+                        ..Default::default()
+                    }))
+                }
+                _ => None,
+            })
+            .collect();
+        TableRow {
+            slots: TableRowSlots { default: cells },
+            ..Default::default()
+        }
+        .into()
+    };
+
+    let has_column_header = !header.is_empty();
+    Table {
+        props: Some(TableProps {
+            has_column_header: Some(has_column_header),
+            has_row_header: Some(has_row_header),
+            caption,
+        }),
+        slots: TableSlots {
+            header: has_column_header.then(|| header.into_iter().map(convert_row).collect()),
+            body: body.into_iter().map(convert_row).collect(),
+        },
+        ..Default::default()
+    }
+}
+
+/// Converts an HTML fragment or document into jarkup components.
+pub fn parse(html: &str) -> Vec<Component> {
+    let dom = parse_dom(html);
+    blocks(&dom, true)
+        .into_iter()
+        .filter(|component| {
+            // Drop components left empty by the cleanup.
+            !matches!(
+                component,
+                Component::BlockComponent(BlockComponent::List(list)) if list.slots.default.is_empty()
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{MAX_DEPTH, parse};
+    use crate::{BlockComponent, Component, InlineComponent, plain};
+
+    #[test]
+    fn test_parse_article() {
+        let html = r#"<!DOCTYPE html>
+            <html><head><title>Ignored</title><script>alert(1)</script></head>
+            <body>
+              <nav><a href="/">Home</a></nav>
+              <article>
+                <h2 id="intro">Intro &amp; <em>more</em></h2>
+                <p>Hello   <b>bold</b> <a href="https://example.com">link</a>
+                <p>Press <kbd>Ctrl</kbd>+<kbd>C</kbd>, read <ruby>漢字<rp>(</rp><rt>かんじ</rt><rp>)</rp></ruby>
+                <ul><li>one<li>two <s>old</s><ol><li>nested</ol></ul>
+                <blockquote cite="https://example.com/q"><p>Quoted</p></blockquote>
+                <details><summary>More</summary><p>Hidden</p></details>
+                <figure><img src="a.png" width="640"><figcaption>Caption</figcaption></figure>
+                <pre><code class="language-rust">fn main() {}
+</code></pre>
+                <table><tr><th>A<th>B<tr><td>1<td><u>2</u></table>
+                <video src="v.mp4"></video>
+              </article>
+              <div class="sidebar">Related posts</div>
+            </body></html>"#;
+        let components = parse(html);
+
+        let types = components
+            .iter()
+            .map(|component| component.type_name())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            types,
+            vec![
+                "Heading",
+                "Paragraph",
+                "Paragraph",
+                "List",
+                "BlockQuote",
+                "Toggle",
+                "Image",
+                "CodeBlock",
+                "Table",
+                "Unsupported",
+            ]
+        );
+
+        let json = serde_json::to_value(&components).unwrap();
+        assert_eq!(json[0]["id"], "intro");
+        assert_eq!(json[0]["slots"]["default"][0]["props"]["text"], "Intro & ");
+        assert_eq!(json[0]["slots"]["default"][1]["props"]["italic"], true);
+        assert_eq!(json[1]["slots"]["default"][0]["props"]["text"], "Hello ");
+        assert_eq!(
+            json[1]["slots"]["default"][3]["props"]["href"],
+            "https://example.com"
+        );
+        assert_eq!(json[2]["slots"]["default"][1]["props"]["kbd"], true);
+        assert_eq!(json[2]["slots"]["default"][5]["props"]["ruby"], "かんじ");
+        assert_eq!(
+            json[3]["slots"]["default"][1]["slots"]["default"][1]["props"]["strikethrough"],
+            true
+        );
+        assert_eq!(
+            json[3]["slots"]["default"][1]["slots"]["default"][2]["props"]["listStyle"],
+            "ordered"
+        );
+        assert_eq!(json[4]["props"]["cite"], "https://example.com/q");
+        assert_eq!(json[5]["slots"]["summary"][0]["props"]["text"], "More");
+        assert_eq!(json[6]["props"]["alt"], "Caption");
+        assert_eq!(json[6]["props"]["width"], 640);
+        assert_eq!(json[7]["props"]["language"], "rust");
+        assert_eq!(json[7]["props"]["code"], "fn main() {}");
+        assert_eq!(json[8]["props"]["hasColumnHeader"], true);
+        assert_eq!(
+            json[8]["slots"]["header"][0]["slots"]["default"][0]["props"]["isHeader"],
+            true
+        );
+        assert_eq!(
+            json[9]["props"]["details"],
+            "Unsupported HTML element: <video src=\"v.mp4\">"
+        );
+    }
+
+    #[test]
+    fn test_inline_images_are_hoisted() {
+        let components = parse("<p>Before <img src=\"x.png\" alt=\"X\"> after</p>");
+        assert_eq!(components.len(), 3);
+
+        let Component::BlockComponent(BlockComponent::Paragraph(paragraph)) = &components[2] else {
+            panic!("expected a paragraph");
+        };
+        let InlineComponent::Text(text) = &paragraph.slots.default[0] else {
+            panic!("expected text");
+        };
+        assert_eq!(text.props.text, "after");
+    }
+
+    #[test]
+    fn test_boilerplate_matches_whole_names() {
+        let html = r#"<div class="comment-body">Kept</div>
+            <div id="shared-notes">Notes</div>
+            <p>The menu is in the footer.</p>
+            <div class="post footer">Dropped</div>
+            <div id="Sidebar">Dropped</div>"#;
+
+        assert_eq!(
+            plain::to_string(&parse(html)),
+            "Kept\n\nNotes\n\nThe menu is in the footer.\n"
+        );
+    }
+
+    #[test]
+    fn test_deep_nesting_is_flattened() {
+        let html = "<blockquote>".repeat(20_000) + "deep";
+        let mut components = parse(&html);
+        let mut depth = 0;
+        while let [Component::BlockComponent(BlockComponent::BlockQuote(block_quote))] =
+            components.as_slice()
+        {
+            components = block_quote.slots.default.clone();
+            depth += 1;
+        }
+        assert_eq!(depth, MAX_DEPTH);
+        assert_eq!(plain::to_string(&components), "deep\n");
+
+        let html = "<div>".repeat(20_000) + &"<b>".repeat(20_000) + "bold";
+        assert_eq!(plain::to_string(&parse(&html)), "bold\n");
+    }
+}
@@ -0,0 +1,1679 @@
+//! Markdown importer.
+//!
+//! [`parse`] reads CommonMark with the GitHub extensions for tables,
+//! strikethrough and alerts, the dialect the [Markdown renderer](crate::markdown)
+//! writes, and converts it into jarkup components. Alerts become callouts,
+//! `<details>` blocks toggles, fenced `mermaid` blocks Mermaid diagrams, `$…$`
+//! and `$$…$$` KaTeX, and a paragraph holding nothing but an image an
+//! [`Image`]; images inside text become [`Icon`]s. The inline HTML the renderer
+//! falls back to (`<kbd>`, `<u>`, `<ruby>` and `<br>`) is read back into text
+//! props, other inline tags are dropped while their content is kept, and other
+//! HTML blocks go through the [HTML importer](super::html). The text a list
+//! item starts with stays inline, as it does for `<li>`. Reference links,
+//! footnotes and link titles are not supported.
+
+use super::html::decode_entities;
+use crate::{
+    BlockQuote, BlockQuoteSlots, Callout, CalloutProps, CalloutSlots, CalloutType, CodeBlock,
+    CodeBlockProps, Component, Divider, Heading, HeadingLevel, HeadingProps, HeadingSlots, Icon,
+    IconProps, Image, ImageProps, InlineComponent, Katex, KatexProps, List, ListItem,
+    ListItemSlots, ListProps, ListSlots, ListStyle, Mermaid, MermaidProps, Paragraph,
+    ParagraphSlots, Table, TableCell, TableCellProps, TableCellSlots, TableProps, TableRow,
+    TableRowSlots, TableSlots, Text, TextProps, Toggle, ToggleSlots,
+};
+
+// Lines # -------------------------------------------------- #
+
+fn is_blank(line: &str) -> bool {
+    line.trim().is_empty()
+}
+
+/// Width of the indentation of `line`, with tab stops every 4 columns.
+fn indentation(line: &str) -> usize {
+    let mut width = 0;
+    for c in line.chars() {
+        match c {
+            ' ' => width += 1,
+            '\t' => width += 4 - width % 4,
+            _ => break,
+        }
+    }
+    width
+}
+
+/// Removes up to `width` columns of indentation from `line`, turning a tab
+/// that straddles the cut into spaces.
+fn dedent(line: &str, width: usize) -> String {
+    let mut column = 0;
+    for (index, c) in line.char_indices() {
+        if column >= width {
+            return line[index..].to_string();
+        }
+        match c {
+            ' ' => column += 1,
+            '\t' => {
+                let next = column + 4 - column % 4;
+                if next > width {
+                    return " ".repeat(next - width) + &line[index + 1..];
+                }
+                column = next;
+            }
+            _ => return line[index..].to_string(),
+        }
+    }
+    String::new()
+}
+
+fn atx_heading(line: &str) -> Option<(HeadingLevel, &str)> {
+    if indentation(line) >= 4 {
+        return None;
+    }
+    let line = line.trim_start();
+    let hashes = line.bytes().take_while(|byte| *byte == b'#').count();
+    let rest = &line[hashes..];
+    if !(1..=6).contains(&hashes) || !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+        return None;
+    }
+
+    let mut text = rest.trim();
+    // An optional closing sequence of `#`s.
+    let unclosed = text.trim_end_matches('#');
+    if unclosed.is_empty() {
+        text = "";
+    } else if unclosed.ends_with([' ', '\t']) {
+        text = unclosed.trim_end();
+    }
+    let level = match hashes {
+        1 => HeadingLevel::H1,
+        2 => HeadingLevel::H2,
+        3 => HeadingLevel::H3,
+        4 => HeadingLevel::H4,
+        5 => HeadingLevel::H5,
+        _ => HeadingLevel::H6,
+    };
+    Some((level, text))
+}
+
+fn is_thematic_break(line: &str) -> bool {
+    if indentation(line) >= 4 {
+        return false;
+    }
+    let mut chars = line.chars().filter(|c| !matches!(c, ' ' | '\t'));
+    let Some(first @ ('-' | '*' | '_')) = chars.next() else {
+        return false;
+    };
+    let mut count = 1;
+    for c in chars {
+        if c != first {
+            return false;
+        }
+        count += 1;
+    }
+    count >= 3
+}
+
+struct Fence {
+    marker: char,
+    length: usize,
+    indent: usize,
+    info: String,
+}
+
+impl Fence {
+    fn open(line: &str) -> Option<Self> {
+        let indent = indentation(line);
+        if indent >= 4 {
+            return None;
+        }
+        let trimmed = line.trim_start();
+        let marker = trimmed.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+        let length = trimmed.chars().take_while(|c| *c == marker).count();
+        let info = trimmed[length..].trim();
+        if length < 3 || (marker == '`' && info.contains('`')) {
+            return None;
+        }
+        Some(Fence {
+            marker,
+            length,
+            indent,
+            info: info.to_string(),
+        })
+    }
+
+    fn is_closed_by(&self, line: &str) -> bool {
+        let trimmed = line.trim();
+        indentation(line) < 4
+            && trimmed.len() >= self.length
+            && trimmed.chars().all(|c| c == self.marker)
+    }
+}
+
+struct ListMarker {
+    ordered: bool,
+    /// The bullet, or the delimiter after the number.
+    symbol: char,
+    number: u32,
+    /// Columns from the start of the line to the content of the item.
+    width: usize,
+    /// The first line of the item without the marker.
+    content: String,
+    empty: bool,
+}
+
+impl ListMarker {
+    fn parse(line: &str) -> Option<Self> {
+        let indent = indentation(line);
+        if indent >= 4 {
+            return None;
+        }
+        let trimmed = line.trim_start();
+        let (ordered, symbol, length, number) = match trimmed.chars().next()? {
+            c @ ('-' | '*' | '+') => (false, c, 1, 0),
+            _ => {
+                let digits = trimmed.bytes().take_while(u8::is_ascii_digit).count();
+                let symbol = trimmed[digits..].chars().next()?;
+                if !(1..=9).contains(&digits) || !matches!(symbol, '.' | ')') {
+                    return None;
+                }
+                (true, symbol, digits + 1, trimmed[..digits].parse().ok()?)
+            }
+        };
+
+        let rest = &trimmed[length..];
+        let (spaces, empty) = if is_blank(rest) {
+            (1, true)
+        } else {
+            match indentation(rest) {
+                0 => return None,
+                // Content indented further is an indented code block.
+                spaces @ 1..=4 => (spaces, false),
+                _ => (1, false),
+            }
+        };
+        let width = indent + length + spaces;
+        Some(ListMarker {
+            ordered,
+            symbol,
+            number,
+            width,
+            content: dedent(&(" ".repeat(indent + length) + rest), width),
+            empty,
+        })
+    }
+
+    fn continues(&self, other: &ListMarker) -> bool {
+        self.ordered == other.ordered && self.symbol == other.symbol
+    }
+}
+
+/// How an HTML block ends.
+enum HtmlBlock {
+    Details,
+    /// At the line containing the given text.
+    Until(&'static str),
+    /// At a blank line.
+    Paragraph,
+}
+
+const HTML_BLOCK_ELEMENTS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "caption",
+    "center",
+    "dd",
+    "dialog",
+    "div",
+    "dl",
+    "dt",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "iframe",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "section",
+    "summary",
+    "table",
+    "tbody",
+    "td",
+    "tfoot",
+    "th",
+    "thead",
+    "tr",
+    "ul",
+    "video",
+];
+
+fn html_block(line: &str) -> Option<HtmlBlock> {
+    if indentation(line) >= 4 {
+        return None;
+    }
+    let rest = line.trim_start().strip_prefix('<')?;
+    if rest.starts_with("!--") {
+        return Some(HtmlBlock::Until("-->"));
+    }
+    let (closing, rest) = match rest.strip_prefix('/') {
+        Some(rest) => (true, rest),
+        None => (false, rest),
+    };
+    let length = rest.bytes().take_while(u8::is_ascii_alphanumeric).count();
+    let name = rest[..length].to_ascii_lowercase();
+    if !(rest[length..].is_empty() || rest[length..].starts_with([' ', '\t', '>', '/'])) {
+        return None;
+    }
+    match name.as_str() {
+        "details" if !closing => Some(HtmlBlock::Details),
+        "pre" if !closing => Some(HtmlBlock::Until("</pre>")),
+        "script" if !closing => Some(HtmlBlock::Until("</script>")),
+        "style" if !closing => Some(HtmlBlock::Until("</style>")),
+        "textarea" if !closing => Some(HtmlBlock::Until("</textarea>")),
+        name if HTML_BLOCK_ELEMENTS.contains(&name) => Some(HtmlBlock::Paragraph),
+        _ => None,
+    }
+}
+
+/// Whether `line` starts a block that ends a paragraph before it.
+fn interrupts_paragraph(line: &str) -> bool {
+    let starts_with = |prefix: &str| indentation(line) < 4 && line.trim_start().starts_with(prefix);
+    atx_heading(line).is_some()
+        || Fence::open(line).is_some()
+        || is_thematic_break(line)
+        || starts_with(">")
+        || starts_with("$$")
+        || html_block(line).is_some()
+        || ListMarker::parse(line)
+            .is_some_and(|marker| !marker.empty && (!marker.ordered || marker.number == 1))
+}
+
+/// Splits a table row into its trimmed cells, unescaping `\|`.
+fn table_cells(line: &str) -> Vec<String> {
+    let line = line.trim();
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let line = match line.strip_suffix('|') {
+        Some(stripped) if !stripped.ends_with('\\') => stripped,
+        _ => line,
+    };
+
+    let mut cells = vec![String::new()];
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        let cell = cells.last_mut().expect("there is always a cell");
+        match c {
+            '\\' => match chars.next() {
+                Some('|') => cell.push('|'),
+                Some(next) => {
+                    cell.push('\\');
+                    cell.push(next);
+                }
+                None => cell.push('\\'),
+            },
+            '|' => cells.push(String::new()),
+            c => cell.push(c),
+        }
+    }
+    cells
+        .into_iter()
+        .map(|cell| cell.trim().to_string())
+        .collect()
+}
+
+/// Returns the number of columns of a table delimiter row such as
+/// `| --- | :-: |`.
+fn delimiter_row(line: &str) -> Option<usize> {
+    if indentation(line) >= 4 || !line.contains('-') {
+        return None;
+    }
+    let cells = table_cells(line);
+    cells
+        .iter()
+        .all(|cell| {
+            let cell = cell.strip_prefix(':').unwrap_or(cell);
+            let cell = cell.strip_suffix(':').unwrap_or(cell);
+            !cell.is_empty() && cell.chars().all(|c| c == '-')
+        })
+        .then_some(cells.len())
+}
+
+// Blocks # -------------------------------------------------- #
+
+/// Block quotes, lists and `<details>` nested deeper than this are read as
+/// paragraphs, and links and inline HTML as text.
+const MAX_DEPTH: usize = 128;
+
+fn blocks(lines: &[String], depth: usize) -> Vec<Component> {
+    let mut output = Vec::new();
+    let mut index = 0;
+    while index < lines.len() {
+        let line = &lines[index];
+        if is_blank(line) {
+            index += 1;
+            continue;
+        }
+
+        // Indented code.
+        if indentation(line) >= 4 {
+            let mut code = Vec::new();
+            while index < lines.len()
+                && (is_blank(&lines[index]) || indentation(&lines[index]) >= 4)
+            {
+                code.push(dedent(&lines[index], 4));
+                index += 1;
+            }
+            while code.last().is_some_and(|line| is_blank(line)) {
+                code.pop();
+            }
+            output.push(code_block(code.join("\n"), ""));
+            continue;
+        }
+
+        if let Some(fence) = Fence::open(line) {
+            index += 1;
+            let mut code = Vec::new();
+            while index < lines.len() {
+                let line = &lines[index];
+                index += 1;
+                if fence.is_closed_by(line) {
+                    break;
+                }
+                code.push(dedent(line, fence.indent));
+            }
+            output.push(code_block(
+                code.join("\n"),
+                fence.info.split_whitespace().next().unwrap_or_default(),
+            ));
+            continue;
+        }
+
+        if let Some(rest) = line.trim().strip_prefix("$$") {
+            index += 1;
+            let expression = match rest.strip_suffix("$$") {
+                Some(expression) => expression.to_string(),
+                None => {
+                    let mut expression = vec![rest.to_string()];
+                    while index < lines.len() {
+                        let line = lines[index].trim_end();
+                        index += 1;
+                        if let Some(last) = line.strip_suffix("$$") {
+                            expression.push(last.to_string());
+                            break;
+                        }
+                        expression.push(line.to_string());
+                    }
+                    expression.join("\n")
+                }
+            };
+            output.push(Component::from(Katex {
+                props: KatexProps {
+                    expression: expression.trim().to_string(),
+                },
+                ..Default::default()
+            }));
+            continue;
+        }
+
+        if let Some((level, text)) = atx_heading(line) {
+            index += 1;
+            let default = inlines(text);
+            if !default.is_empty() {
+                output.push(heading(level, default));
+            }
+            continue;
+        }
+
+        if is_thematic_break(line) {
+            index += 1;
+            output.push(Divider::default().into());
+            continue;
+        }
+
+        if line.trim_start().starts_with('>') && depth < MAX_DEPTH {
+            let mut quoted: Vec<String> = Vec::new();
+            while index < lines.len() {
+                let line = &lines[index];
+                if let Some(rest) = line.trim_start().strip_prefix('>')
+                    && indentation(line) < 4
+                {
+                    quoted.push(rest.strip_prefix(' ').unwrap_or(rest).to_string());
+                } else if !is_blank(line)
+                    && quoted.last().is_some_and(|last| !is_blank(last))
+                    && !interrupts_paragraph(line)
+                {
+                    // A lazy continuation of a quoted paragraph.
+                    quoted.push(line.to_string());
+                } else {
+                    break;
+                }
+                index += 1;
+            }
+            output.push(block_quote(&quoted, depth + 1));
+            continue;
+        }
+
+        if let Some(marker) = ListMarker::parse(line).filter(|_| depth < MAX_DEPTH) {
+            let (list, next) = list(lines, index, marker, depth + 1);
+            output.push(list);
+            index = next;
+            continue;
+        }
+
+        if let Some(html) = html_block(line) {
+            let start = index;
+            match html {
+                HtmlBlock::Details => {
+                    let mut depth = 0;
+                    while index < lines.len() {
+                        let lowercase = lines[index].to_ascii_lowercase();
+                        depth += lowercase.matches("<details").count();
+                        depth = depth.saturating_sub(lowercase.matches("</details>").count());
+                        index += 1;
+                        if depth == 0 {
+                            break;
+                        }
+                    }
+                    if depth < MAX_DEPTH {
+                        output.push(details(&lines[start..index].join("\n"), depth + 1));
+                        continue;
+                    }
+                }
+                HtmlBlock::Until(end) => {
+                    while index < lines.len() {
+                        index += 1;
+                        if lines[index - 1].to_ascii_lowercase().contains(end) {
+                            break;
+                        }
+                    }
+                }
+                HtmlBlock::Paragraph => {
+                    while index < lines.len() && !is_blank(&lines[index]) {
+                        index += 1;
+                    }
+                }
+            }
+            output.extend(super::html::parse(&lines[start..index].join("\n")));
+            continue;
+        }
+
+        if line.contains('|')
+            && let Some(columns) = lines.get(index + 1).and_then(|line| delimiter_row(line))
+            && table_cells(line).len() == columns
+        {
+            let header = table_row(line, columns, true);
+            index += 2;
+            let mut body = Vec::new();
+            while index < lines.len()
+                && !is_blank(&lines[index])
+                && !interrupts_paragraph(&lines[index])
+            {
+                body.push(table_row(&lines[index], columns, false));
+                index += 1;
+            }
+            output.push(Component::from(Table {
+                props: Some(TableProps {
+                    has_column_header: Some(true),
+                    has_row_header: Some(false),
+                    caption: None,
+                }),
+                slots: TableSlots {
+                    header: Some(vec![header]),
+                    body,
+                },
+                ..Default::default()
+            }));
+            continue;
+        }
+
+        // A paragraph, or a setext heading when underlined.
+        let mut text = vec![line.trim_start()];
+        index += 1;
+        let mut level = None;
+        while index < lines.len() {
+            let line = &lines[index];
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                break;
+            }
+            if indentation(line) < 4 {
+                if trimmed.chars().all(|c| c == '=') {
+                    level = Some(HeadingLevel::H1);
+                } else if trimmed.chars().all(|c| c == '-') {
+                    level = Some(HeadingLevel::H2);
+                }
+                if level.is_some() {
+                    index += 1;
+                    break;
+                }
+            }
+            if interrupts_paragraph(line) {
+                break;
+            }
+            text.push(line.trim_start());
+            index += 1;
+        }
+        let text = text.join("\n");
+        let text = text.trim_end();
+        match level {
+            Some(level) => output.push(heading(level, inlines(text))),
+            None => output.extend(paragraph(text)),
+        }
+    }
+    output
+}
+
+/// Parses the list starting at `lines[index]`, returning it and the index of
+/// the line after it.
+fn list(lines: &[String], mut index: usize, first: ListMarker, depth: usize) -> (Component, usize) {
+    let list_style = if first.ordered {
+        ListStyle::Ordered
+    } else {
+        ListStyle::Unordered
+    };
+    let mut items = Vec::new();
+    let mut marker = first;
+    loop {
+        let mut content = vec![std::mem::take(&mut marker.content)];
+        index += 1;
+        while index < lines.len() {
+            let line = &lines[index];
+            if is_blank(line) {
+                content.push(String::new());
+            } else if indentation(line) >= marker.width {
+                content.push(dedent(line, marker.width));
+            } else if content.last().is_some_and(|last| !is_blank(last))
+                && !interrupts_paragraph(line)
+                && ListMarker::parse(line).is_none()
+            {
+                // A lazy continuation of the item's paragraph.
+                content.push(line.trim_start().to_string());
+            } else {
+                break;
+            }
+            index += 1;
+        }
+        while content.last().is_some_and(|line| is_blank(line)) {
+            content.pop();
+        }
+        items.push(list_item(&content, depth));
+
+        match lines.get(index).and_then(|line| {
+            ListMarker::parse(line)
+                .filter(|next| !is_thematic_break(line) && next.continues(&marker))
+        }) {
+            Some(next) => marker = next,
+            None => break,
+        }
+    }
+
+    let list = List {
+        props: Some(ListProps {
+            list_style: Some(list_style),
+        }),
+        slots: ListSlots {
+            default: items.into_iter().map(Component::from).collect(),
+        },
+        ..Default::default()
+    };
+    (list.into(), index)
+}
+
+fn list_item(content: &[String], depth: usize) -> ListItem {
+    let mut default = blocks(content, depth);
+    if let Some(Component::BlockComponent(crate::BlockComponent::Paragraph(_))) = default.first() {
+        let Component::BlockComponent(crate::BlockComponent::Paragraph(paragraph)) =
+            default.remove(0)
+        else {
+            unreachable!("the first component is a paragraph");
+        };
+        default.splice(
+            0..0,
+            paragraph
+                .slots
+                .default
+                .into_iter()
+                .map(Component::InlineComponent),
+        );
+    }
+    ListItem {
+        slots: ListItemSlots { default },
+        ..Default::default()
+    }
+}
+
+fn block_quote(lines: &[String], depth: usize) -> Component {
+    let alert = lines.first().and_then(|line| {
+        let kind = line.trim().strip_prefix("[!")?.strip_suffix(']')?;
+        match kind.to_ascii_uppercase().as_str() {
+            "NOTE" => Some(CalloutType::Note),
+            "TIP" => Some(CalloutType::Tip),
+            "IMPORTANT" => Some(CalloutType::Important),
+            "WARNING" => Some(CalloutType::Warning),
+            "CAUTION" => Some(CalloutType::Caution),
+            _ => None,
+        }
+    });
+    match alert {
+        Some(r#type) => Callout {
+            props: Some(CalloutProps {
+                r#type: Some(r#type),
+            }),
+            slots: CalloutSlots {
+                default: blocks(&lines[1..], depth),
+            },
+            ..Default::default()
+        }
+        .into(),
+        None => BlockQuote {
+            slots: BlockQuoteSlots {
+                default: blocks(lines, depth),
+            },
+            ..Default::default()
+        }
+        .into(),
+    }
+}
+
+/// Converts a `<details>` block, reading its summary and content as Markdown.
+fn details(html: &str, depth: usize) -> Component {
+    let lowercase = html.to_ascii_lowercase();
+    let start = lowercase
+        .find("<details")
+        .and_then(|start| lowercase[start..].find('>').map(|end| start + end + 1))
+        .unwrap_or(html.len());
+    let end = lowercase.rfind("</details>").filter(|end| *end >= start);
+    let inner = &html[start..end.unwrap_or(html.len())];
+
+    let trimmed = inner.trim_start();
+    let (summary, rest) = match trimmed.to_ascii_lowercase().strip_prefix("<summary>") {
+        Some(lowercase) => match lowercase.find("</summary>") {
+            Some(end) => {
+                let offset = "<summary>".len();
+                (
+                    &trimmed[offset..offset + end],
+                    &trimmed[offset + end + "</summary>".len()..],
+                )
+            }
+            None => ("", trimmed),
+        },
+        None => ("", trimmed),
+    };
+    let lines = rest.lines().map(str::to_string).collect::<Vec<_>>();
+    Toggle {
+        slots: ToggleSlots {
+            default: blocks(&lines, depth),
+            summary: inlines(summary.trim()),
+        },
+        ..Default::default()
+    }
+    .into()
+}
+
+fn table_row(line: &str, columns: usize, is_header: bool) -> Component {
+    let mut cells = table_cells(line);
+    cells.resize(columns, String::new());
+    let cells = cells
+        .iter()
+        .map(|cell| {
+            TableCell {
+                props: is_header.then_some(TableCellProps {
+                    is_header: Some(true),
+                }),
+                slots: TableCellSlots {
+                    default: inlines(cell),
+                },
+                ..Default::default()
+            }
+            .into()
+        })
+        .collect();
+    TableRow {
+        slots: TableRowSlots { default: cells },
+        ..Default::default()
+    }
+    .into()
+}
+
+fn heading(level: HeadingLevel, default: Vec<InlineComponent>) -> Component {
+    Heading {
+        id: None,
+        props: HeadingProps { level },
+        slots: HeadingSlots { default },
+    }
+    .into()
+}
+
+fn code_block(code: String, language: &str) -> Component {
+    match language {
+        "mermaid" => Mermaid {
+            props: MermaidProps { code },
+            ..Default::default()
+        }
+        .into(),
+        "math" => Katex {
+            props: KatexProps { expression: code },
+            ..Default::default()
+        }
+        .into(),
+        language => CodeBlock {
+            props: CodeBlockProps {
+                code,
+                language: if language.is_empty() {
+                    "plaintext".to_string()
+                } else {
+                    language.to_string()
+                },
+            },
+            ..Default::default()
+        }
+        .into(),
+    }
+}
+
+/// A paragraph, or an image when it holds nothing else.
+fn paragraph(text: &str) -> Option<Component> {
+    let mut default = inlines(text);
+    match default.as_slice() {
+        [] => None,
+        [InlineComponent::Icon(_)] => {
+            let Some(InlineComponent::Icon(icon)) = default.pop() else {
+                unreachable!("the paragraph holds an icon");
+            };
+            Some(
+                Image {
+                    props: ImageProps {
+                        src: icon.props.src,
+                        alt: icon.props.alt,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                }
+                .into(),
+            )
+        }
+        _ => Some(
+            Paragraph {
+                slots: ParagraphSlots { default },
+                ..Default::default()
+            }
+            .into(),
+        ),
+    }
+}
+
+// Inlines # -------------------------------------------------- #
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Marks {
+    bold: bool,
+    italic: bool,
+    underline: bool,
+    strikethrough: bool,
+    code: bool,
+    kbd: bool,
+    katex: bool,
+    ruby: Option<String>,
+    href: Option<String>,
+}
+
+impl Marks {
+    fn props(&self, text: String) -> TextProps {
+        let flag = |value: bool| if value { Some(true) } else { None };
+        TextProps {
+            text,
+            bold: flag(self.bold),
+            italic: flag(self.italic),
+            underline: flag(self.underline),
+            strikethrough: flag(self.strikethrough),
+            code: flag(self.code),
+            kbd: flag(self.kbd),
+            katex: flag(self.katex),
+            ruby: self.ruby.clone(),
+            href: self.href.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Item {
+    Text(String, Marks),
+    Icon(Icon),
+    /// A run of `*`, `_` or `~` that may open or close emphasis.
+    Delimiter {
+        c: char,
+        count: usize,
+        length: usize,
+        open: bool,
+        close: bool,
+    },
+}
+
+fn push_text(items: &mut Vec<Item>, text: &str, marks: &Marks) {
+    if let Some(Item::Text(last, last_marks)) = items.last_mut()
+        && last_marks == marks
+    {
+        last.push_str(text);
+        return;
+    }
+    items.push(Item::Text(text.to_string(), marks.clone()));
+}
+
+fn is_punctuation(c: char) -> bool {
+    c.is_ascii_punctuation() || !(c.is_alphanumeric() || c.is_whitespace())
+}
+
+/// Removes backslash escapes and decodes entities.
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match chars.peek() {
+            Some(next) if c == '\\' && next.is_ascii_punctuation() => {
+                unescaped.push(*next);
+                chars.next();
+            }
+            _ => unescaped.push(c),
+        }
+    }
+    decode_entities(&unescaped)
+}
+
+/// Returns the index of the `]` closing the `[` that `text` starts with.
+fn label_end(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut chars = text.char_indices().skip(1);
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '[' => depth += 1,
+            ']' if depth == 0 => return Some(index),
+            ']' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Parses `[label](destination "title")` at the start of `text`, returning
+/// the label, the destination and the length of the link.
+fn link(text: &str) -> Option<(&str, String, usize)> {
+    let end = label_end(text)?;
+    let rest = text[end + 1..].strip_prefix('(')?;
+    let mut position = rest.len() - rest.trim_start().len();
+
+    let destination = match rest[position..].strip_prefix('<') {
+        Some(bracketed) => {
+            let length = bracketed.find(['>', '\n'])?;
+            if !bracketed[length..].starts_with('>') {
+                return None;
+            }
+            position += length + 2;
+            &bracketed[..length]
+        }
+        None => {
+            let start = position;
+            let mut depth = 0;
+            position = rest.len();
+            let mut chars = rest[start..].char_indices();
+            while let Some((index, c)) = chars.next() {
+                match c {
+                    '\\' => {
+                        chars.next();
+                    }
+                    '(' => depth += 1,
+                    ')' if depth > 0 => depth -= 1,
+                    c if c == ')' || c.is_whitespace() || c.is_control() => {
+                        position = start + index;
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+            &rest[start..position]
+        }
+    };
+
+    // An optional title, which jarkup has no place for.
+    position += rest[position..].len() - rest[position..].trim_start().len();
+    let close = match rest[position..].chars().next()? {
+        '"' => Some('"'),
+        '\'' => Some('\''),
+        '(' => Some(')'),
+        _ => None,
+    };
+    if let Some(close) = close {
+        let title = &rest[position + 1..];
+        let mut escaped = false;
+        let length = title.find(|c| {
+            let found = c == close && !escaped;
+            escaped = c == '\\' && !escaped;
+            found
+        })?;
+        position += length + 2;
+        position += rest[position..].len() - rest[position..].trim_start().len();
+    }
+    rest[position..]
+        .starts_with(')')
+        .then(|| (&text[1..end], unescape(destination), end + 2 + position + 1))
+}
+
+/// Parses `<https://…>` or `<name@example.com>` at the start of `text`,
+/// returning the destination, the text and the length of the link.
+fn autolink(text: &str) -> Option<(String, &str, usize)> {
+    let end = text.find('>')?;
+    let inner = &text[1..end];
+    if inner.is_empty() || inner.contains(|c: char| c.is_whitespace() || c == '<') {
+        return None;
+    }
+    if let Some(colon) = inner.find(':') {
+        let scheme = &inner[..colon];
+        let valid = (2..=32).contains(&scheme.len())
+            && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '.' | '-'));
+        return valid.then(|| (inner.to_string(), inner, end + 1));
+    }
+    let (local, domain) = inner.split_once('@')?;
+    (!local.is_empty() && domain.contains('.'))
+        .then(|| (format!("mailto:{}", inner), inner, end + 1))
+}
+
+/// Parses an HTML tag at the start of `text`, returning its lowercase name,
+/// whether it is a closing tag and its length.
+fn html_tag(text: &str) -> Option<(String, bool, usize)> {
+    let rest = text.strip_prefix('<')?;
+    let (closing, rest) = match rest.strip_prefix('/') {
+        Some(rest) => (true, rest),
+        None => (false, rest),
+    };
+    if !rest.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let length = rest
+        .bytes()
+        .take_while(|byte| byte.is_ascii_alphanumeric() || *byte == b'-')
+        .count();
+    let end = rest.find(['>', '<'])?;
+    if !rest[end..].starts_with('>')
+        || !(rest[length..].starts_with(['>', '/'])
+            || rest[length..].starts_with(char::is_whitespace))
+    {
+        return None;
+    }
+    let prefix = if closing { 2 } else { 1 };
+    Some((
+        rest[..length].to_ascii_lowercase(),
+        closing,
+        prefix + end + 1,
+    ))
+}
+
+/// Parses the inline HTML element `<name …>…</name>` at the start of `text`,
+/// returning its content and its length.
+fn html_element<'a>(text: &'a str, name: &str, tag_length: usize) -> Option<(&'a str, usize)> {
+    let closing = format!("</{}>", name);
+    let end = text[tag_length..].to_ascii_lowercase().find(&closing)?;
+    Some((
+        &text[tag_length..tag_length + end],
+        tag_length + end + closing.len(),
+    ))
+}
+
+/// Scans `text` into items, resolving emphasis.
+fn scan(text: &str, marks: &Marks, depth: usize) -> Vec<Item> {
+    let mut items = Vec::new();
+    let mut position = 0;
+    while let Some(c) = text[position..].chars().next() {
+        let rest = &text[position..];
+        let length = match c {
+            '\\' => match rest[1..].chars().next() {
+                Some('\n') => {
+                    push_text(&mut items, "\n", marks);
+                    2
+                }
+                Some(next) if next.is_ascii_punctuation() => {
+                    push_text(&mut items, &rest[1..2], marks);
+                    2
+                }
+                _ => {
+                    push_text(&mut items, "\\", marks);
+                    1
+                }
+            },
+            '`' => {
+                let ticks = rest.bytes().take_while(|byte| *byte == b'`').count();
+                let after = &rest[ticks..];
+                let mut search = 0;
+                let mut end = None;
+                while let Some(start) = after[search..].find('`') {
+                    let start = search + start;
+                    let run = after[start..]
+                        .bytes()
+                        .take_while(|byte| *byte == b'`')
+                        .count();
+                    if run == ticks {
+                        end = Some(start);
+                        break;
+                    }
+                    search = start + run;
+                }
+                match end {
+                    Some(end) => {
+                        let mut code = after[..end].replace('\n', " ");
+                        if code.len() >= 2
+                            && code.starts_with(' ')
+                            && code.ends_with(' ')
+                            && !code.trim().is_empty()
+                        {
+                            code = code[1..code.len() - 1].to_string();
+                        }
+                        let marks = Marks {
+                            code: true,
+                            ..marks.clone()
+                        };
+                        push_text(&mut items, &code, &marks);
+                        2 * ticks + end
+                    }
+                    None => {
+                        push_text(&mut items, &rest[..ticks], marks);
+                        ticks
+                    }
+                }
+            }
+            '$' => match math(rest) {
+                Some((expression, length)) => {
+                    let marks = Marks {
+                        katex: true,
+                        ..marks.clone()
+                    };
+                    push_text(&mut items, expression, &marks);
+                    length
+                }
+                None => {
+                    push_text(&mut items, "$", marks);
+                    1
+                }
+            },
+            '*' | '_' | '~' => {
+                let count = rest.chars().take_while(|next| *next == c).count();
+                let before = text[..position].chars().next_back().unwrap_or(' ');
+                let after = rest[count..].chars().next().unwrap_or(' ');
+                let left = !after.is_whitespace()
+                    && (!is_punctuation(after) || before.is_whitespace() || is_punctuation(before));
+                let right = !before.is_whitespace()
+                    && (!is_punctuation(before) || after.is_whitespace() || is_punctuation(after));
+                let (open, close) = match c {
+                    '_' => (
+                        left && (!right || is_punctuation(before)),
+                        right && (!left || is_punctuation(after)),
+                    ),
+                    _ => (left, right),
+                };
+                if c == '~' && count > 2 {
+                    push_text(&mut items, &rest[..count], marks);
+                } else {
+                    items.push(Item::Delimiter {
+                        c,
+                        count,
+                        length: count,
+                        open,
+                        close,
+                    });
+                }
+                count
+            }
+            '!' if rest[1..].starts_with('[') && depth < MAX_DEPTH => match link(&rest[1..]) {
+                Some((label, src, length)) => {
+                    let alt = scan(label, &Marks::default(), depth + 1)
+                        .into_iter()
+                        .filter_map(|item| match item {
+                            Item::Text(text, _) => Some(text),
+                            _ => None,
+                        })
+                        .collect::<String>();
+                    items.push(Item::Icon(Icon {
+                        props: IconProps {
+                            src,
+                            alt: (!alt.is_empty()).then_some(alt),
+                        },
+                        ..Default::default()
+                    }));
+                    1 + length
+                }
+                None => {
+                    push_text(&mut items, "!", marks);
+                    1
+                }
+            },
+            '[' if depth < MAX_DEPTH => match link(rest) {
+                Some((label, href, length)) => {
+                    let marks = Marks {
+                        href: Some(href),
+                        ..marks.clone()
+                    };
+                    items.extend(scan(label, &marks, depth + 1));
+                    length
+                }
+                None => {
+                    push_text(&mut items, "[", marks);
+                    1
+                }
+            },
+            '<' if depth < MAX_DEPTH => inline_html(rest, marks, &mut items, depth),
+            '&' => {
+                let entity = rest[1..]
+                    .find(';')
+                    .filter(|end| *end <= 32)
+                    .map(|end| &rest[..end + 2]);
+                match entity.map(|entity| (entity, decode_entities(entity))) {
+                    Some((entity, decoded)) if decoded != entity => {
+                        push_text(&mut items, &decoded, marks);
+                        entity.len()
+                    }
+                    _ => {
+                        push_text(&mut items, "&", marks);
+                        1
+                    }
+                }
+            }
+            '\n' => {
+                // Two trailing spaces make a hard break, anything else a space.
+                let mut hard = false;
+                if let Some(Item::Text(last, _)) = items.last_mut() {
+                    let trimmed = last.trim_end_matches(' ').len();
+                    hard = last.len() - trimmed >= 2;
+                    last.truncate(trimmed);
+                }
+                push_text(&mut items, if hard { "\n" } else { " " }, marks);
+                1
+            }
+            c => {
+                push_text(&mut items, &rest[..c.len_utf8()], marks);
+                c.len_utf8()
+            }
+        };
+        position += length;
+    }
+    resolve_emphasis(&mut items, marks);
+    items
+}
+
+/// Parses `$…$` or `$$…$$` at the start of `text`, returning the expression
+/// and the length of the math.
+fn math(text: &str) -> Option<(&str, usize)> {
+    if let Some(rest) = text.strip_prefix("$$") {
+        let end = rest.find("$$").filter(|end| *end > 0)?;
+        return Some((&rest[..end], end + 4));
+    }
+    let rest = &text[1..];
+    if rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let mut previous = ' ';
+    let mut escaped = false;
+    for (index, c) in rest.char_indices() {
+        if c == '$'
+            && !escaped
+            && index > 0
+            && !previous.is_whitespace()
+            && !rest[index + 1..].starts_with(|c: char| c.is_ascii_digit())
+        {
+            return Some((&rest[..index], index + 2));
+        }
+        escaped = c == '\\' && !escaped;
+        previous = c;
+    }
+    None
+}
+
+/// Handles the `<` at the start of `text`, returning how much was consumed.
+fn inline_html(text: &str, marks: &Marks, items: &mut Vec<Item>, depth: usize) -> usize {
+    if let Some((href, label, length)) = autolink(text) {
+        let marks = Marks {
+            href: Some(href),
+            ..marks.clone()
+        };
+        push_text(items, label, &marks);
+        return length;
+    }
+    if let Some(comment) = text.strip_prefix("<!--") {
+        return comment
+            .find("-->")
+            .map_or(4, |end| "<!--".len() + end + "-->".len());
+    }
+    let Some((name, closing, tag_length)) = html_tag(text) else {
+        push_text(items, "<", marks);
+        return 1;
+    };
+    if closing {
+        // A closing tag without its start tag.
+        return tag_length;
+    }
+
+    let element = |name: &str| html_element(text, name, tag_length);
+    let nested = |content: &str, marks: Marks, items: &mut Vec<Item>| {
+        items.extend(scan(content, &marks, depth + 1));
+    };
+    match name.as_str() {
+        "br" => {
+            push_text(items, "\n", marks);
+            tag_length
+        }
+        "kbd" | "code" => match element(&name) {
+            Some((content, length)) => {
+                let mut marks = marks.clone();
+                if name == "kbd" {
+                    marks.kbd = true;
+                } else {
+                    marks.code = true;
+                }
+                push_text(items, &decode_entities(content), &marks);
+                length
+            }
+            None => tag_length,
+        },
+        "ruby" => match element("ruby") {
+            Some((content, length)) => {
+                let lowercase = content.to_ascii_lowercase();
+                let (base, ruby) = match lowercase.find("<rt>") {
+                    Some(start) => {
+                        let reading = &content[start + "<rt>".len()..];
+                        let end = reading.to_ascii_lowercase().find("</rt>");
+                        (
+                            &content[..start],
+                            Some(decode_entities(&reading[..end.unwrap_or(reading.len())])),
+                        )
+                    }
+                    None => (content, None),
+                };
+                nested(
+                    base,
+                    Marks {
+                        ruby,
+                        ..marks.clone()
+                    },
+                    items,
+                );
+                length
+            }
+            None => tag_length,
+        },
+        "u" | "ins" | "s" | "del" | "strike" | "b" | "strong" | "i" | "em" => {
+            match element(&name) {
+                Some((content, length)) => {
+                    let mut marks = marks.clone();
+                    match name.as_str() {
+                        "u" | "ins" => marks.underline = true,
+                        "s" | "del" | "strike" => marks.strikethrough = true,
+                        "b" | "strong" => marks.bold = true,
+                        _ => marks.italic = true,
+                    }
+                    nested(content, marks, items);
+                    length
+                }
+                None => tag_length,
+            }
+        }
+        // Other tags are dropped, keeping their content.
+        _ => tag_length,
+    }
+}
+
+/// Matches emphasis delimiters the way CommonMark does, applying emphasis to
+/// the items between them and turning the rest into text.
+fn resolve_emphasis(items: &mut [Item], marks: &Marks) {
+    // The index below which no opener was found, by delimiter, length modulo
+    // 3 and whether the closer can open.
+    let mut bottoms = [[[0; 2]; 3]; 3];
+    let mut closer = 0;
+    while closer < items.len() {
+        let Item::Delimiter {
+            c,
+            count,
+            length,
+            open: closer_opens,
+            close: true,
+        } = items[closer]
+        else {
+            closer += 1;
+            continue;
+        };
+        if count == 0 {
+            closer += 1;
+            continue;
+        }
+
+        let kind = match c {
+            '*' => 0,
+            '_' => 1,
+            _ => 2,
+        };
+        let bottom = &mut bottoms[kind][length % 3][usize::from(closer_opens)];
+        let opener = (*bottom..closer).rev().find(|index| match items[*index] {
+            Item::Delimiter {
+                c: opener_c,
+                count: opener_count,
+                length: opener_length,
+                open: true,
+                close: opener_closes,
+            } if opener_c == c && opener_count > 0 => {
+                if c == '~' {
+                    opener_count == count
+                } else {
+                    // The rule of 3.
+                    !((opener_closes || closer_opens)
+                        && (opener_length + length) % 3 == 0
+                        && !(opener_length % 3 == 0 && length % 3 == 0))
+                }
+            }
+            _ => false,
+        });
+        let Some(opener) = opener else {
+            *bottom = closer;
+            closer += 1;
+            continue;
+        };
+
+        let Item::Delimiter {
+            count: opener_count,
+            ..
+        } = items[opener]
+        else {
+            unreachable!("openers are delimiters");
+        };
+        let used = if c == '~' {
+            count
+        } else if count >= 2 && opener_count >= 2 {
+            2
+        } else {
+            1
+        };
+        for item in &mut items[opener + 1..closer] {
+            // Delimiters left between the two can no longer match.
+            if let Item::Delimiter { c, count, .. } = *item {
+                *item = Item::Text(c.to_string().repeat(count), marks.clone());
+            }
+            if let Item::Text(_, item_marks) = item {
+                match (c, used) {
+                    ('~', _) => item_marks.strikethrough = true,
+                    (_, 2) => item_marks.bold = true,
+                    _ => item_marks.italic = true,
+                }
+            }
+        }
+        for index in [opener, closer] {
+            if let Item::Delimiter { count, .. } = &mut items[index] {
+                *count -= used;
+            }
+        }
+    }
+
+    for item in items.iter_mut() {
+        if let Item::Delimiter { c, count, .. } = item {
+            *item = Item::Text(c.to_string().repeat(*count), marks.clone());
+        }
+    }
+}
+
+/// Parses inline Markdown, merging adjacent text with identical marks and
+/// trimming whitespace at both ends.
+fn inlines(text: &str) -> Vec<InlineComponent> {
+    let mut components = Vec::new();
+    let mut last_marks = None;
+    for item in scan(text, &Marks::default(), 0) {
+        match item {
+            Item::Text(text, marks) => {
+                if text.is_empty() {
+                    continue;
+                }
+                if last_marks.as_ref() == Some(&marks)
+                    && let Some(InlineComponent::Text(last)) = components.last_mut()
+                {
+                    last.props.text.push_str(&text);
+                    continue;
+                }
+                components.push(InlineComponent::Text(Text {
+                    props: marks.props(text),
+                    ..Default::default()
+                }));
+                last_marks = Some(marks);
+            }
+            Item::Icon(icon) => {
+                components.push(InlineComponent::Icon(icon));
+                last_marks = None;
+            }
+            Item::Delimiter { .. } => unreachable!("delimiters are resolved by `scan`"),
+        }
+    }
+
+    if let Some(InlineComponent::Text(first)) = components.first_mut() {
+        first.props.text = first.props.text.trim_start().to_string();
+    }
+    if let Some(InlineComponent::Text(last)) = components.last_mut() {
+        last.props.text = last.props.text.trim_end().to_string();
+    }
+    components.retain(|component| match component {
+        InlineComponent::Text(text) => !text.props.text.is_empty(),
+        InlineComponent::Icon(_) => true,
+    });
+    components
+}
+
+/// Converts a Markdown document into jarkup components.
+pub fn parse(markdown: &str) -> Vec<Component> {
+    let lines = markdown.lines().map(str::to_string).collect::<Vec<_>>();
+    blocks(&lines, 0)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use serde_json::{Value, json};
+
+    use super::{MAX_DEPTH, parse};
+    use crate::{BlockComponent, Component, InlineComponent, plain};
+
+    fn inlines(markdown: &str) -> Vec<InlineComponent> {
+        match parse(markdown).into_iter().next() {
+            Some(Component::BlockComponent(BlockComponent::Paragraph(paragraph))) => {
+                paragraph.slots.default
+            }
+            other => panic!("expected a paragraph, found {:?}", other),
+        }
+    }
+
+    fn from<T>(value: Value) -> T
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_parse_blocks() {
+        let markdown = r#"Title
+=====
+
+Some *text*
+continued  
+after a break.
+
+    indented code
+
+- one
+- two
+  lazy
+  1. nested
+
+> quoted
+lazy
+
+> [!tip]
+> Use it.
+
+| A | B |
+|:--|--:|
+| `a\|b` | 2 | ignored |
+
+<div class="note">Raw <b>HTML</b></div>
+
+![Logo](/logo.png "Title")
+"#;
+        assert_eq!(
+            parse(markdown),
+            from::<Vec<Component>>(json!([
+                {"type":"Heading","props":{"level":1},"slots":{"default":[
+                    {"type":"Text","props":{"text":"Title"}}
+                ]}},
+                {"type":"Paragraph","slots":{"default":[
+                    {"type":"Text","props":{"text":"Some "}},
+                    {"type":"Text","props":{"text":"text","italic":true}},
+                    {"type":"Text","props":{"text":" continued\nafter a break."}}
+                ]}},
+                {"type":"CodeBlock","props":{"code":"indented code","language":"plaintext"}},
+                {"type":"List","props":{"listStyle":"unordered"},"slots":{"default":[
+                    {"type":"ListItem","slots":{"default":[{"type":"Text","props":{"text":"one"}}]}},
+                    {"type":"ListItem","slots":{"default":[
+                        {"type":"Text","props":{"text":"two lazy"}},
+                        {"type":"List","props":{"listStyle":"ordered"},"slots":{"default":[
+                            {"type":"ListItem","slots":{"default":[
+                                {"type":"Text","props":{"text":"nested"}}
+                            ]}}
+                        ]}}
+                    ]}}
+                ]}},
+                {"type":"BlockQuote","slots":{"default":[
+                    {"type":"Paragraph","slots":{"default":[
+                        {"type":"Text","props":{"text":"quoted lazy"}}
+                    ]}}
+                ]}},
+                {"type":"Callout","props":{"type":"tip"},"slots":{"default":[
+                    {"type":"Paragraph","slots":{"default":[
+                        {"type":"Text","props":{"text":"Use it."}}
+                    ]}}
+                ]}},
+                {"type":"Table","props":{"hasColumnHeader":true,"hasRowHeader":false},"slots":{
+                    "header":[{"type":"TableRow","slots":{"default":[
+                        {"type":"TableCell","props":{"isHeader":true},"slots":{"default":[
+                            {"type":"Text","props":{"text":"A"}}
+                        ]}},
+                        {"type":"TableCell","props":{"isHeader":true},"slots":{"default":[
+                            {"type":"Text","props":{"text":"B"}}
+                        ]}}
+                    ]}}],
+                    "body":[{"type":"TableRow","slots":{"default":[
+                        {"type":"TableCell","slots":{"default":[
+                            {"type":"Text","props":{"text":"a|b","code":true}}
+                        ]}},
+                        {"type":"TableCell","slots":{"default":[
+                            {"type":"Text","props":{"text":"2"}}
+                        ]}}
+                    ]}}]
+                }},
+                {"type":"Paragraph","slots":{"default":[
+                    {"type":"Text","props":{"text":"Raw "}},
+                    {"type":"Text","props":{"text":"HTML","bold":true}}
+                ]}},
+                {"type":"Image","props":{"src":"/logo.png","alt":"Logo"}}
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_parse_inlines() {
+        assert_eq!(
+            inlines("***both*** and **bold *nested***"),
+            from::<Vec<InlineComponent>>(json!([
+                {"type":"Text","props":{"text":"both","bold":true,"italic":true}},
+                {"type":"Text","props":{"text":" and "}},
+                {"type":"Text","props":{"text":"bold ","bold":true}},
+                {"type":"Text","props":{"text":"nested","bold":true,"italic":true}}
+            ]))
+        );
+        assert_eq!(
+            inlines(r"snake_case_name \*not\* ~~gone~~ ~~~kept~~~"),
+            from::<Vec<InlineComponent>>(json!([
+                {"type":"Text","props":{"text":"snake_case_name *not* "}},
+                {"type":"Text","props":{"text":"gone","strikethrough":true}},
+                {"type":"Text","props":{"text":" ~~~kept~~~"}}
+            ]))
+        );
+        assert_eq!(
+            inlines("[a **b**](https://example.com/a_(b)) <https://example.com> ![i](/i.svg)"),
+            from::<Vec<InlineComponent>>(json!([
+                {"type":"Text","props":{"text":"a ","href":"https://example.com/a_(b)"}},
+                {"type":"Text","props":{"text":"b","bold":true,"href":"https://example.com/a_(b)"}},
+                {"type":"Text","props":{"text":" "}},
+                {"type":"Text","props":{"text":"https://example.com","href":"https://example.com"}},
+                {"type":"Text","props":{"text":" "}},
+                {"type":"Icon","props":{"src":"/i.svg","alt":"i"}}
+            ]))
+        );
+        assert_eq!(
+            inlines("$x^2$ costs $5 and $6, `` `code` `` &amp; <kbd>K</kbd><br><span>kept</span>"),
+            from::<Vec<InlineComponent>>(json!([
+                {"type":"Text","props":{"text":"x^2","katex":true}},
+                {"type":"Text","props":{"text":" costs $5 and $6, "}},
+                {"type":"Text","props":{"text":"`code`","code":true}},
+                {"type":"Text","props":{"text":" & "}},
+                {"type":"Text","props":{"text":"K","kbd":true}},
+                {"type":"Text","props":{"text":"\nkept"}}
+            ]))
+        );
+        assert_eq!(
+            inlines("[not a link] (x)"),
+            from::<Vec<InlineComponent>>(json!([
+                {"type":"Text","props":{"text":"[not a link] (x)"}}
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_parse_rendered() {
+        let markdown = crate::markdown::to_string(&parse(
+            "# Title\n\n<details>\n<summary>More</summary>\n\n```mermaid\ngraph TD;\n```\n\n</details>\n\n$$\nx^2\n$$\n",
+        ));
+        assert_eq!(
+            markdown,
+            "# Title\n\n<details>\n<summary>More</summary>\n\n```mermaid\ngraph TD;\n```\n\n</details>\n\n$$\nx^2\n$$\n"
+        );
+    }
+
+    #[test]
+    fn test_deep_nesting_is_flattened() {
+        let mut components = parse(&(">".repeat(20_000) + " x"));
+        let mut depth = 0;
+        while let [Component::BlockComponent(BlockComponent::BlockQuote(block_quote))] =
+            components.as_slice()
+        {
+            components = block_quote.slots.default.clone();
+            depth += 1;
+        }
+        assert_eq!(depth, MAX_DEPTH);
+        assert_eq!(
+            plain::to_string(&components),
+            ">".repeat(20_000 - MAX_DEPTH) + " x\n"
+        );
+
+        let links = "[".repeat(20_000) + "x" + &"](u)".repeat(20_000);
+        let text = plain::to_string(&parse(&links));
+        assert!(text.starts_with(&"[".repeat(20_000 - MAX_DEPTH)));
+        assert!(text.ends_with(&(String::from("x") + &"](u)".repeat(20_000 - MAX_DEPTH) + "\n")));
+    }
+}
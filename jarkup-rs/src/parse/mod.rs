@@ -0,0 +1,4 @@
+//! Importers converting other formats into jarkup components.
+
+pub mod html;
+pub mod markdown;
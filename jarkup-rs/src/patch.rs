@@ -0,0 +1,515 @@
+//! Incremental edits to a document.
+//!
+//! A patch is a list of [`PatchOperation`]s addressing nodes either by path,
+//! such as `/0/slots/default/2`, or by `id`. [`apply_patch`] checks every
+//! operation against the typed model and returns the inverse patch, which
+//! undoes the edit when applied in turn.
+//!
+//! As in JSON Patch, the destination of a move is resolved after the node has
+//! been removed from its source.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{Map, Value};
+
+use crate::{
+    Component,
+    node::{NodeMut, NodeRef},
+    path::{NodePath, PathError},
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "camelCase")]
+pub enum PatchOperation {
+    Insert {
+        #[serde(flatten)]
+        at: Location,
+        component: Component,
+    },
+    Remove {
+        #[serde(flatten)]
+        target: Target,
+    },
+    Replace {
+        #[serde(flatten)]
+        target: Target,
+        component: Component,
+    },
+    Move {
+        #[serde(flatten)]
+        target: Target,
+        to: Location,
+    },
+    SetProp {
+        #[serde(flatten)]
+        target: Target,
+        name: String,
+        value: Value,
+    },
+    UnsetProp {
+        #[serde(flatten)]
+        target: Target,
+        name: String,
+    },
+}
+
+/// An existing node.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Target {
+    Path { path: String },
+    Id { id: String },
+}
+
+/// A position within a slot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Location {
+    /// Path of the node once inserted, e.g. `/0/slots/default/2`.
+    Path { path: String },
+    /// Index within the slot of the node with the `parent` id.
+    Slot {
+        parent: String,
+        slot: String,
+        index: usize,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatchErrorKind {
+    InvalidPath(String),
+    /// No node, slot or index matches the path or `#id`.
+    NotFound(String),
+    /// A block component was placed in a slot that only holds inline ones.
+    NotInline(String),
+    InvalidProps {
+        path: String,
+        message: String,
+    },
+    UnknownProp {
+        path: String,
+        name: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchError {
+    /// Index of the failing operation within the patch.
+    pub index: usize,
+    pub kind: PatchErrorKind,
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "operation {}: ", self.index)?;
+        match &self.kind {
+            PatchErrorKind::InvalidPath(path) => write!(f, "invalid path `{}`", path),
+            PatchErrorKind::NotFound(target) => write!(f, "nothing found at `{}`", target),
+            PatchErrorKind::NotInline(path) => {
+                write!(f, "`{}` only accepts inline components", path)
+            }
+            PatchErrorKind::InvalidProps { path, message } => {
+                write!(f, "invalid props at `{}`: {}", path, message)
+            }
+            PatchErrorKind::UnknownProp { path, name } => {
+                write!(f, "`{}` has no prop `{}`", path, name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+/// Applies `patch` to `components` and returns the patch undoing it.
+///
+/// Operations are applied in order. If one fails, those already applied are
+/// rolled back, so the document is either fully patched or left untouched.
+pub fn apply_patch(
+    components: &mut Vec<Component>,
+    patch: &[PatchOperation],
+) -> Result<Vec<PatchOperation>, PatchError> {
+    let mut inverse = Vec::new();
+    for (index, operation) in patch.iter().enumerate() {
+        match apply(components, operation) {
+            Ok(undo) => inverse.extend(undo),
+            Err(kind) => {
+                // Inverse operations are built from the document itself and
+                // cannot fail.
+                for undo in inverse.iter().rev() {
+                    let _ = apply(components, undo);
+                }
+                return Err(PatchError { index, kind });
+            }
+        }
+    }
+    inverse.reverse();
+    Ok(inverse)
+}
+
+fn apply(
+    components: &mut Vec<Component>,
+    operation: &PatchOperation,
+) -> Result<Option<PatchOperation>, PatchErrorKind> {
+    let undo = match operation {
+        PatchOperation::Insert { at, component } => {
+            let address = locate(components, at)?;
+            address.insert(components, component.clone())?;
+            PatchOperation::Remove {
+                target: Target::from(&address),
+            }
+        }
+        PatchOperation::Remove { target } => {
+            let address = resolve(components, target)?;
+            let removed = address.remove(components)?;
+            PatchOperation::Insert {
+                at: Location::from(&address),
+                component: removed,
+            }
+        }
+        PatchOperation::Replace { target, component } => {
+            let address = resolve(components, target)?;
+            let replaced = address.remove(components)?;
+            if let Err(error) = address.insert(components, component.clone()) {
+                let _ = address.insert(components, replaced);
+                return Err(error.into());
+            }
+            PatchOperation::Replace {
+                target: Target::from(&address),
+                component: replaced,
+            }
+        }
+        PatchOperation::Move { target, to } => {
+            let from = resolve(components, target)?;
+            let moved = from.remove(components)?;
+            let to = match locate(components, to) {
+                Ok(to) => to
+                    .insert(components, moved.clone())
+                    .map(|()| to)
+                    .map_err(Into::into),
+                Err(error) => Err(error),
+            };
+            match to {
+                Ok(to) => PatchOperation::Move {
+                    target: Target::from(&to),
+                    to: Location::from(&from),
+                },
+                Err(error) => {
+                    let _ = from.insert(components, moved);
+                    return Err(error);
+                }
+            }
+        }
+        PatchOperation::SetProp {
+            target,
+            name,
+            value,
+        } => {
+            let address = resolve(components, target)?;
+            let previous = set_prop(components, &address, name, Some(value))?;
+            match previous {
+                Some(value) => PatchOperation::SetProp {
+                    target: Target::from(&address),
+                    name: name.clone(),
+                    value,
+                },
+                None => PatchOperation::UnsetProp {
+                    target: Target::from(&address),
+                    name: name.clone(),
+                },
+            }
+        }
+        PatchOperation::UnsetProp { target, name } => {
+            let address = resolve(components, target)?;
+            match set_prop(components, &address, name, None)? {
+                Some(value) => PatchOperation::SetProp {
+                    target: Target::from(&address),
+                    name: name.clone(),
+                    value,
+                },
+                None => return Ok(None),
+            }
+        }
+    };
+    Ok(Some(undo))
+}
+
+// Addressing # -------------------------------------------------- #
+
+impl From<PathError> for PatchErrorKind {
+    fn from(error: PathError) -> Self {
+        match error {
+            PathError::Invalid(path) => PatchErrorKind::InvalidPath(path),
+            PathError::NotFound(path) => PatchErrorKind::NotFound(path),
+            PathError::NotInline(path) => PatchErrorKind::NotInline(path),
+        }
+    }
+}
+
+impl From<&NodePath> for Target {
+    fn from(path: &NodePath) -> Self {
+        Target::Path {
+            path: path.to_string(),
+        }
+    }
+}
+
+impl From<&NodePath> for Location {
+    fn from(path: &NodePath) -> Self {
+        Location::Path {
+            path: path.to_string(),
+        }
+    }
+}
+
+fn resolve(components: &[Component], target: &Target) -> Result<NodePath, PatchErrorKind> {
+    match target {
+        Target::Path { path } => Ok(NodePath::parse(path)?),
+        Target::Id { id } => find_id(components, id),
+    }
+}
+
+fn locate(components: &[Component], location: &Location) -> Result<NodePath, PatchErrorKind> {
+    match location {
+        Location::Path { path } => Ok(NodePath::parse(path)?),
+        Location::Slot {
+            parent,
+            slot,
+            index,
+        } => Ok(find_id(components, parent)?.child(slot, *index)),
+    }
+}
+
+fn find_id(components: &[Component], id: &str) -> Result<NodePath, PatchErrorKind> {
+    fn find(
+        nodes: &[NodeRef<'_>],
+        id: &str,
+        parent: Option<(&NodePath, &str)>,
+    ) -> Option<NodePath> {
+        for (index, node) in nodes.iter().enumerate() {
+            let path = match parent {
+                Some((parent, slot)) => parent.child(slot, index),
+                None => NodePath::new(index),
+            };
+            if node.id() == Some(id) {
+                return Some(path);
+            }
+            for (name, slot) in node.slots() {
+                let children = slot.iter().collect::<Vec<_>>();
+                if let Some(path) = find(&children, id, Some((&path, name))) {
+                    return Some(path);
+                }
+            }
+        }
+        None
+    }
+
+    let nodes = components
+        .iter()
+        .map(NodeRef::Component)
+        .collect::<Vec<_>>();
+    find(&nodes, id, None).ok_or_else(|| PatchErrorKind::NotFound(format!("#{}", id)))
+}
+
+// Edits # -------------------------------------------------- #
+
+/// Sets or, given `None`, removes a prop and returns its previous value.
+fn set_prop(
+    components: &mut Vec<Component>,
+    path: &NodePath,
+    name: &str,
+    value: Option<&Value>,
+) -> Result<Option<Value>, PatchErrorKind> {
+    let printed = path.to_string();
+    // Deserialization silently drops unknown props, so check the name itself
+    // rather than whether a value survives.
+    if let Some(node) = path.get(components)
+        && !node.prop_names().contains(&name)
+    {
+        return Err(PatchErrorKind::UnknownProp {
+            path: printed,
+            name: name.to_string(),
+        });
+    }
+    match path.get_mut(components) {
+        Some(NodeMut::Component(component)) => update_props(component, &printed, name, value),
+        Some(NodeMut::Inline(inline_component)) => {
+            update_props(inline_component, &printed, name, value)
+        }
+        None => Err(PatchErrorKind::NotFound(printed)),
+    }
+}
+
+/// Edits the props of `node` through its JSON form, so the result is checked
+/// by the same deserializer as any other document.
+fn update_props<T>(
+    node: &mut T,
+    path: &str,
+    name: &str,
+    value: Option<&Value>,
+) -> Result<Option<Value>, PatchErrorKind>
+where
+    T: Serialize + DeserializeOwned,
+{
+    let invalid = |message: String| PatchErrorKind::InvalidProps {
+        path: path.to_string(),
+        message,
+    };
+
+    let mut json = serde_json::to_value(&*node).map_err(|error| invalid(error.to_string()))?;
+    let Some(object) = json.as_object_mut() else {
+        return Err(invalid("not an object".to_string()));
+    };
+    let props = object
+        .entry("props")
+        .or_insert_with(|| Value::Object(Map::new()));
+    if props.is_null() {
+        *props = Value::Object(Map::new());
+    }
+    let Some(props) = props.as_object_mut() else {
+        return Err(invalid("props are not an object".to_string()));
+    };
+    let previous = match value {
+        Some(value) => props.insert(name.to_string(), value.clone()),
+        None => props.remove(name),
+    }
+    .filter(|previous| !previous.is_null());
+    if value.is_none() && previous.is_none() {
+        return Ok(None);
+    }
+
+    // Optional props left empty are `None` rather than all defaults, which
+    // keeps a set followed by an unset an exact round trip.
+    props.retain(|_, value| !value.is_null());
+    if props.is_empty() {
+        object.insert("props".to_string(), Value::Null);
+    }
+    let updated = match serde_json::from_value::<T>(json.clone()) {
+        Ok(updated) => updated,
+        Err(error) => {
+            let props = &mut json["props"];
+            if !props.is_null() {
+                return Err(invalid(error.to_string()));
+            }
+            *props = Value::Object(Map::new());
+            serde_json::from_value::<T>(json).map_err(|error| invalid(error.to_string()))?
+        }
+    };
+
+    *node = updated;
+    Ok(previous)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{PatchError, PatchErrorKind, PatchOperation, apply_patch};
+    use crate::Component;
+
+    fn document() -> Vec<Component> {
+        serde_json::from_str(
+            r#"[
+                {"type":"Heading","id":"title","props":{"level":1},"slots":{"default":[{"type":"Text","props":{"text":"Title"}}]}},
+                {"type":"Callout","id":"note","slots":{"default":[
+                    {"type":"Paragraph","slots":{"default":[{"type":"Text","props":{"text":"Careful"}}]}}
+                ]}},
+                {"type":"Divider"}
+            ]"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_apply_and_undo() {
+        let patch = serde_json::from_str::<Vec<PatchOperation>>(
+            r#"[
+                {"op":"insert","parent":"note","slot":"default","index":1,"component":{"type":"Divider"}},
+                {"op":"move","path":"/2","to":{"path":"/0"}},
+                {"op":"setProp","id":"title","name":"level","value":2},
+                {"op":"setProp","path":"/2/slots/default/0/slots/default/0","name":"bold","value":true},
+                {"op":"unsetProp","id":"note","name":"type"},
+                {"op":"remove","path":"/2/slots/default/1"},
+                {"op":"replace","id":"title","component":{"type":"Divider"}}
+            ]"#,
+        )
+        .unwrap();
+
+        let original = document();
+        let mut components = original.clone();
+        let inverse = apply_patch(&mut components, &patch).unwrap();
+
+        let json = serde_json::to_value(&components).unwrap();
+        assert_eq!(json[0]["type"], "Divider");
+        assert_eq!(json[1]["type"], "Divider");
+        assert_eq!(
+            json[2]["slots"]["default"][0]["slots"]["default"][0]["props"]["bold"],
+            true
+        );
+        assert_eq!(json[2]["slots"]["default"].as_array().unwrap().len(), 1);
+
+        let round_trip = serde_json::to_string(&inverse).unwrap();
+        let inverse = serde_json::from_str::<Vec<PatchOperation>>(&round_trip).unwrap();
+        apply_patch(&mut components, &inverse).unwrap();
+        assert_eq!(components, original);
+    }
+
+    #[test]
+    fn test_invalid_operations_roll_back() {
+        let cases = [
+            (
+                r#"{"op":"insert","path":"/0/slots/default/0","component":{"type":"Divider"}}"#,
+                PatchErrorKind::NotInline("/0/slots/default/0".to_string()),
+            ),
+            (
+                r#"{"op":"setProp","id":"title","name":"colour","value":"red"}"#,
+                PatchErrorKind::UnknownProp {
+                    path: "/0".to_string(),
+                    name: "colour".to_string(),
+                },
+            ),
+            (
+                r#"{"op":"setProp","id":"title","name":"bold","value":false}"#,
+                PatchErrorKind::UnknownProp {
+                    path: "/0".to_string(),
+                    name: "bold".to_string(),
+                },
+            ),
+            (
+                r#"{"op":"setProp","id":"note","name":"colour","value":null}"#,
+                PatchErrorKind::UnknownProp {
+                    path: "/1".to_string(),
+                    name: "colour".to_string(),
+                },
+            ),
+            (
+                r#"{"op":"remove","id":"missing"}"#,
+                PatchErrorKind::NotFound("#missing".to_string()),
+            ),
+            (
+                r#"{"op":"remove","path":"/0/children/1"}"#,
+                PatchErrorKind::InvalidPath("/0/children/1".to_string()),
+            ),
+        ];
+
+        for (operation, kind) in cases {
+            let patch = serde_json::from_str::<Vec<PatchOperation>>(&format!(
+                r#"[{{"op":"remove","path":"/2"}},{}]"#,
+                operation
+            ))
+            .unwrap();
+            let mut components = document();
+            assert_eq!(
+                apply_patch(&mut components, &patch),
+                Err(PatchError { index: 1, kind })
+            );
+            assert_eq!(components, document());
+        }
+
+        let patch = serde_json::from_str::<Vec<PatchOperation>>(
+            r#"[{"op":"setProp","id":"title","name":"level","value":9}]"#,
+        )
+        .unwrap();
+        let error = apply_patch(&mut document(), &patch).unwrap_err();
+        assert!(matches!(error.kind, PatchErrorKind::InvalidProps { .. }));
+    }
+}
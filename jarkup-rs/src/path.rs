@@ -0,0 +1,489 @@
+//! Stable addresses of nodes within a document.
+//!
+//! A [`NodePath`] gives the index of a node among the top-level components,
+//! then the slot name and index of every step down to it, and prints as
+//! `/0/slots/default/2`. Named slots such as `summary`, `labels`, `contents`,
+//! `header` and `body` are resolved like `default`, and the last step may end
+//! on an inline child. [`Nodes`] offers the same lookups and edits as methods
+//! on a document.
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BlockComponent, Component,
+    node::{NodeMut, NodeRef, SlotMut},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct NodePath {
+    /// Index among the top-level components.
+    root: usize,
+    /// Slot name and index of each step below the top level.
+    steps: Vec<(String, usize)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathError {
+    /// The string is not of the form `/0/slots/default/2`.
+    Invalid(String),
+    /// No node, slot or index matches the path.
+    NotFound(String),
+    /// A block component was placed in a slot that only holds inline ones.
+    NotInline(String),
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathError::Invalid(path) => write!(f, "invalid path `{}`", path),
+            PathError::NotFound(path) => write!(f, "nothing found at `{}`", path),
+            PathError::NotInline(path) => {
+                write!(f, "`{}` only accepts inline components", path)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PathError {}
+
+impl NodePath {
+    /// The path of the top-level component at `index`.
+    pub fn new(index: usize) -> Self {
+        NodePath {
+            root: index,
+            steps: Vec::new(),
+        }
+    }
+
+    pub fn parse(path: &str) -> Result<Self, PathError> {
+        let invalid = || PathError::Invalid(path.to_string());
+        let index = |segment: &str| {
+            if segment.is_empty() || !segment.bytes().all(|byte| byte.is_ascii_digit()) {
+                return Err(invalid());
+            }
+            segment.parse::<usize>().map_err(|_| invalid())
+        };
+
+        let segments = path
+            .strip_prefix('/')
+            .ok_or_else(invalid)?
+            .split('/')
+            .collect::<Vec<_>>();
+        if segments.len() % 3 != 1 {
+            return Err(invalid());
+        }
+
+        let mut steps = Vec::new();
+        for step in segments[1..].chunks(3) {
+            if step[0] != "slots" || step[1].is_empty() {
+                return Err(invalid());
+            }
+            steps.push((step[1].to_string(), index(step[2])?));
+        }
+        Ok(NodePath {
+            root: index(segments[0])?,
+            steps,
+        })
+    }
+
+    /// The path of the child at `index` in the `slot` of this node.
+    pub fn child(&self, slot: &str, index: usize) -> Self {
+        let mut steps = self.steps.clone();
+        steps.push((slot.to_string(), index));
+        NodePath {
+            root: self.root,
+            steps,
+        }
+    }
+
+    /// The path of the node holding this one, if it is not top-level.
+    pub fn parent(&self) -> Option<Self> {
+        let (_, steps) = self.steps.split_last()?;
+        Some(NodePath {
+            root: self.root,
+            steps: steps.to_vec(),
+        })
+    }
+
+    /// The name of the slot holding this node, if it is not top-level.
+    pub fn slot(&self) -> Option<&str> {
+        self.steps.last().map(|(slot, _)| slot.as_str())
+    }
+
+    /// The index of this node within its slot.
+    pub fn index(&self) -> usize {
+        self.steps.last().map_or(self.root, |(_, index)| *index)
+    }
+
+    /// Number of slots between the top level and this node.
+    pub fn depth(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn get<'a>(&self, components: &'a [Component]) -> Option<NodeRef<'a>> {
+        let mut node = NodeRef::Component(components.get(self.root)?);
+        for (name, index) in &self.steps {
+            let (_, slot) = node
+                .slots()
+                .into_iter()
+                .find(|(slot_name, _)| slot_name == name)?;
+            node = slot.get(*index)?;
+        }
+        Some(node)
+    }
+
+    pub fn get_mut<'a>(&self, components: &'a mut Vec<Component>) -> Option<NodeMut<'a>> {
+        let index = self.index();
+        match self.slot_mut(components, false)? {
+            SlotMut::Components(children) => children.get_mut(index).map(NodeMut::Component),
+            SlotMut::Inlines(children) => children.get_mut(index).map(NodeMut::Inline),
+        }
+    }
+
+    /// Inserts `component` so that it ends up at this path.
+    ///
+    /// The index may be one past the last child, which appends to the slot.
+    /// Optional slots that are absent, such as the `header` of a `Table`, are
+    /// created when inserting their first child.
+    pub fn insert(
+        &self,
+        components: &mut Vec<Component>,
+        component: Component,
+    ) -> Result<(), PathError> {
+        let index = self.index();
+        match self.slot_mut(components, index == 0) {
+            Some(SlotMut::Components(children)) if index <= children.len() => {
+                children.insert(index, component);
+            }
+            Some(SlotMut::Inlines(children)) if index <= children.len() => match component {
+                Component::InlineComponent(inline_component) => {
+                    children.insert(index, inline_component);
+                }
+                Component::BlockComponent(_) => {
+                    return Err(PathError::NotInline(self.to_string()));
+                }
+            },
+            _ => return Err(PathError::NotFound(self.to_string())),
+        }
+        Ok(())
+    }
+
+    /// Removes and returns the node at this path.
+    pub fn remove(&self, components: &mut Vec<Component>) -> Result<Component, PathError> {
+        let index = self.index();
+        match self.slot_mut(components, false) {
+            Some(SlotMut::Components(children)) if index < children.len() => {
+                Ok(children.remove(index))
+            }
+            Some(SlotMut::Inlines(children)) if index < children.len() => {
+                Ok(Component::InlineComponent(children.remove(index)))
+            }
+            _ => Err(PathError::NotFound(self.to_string())),
+        }
+    }
+
+    /// Returns the slot holding the node at this path, creating it first if
+    /// `create` is set and it is an absent optional slot.
+    fn slot_mut<'a>(
+        &self,
+        components: &'a mut Vec<Component>,
+        create: bool,
+    ) -> Option<SlotMut<'a>> {
+        let mut slot = SlotMut::Components(components);
+        let mut index = self.root;
+        for (step, (name, next)) in self.steps.iter().enumerate() {
+            let SlotMut::Components(children) = slot else {
+                return None;
+            };
+            let component = children.get_mut(index)?;
+            if create && step + 1 == self.steps.len() {
+                create_slot(component, name);
+            }
+            slot = component
+                .slots_mut()
+                .into_iter()
+                .find(|(slot_name, _)| slot_name == name)
+                .map(|(_, slot)| slot)?;
+            index = *next;
+        }
+        Some(slot)
+    }
+}
+
+/// Creates the optional slot `name` of `component` if it is absent.
+fn create_slot(component: &mut Component, name: &str) {
+    match (component, name) {
+        (Component::BlockComponent(BlockComponent::Table(table)), "header") => {
+            table.slots.header.get_or_insert_with(Vec::new);
+        }
+        (Component::BlockComponent(BlockComponent::CodeBlock(code_block)), "default") => {
+            code_block.slots.get_or_insert_with(Default::default);
+        }
+        _ => {}
+    }
+}
+
+/// Lookups and edits by [`NodePath`] on a document.
+///
+/// The methods mirror [`NodePath::get`], [`NodePath::get_mut`],
+/// [`NodePath::insert`] and [`NodePath::remove`], under names that do not
+/// clash with those of `Vec`.
+pub trait Nodes {
+    fn node(&self, path: &NodePath) -> Option<NodeRef<'_>>;
+
+    fn node_mut(&mut self, path: &NodePath) -> Option<NodeMut<'_>>;
+
+    fn insert_node(&mut self, path: &NodePath, component: Component) -> Result<(), PathError>;
+
+    fn remove_node(&mut self, path: &NodePath) -> Result<Component, PathError>;
+}
+
+impl Nodes for Vec<Component> {
+    fn node(&self, path: &NodePath) -> Option<NodeRef<'_>> {
+        path.get(self)
+    }
+
+    fn node_mut(&mut self, path: &NodePath) -> Option<NodeMut<'_>> {
+        path.get_mut(self)
+    }
+
+    fn insert_node(&mut self, path: &NodePath, component: Component) -> Result<(), PathError> {
+        path.insert(self, component)
+    }
+
+    fn remove_node(&mut self, path: &NodePath) -> Result<Component, PathError> {
+        path.remove(self)
+    }
+}
+
+impl fmt::Display for NodePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "/{}", self.root)?;
+        for (slot, index) in &self.steps {
+            write!(f, "/slots/{}/{}", slot, index)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for NodePath {
+    type Err = PathError;
+
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        NodePath::parse(path)
+    }
+}
+
+impl From<NodePath> for String {
+    fn from(path: NodePath) -> Self {
+        path.to_string()
+    }
+}
+
+impl TryFrom<String> for NodePath {
+    type Error = PathError;
+
+    fn try_from(path: String) -> Result<Self, Self::Error> {
+        NodePath::parse(&path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{NodePath, Nodes, PathError};
+    use crate::{Component, node::NodeMut};
+
+    fn document() -> Vec<Component> {
+        serde_json::from_str(
+            r#"[
+                {"type":"Toggle","slots":{
+                    "default":[{"type":"Paragraph","slots":{"default":[{"type":"Text","props":{"text":"Body"}}]}}],
+                    "summary":[{"type":"Text","props":{"text":"More"}}]
+                }},
+                {"type":"Tabs","slots":{"default":[
+                    {"type":"Tab","slots":{"labels":[{"type":"Text","props":{"text":"Rust"}}],"contents":[{"type":"Divider"}]}}
+                ]}},
+                {"type":"Table","props":{"hasColumnHeader":true,"hasRowHeader":false},"slots":{
+                    "header":[{"type":"TableRow","slots":{"default":[{"type":"TableCell","slots":{"default":[{"type":"Text","props":{"text":"Name"}}]}}]}}],
+                    "body":[]
+                }}
+            ]"#,
+        )
+        .unwrap()
+    }
+
+    fn text(components: &[Component], path: &str) -> Option<String> {
+        let node = path.parse::<NodePath>().unwrap().get(components)?;
+        let json = serde_json::to_value(node.to_component()).unwrap();
+        json["props"]["text"].as_str().map(str::to_string)
+    }
+
+    #[test]
+    fn test_parse_and_print() {
+        for path in [
+            "/0",
+            "/3/slots/default/12",
+            "/1/slots/summary/0/slots/default/2",
+        ] {
+            assert_eq!(NodePath::parse(path).unwrap().to_string(), path);
+        }
+        for path in [
+            "",
+            "/",
+            "0",
+            "/0/",
+            "/-1",
+            "/+1",
+            "/0/slots",
+            "/0/children/default/1",
+            "/0/slots//1",
+        ] {
+            assert_eq!(
+                NodePath::parse(path),
+                Err(PathError::Invalid(path.to_string()))
+            );
+        }
+
+        let path = NodePath::new(1).child("contents", 0);
+        assert_eq!(path.to_string(), "/1/slots/contents/0");
+        assert_eq!(path.slot(), Some("contents"));
+        assert_eq!(path.index(), 0);
+        assert_eq!(path.parent(), Some(NodePath::new(1)));
+        assert_eq!(
+            serde_json::to_string(&path).unwrap(),
+            r#""/1/slots/contents/0""#
+        );
+        assert!(serde_json::from_str::<NodePath>(r#""/1/contents""#).is_err());
+    }
+
+    #[test]
+    fn test_resolve_named_slots() {
+        let components = document();
+        assert_eq!(
+            text(&components, "/0/slots/summary/0").as_deref(),
+            Some("More")
+        );
+        assert_eq!(
+            text(&components, "/0/slots/default/0/slots/default/0").as_deref(),
+            Some("Body")
+        );
+        assert_eq!(
+            text(&components, "/1/slots/default/0/slots/labels/0").as_deref(),
+            Some("Rust")
+        );
+        assert_eq!(
+            text(
+                &components,
+                "/2/slots/header/0/slots/default/0/slots/default/0"
+            )
+            .as_deref(),
+            Some("Name")
+        );
+
+        let divider = "/1/slots/default/0/slots/contents/0".parse::<NodePath>();
+        assert_eq!(
+            divider
+                .unwrap()
+                .get(&components)
+                .map(|node| node.type_name()),
+            Some("Divider")
+        );
+        for path in [
+            "/3",
+            "/0/slots/labels/0",
+            "/0/slots/summary/0/slots/default/0",
+            "/2/slots/body/0",
+        ] {
+            assert!(NodePath::parse(path).unwrap().get(&components).is_none());
+        }
+    }
+
+    #[test]
+    fn test_edit() {
+        let mut components = document();
+        let summary = NodePath::parse("/0/slots/summary/1").unwrap();
+        let text =
+            serde_json::from_str::<Component>(r#"{"type":"Text","props":{"text":"!"}}"#).unwrap();
+        summary.insert(&mut components, text.clone()).unwrap();
+        assert_eq!(text_at(&components, &summary), Some("!".to_string()));
+
+        let Some(NodeMut::Inline(inline_component)) = summary.get_mut(&mut components) else {
+            panic!("expected an inline child");
+        };
+        *inline_component =
+            serde_json::from_str(r#"{"type":"Text","props":{"text":"?"}}"#).unwrap();
+        assert_eq!(text_at(&components, &summary), Some("?".to_string()));
+
+        let divider = serde_json::from_str::<Component>(r#"{"type":"Divider"}"#).unwrap();
+        assert_eq!(
+            summary.insert(&mut components, divider.clone()),
+            Err(PathError::NotInline("/0/slots/summary/1".to_string()))
+        );
+        assert_eq!(
+            NodePath::parse("/0/slots/summary/5")
+                .unwrap()
+                .insert(&mut components, text),
+            Err(PathError::NotFound("/0/slots/summary/5".to_string()))
+        );
+
+        let body = NodePath::new(2).child("body", 0);
+        body.insert(&mut components, divider.clone()).unwrap();
+        assert_eq!(body.remove(&mut components), Ok(divider));
+        assert!(body.remove(&mut components).is_err());
+
+        let removed = NodePath::new(1).remove(&mut components).unwrap();
+        assert_eq!(removed.type_name(), "Tabs");
+        assert_eq!(components.len(), 2);
+    }
+
+    #[test]
+    fn test_nodes() {
+        let mut components = serde_json::from_str::<Vec<Component>>(
+            r#"[
+                {"type":"Table","props":{"hasColumnHeader":true,"hasRowHeader":false},"slots":{"body":[]}},
+                {"type":"CodeBlock","props":{"code":"x","language":"rust"}}
+            ]"#,
+        )
+        .unwrap();
+        let row =
+            serde_json::from_str::<Component>(r#"{"type":"TableRow","slots":{"default":[]}}"#)
+                .unwrap();
+        let run =
+            serde_json::from_str::<Component>(r#"{"type":"Text","props":{"text":"x"}}"#).unwrap();
+
+        let header = NodePath::new(0).child("header", 0);
+        assert_eq!(
+            components.insert_node(&NodePath::new(0).child("header", 1), row.clone()),
+            Err(PathError::NotFound("/0/slots/header/1".to_string()))
+        );
+        assert_eq!(
+            serde_json::to_value(&components[0]).unwrap()["slots"]["header"],
+            serde_json::Value::Null
+        );
+        components.insert_node(&header, row.clone()).unwrap();
+        assert_eq!(
+            components.node(&header).map(|node| node.type_name()),
+            Some("TableRow")
+        );
+
+        let code = NodePath::new(1).child("default", 0);
+        components.insert_node(&code, run.clone()).unwrap();
+        assert!(matches!(
+            components.node_mut(&code),
+            Some(NodeMut::Inline(_))
+        ));
+        assert_eq!(text_at(&components, &code), Some("x".to_string()));
+
+        assert_eq!(components.remove_node(&header), Ok(row));
+        assert_eq!(components.remove_node(&code), Ok(run));
+        assert!(components.node(&code).is_none());
+    }
+
+    fn text_at(components: &[Component], path: &NodePath) -> Option<String> {
+        text(components, &path.to_string())
+    }
+}
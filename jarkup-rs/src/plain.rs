@@ -0,0 +1,170 @@
+//! Plain text extraction.
+//!
+//! Blocks are separated by blank lines, list items and table rows by line
+//! breaks and table cells by tabs. Code blocks keep their code and KaTeX its
+//! expression, while dividers, images, diagrams and
+//! [`Unsupported`](crate::Unsupported) components contribute nothing.
+
+use std::fmt;
+
+use crate::{BlockComponent, Component, InlineComponent, node::Slot};
+
+/// Renders the text of `components` into a `fmt::Write` sink such as a
+/// `String`.
+pub fn render<W>(components: &[Component], sink: &mut W) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    let text = blocks(components, "\n\n");
+    if !text.is_empty() {
+        sink.write_str(&text)?;
+        sink.write_char('\n')?;
+    }
+    Ok(())
+}
+
+/// Renders the text of `components` into a new `String`.
+pub fn to_string(components: &[Component]) -> String {
+    let mut text = String::new();
+    // Writing into a `String` never fails.
+    let _ = render(components, &mut text);
+    text
+}
+
+/// Renders components, joining blocks with `separator` and runs of inline
+/// components into a line.
+fn blocks(components: &[Component], separator: &str) -> String {
+    let mut parts = Vec::new();
+    let mut line = String::new();
+    for component in components {
+        match component {
+            Component::InlineComponent(inline_component) => {
+                inline(inline_component, &mut line);
+            }
+            Component::BlockComponent(block_component) => {
+                if !line.is_empty() {
+                    parts.push(std::mem::take(&mut line));
+                }
+                parts.push(block(block_component));
+            }
+        }
+    }
+    parts.push(line);
+    parts.retain(|part| !part.is_empty());
+    parts.join(separator)
+}
+
+fn inlines(inline_components: &[InlineComponent]) -> String {
+    let mut line = String::new();
+    for inline_component in inline_components {
+        inline(inline_component, &mut line);
+    }
+    line
+}
+
+fn inline(inline_component: &InlineComponent, line: &mut String) {
+    match inline_component {
+        InlineComponent::Text(text) => line.push_str(&text.props.text),
+        InlineComponent::Icon(icon) => line.push_str(icon.props.alt.as_deref().unwrap_or("")),
+    }
+}
+
+fn block(block_component: &BlockComponent) -> String {
+    match block_component {
+        BlockComponent::List(list) => blocks(&list.slots.default, "\n"),
+        BlockComponent::TableRow(table_row) => table_row
+            .slots
+            .default
+            .iter()
+            .map(|cell| blocks(std::slice::from_ref(cell), " "))
+            .collect::<Vec<_>>()
+            .join("\t"),
+        BlockComponent::Table(table) => {
+            let rows = table
+                .slots
+                .header
+                .iter()
+                .flatten()
+                .chain(&table.slots.body)
+                .map(|row| blocks(std::slice::from_ref(row), "\n"))
+                .filter(|row| !row.is_empty())
+                .collect::<Vec<_>>()
+                .join("\n");
+            match table
+                .props
+                .as_ref()
+                .and_then(|props| props.caption.as_ref())
+            {
+                Some(caption) => format!("{}\n\n{}", rows, caption),
+                None => rows,
+            }
+        }
+        BlockComponent::CodeBlock(code_block) => code_block.props.code.clone(),
+        BlockComponent::Katex(katex) => katex.props.expression.clone(),
+        BlockComponent::Bookmark(bookmark) => bookmark
+            .props
+            .title
+            .clone()
+            .unwrap_or_else(|| bookmark.props.url.clone()),
+        BlockComponent::File(file) => file
+            .props
+            .name
+            .clone()
+            .unwrap_or_else(|| file.props.src.clone()),
+        BlockComponent::Divider(_)
+        | BlockComponent::Image(_)
+        | BlockComponent::Mermaid(_)
+        | BlockComponent::Unsupported(_) => String::new(),
+        _ => {
+            let mut parts = block_component
+                .slots()
+                .into_iter()
+                .map(|(_, slot)| match slot {
+                    Slot::Components(components) => blocks(components, "\n\n"),
+                    Slot::Inlines(inline_components) => inlines(inline_components),
+                })
+                .collect::<Vec<_>>();
+            parts.retain(|part| !part.is_empty());
+            parts.join("\n\n")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::to_string;
+    use crate::Component;
+
+    #[test]
+    fn test_to_string() {
+        let components = serde_json::from_str::<Vec<Component>>(
+            r#"[
+                {"type":"Heading","props":{"level":1},"slots":{"default":[{"type":"Text","props":{"text":"Title"}}]}},
+                {"type":"Paragraph","slots":{"default":[
+                    {"type":"Text","props":{"text":"Hello "}},
+                    {"type":"Text","props":{"text":"world","bold":true}}
+                ]}},
+                {"type":"List","slots":{"default":[
+                    {"type":"ListItem","slots":{"default":[{"type":"Text","props":{"text":"One"}}]}},
+                    {"type":"ListItem","slots":{"default":[{"type":"Text","props":{"text":"Two"}}]}}
+                ]}},
+                {"type":"Divider"},
+                {"type":"Table","slots":{"body":[
+                    {"type":"TableRow","slots":{"default":[
+                        {"type":"TableCell","slots":{"default":[{"type":"Text","props":{"text":"a"}}]}},
+                        {"type":"TableCell","slots":{"default":[{"type":"Text","props":{"text":"b"}}]}}
+                    ]}}
+                ]}},
+                {"type":"CodeBlock","props":{"code":"let a = 1;","language":"rust"}}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            to_string(&components),
+            "Title\n\nHello world\n\nOne\nTwo\n\na\tb\n\nlet a = 1;\n"
+        );
+        assert_eq!(to_string(&[]), "");
+    }
+}
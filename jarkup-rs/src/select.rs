@@ -0,0 +1,500 @@
+//! CSS-like selectors over documents.
+//!
+//! ```text
+//! Callout[type=warning] Text[href]
+//! Heading[level=2], CodeBlock[language="rust"]
+//! Toggle::summary > Text[bold]
+//! ```
+//!
+//! A compound selector names a component type (or `*`) and may add
+//! `#id` and `[prop]`, `[prop=value]`, `[prop^=value]`, `[prop$=value]` or
+//! `[prop*=value]` filters on props. Compounds are joined by whitespace
+//! (descendant) or `>` (child), and `::slot` after a compound only follows
+//! that slot, e.g. `Table::header TableCell`. Groups are separated by `,`.
+
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::{Component, node::NodeRef, path::NodePath};
+
+const TYPE_NAMES: &[&str] = &[
+    "Text",
+    "Icon",
+    "Fragment",
+    "Heading",
+    "Paragraph",
+    "ListItem",
+    "List",
+    "BlockQuote",
+    "Callout",
+    "Divider",
+    "Toggle",
+    "Bookmark",
+    "File",
+    "Image",
+    "CodeBlock",
+    "Katex",
+    "Mermaid",
+    "Tab",
+    "Tabs",
+    "Table",
+    "TableRow",
+    "TableCell",
+    "ColumnList",
+    "Column",
+    "Unsupported",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectorError {
+    /// Byte offset within the selector.
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for SelectorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "at {}: {}", self.position, self.message)
+    }
+}
+
+impl std::error::Error for SelectorError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Exists,
+    Equals,
+    Prefix,
+    Suffix,
+    Contains,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Attribute {
+    name: String,
+    operator: Operator,
+    value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+struct Compound {
+    /// `None` for `*`.
+    type_name: Option<String>,
+    id: Option<String>,
+    attributes: Vec<Attribute>,
+    /// Restricts the following combinator to this slot.
+    slot: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Combinator {
+    Descendant,
+    Child,
+}
+
+/// A compound and the combinator linking it to the previous one.
+type Step = (Combinator, Compound);
+
+/// A parsed selector, reusable across documents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selector {
+    groups: Vec<Vec<Step>>,
+}
+
+/// A node matched by a selector.
+#[derive(Debug, Clone)]
+pub struct Selected<'a> {
+    pub path: NodePath,
+    pub node: NodeRef<'a>,
+}
+
+/// Returns the nodes of `components` matching `selector`, in document order.
+pub fn select<'a>(
+    components: &'a [Component],
+    selector: &str,
+) -> Result<Vec<Selected<'a>>, SelectorError> {
+    Ok(Selector::parse(selector)?.select(components))
+}
+
+impl Selector {
+    pub fn parse(selector: &str) -> Result<Self, SelectorError> {
+        Parser {
+            source: selector,
+            position: 0,
+        }
+        .selector()
+    }
+
+    /// Returns the nodes of `components` matching this selector, in document
+    /// order.
+    pub fn select<'a>(&self, components: &'a [Component]) -> Vec<Selected<'a>> {
+        let mut selected = Vec::new();
+        let nodes = components
+            .iter()
+            .map(NodeRef::Component)
+            .collect::<Vec<_>>();
+        self.walk(&nodes, None, &mut Vec::new(), &mut selected);
+        selected
+    }
+
+    fn walk<'a>(
+        &self,
+        nodes: &[NodeRef<'a>],
+        parent: Option<(&NodePath, &str)>,
+        ancestors: &mut Vec<(NodeRef<'a>, &'static str)>,
+        selected: &mut Vec<Selected<'a>>,
+    ) {
+        for (index, node) in nodes.iter().enumerate() {
+            let path = match parent {
+                Some((parent, slot)) => parent.child(slot, index),
+                None => NodePath::new(index),
+            };
+            if self.matches(*node, ancestors) {
+                selected.push(Selected {
+                    path: path.clone(),
+                    node: *node,
+                });
+            }
+            for (name, slot) in node.slots() {
+                ancestors.push((*node, name));
+                let children = slot.iter().collect::<Vec<_>>();
+                self.walk(&children, Some((&path, name)), ancestors, selected);
+                ancestors.pop();
+            }
+        }
+    }
+
+    /// `ancestors` lists each ancestor with the slot leading down to `node`.
+    fn matches(&self, node: NodeRef<'_>, ancestors: &[(NodeRef<'_>, &str)]) -> bool {
+        self.groups.iter().any(|steps| {
+            let (last, rest) = steps.split_last().expect("groups are never empty");
+            last.1.matches(node) && matches_ancestors(rest, last.0, ancestors)
+        })
+    }
+}
+
+/// Matches `steps` right to left against `ancestors`, the nearest last.
+/// `combinator` links the last of `steps` to the node below `ancestors`.
+fn matches_ancestors(
+    steps: &[Step],
+    combinator: Combinator,
+    ancestors: &[(NodeRef<'_>, &str)],
+) -> bool {
+    let Some(((next, compound), rest)) = steps.split_last() else {
+        return true;
+    };
+    let candidates = match combinator {
+        Combinator::Child => ancestors.len().saturating_sub(1)..ancestors.len(),
+        Combinator::Descendant => 0..ancestors.len(),
+    };
+    candidates.rev().any(|index| {
+        let (ancestor, slot) = ancestors[index];
+        compound.matches(ancestor)
+            && compound.slot.as_deref().is_none_or(|name| name == slot)
+            && matches_ancestors(rest, *next, &ancestors[..index])
+    })
+}
+
+impl Compound {
+    fn matches(&self, node: NodeRef<'_>) -> bool {
+        if self
+            .type_name
+            .as_deref()
+            .is_some_and(|type_name| type_name != node.type_name())
+        {
+            return false;
+        }
+        if self.id.is_some() && self.id.as_deref() != node.id() {
+            return false;
+        }
+        if self.attributes.is_empty() {
+            return true;
+        }
+
+        let props = node.props();
+        self.attributes.iter().all(|attribute| {
+            let text = match attribute.name.as_str() {
+                "id" => node.id().map(str::to_string),
+                name => match props.get(name) {
+                    None | Some(Value::Null) | Some(Value::Bool(false)) => None,
+                    Some(Value::String(string)) => Some(string.clone()),
+                    Some(value) => Some(value.to_string()),
+                },
+            };
+            let Some(text) = text else {
+                return false;
+            };
+            let expected = attribute.value.as_str();
+            match attribute.operator {
+                Operator::Exists => true,
+                Operator::Equals => text == expected,
+                Operator::Prefix => text.starts_with(expected),
+                Operator::Suffix => text.ends_with(expected),
+                Operator::Contains => text.contains(expected),
+            }
+        })
+    }
+}
+
+// Parser # -------------------------------------------------- #
+
+struct Parser<'a> {
+    source: &'a str,
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, SelectorError> {
+        Err(SelectorError {
+            position: self.position,
+            message: message.into(),
+        })
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.source[self.position..].chars().next()
+    }
+
+    fn eat(&mut self, prefix: &str) -> bool {
+        let matched = self.source[self.position..].starts_with(prefix);
+        if matched {
+            self.position += prefix.len();
+        }
+        matched
+    }
+
+    fn skip_whitespace(&mut self) -> bool {
+        let start = self.position;
+        while let Some(c) = self.peek().filter(|c| c.is_whitespace()) {
+            self.position += c.len_utf8();
+        }
+        self.position > start
+    }
+
+    fn identifier(&mut self) -> Result<String, SelectorError> {
+        let rest = &self.source[self.position..];
+        let length = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+            .unwrap_or(rest.len());
+        if length == 0 {
+            return self.error("expected a name");
+        }
+        self.position += length;
+        Ok(rest[..length].to_string())
+    }
+
+    fn selector(&mut self) -> Result<Selector, SelectorError> {
+        let mut groups = Vec::new();
+        loop {
+            self.skip_whitespace();
+            groups.push(self.group()?);
+            if !self.eat(",") {
+                break;
+            }
+        }
+        if self.position < self.source.len() {
+            return self.error(format!("unexpected `{}`", self.peek().unwrap_or_default()));
+        }
+        Ok(Selector { groups })
+    }
+
+    fn group(&mut self) -> Result<Vec<Step>, SelectorError> {
+        let mut steps = vec![(Combinator::Descendant, self.compound()?)];
+        loop {
+            let spaced = self.skip_whitespace();
+            let combinator = if self.eat(">") {
+                self.skip_whitespace();
+                Combinator::Child
+            } else if spaced && !matches!(self.peek(), None | Some(',')) {
+                Combinator::Descendant
+            } else {
+                return Ok(steps);
+            };
+            steps.push((combinator, self.compound()?));
+        }
+    }
+
+    fn compound(&mut self) -> Result<Compound, SelectorError> {
+        let start = self.position;
+        let mut compound = Compound::default();
+        if !self.eat("*") && self.peek().is_some_and(char::is_alphabetic) {
+            let type_name = self.identifier()?;
+            if !TYPE_NAMES.contains(&type_name.as_str()) {
+                self.position = start;
+                return self.error(format!("unknown component type `{}`", type_name));
+            }
+            compound.type_name = Some(type_name);
+        }
+
+        loop {
+            if self.eat("#") {
+                compound.id = Some(self.identifier()?);
+            } else if self.eat("[") {
+                compound.attributes.push(self.attribute()?);
+            } else if self.eat("::") {
+                compound.slot = Some(self.identifier()?);
+            } else {
+                break;
+            }
+        }
+
+        if self.position == start {
+            return self.error("expected a component type, `*`, `#id` or `[prop]`");
+        }
+        Ok(compound)
+    }
+
+    fn attribute(&mut self) -> Result<Attribute, SelectorError> {
+        self.skip_whitespace();
+        let name = self.identifier()?;
+        self.skip_whitespace();
+        let operator = if self.eat("]") {
+            return Ok(Attribute {
+                name,
+                operator: Operator::Exists,
+                value: String::new(),
+            });
+        } else if self.eat("=") {
+            Operator::Equals
+        } else if self.eat("^=") {
+            Operator::Prefix
+        } else if self.eat("$=") {
+            Operator::Suffix
+        } else if self.eat("*=") {
+            Operator::Contains
+        } else {
+            return self.error("expected `]`, `=`, `^=`, `$=` or `*=`");
+        };
+        self.skip_whitespace();
+
+        let value = match self.peek() {
+            Some(quote @ ('"' | '\'')) => {
+                self.position += 1;
+                let mut value = String::new();
+                loop {
+                    match self.peek() {
+                        None => return self.error("unterminated string"),
+                        Some('\\') => {
+                            self.position += 1;
+                            let Some(escaped) = self.peek() else {
+                                return self.error("unterminated string");
+                            };
+                            value.push(escaped);
+                            self.position += escaped.len_utf8();
+                        }
+                        Some(c) => {
+                            self.position += c.len_utf8();
+                            if c == quote {
+                                break;
+                            }
+                            value.push(c);
+                        }
+                    }
+                }
+                value
+            }
+            _ => {
+                let rest = &self.source[self.position..];
+                let length = rest
+                    .find(|c: char| c == ']' || c.is_whitespace())
+                    .unwrap_or(rest.len());
+                if length == 0 {
+                    return self.error("expected a value");
+                }
+                self.position += length;
+                rest[..length].to_string()
+            }
+        };
+
+        self.skip_whitespace();
+        if !self.eat("]") {
+            return self.error("expected `]`");
+        }
+        Ok(Attribute {
+            name,
+            operator,
+            value,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{Selector, SelectorError, select};
+    use crate::Component;
+
+    fn document() -> Vec<Component> {
+        serde_json::from_str(
+            r##"[
+                {"type":"Heading","props":{"level":2},"slots":{"default":[{"type":"Text","props":{"text":"Intro","href":"#intro"}}]}},
+                {"type":"Callout","props":{"type":"warning"},"slots":{"default":[
+                    {"type":"Paragraph","slots":{"default":[
+                        {"type":"Text","props":{"text":"See "}},
+                        {"type":"Text","props":{"text":"docs","href":"https://example.com"}}
+                    ]}},
+                    {"type":"Toggle","slots":{
+                        "summary":[{"type":"Text","props":{"text":"More","bold":true}}],
+                        "default":[{"type":"CodeBlock","props":{"code":"fn main() {}","language":"rust"}}]
+                    }}
+                ]}},
+                {"type":"Callout","props":{"type":"tip"},"slots":{"default":[
+                    {"type":"Paragraph","slots":{"default":[{"type":"Text","props":{"text":"x","href":"https://example.org"}}]}}
+                ]}}
+            ]"##,
+        )
+        .unwrap()
+    }
+
+    fn paths(selector: &str) -> Vec<String> {
+        let components = document();
+        Selector::parse(selector)
+            .unwrap()
+            .select(&components)
+            .into_iter()
+            .map(|selected| selected.path.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_select() {
+        assert_eq!(
+            paths("Callout[type=warning] Text[href]"),
+            vec!["/1/slots/default/0/slots/default/1"]
+        );
+        assert_eq!(paths("Heading[level=2] > Text"), vec!["/0/slots/default/0"]);
+        assert_eq!(
+            paths("Toggle::summary Text, CodeBlock[language=\"rust\"]"),
+            vec![
+                "/1/slots/default/1/slots/default/0",
+                "/1/slots/default/1/slots/summary/0",
+            ]
+        );
+        assert_eq!(paths("Callout > Text"), Vec::<String>::new());
+        assert_eq!(
+            paths("Text[href^=https]"),
+            vec![
+                "/1/slots/default/0/slots/default/1",
+                "/2/slots/default/0/slots/default/0",
+            ]
+        );
+        assert_eq!(paths("*[bold]"), vec!["/1/slots/default/1/slots/summary/0"]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Selector::parse("Calout Text"),
+            Err(SelectorError {
+                position: 0,
+                message: "unknown component type `Calout`".to_string()
+            })
+        );
+        assert_eq!(Selector::parse("Text[href").unwrap_err().position, 9);
+        assert!(Selector::parse("Callout >").is_err());
+        assert!(Selector::parse("Text[text=\"open]").is_err());
+        assert!(select(&document(), "Divider >").is_err());
+        assert_eq!(paths("Callout\u{3000}Text"), paths("Callout Text"));
+    }
+}
@@ -0,0 +1,761 @@
+//! Static site generation.
+//!
+//! [`build`] renders every jarkup JSON (`.json`) and text (`.jarkup`) document
+//! below a directory into the same place in the output directory, with a
+//! `.html` extension. A document's id is its path without the extension,
+//! such as `guide/intro`, and links to `jarkup:guide/intro` or
+//! `jarkup:guide/intro#setup` are rewritten to point at its page. Headings
+//! without an id are given one derived from their text so that the table of
+//! contents of each page can link to them.
+//!
+//! Besides the pages, the output holds an `index.html` listing them (unless
+//! a document has the id `index`), a `sitemap.xml` when
+//! [`SiteConfig::base_url`] is set, and a copy of every local icon, file and
+//! image the documents reference.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    Assets, BlockComponent, Component, InlineComponent, html,
+    node::{NodeMut, NodeRef, SlotMut},
+    syntax,
+    toc::{self, TocEntry},
+};
+
+/// Scheme of links to other documents of the site.
+const LINK_SCHEME: &str = "jarkup:";
+
+pub const DEFAULT_LAYOUT: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{{title}} | {{site}}</title>
+</head>
+<body>
+<header><a href="{{root}}index.html">{{site}}</a></header>
+<nav>{{toc}}</nav>
+<main>{{content}}</main>
+</body>
+</html>
+"#;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SiteConfig {
+    /// Name of the site, also the title of the index page.
+    pub title: String,
+
+    /// HTML template of every page, in which `{{title}}`, `{{site}}`,
+    /// `{{toc}}` and `{{content}}` are replaced, and `{{root}}` by the
+    /// relative path from the page to the root of the site, e.g. `../`.
+    pub layout: String,
+
+    /// Absolute URL the site is published at, e.g. `https://example.com/docs/`.
+    /// Sitemaps need absolute URLs, so `sitemap.xml` is only written when set.
+    pub base_url: Option<String>,
+}
+
+impl Default for SiteConfig {
+    fn default() -> Self {
+        Self {
+            title: "Documentation".to_string(),
+            layout: DEFAULT_LAYOUT.to_string(),
+            base_url: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SiteError {
+    Io {
+        path: PathBuf,
+        error: io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        message: String,
+    },
+    /// A `jarkup:` link names a document or anchor that does not exist.
+    BrokenLink {
+        document: String,
+        href: String,
+    },
+    /// A referenced local asset does not exist or lies outside the site.
+    MissingAsset {
+        document: String,
+        src: String,
+    },
+    /// Two sources, such as `a.json` and `a.jarkup`, map to the same page.
+    DuplicateId {
+        id: String,
+        first: PathBuf,
+        second: PathBuf,
+    },
+}
+
+impl fmt::Display for SiteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SiteError::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+            SiteError::Parse { path, message } => write!(f, "{}: {}", path.display(), message),
+            SiteError::BrokenLink { document, href } => {
+                write!(f, "{}: broken link `{}`", document, href)
+            }
+            SiteError::MissingAsset { document, src } => {
+                write!(f, "{}: missing asset `{}`", document, src)
+            }
+            SiteError::DuplicateId { id, first, second } => write!(
+                f,
+                "{}: both {} and {} map to this page",
+                id,
+                first.display(),
+                second.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SiteError {}
+
+struct Document {
+    /// Path without extension, relative to the source directory.
+    id: String,
+    title: String,
+    components: Vec<Component>,
+}
+
+impl Document {
+    /// Path of the page relative to the output directory.
+    fn page(&self) -> String {
+        format!("{}.html", self.id)
+    }
+
+    /// Relative path from the page to the root of the site.
+    fn root(&self) -> String {
+        "../".repeat(self.id.matches('/').count())
+    }
+}
+
+/// Builds the site of the documents in `source` into `output` and returns
+/// the paths of the files written.
+///
+/// Nothing is written unless every document parses and all of their links and
+/// assets resolve; otherwise every problem found is returned.
+pub fn build(
+    source: &Path,
+    output: &Path,
+    config: &SiteConfig,
+) -> Result<Vec<PathBuf>, Vec<SiteError>> {
+    let mut errors = Vec::new();
+    let mut documents = Vec::new();
+    let mut files = Vec::new();
+    let skip = output.canonicalize().ok();
+    collect_sources(source, skip.as_deref(), &mut files, &mut errors);
+    files.sort();
+    let mut paths = BTreeMap::<String, PathBuf>::new();
+    for path in files {
+        match load(source, &path) {
+            Ok(document) => match paths.get(&document.id) {
+                Some(first) => errors.push(SiteError::DuplicateId {
+                    id: document.id,
+                    first: first.clone(),
+                    second: path,
+                }),
+                None => {
+                    paths.insert(document.id.clone(), path);
+                    documents.push(document);
+                }
+            },
+            Err(error) => errors.push(error),
+        }
+    }
+    documents.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let anchors = documents
+        .iter_mut()
+        .map(|document| {
+            (
+                document.id.clone(),
+                assign_anchors(&mut document.components),
+            )
+        })
+        .collect::<BTreeMap<_, _>>();
+    for document in &mut documents {
+        resolve_links(document, &anchors, &mut errors);
+    }
+    let mut assets = BTreeSet::new();
+    for document in &documents {
+        collect_assets(source, document, &mut assets, &mut errors);
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let mut written = Vec::new();
+    let mut write = |path: &str, contents: &[u8]| -> Result<(), Vec<SiteError>> {
+        let path = output.join(path);
+        path.parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&path, contents))
+            .map_err(|error| {
+                vec![SiteError::Io {
+                    path: path.clone(),
+                    error,
+                }]
+            })?;
+        written.push(path);
+        Ok(())
+    };
+
+    for document in &documents {
+        let html = fill(
+            &config.layout,
+            &[
+                ("title", &escape(&document.title)),
+                ("site", &escape(&config.title)),
+                ("root", &document.root()),
+                ("toc", &render_toc(&toc::toc(&document.components))),
+                ("content", &html::to_string(&document.components)),
+            ],
+        );
+        write(&document.page(), html.as_bytes())?;
+    }
+    if !documents.iter().any(|document| document.id == "index") {
+        write("index.html", render_index(&documents, config).as_bytes())?;
+    }
+    if let Some(base_url) = &config.base_url {
+        write(
+            "sitemap.xml",
+            render_sitemap(&documents, base_url).as_bytes(),
+        )?;
+    }
+    for asset in assets {
+        let from = source.join(&asset);
+        let contents =
+            fs::read(&from).map_err(|error| vec![SiteError::Io { path: from, error }])?;
+        write(&asset, &contents)?;
+    }
+    Ok(written)
+}
+
+// Loading # -------------------------------------------------- #
+
+fn collect_sources(
+    directory: &Path,
+    skip: Option<&Path>,
+    files: &mut Vec<PathBuf>,
+    errors: &mut Vec<SiteError>,
+) {
+    let entries = match fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(error) => {
+            errors.push(SiteError::Io {
+                path: directory.to_path_buf(),
+                error,
+            });
+            return;
+        }
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            if skip.is_none() || path.canonicalize().ok().as_deref() != skip {
+                collect_sources(&path, skip, files, errors);
+            }
+        } else if matches!(
+            path.extension().and_then(|extension| extension.to_str()),
+            Some("json" | "jarkup")
+        ) {
+            files.push(path);
+        }
+    }
+}
+
+fn load(source: &Path, path: &Path) -> Result<Document, SiteError> {
+    let text = fs::read_to_string(path).map_err(|error| SiteError::Io {
+        path: path.to_path_buf(),
+        error,
+    })?;
+    let parsed = if path
+        .extension()
+        .is_some_and(|extension| extension == "json")
+    {
+        serde_json::from_str(&text).map_err(|error| error.to_string())
+    } else {
+        syntax::parse(&text).map_err(|error| error.to_string())
+    };
+    let components = parsed.map_err(|message| SiteError::Parse {
+        path: path.to_path_buf(),
+        message,
+    })?;
+
+    let relative = path.strip_prefix(source).unwrap_or(path).with_extension("");
+    let id = relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    let title = toc::toc(&components)
+        .into_iter()
+        .next()
+        .map(|entry| entry.text.trim().to_string())
+        .unwrap_or_else(|| id.rsplit('/').next().unwrap_or_default().to_string());
+    Ok(Document {
+        id,
+        title,
+        components,
+    })
+}
+
+// Links # -------------------------------------------------- #
+
+/// Visits every node below `slot`, parents before children.
+fn visit_mut(slot: SlotMut<'_>, f: &mut dyn FnMut(NodeMut<'_>)) {
+    match slot {
+        SlotMut::Components(children) => {
+            for child in children {
+                f(NodeMut::Component(child));
+                for (_, slot) in child.slots_mut() {
+                    visit_mut(slot, f);
+                }
+            }
+        }
+        SlotMut::Inlines(children) => {
+            for child in children {
+                f(NodeMut::Inline(child));
+            }
+        }
+    }
+}
+
+fn collect_ids(node: NodeRef<'_>, ids: &mut HashSet<String>) {
+    if let Some(id) = node.id() {
+        ids.insert(id.to_string());
+    }
+    for (_, slot) in node.slots() {
+        for child in slot.iter() {
+            collect_ids(child, ids);
+        }
+    }
+}
+
+/// Gives every heading without an id one derived from its text, and returns
+/// every id of the document.
+fn assign_anchors(components: &mut Vec<Component>) -> HashSet<String> {
+    let mut ids = HashSet::new();
+    for component in components.iter() {
+        collect_ids(NodeRef::Component(component), &mut ids);
+    }
+    visit_mut(SlotMut::Components(components), &mut |node| {
+        let NodeMut::Component(Component::BlockComponent(BlockComponent::Heading(heading))) = node
+        else {
+            return;
+        };
+        if heading.id.is_some() {
+            return;
+        }
+        let text = heading
+            .slots
+            .default
+            .iter()
+            .filter_map(|inline_component| match inline_component {
+                InlineComponent::Text(text) => Some(text.props.text.as_str()),
+                InlineComponent::Icon(_) => None,
+            })
+            .collect::<String>();
+        let slug = slugify(&text);
+        let mut id = slug.clone();
+        let mut suffix = 1;
+        while ids.contains(&id) {
+            suffix += 1;
+            id = format!("{}-{}", slug, suffix);
+        }
+        ids.insert(id.clone());
+        heading.id = Some(id);
+    });
+    ids
+}
+
+fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if (c.is_whitespace() || c == '-' || c == '_') && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_matches('-');
+    if slug.is_empty() {
+        "section".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+/// Rewrites `jarkup:` links of `document` to relative URLs.
+fn resolve_links(
+    document: &mut Document,
+    anchors: &BTreeMap<String, HashSet<String>>,
+    errors: &mut Vec<SiteError>,
+) {
+    let root = document.root();
+    let mut resolve = |href: &mut String| {
+        let Some(target) = href.strip_prefix(LINK_SCHEME) else {
+            return;
+        };
+        let (id, anchor) = match target.split_once('#') {
+            Some((id, anchor)) => (id, Some(anchor)),
+            None => (target, None),
+        };
+        let id = if id.is_empty() {
+            document.id.as_str()
+        } else {
+            id
+        };
+        let resolved = anchors.get(id).and_then(|ids| match anchor {
+            Some(anchor) if !ids.contains(anchor) => None,
+            Some(anchor) => Some(format!("{}{}.html#{}", root, id, anchor)),
+            None => Some(format!("{}{}.html", root, id)),
+        });
+        match resolved {
+            Some(resolved) => *href = resolved,
+            None => errors.push(SiteError::BrokenLink {
+                document: document.id.clone(),
+                href: href.clone(),
+            }),
+        }
+    };
+
+    visit_mut(
+        SlotMut::Components(&mut document.components),
+        &mut |node| match node {
+            NodeMut::Inline(InlineComponent::Text(text)) => {
+                if let Some(href) = &mut text.props.href {
+                    resolve(href);
+                }
+            }
+            NodeMut::Component(Component::BlockComponent(BlockComponent::Bookmark(bookmark))) => {
+                resolve(&mut bookmark.props.url);
+            }
+            _ => {}
+        },
+    );
+}
+
+// Assets # -------------------------------------------------- #
+
+/// Adds the local assets of `document` to `assets`, as paths relative to the
+/// source directory.
+fn collect_assets(
+    source: &Path,
+    document: &Document,
+    assets: &mut BTreeSet<String>,
+    errors: &mut Vec<SiteError>,
+) {
+    let Assets {
+        icons,
+        files,
+        images,
+    } = document
+        .components
+        .iter()
+        .cloned()
+        .map(Component::extract_assets)
+        .fold(Assets::default(), |assets, more| assets + more);
+
+    let directory = document
+        .id
+        .rsplit_once('/')
+        .map_or("", |(directory, _)| directory);
+    for src in icons.into_iter().chain(files).chain(images) {
+        let remote = src.contains("://") || src.starts_with("//") || src.starts_with("data:");
+        if remote || src.is_empty() {
+            continue;
+        }
+        let path = src.split(['?', '#']).next().unwrap_or_default();
+        let joined = match path.strip_prefix('/') {
+            Some(path) => path.to_string(),
+            None if directory.is_empty() => path.to_string(),
+            None => format!("{}/{}", directory, path),
+        };
+        match normalize(&joined) {
+            Some(asset) if source.join(&asset).is_file() => {
+                assets.insert(asset);
+            }
+            _ => errors.push(SiteError::MissingAsset {
+                document: document.id.clone(),
+                src,
+            }),
+        }
+    }
+}
+
+/// Resolves `.` and `..` segments, or returns `None` when the path leaves the
+/// site.
+fn normalize(path: &str) -> Option<String> {
+    let mut segments = Vec::new();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop()?;
+            }
+            segment => segments.push(segment),
+        }
+    }
+    (!segments.is_empty()).then(|| segments.join("/"))
+}
+
+// Rendering # -------------------------------------------------- #
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::new();
+    html::escape(text, &mut escaped).expect("writing to a string cannot fail");
+    escaped
+}
+
+/// Replaces the `{{name}}` placeholders of `template` in a single pass, so
+/// that values containing placeholders are left alone.
+fn fill(template: &str, values: &[(&str, &str)]) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let value = after.find("}}").and_then(|end| {
+            let name = after[..end].trim();
+            values
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| (*value, end))
+        });
+        match value {
+            Some((value, end)) => {
+                output.push_str(value);
+                rest = &after[end + 2..];
+            }
+            None => {
+                output.push_str("{{");
+                rest = after;
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Renders headings as nested lists following their levels.
+fn render_toc(entries: &[TocEntry]) -> String {
+    let mut html = String::new();
+    let mut levels = Vec::<u8>::new();
+    for entry in entries {
+        while levels.last().is_some_and(|level| *level > entry.level) {
+            html.push_str("</li></ul>");
+            levels.pop();
+        }
+        if levels.last() == Some(&entry.level) {
+            html.push_str("</li>");
+        } else {
+            html.push_str("<ul class=\"jarkup-toc\">");
+            levels.push(entry.level);
+        }
+        html.push_str("<li>");
+        match &entry.id {
+            Some(id) => html.push_str(&format!(
+                "<a href=\"#{}\">{}</a>",
+                escape(id),
+                escape(&entry.text)
+            )),
+            None => html.push_str(&escape(&entry.text)),
+        }
+    }
+    for _ in levels {
+        html.push_str("</li></ul>");
+    }
+    html
+}
+
+fn render_index(documents: &[Document], config: &SiteConfig) -> String {
+    let mut content = String::from("<ul class=\"jarkup-index\">");
+    for document in documents {
+        content.push_str(&format!(
+            "<li><a href=\"{}\">{}</a></li>",
+            escape(&document.page()),
+            escape(&document.title)
+        ));
+    }
+    content.push_str("</ul>");
+    fill(
+        &config.layout,
+        &[
+            ("title", &escape(&config.title)),
+            ("site", &escape(&config.title)),
+            ("root", ""),
+            ("toc", ""),
+            ("content", &content),
+        ],
+    )
+}
+
+fn render_sitemap(documents: &[Document], base_url: &str) -> String {
+    let base_url = base_url.trim_end_matches('/');
+    let mut pages = documents.iter().map(Document::page).collect::<Vec<_>>();
+    if !pages.iter().any(|page| page == "index.html") {
+        pages.insert(0, "index.html".to_string());
+    }
+
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+    );
+    for page in pages {
+        xml.push_str(&format!(
+            "  <url><loc>{}</loc></url>\n",
+            escape(&format!("{}/{}", base_url, page))
+        ));
+    }
+    xml.push_str("</urlset>\n");
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::{env, fs, path::PathBuf};
+
+    use super::{SiteConfig, SiteError, build, fill, slugify};
+
+    fn site(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let root = env::temp_dir().join(format!("jarkup-site-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        for (path, contents) in files {
+            let path = root.join("source").join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+        root
+    }
+
+    #[test]
+    fn test_build() {
+        let root = site(
+            "build",
+            &[
+                (
+                    "guide/intro.jarkup",
+                    "# Getting started\n\nSee the [API](href=jarkup:api#usage).\n\n## Setup\n\n## Setup\n\n@Image(src=img/cat.png)\n",
+                ),
+                ("guide/img/cat.png", "png"),
+                ("icons/rust.svg", "svg"),
+                (
+                    "api.json",
+                    r#"[{"type":"Heading","id":"usage","props":{"level":1},"slots":{"default":[{"type":"Text","props":{"text":"Usage "}},{"type":"Icon","props":{"src":"/icons/rust.svg"}}]}},
+                        {"type":"Bookmark","props":{"url":"jarkup:guide/intro#setup-2"}}]"#,
+                ),
+            ],
+        );
+        let output = root.join("public");
+        let config = SiteConfig {
+            title: "Docs".to_string(),
+            base_url: Some("https://example.com/docs/".to_string()),
+            ..Default::default()
+        };
+        let written = build(&root.join("source"), &output, &config).unwrap();
+        assert_eq!(written.len(), 6);
+
+        let intro = fs::read_to_string(output.join("guide/intro.html")).unwrap();
+        assert!(intro.contains("<title>Getting started | Docs</title>"));
+        assert!(intro.contains("<a href=\"../index.html\">Docs</a>"));
+        assert!(intro.contains("href=\"../api.html#usage\""));
+        assert!(intro.contains(
+            "<ul class=\"jarkup-toc\"><li><a href=\"#getting-started\">Getting started</a><ul class=\"jarkup-toc\"><li><a href=\"#setup\">Setup</a></li><li><a href=\"#setup-2\">Setup</a></li></ul></li></ul>"
+        ));
+        assert!(intro.contains("id=\"setup-2\""));
+
+        let api = fs::read_to_string(output.join("api.html")).unwrap();
+        assert!(api.contains("<title>Usage | Docs</title>"));
+        assert!(api.contains("guide/intro.html#setup-2"));
+
+        let index = fs::read_to_string(output.join("index.html")).unwrap();
+        assert!(index.contains("<a href=\"api.html\">Usage</a>"));
+        assert!(index.contains("<a href=\"guide/intro.html\">Getting started</a>"));
+
+        let sitemap = fs::read_to_string(output.join("sitemap.xml")).unwrap();
+        assert!(sitemap.contains("<loc>https://example.com/docs/index.html</loc>"));
+        assert!(sitemap.contains("<loc>https://example.com/docs/guide/intro.html</loc>"));
+
+        assert_eq!(
+            fs::read_to_string(output.join("guide/img/cat.png")).unwrap(),
+            "png"
+        );
+        assert_eq!(
+            fs::read_to_string(output.join("icons/rust.svg")).unwrap(),
+            "svg"
+        );
+
+        // Building again skips the output directory inside the source.
+        let nested = root.join("source/public");
+        build(&root.join("source"), &nested, &config).unwrap();
+        build(&root.join("source"), &nested, &config).unwrap();
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_errors() {
+        let root = site(
+            "errors",
+            &[
+                (
+                    "a.jarkup",
+                    "[b](href=jarkup:b)\n[c](href=jarkup:c)\n[x](href=jarkup:b#x)\n@Image(src=../../etc/passwd)\n@File(src=missing.pdf)\n",
+                ),
+                ("b.jarkup", "# B\n"),
+                ("b.json", "[]"),
+                ("broken.json", "["),
+            ],
+        );
+        let errors = build(
+            &root.join("source"),
+            &root.join("public"),
+            &SiteConfig::default(),
+        )
+        .unwrap_err();
+        let messages = errors.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(messages.len(), 6, "{:?}", messages);
+        assert!(matches!(
+            &errors[0],
+            SiteError::DuplicateId { id, first, second }
+                if id == "b" && first.ends_with("b.jarkup") && second.ends_with("b.json")
+        ));
+        assert!(
+            matches!(&errors[1], SiteError::Parse { path, .. } if path.ends_with("broken.json"))
+        );
+        assert!(messages.contains(&"a: broken link `jarkup:c`".to_string()));
+        assert!(messages.contains(&"a: broken link `jarkup:b#x`".to_string()));
+        assert!(messages.contains(&"a: missing asset `../../etc/passwd`".to_string()));
+        assert!(messages.contains(&"a: missing asset `missing.pdf`".to_string()));
+        assert!(!root.join("public").exists());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_helpers() {
+        assert_eq!(slugify("Hello, World — 2024!"), "hello-world-2024");
+        assert_eq!(slugify("日本語 の 見出し"), "日本語-の-見出し");
+        assert_eq!(slugify("?!"), "section");
+        assert_eq!(
+            fill(
+                "{{ title }}: {{content}} {{unknown}} {{",
+                &[("title", "T"), ("content", "{{title}}")]
+            ),
+            "T: {{title}} {{unknown}} {{"
+        );
+    }
+}
@@ -0,0 +1,470 @@
+//! Streaming input and output.
+//!
+//! [`NdjsonReader`] and [`NdjsonWriter`] handle newline-delimited JSON with one
+//! top-level component per line, and [`ArrayReader`] parses the usual
+//! top-level JSON array one component at a time. Only the current component is
+//! held in memory, so documents of any length are processed in constant space.
+
+use std::{
+    fmt,
+    io::{self, BufRead},
+};
+
+use crate::Component;
+
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    /// A line did not hold a component. Lines are numbered from 1.
+    Line {
+        line: usize,
+        error: serde_json::Error,
+    },
+    /// An array element did not hold a component. Elements are numbered from 0.
+    Element {
+        index: usize,
+        offset: usize,
+        error: serde_json::Error,
+    },
+    /// The array itself was malformed.
+    Syntax {
+        offset: usize,
+        expected: &'static str,
+    },
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::Io(error) => write!(f, "cannot read input: {}", error),
+            StreamError::Line { line, error } => write!(f, "line {}: {}", line, error),
+            StreamError::Element {
+                index,
+                offset,
+                error,
+            } => write!(f, "element {} at byte {}: {}", index, offset, error),
+            StreamError::Syntax { offset, expected } => {
+                write!(f, "expected {} at byte {}", expected, offset)
+            }
+        }
+    }
+}
+
+impl std::error::Error for StreamError {}
+
+impl From<io::Error> for StreamError {
+    fn from(error: io::Error) -> Self {
+        StreamError::Io(error)
+    }
+}
+
+/// Reads components from newline-delimited JSON.
+///
+/// Blank lines are skipped. A line that fails to parse yields an error, and
+/// reading resumes with the next line. Iteration stops after an I/O error.
+pub struct NdjsonReader<R> {
+    reader: R,
+    line: String,
+    number: usize,
+    done: bool,
+}
+
+impl<R> NdjsonReader<R>
+where
+    R: BufRead,
+{
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            line: String::new(),
+            number: 0,
+            done: false,
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R> Iterator for NdjsonReader<R>
+where
+    R: BufRead,
+{
+    type Item = Result<Component, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        loop {
+            self.line.clear();
+            match self.reader.read_line(&mut self.line) {
+                Ok(0) => return None,
+                Ok(_) => self.number += 1,
+                Err(error) => {
+                    self.number += 1;
+                    self.done = true;
+                    return Some(Err(StreamError::Io(error)));
+                }
+            }
+            let line = self.line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            return Some(
+                serde_json::from_str(line).map_err(|error| StreamError::Line {
+                    line: self.number,
+                    error,
+                }),
+            );
+        }
+    }
+}
+
+/// Writes components as newline-delimited JSON.
+///
+/// Every component is written with its own `write` call, so wrap unbuffered
+/// sinks in an `io::BufWriter`.
+pub struct NdjsonWriter<W> {
+    writer: W,
+}
+
+impl<W> NdjsonWriter<W>
+where
+    W: io::Write,
+{
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Writes `component` followed by a newline.
+    pub fn write(&mut self, component: &Component) -> io::Result<()> {
+        // Compact JSON escapes newlines in strings, so one line holds one component.
+        serde_json::to_writer(&mut self.writer, component)?;
+        self.writer.write_all(b"\n")
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Start,
+    First,
+    Rest,
+    Done,
+}
+
+/// Reads the components of a top-level JSON array one at a time.
+///
+/// The bytes of each element are delimited without being parsed, then handed
+/// to `serde_json` on their own. Iteration stops after the first error.
+pub struct ArrayReader<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    state: State,
+    offset: usize,
+    index: usize,
+}
+
+impl<R> ArrayReader<R>
+where
+    R: BufRead,
+{
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: Vec::new(),
+            state: State::Start,
+            offset: 0,
+            index: 0,
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Skips whitespace and returns the next byte without consuming it.
+    fn peek(&mut self) -> io::Result<Option<u8>> {
+        loop {
+            let available = match self.reader.fill_buf() {
+                Ok(available) => available,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+            let Some(&byte) = available.first() else {
+                return Ok(None);
+            };
+            let skipped = available
+                .iter()
+                .take_while(|byte| byte.is_ascii_whitespace())
+                .count();
+            if skipped == 0 {
+                return Ok(Some(byte));
+            }
+            self.reader.consume(skipped);
+            self.offset += skipped;
+        }
+    }
+
+    fn expect(&mut self, expected: &'static str) -> StreamError {
+        self.state = State::Done;
+        StreamError::Syntax {
+            offset: self.offset,
+            expected,
+        }
+    }
+
+    fn advance(&mut self) {
+        self.reader.consume(1);
+        self.offset += 1;
+    }
+
+    /// Copies the bytes of the next element into `buffer`.
+    fn delimit(&mut self) -> Result<(), StreamError> {
+        self.buffer.clear();
+        let mut depth = 0usize;
+        let mut string = false;
+        let mut escaped = false;
+        loop {
+            let available = match self.reader.fill_buf() {
+                Ok(available) => available,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(StreamError::Io(error)),
+            };
+            if available.is_empty() {
+                return Err(self.expect("`,` or `]`"));
+            }
+            let mut used = 0;
+            let mut complete = false;
+            for &byte in available {
+                if string {
+                    used += 1;
+                    match byte {
+                        _ if escaped => escaped = false,
+                        b'\\' => escaped = true,
+                        b'"' => string = false,
+                        _ => {}
+                    }
+                    if !string && depth == 0 {
+                        complete = true;
+                        break;
+                    }
+                    continue;
+                }
+                match byte {
+                    b',' | b']' | b'}' if depth == 0 => {
+                        complete = true;
+                        break;
+                    }
+                    _ if depth == 0 && byte.is_ascii_whitespace() => {
+                        complete = true;
+                        break;
+                    }
+                    b'"' => string = true,
+                    b'{' | b'[' => depth += 1,
+                    b'}' | b']' => depth -= 1,
+                    _ => {}
+                }
+                used += 1;
+                if depth == 0 && matches!(byte, b'}' | b']') {
+                    complete = true;
+                    break;
+                }
+            }
+            self.buffer.extend_from_slice(&available[..used]);
+            self.reader.consume(used);
+            self.offset += used;
+            if complete {
+                return Ok(());
+            }
+        }
+    }
+
+    fn element(&mut self) -> Result<Component, StreamError> {
+        self.peek()?;
+        let offset = self.offset;
+        self.delimit()?;
+        if self.buffer.is_empty() {
+            return Err(self.expect("a component"));
+        }
+        let index = self.index;
+        self.index += 1;
+        serde_json::from_slice(&self.buffer).map_err(|error| {
+            self.state = State::Done;
+            StreamError::Element {
+                index,
+                offset,
+                error,
+            }
+        })
+    }
+
+    fn step(&mut self) -> Result<Option<Component>, StreamError> {
+        match self.state {
+            State::Done => Ok(None),
+            State::Start => match self.peek()? {
+                Some(b'[') => {
+                    self.advance();
+                    self.state = State::First;
+                    self.step()
+                }
+                _ => Err(self.expect("`[`")),
+            },
+            State::First | State::Rest => {
+                let first = self.state == State::First;
+                match self.peek()? {
+                    Some(b']') => {
+                        self.advance();
+                        self.state = State::Done;
+                        match self.peek()? {
+                            Some(_) => Err(self.expect("the end of input")),
+                            None => Ok(None),
+                        }
+                    }
+                    Some(b',') if !first => {
+                        self.advance();
+                        self.element().map(Some)
+                    }
+                    Some(_) if first => {
+                        self.state = State::Rest;
+                        self.element().map(Some)
+                    }
+                    _ => Err(self.expect("`,` or `]`")),
+                }
+            }
+        }
+    }
+}
+
+impl<R> Iterator for ArrayReader<R>
+where
+    R: BufRead,
+{
+    type Item = Result<Component, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.step()
+            .inspect_err(|_| self.state = State::Done)
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::io::BufReader;
+
+    use super::*;
+
+    fn seed() -> (&'static [u8], Vec<Component>) {
+        let slice = include_bytes!("./jarkup.json").as_slice();
+        (slice, serde_json::from_slice(slice).unwrap())
+    }
+
+    #[test]
+    fn test_ndjson() {
+        let (_, components) = seed();
+        let mut writer = NdjsonWriter::new(Vec::new());
+        for component in &components {
+            writer.write(component).unwrap();
+        }
+        let ndjson = writer.into_inner();
+        assert_eq!(
+            ndjson.iter().filter(|&&byte| byte == b'\n').count(),
+            components.len()
+        );
+
+        let read = NdjsonReader::new(ndjson.as_slice())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(read, components);
+
+        let input = "\n{\"type\":\"Divider\"}\r\n{\"type\":\"Nope\"}\n\n{\"type\":\"Divider\"}";
+        let results = NdjsonReader::new(input.as_bytes()).collect::<Vec<_>>();
+        assert_eq!(results.len(), 3);
+        assert!(matches!(results[1], Err(StreamError::Line { line: 3, .. })));
+        assert!(results[2].is_ok());
+
+        // Invalid UTF-8 fails the read itself, which ends the stream.
+        let input = b"{\"type\":\"Divider\"}\n\xff\n{\"type\":\"Divider\"}\n";
+        let mut reader = NdjsonReader::new(input.as_slice());
+        assert!(matches!(reader.next(), Some(Ok(_))));
+        assert!(matches!(reader.next(), Some(Err(StreamError::Io(_)))));
+        assert_eq!(reader.number, 2);
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn test_array() {
+        let (slice, components) = seed();
+        // A one-byte buffer splits every element across reads.
+        let reader = BufReader::with_capacity(1, slice);
+        let read = ArrayReader::new(reader)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(read, components);
+
+        let json = r#" [ {"type":"Text","props":{"text":"]}\"["}} , {"type":"Divider"} ] "#;
+        assert_eq!(ArrayReader::new(json.as_bytes()).count(), 2);
+        assert_eq!(ArrayReader::new(" [ ] ".as_bytes()).count(), 0);
+    }
+
+    #[test]
+    fn test_array_errors() {
+        let error = |json: &str| {
+            ArrayReader::new(json.as_bytes())
+                .find_map(Result::err)
+                .unwrap()
+        };
+        assert!(matches!(
+            error("{}"),
+            StreamError::Syntax {
+                offset: 0,
+                expected: "`[`"
+            }
+        ));
+        assert!(matches!(
+            error(r#"[{"type":"Divider"} {"type":"Divider"}]"#),
+            StreamError::Syntax { offset: 20, .. }
+        ));
+        assert!(matches!(
+            error(r#"[{"type":"Divider"},]"#),
+            StreamError::Syntax {
+                expected: "a component",
+                ..
+            }
+        ));
+        assert!(matches!(
+            error(r#"[{"type":"Divider"}"#),
+            StreamError::Syntax { offset: 19, .. }
+        ));
+        assert!(matches!(
+            error(r#"[{"type":"Divider"}] x"#),
+            StreamError::Syntax {
+                expected: "the end of input",
+                ..
+            }
+        ));
+        assert!(matches!(
+            error(r#"[{"type":"Divider"}, {"type":"Nope"}]"#),
+            StreamError::Element {
+                index: 1,
+                offset: 21,
+                ..
+            }
+        ));
+
+        let mut reader = ArrayReader::new(r#"[{"type":"Nope"},{"type":"Divider"}]"#.as_bytes());
+        assert!(reader.next().unwrap().is_err());
+        assert!(reader.next().is_none());
+    }
+}
@@ -0,0 +1,1629 @@
+//! A human-writable text syntax for documents.
+//!
+//! Every block component can be written as a directive naming its type, with
+//! an optional `#id`, props in parentheses and children in braces:
+//!
+//! ```text
+//! @Callout#note(type=warning) {
+//!   Mind the [hot](bold color=red) plate.
+//! }
+//!
+//! @Toggle {
+//!   :summary { More }
+//!   Hidden until opened.
+//! }
+//! ```
+//!
+//! Children outside a `:name { … }` section go to the `default` slot, or to
+//! `contents` for `Tab` and `body` for `Table`, and leaving out the braces
+//! leaves every slot empty. Props are `key=value` pairs
+//! named as in JSON; a bare `key` means `true` and values that are not
+//! numbers or booleans may be left unquoted when they contain no spaces.
+//!
+//! Text runs are plain text or `[text](props)` spans such as
+//! `[Ctrl](kbd)` or `[漢字](ruby=かんじ)`, and icons are `@Icon(src=…)`.
+//! `\` escapes the next character, with `\n`, `\r` and `\t` standing for
+//! control characters. A line starting with `|` holds text runs placed
+//! directly in a block slot rather than in a paragraph.
+//!
+//! Common blocks have shorthands: lines of text form a `Paragraph`,
+//! `# Title` to `###### Title` a `Heading`, `---` a `Divider`, `- item` and
+//! `1. item` lines a `List`, and fenced code a `CodeBlock`.
+//!
+//! [`to_string`] prints the shorthands wherever they are exact, so that
+//! [`parse`] gives back the same document.
+
+use std::{fmt, ops::Range};
+
+use serde_json::{Map, Number, Value};
+
+use crate::{
+    BlockComponent, Component, Divider, Heading, HeadingLevel, HeadingProps, HeadingSlots,
+    InlineComponent, List, ListItem, ListItemSlots, ListProps, ListSlots, ListStyle, Paragraph,
+    ParagraphSlots,
+    node::{Slot, SlotMut},
+};
+
+/// Directives and sections nested deeper than this are rejected.
+const MAX_DEPTH: usize = 128;
+
+const BLOCK_TYPES: &[&str] = &[
+    "Fragment",
+    "Heading",
+    "Paragraph",
+    "ListItem",
+    "List",
+    "BlockQuote",
+    "Callout",
+    "Divider",
+    "Toggle",
+    "Bookmark",
+    "File",
+    "Image",
+    "CodeBlock",
+    "Katex",
+    "Mermaid",
+    "Tab",
+    "Tabs",
+    "Table",
+    "TableRow",
+    "TableCell",
+    "ColumnList",
+    "Column",
+    "Unsupported",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxError {
+    /// Byte range of the offending source.
+    pub span: Range<usize>,
+    /// 1-based line of the start of `span`.
+    pub line: usize,
+    /// 1-based column, in characters, of the start of `span`.
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for SyntaxError {}
+
+/// Slots of each component type as `(name, inline)`, in declaration order.
+fn slot_kinds(type_name: &str) -> &'static [(&'static str, bool)] {
+    match type_name {
+        "Heading" | "Paragraph" | "TableCell" | "CodeBlock" => &[("default", true)],
+        "Toggle" => &[("default", false), ("summary", true)],
+        "Tab" => &[("labels", true), ("contents", false)],
+        "Table" => &[("header", false), ("body", false)],
+        "Fragment" | "ListItem" | "List" | "BlockQuote" | "Callout" | "Tabs" | "TableRow"
+        | "ColumnList" | "Column" => &[("default", false)],
+        _ => &[],
+    }
+}
+
+/// Whether a slot is `None` rather than empty when not written.
+fn is_optional_slot(type_name: &str, slot: &str) -> bool {
+    type_name == "Table" && slot == "header"
+}
+
+/// Whether the slots of a component type are always present, so that a
+/// directive without braces stands for empty slots rather than none.
+fn has_required_slots(type_name: &str) -> bool {
+    type_name != "CodeBlock" && !slot_kinds(type_name).is_empty()
+}
+
+/// The slot receiving children written outside a section.
+fn primary_slot(type_name: &str) -> Option<(&'static str, bool)> {
+    let kinds = slot_kinds(type_name);
+    kinds
+        .iter()
+        .find(|(name, _)| *name == "default")
+        .or(kinds.last())
+        .copied()
+}
+
+/// Empty sections for every slot that cannot be left out.
+fn empty_sections(type_name: &str, span: &Range<usize>) -> Vec<Section> {
+    slot_kinds(type_name)
+        .iter()
+        .filter(|(name, _)| !is_optional_slot(type_name, name))
+        .map(|(name, inline)| Section {
+            name: name.to_string(),
+            span: span.clone(),
+            content: if *inline {
+                Content::Inlines(Vec::new())
+            } else {
+                Content::Components(Vec::new())
+            },
+        })
+        .collect()
+}
+
+// Parser # -------------------------------------------------- #
+
+/// Parses a document written in the text syntax.
+pub fn parse(source: &str) -> Result<Vec<Component>, SyntaxError> {
+    let mut parser = Parser {
+        source,
+        position: 0,
+        depth: 0,
+    };
+    let (children, _) = parser.blocks(false, None)?;
+    Ok(children)
+}
+
+/// Where a run of inline content ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Until {
+    /// At the end of the line.
+    Line,
+    /// At the end of the line, unless the next one continues the paragraph.
+    Paragraph,
+    /// At the closing brace of a body.
+    Brace,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Construct {
+    Directive,
+    Section,
+    Inlines,
+    Heading(usize),
+    Divider,
+    Fence,
+    Bullet,
+    Number,
+}
+
+enum Content {
+    Components(Vec<Component>),
+    Inlines(Vec<InlineComponent>),
+}
+
+struct Section {
+    name: String,
+    span: Range<usize>,
+    content: Content,
+}
+
+#[derive(Default)]
+struct Attributes {
+    id: Option<String>,
+    props: Map<String, Value>,
+    /// Span of each prop, to report unknown ones.
+    spans: Vec<(String, Range<usize>)>,
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    position: usize,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.source[self.position..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, prefix: &str) -> bool {
+        if self.rest().starts_with(prefix) {
+            self.position += prefix.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn error<T>(&self, span: Range<usize>, message: impl Into<String>) -> Result<T, SyntaxError> {
+        let before = &self.source[..span.start];
+        let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+        Err(SyntaxError {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            span,
+            message: message.into(),
+        })
+    }
+
+    fn here(&self) -> Range<usize> {
+        let end = self.position + self.peek().map_or(0, char::len_utf8);
+        self.position..end
+    }
+
+    fn skip_spaces(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t')) {
+            self.bump();
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    fn at_line_end(&self) -> bool {
+        let rest = self.rest();
+        rest.is_empty() || rest.starts_with('\n') || rest.starts_with("\r\n")
+    }
+
+    fn enter(&mut self) -> Result<(), SyntaxError> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return self.error(self.here(), "nesting too deep");
+        }
+        Ok(())
+    }
+
+    fn identifier(&mut self) -> &'a str {
+        let start = self.position;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            self.bump();
+        }
+        &self.source[start..self.position]
+    }
+
+    /// Recognizes the construct starting at the current position.
+    fn construct(&self, in_body: bool) -> Option<Construct> {
+        let rest = self.rest();
+        let line = rest.split('\n').next().unwrap_or_default().trim_end();
+        let hashes = rest.bytes().take_while(|byte| *byte == b'#').count();
+        let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+        if rest.starts_with('@') {
+            Some(Construct::Directive)
+        } else if rest.starts_with('|') {
+            Some(Construct::Inlines)
+        } else if (1..=6).contains(&hashes) && rest[hashes..].starts_with(' ') {
+            Some(Construct::Heading(hashes))
+        } else if line == "---" {
+            Some(Construct::Divider)
+        } else if rest.starts_with("```") {
+            Some(Construct::Fence)
+        } else if rest.starts_with("- ") {
+            Some(Construct::Bullet)
+        } else if digits > 0 && rest[digits..].starts_with(". ") {
+            Some(Construct::Number)
+        } else if in_body && self.section_start() {
+            Some(Construct::Section)
+        } else {
+            None
+        }
+    }
+
+    /// Whether a `:name {` section header starts here.
+    fn section_start(&self) -> bool {
+        let Some(rest) = self.rest().strip_prefix(':') else {
+            return false;
+        };
+        let name = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+            .unwrap_or(rest.len());
+        name > 0
+            && rest[name..]
+                .trim_start_matches([' ', '\t'])
+                .starts_with('{')
+    }
+
+    /// Parses blocks up to the end of input or, in a body, the closing brace.
+    fn blocks(
+        &mut self,
+        in_body: bool,
+        owner: Option<&str>,
+    ) -> Result<(Vec<Component>, Vec<Section>), SyntaxError> {
+        let mut children = Vec::new();
+        let mut sections = Vec::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                None if in_body => return self.error(self.here(), "expected `}`"),
+                None => break,
+                Some('}') if in_body => break,
+                Some('}') => return self.error(self.here(), "unexpected `}`"),
+                Some(_) => {}
+            }
+
+            match self.construct(owner.is_some()) {
+                Some(Construct::Directive) => children.push(self.directive()?),
+                Some(Construct::Section) => {
+                    let owner = owner.expect("sections are only recognized in bodies");
+                    sections.push(self.section(owner)?);
+                }
+                Some(Construct::Inlines) => {
+                    self.bump();
+                    self.skip_spaces();
+                    let inlines = self.inlines(Until::Line)?;
+                    children.extend(inlines.into_iter().map(Component::InlineComponent));
+                }
+                Some(Construct::Heading(level)) => {
+                    self.position += level + 1;
+                    self.skip_spaces();
+                    let level = HeadingLevel::try_from(level as u8).expect("levels are 1 to 6");
+                    children.push(
+                        Heading {
+                            id: None,
+                            props: HeadingProps { level },
+                            slots: HeadingSlots {
+                                default: self.inlines(Until::Line)?,
+                            },
+                        }
+                        .into(),
+                    );
+                }
+                Some(Construct::Divider) => {
+                    self.eat("---");
+                    self.skip_spaces();
+                    children.push(Divider::default().into());
+                }
+                Some(Construct::Fence) => children.push(self.fence()?),
+                Some(Construct::Bullet) => children.push(self.list(ListStyle::Unordered)?),
+                Some(Construct::Number) => children.push(self.list(ListStyle::Ordered)?),
+                None => children.push(
+                    Paragraph {
+                        id: None,
+                        props: None,
+                        slots: ParagraphSlots {
+                            default: self.inlines(Until::Paragraph)?,
+                        },
+                    }
+                    .into(),
+                ),
+            }
+        }
+        Ok((children, sections))
+    }
+
+    fn list(&mut self, style: ListStyle) -> Result<Component, SyntaxError> {
+        let mut items = Vec::new();
+        loop {
+            let marker = match style {
+                ListStyle::Unordered => 2,
+                ListStyle::Ordered => {
+                    self.rest().bytes().take_while(u8::is_ascii_digit).count() + 2
+                }
+            };
+            self.position += marker;
+            self.skip_spaces();
+            let inlines = self.inlines(Until::Line)?;
+            items.push(Component::from(ListItem {
+                id: None,
+                props: None,
+                slots: ListItemSlots {
+                    default: inlines
+                        .into_iter()
+                        .map(Component::InlineComponent)
+                        .collect(),
+                },
+            }));
+
+            let end = self.position;
+            if !self.eat("\n") && !self.eat("\r\n") {
+                break;
+            }
+            self.skip_spaces();
+            let expected = match style {
+                ListStyle::Unordered => Construct::Bullet,
+                ListStyle::Ordered => Construct::Number,
+            };
+            if self.construct(false) != Some(expected) {
+                self.position = end;
+                break;
+            }
+        }
+
+        let props = match style {
+            ListStyle::Unordered => None,
+            ListStyle::Ordered => Some(ListProps {
+                list_style: Some(ListStyle::Ordered),
+            }),
+        };
+        Ok(List {
+            id: None,
+            props,
+            slots: ListSlots { default: items },
+        }
+        .into())
+    }
+
+    fn fence(&mut self) -> Result<Component, SyntaxError> {
+        let start = self.position;
+        let line_start = self.source[..start]
+            .rfind('\n')
+            .map_or(0, |index| index + 1);
+        let indent = &self.source[line_start..start];
+        let indent = if indent.trim().is_empty() {
+            indent.chars().count()
+        } else {
+            0
+        };
+
+        let ticks = self.rest().bytes().take_while(|byte| *byte == b'`').count();
+        let fence = &self.source[start..start + ticks];
+        self.position += ticks;
+        let info_end = self.rest().find('\n').map(|index| self.position + index);
+        let Some(info_end) = info_end else {
+            return self.error(start..self.source.len(), "unclosed code fence");
+        };
+        let language = self.source[self.position..info_end].trim().to_string();
+
+        let content_start = info_end + 1;
+        let mut line_start = content_start;
+        let closing = loop {
+            if line_start > self.source.len() {
+                return self.error(start..start + ticks, "unclosed code fence");
+            }
+            let line_end = self.source[line_start..]
+                .find('\n')
+                .map_or(self.source.len(), |index| line_start + index);
+            if self.source[line_start..line_end].trim() == fence {
+                break (line_start, line_end);
+            }
+            line_start = line_end + 1;
+        };
+
+        let content = &self.source[content_start..closing.0.saturating_sub(1).max(content_start)];
+        let code = content
+            .split('\n')
+            .map(|line| {
+                let strip = line
+                    .char_indices()
+                    .take(indent)
+                    .take_while(|(_, c)| *c == ' ' || *c == '\t')
+                    .map(|(index, c)| index + c.len_utf8())
+                    .last()
+                    .unwrap_or(0);
+                &line[strip..]
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.position = closing.1;
+
+        let mut json = Map::new();
+        json.insert("type".to_string(), Value::from("CodeBlock"));
+        json.insert(
+            "props".to_string(),
+            serde_json::json!({ "code": code, "language": language }),
+        );
+        self.deserialize(Value::Object(json), start..start + ticks)
+    }
+
+    fn directive(&mut self) -> Result<Component, SyntaxError> {
+        let start = self.position;
+        self.bump();
+        let type_name = self.identifier();
+        let name_span = start..self.position;
+        if !BLOCK_TYPES.contains(&type_name) {
+            if matches!(type_name, "Text" | "Icon") {
+                return self.error(
+                    name_span,
+                    format!(
+                        "`{}` is inline; write it on a line starting with `|`",
+                        type_name
+                    ),
+                );
+            }
+            return self.error(name_span, format!("unknown component type `{}`", type_name));
+        }
+
+        let id = if self.peek() == Some('#') {
+            self.bump();
+            Some(self.id()?)
+        } else {
+            None
+        };
+        self.skip_spaces();
+        let attributes = if self.eat("(") {
+            let attributes = self.attributes()?;
+            if attributes.id.is_some() {
+                return self.error(
+                    name_span,
+                    "write the id after the type name, as in `@Type#id`",
+                );
+            }
+            Some(attributes)
+        } else {
+            None
+        };
+        self.skip_spaces();
+        let body = if self.peek() == Some('{') {
+            let open = self.here();
+            self.bump();
+            self.enter()?;
+            let body = self.body(type_name, name_span.clone())?;
+            if !self.eat("}") {
+                return self.error(open, "unclosed `{`");
+            }
+            self.depth -= 1;
+            Some(body)
+        } else if has_required_slots(type_name) {
+            Some(empty_sections(type_name, &name_span))
+        } else {
+            None
+        };
+
+        let mut json = Map::new();
+        json.insert("type".to_string(), Value::from(type_name));
+        json.insert("id".to_string(), id.map_or(Value::Null, Value::from));
+        if let Some(attributes) = &attributes {
+            json.insert("props".to_string(), Value::Object(attributes.props.clone()));
+        }
+        if let Some(body) = &body {
+            let slots = body
+                .iter()
+                .map(|section| (section.name.clone(), Value::Array(Vec::new())))
+                .collect();
+            json.insert("slots".to_string(), Value::Object(slots));
+        }
+
+        let mut component = self.deserialize(Value::Object(json), name_span.clone())?;
+        if let Some(attributes) = attributes {
+            self.check_props(&component, &attributes)?;
+        }
+        for section in body.unwrap_or_default() {
+            let slot = component
+                .slots_mut()
+                .into_iter()
+                .find(|(name, _)| *name == section.name);
+            match (slot, section.content) {
+                (Some((_, SlotMut::Components(slot))), Content::Components(children)) => {
+                    slot.extend(children)
+                }
+                (Some((_, SlotMut::Inlines(slot))), Content::Inlines(children)) => {
+                    slot.extend(children)
+                }
+                _ => {
+                    return self.error(
+                        section.span,
+                        format!("`{}` has no slot `{}`", type_name, section.name),
+                    );
+                }
+            }
+        }
+        Ok(component)
+    }
+
+    /// Parses the inside of a directive's braces into its slots.
+    fn body(
+        &mut self,
+        type_name: &str,
+        name_span: Range<usize>,
+    ) -> Result<Vec<Section>, SyntaxError> {
+        let Some((primary, inline)) = primary_slot(type_name) else {
+            self.skip_whitespace();
+            if self.peek() != Some('}') {
+                return self.error(self.here(), format!("`{}` has no slots", type_name));
+            }
+            return Ok(Vec::new());
+        };
+        if inline {
+            self.skip_whitespace();
+            let content = Content::Inlines(self.inlines(Until::Brace)?);
+            return Ok(vec![Section {
+                name: primary.to_string(),
+                span: name_span,
+                content,
+            }]);
+        }
+
+        let (children, mut sections) = self.blocks(true, Some(type_name))?;
+        for (index, section) in sections.iter().enumerate() {
+            if sections[..index]
+                .iter()
+                .any(|other| other.name == section.name)
+                || (section.name == primary && !children.is_empty())
+            {
+                return self.error(
+                    section.span.clone(),
+                    format!("slot `{}` is given twice", section.name),
+                );
+            }
+        }
+        for empty in empty_sections(type_name, &name_span) {
+            if !sections.iter().any(|section| section.name == empty.name) {
+                sections.push(empty);
+            }
+        }
+        if let Some(section) = sections.iter_mut().find(|section| section.name == primary)
+            && !children.is_empty()
+        {
+            section.content = Content::Components(children);
+        }
+        Ok(sections)
+    }
+
+    fn section(&mut self, owner: &str) -> Result<Section, SyntaxError> {
+        let start = self.position;
+        self.bump();
+        let name = self.identifier().to_string();
+        let span = start..self.position;
+        let Some((_, inline)) = slot_kinds(owner)
+            .iter()
+            .find(|(slot, _)| *slot == name)
+            .copied()
+        else {
+            return self.error(span, format!("`{}` has no slot `{}`", owner, name));
+        };
+
+        self.skip_spaces();
+        let open = self.here();
+        self.bump();
+        self.enter()?;
+        let content = if inline {
+            self.skip_whitespace();
+            Content::Inlines(self.inlines(Until::Brace)?)
+        } else {
+            Content::Components(self.blocks(true, None)?.0)
+        };
+        if !self.eat("}") {
+            return self.error(open, "unclosed `{`");
+        }
+        self.depth -= 1;
+        Ok(Section {
+            name,
+            span,
+            content,
+        })
+    }
+
+    /// Parses text runs and icons.
+    fn inlines(&mut self, until: Until) -> Result<Vec<InlineComponent>, SyntaxError> {
+        let mut inlines = Vec::new();
+        let mut plain = String::new();
+        // Length of the unescaped whitespace ending `plain`, trimmed at the end.
+        let mut trailing = 0;
+        let flush = |inlines: &mut Vec<InlineComponent>, plain: &mut String| {
+            if !plain.is_empty() {
+                inlines.push(InlineComponent::from(std::mem::take(plain)));
+            }
+        };
+
+        loop {
+            let Some(c) = self.peek() else {
+                if until == Until::Brace {
+                    return self.error(self.here(), "expected `}`");
+                }
+                break;
+            };
+            match c {
+                '}' => break,
+                '\n' | '\r' if self.at_line_end() => {
+                    if until == Until::Line {
+                        break;
+                    }
+                    let end = self.position;
+                    if until == Until::Paragraph {
+                        self.eat("\r");
+                        self.bump();
+                        self.skip_spaces();
+                        if self.at_line_end()
+                            || self.peek() == Some('}')
+                            || self.construct(true).is_some()
+                        {
+                            self.position = end;
+                            break;
+                        }
+                    } else {
+                        self.skip_whitespace();
+                        if self.peek() == Some('}') {
+                            continue;
+                        }
+                    }
+                    plain.truncate(plain.len() - trailing);
+                    plain.push(' ');
+                    trailing = 1;
+                }
+                '\\' => {
+                    let start = self.position;
+                    self.bump();
+                    match self.bump() {
+                        Some('n') => plain.push('\n'),
+                        Some('r') => plain.push('\r'),
+                        Some('t') => plain.push('\t'),
+                        Some(c) => plain.push(c),
+                        None => return self.error(start..self.position, "dangling `\\`"),
+                    }
+                    trailing = 0;
+                }
+                '[' => {
+                    flush(&mut inlines, &mut plain);
+                    trailing = 0;
+                    inlines.push(self.text_run()?);
+                }
+                ']' => return self.error(self.here(), "unexpected `]`; escape it as `\\]`"),
+                '@' if self.inline_directive_start() => {
+                    flush(&mut inlines, &mut plain);
+                    trailing = 0;
+                    inlines.push(self.icon()?);
+                }
+                c => {
+                    self.bump();
+                    plain.push(c);
+                    if c.is_whitespace() {
+                        trailing += c.len_utf8();
+                    } else {
+                        trailing = 0;
+                    }
+                }
+            }
+        }
+        plain.truncate(plain.len() - trailing);
+        flush(&mut inlines, &mut plain);
+        Ok(inlines)
+    }
+
+    /// Whether `@Name(` starts here.
+    fn inline_directive_start(&self) -> bool {
+        let rest = &self.rest()[1..];
+        let name = rest
+            .find(|c: char| !c.is_ascii_alphanumeric())
+            .unwrap_or(rest.len());
+        name > 0 && rest[name..].starts_with('(')
+    }
+
+    fn text_run(&mut self) -> Result<InlineComponent, SyntaxError> {
+        let start = self.position;
+        self.bump();
+        let mut text = String::new();
+        loop {
+            match self.bump() {
+                None => return self.error(start..start + 1, "unclosed `[`"),
+                Some(']') => break,
+                Some('\\') => match self.bump() {
+                    Some('n') => text.push('\n'),
+                    Some('r') => text.push('\r'),
+                    Some('t') => text.push('\t'),
+                    Some(c) => text.push(c),
+                    None => return self.error(start..start + 1, "unclosed `[`"),
+                },
+                Some(c) => text.push(c),
+            }
+        }
+        let span = start..self.position;
+        if !self.eat("(") {
+            return self.error(self.here(), "expected `(` after `]`");
+        }
+
+        let mut attributes = self.attributes()?;
+        if let Some((_, span)) = attributes.spans.iter().find(|(name, _)| name == "text") {
+            return self.error(span.clone(), "the text of a run goes between the brackets");
+        }
+        attributes
+            .props
+            .insert("text".to_string(), Value::from(text));
+        self.inline_component("Text", attributes, span)
+    }
+
+    fn icon(&mut self) -> Result<InlineComponent, SyntaxError> {
+        let start = self.position;
+        self.bump();
+        let type_name = self.identifier();
+        let span = start..self.position;
+        if type_name != "Icon" {
+            let message = if BLOCK_TYPES.contains(&type_name) {
+                format!(
+                    "`{}` is a block and cannot appear among text runs",
+                    type_name
+                )
+            } else {
+                format!("unknown inline component `{}`", type_name)
+            };
+            return self.error(span, message);
+        }
+        self.bump();
+        let attributes = self.attributes()?;
+        self.inline_component("Icon", attributes, span)
+    }
+
+    fn inline_component(
+        &self,
+        type_name: &str,
+        attributes: Attributes,
+        span: Range<usize>,
+    ) -> Result<InlineComponent, SyntaxError> {
+        let json = serde_json::json!({
+            "type": type_name,
+            "id": attributes.id,
+            "props": attributes.props,
+        });
+        let inline_component = serde_json::from_value::<InlineComponent>(json)
+            .or_else(|error| self.error(span, format!("invalid `{}`: {}", type_name, error)))?;
+        self.check_props(
+            &Component::InlineComponent(inline_component.clone()),
+            &attributes,
+        )?;
+        Ok(inline_component)
+    }
+
+    fn deserialize(&self, json: Value, span: Range<usize>) -> Result<Component, SyntaxError> {
+        let type_name = json["type"].as_str().unwrap_or_default().to_string();
+        serde_json::from_value::<BlockComponent>(json)
+            .map(Component::BlockComponent)
+            .or_else(|error| self.error(span, format!("invalid `{}`: {}", type_name, error)))
+    }
+
+    /// Rejects props that deserialization silently dropped.
+    fn check_props(
+        &self,
+        component: &Component,
+        attributes: &Attributes,
+    ) -> Result<(), SyntaxError> {
+        let json = serde_json::to_value(component).unwrap_or_default();
+        for (name, span) in &attributes.spans {
+            let value = &attributes.props[name];
+            if !matches!(value, Value::Null | Value::Bool(false))
+                && json["props"].get(name).is_none()
+            {
+                return self.error(
+                    span.clone(),
+                    format!("`{}` has no prop `{}`", component.type_name(), name),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn id(&mut self) -> Result<String, SyntaxError> {
+        if self.peek() == Some('"') {
+            return self.string();
+        }
+        let start = self.position;
+        while self.peek().is_some_and(is_id_char) {
+            self.bump();
+        }
+        if start == self.position {
+            return self.error(self.here(), "expected an id");
+        }
+        Ok(self.source[start..self.position].to_string())
+    }
+
+    /// Parses `key=value` pairs up to and including the closing parenthesis.
+    fn attributes(&mut self) -> Result<Attributes, SyntaxError> {
+        let open = self.position - 1;
+        let mut attributes = Attributes::default();
+        loop {
+            self.skip_whitespace();
+            let start = self.position;
+            match self.peek() {
+                None => return self.error(open..open + 1, "unclosed `(`"),
+                Some(')') => {
+                    self.bump();
+                    return Ok(attributes);
+                }
+                Some('#') => {
+                    self.bump();
+                    attributes.id = Some(self.id()?);
+                    continue;
+                }
+                Some(_) => {}
+            }
+
+            let name = self.identifier().to_string();
+            if name.is_empty() {
+                return self.error(self.here(), "expected a prop name");
+            }
+            let value = if self.eat("=") {
+                self.value()?
+            } else {
+                Value::Bool(true)
+            };
+            let span = start..self.position;
+            if name == "id" {
+                let Value::String(id) = value else {
+                    return self.error(span, "an id must be a string");
+                };
+                attributes.id = Some(id);
+                continue;
+            }
+            attributes.spans.retain(|(other, _)| *other != name);
+            attributes.spans.push((name.clone(), span));
+            attributes.props.insert(name, value);
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, SyntaxError> {
+        if self.peek() == Some('"') {
+            return self.string().map(Value::String);
+        }
+        let start = self.position;
+        while self.peek().is_some_and(|c| !c.is_whitespace() && c != ')') {
+            self.bump();
+        }
+        let token = &self.source[start..self.position];
+        Ok(match token {
+            "" => return self.error(self.here(), "expected a value"),
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            "null" => Value::Null,
+            _ => match serde_json::from_str::<Number>(token) {
+                Ok(number) => Value::Number(number),
+                Err(_) => Value::from(token),
+            },
+        })
+    }
+
+    fn string(&mut self) -> Result<String, SyntaxError> {
+        let start = self.position;
+        self.bump();
+        let mut string = String::new();
+        loop {
+            match self.bump() {
+                None => return self.error(start..start + 1, "unclosed string"),
+                Some('"') => return Ok(string),
+                Some('\\') => match self.bump() {
+                    Some('n') => string.push('\n'),
+                    Some('r') => string.push('\r'),
+                    Some('t') => string.push('\t'),
+                    Some(c) => string.push(c),
+                    None => return self.error(start..start + 1, "unclosed string"),
+                },
+                Some(c) => string.push(c),
+            }
+        }
+    }
+}
+
+fn is_id_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':')
+}
+
+// Printer # -------------------------------------------------- #
+
+/// Prints `components` in the text syntax.
+pub fn render<W>(components: &[Component], sink: &mut W) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    write_blocks(components, 0, true, sink)
+}
+
+pub fn to_string(components: &[Component]) -> String {
+    let mut output = String::new();
+    render(components, &mut output).expect("writing to a string cannot fail");
+    output
+}
+
+/// How a block was printed, to keep neighbouring shorthands apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Printed {
+    Paragraph,
+    Bullets,
+    Numbers,
+    Other,
+}
+
+fn write_indent<W>(indent: usize, sink: &mut W) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    for _ in 0..indent {
+        sink.write_str("  ")?;
+    }
+    Ok(())
+}
+
+fn write_blocks<W>(children: &[Component], indent: usize, top: bool, sink: &mut W) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    let mut previous = None;
+    let mut index = 0;
+    while index < children.len() {
+        let inlines = children[index..]
+            .iter()
+            .map_while(|child| match child {
+                Component::InlineComponent(inline_component) => Some(inline_component),
+                Component::BlockComponent(_) => None,
+            })
+            .collect::<Vec<_>>();
+
+        let mut line = String::new();
+        let printed = if inlines.is_empty() {
+            let Component::BlockComponent(block) = &children[index] else {
+                unreachable!("inline children are grouped above");
+            };
+            index += 1;
+            write_block(block, indent, &mut line)?
+        } else {
+            index += inlines.len();
+            line.push('|');
+            if !inlines.is_empty() {
+                line.push(' ');
+            }
+            write_inlines(&inlines, false, &mut line)?;
+            Printed::Other
+        };
+
+        if let Some(previous) = previous
+            && (top || (previous == printed && printed != Printed::Other))
+        {
+            sink.write_char('\n')?;
+        }
+        write_indent(indent, sink)?;
+        sink.write_str(&line)?;
+        sink.write_char('\n')?;
+        previous = Some(printed);
+    }
+    Ok(())
+}
+
+fn write_block<W>(
+    block: &BlockComponent,
+    indent: usize,
+    sink: &mut W,
+) -> Result<Printed, fmt::Error>
+where
+    W: fmt::Write,
+{
+    let json = serde_json::to_value(block).map_err(|_| fmt::Error)?;
+    let props = json.get("props").filter(|props| !props.is_null());
+    let plain = block.id().is_none() && props.is_none();
+
+    match block {
+        BlockComponent::Heading(heading)
+            if block.id().is_none() && !heading.slots.default.is_empty() =>
+        {
+            let level = u8::from(heading.props.level.clone()) as usize;
+            sink.write_str(&"#".repeat(level))?;
+            sink.write_char(' ')?;
+            write_inlines(
+                &heading.slots.default.iter().collect::<Vec<_>>(),
+                false,
+                sink,
+            )?;
+            return Ok(Printed::Other);
+        }
+        BlockComponent::Paragraph(paragraph)
+            if plain
+                && matches!(
+                    paragraph.slots.default.first(),
+                    Some(InlineComponent::Text(_))
+                ) =>
+        {
+            write_inlines(
+                &paragraph.slots.default.iter().collect::<Vec<_>>(),
+                true,
+                sink,
+            )?;
+            return Ok(Printed::Paragraph);
+        }
+        BlockComponent::Divider(divider) if plain && divider.slots.is_none() => {
+            sink.write_str("---")?;
+            return Ok(Printed::Other);
+        }
+        BlockComponent::CodeBlock(code_block)
+            if block.id().is_none()
+                && code_block.slots.is_none()
+                && code_block.props.language.trim() == code_block.props.language
+                && !code_block.props.language.contains(['\n', '`']) =>
+        {
+            let code = &code_block.props.code;
+            let longest = code.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+            let fence = "`".repeat((longest + 1).max(3));
+            writeln!(sink, "{}{}", fence, code_block.props.language)?;
+            for line in code.split('\n') {
+                if !line.is_empty() {
+                    write_indent(indent, sink)?;
+                    sink.write_str(line)?;
+                }
+                sink.write_char('\n')?;
+            }
+            write_indent(indent, sink)?;
+            sink.write_str(&fence)?;
+            return Ok(Printed::Other);
+        }
+        BlockComponent::List(list) => {
+            let printed = match &list.props {
+                None => Printed::Bullets,
+                Some(ListProps {
+                    list_style: Some(ListStyle::Ordered),
+                }) => Printed::Numbers,
+                Some(_) => Printed::Other,
+            };
+            let items = list
+                .slots
+                .default
+                .iter()
+                .map(|item| match item {
+                    Component::BlockComponent(BlockComponent::ListItem(item))
+                        if item.id.is_none() && item.props.is_none() =>
+                    {
+                        item.slots
+                            .default
+                            .iter()
+                            .map(|child| match child {
+                                Component::InlineComponent(inline_component) => {
+                                    Some(inline_component)
+                                }
+                                Component::BlockComponent(_) => None,
+                            })
+                            .collect::<Option<Vec<_>>>()
+                            .filter(|inlines| !inlines.is_empty())
+                    }
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()
+                .filter(|items| !items.is_empty());
+
+            if let Some(items) = items
+                && list.id.is_none()
+                && printed != Printed::Other
+            {
+                for (number, inlines) in items.iter().enumerate() {
+                    if number > 0 {
+                        sink.write_char('\n')?;
+                        write_indent(indent, sink)?;
+                    }
+                    match printed {
+                        Printed::Numbers => write!(sink, "{}. ", number + 1)?,
+                        _ => sink.write_str("- ")?,
+                    }
+                    write_inlines(inlines, false, sink)?;
+                }
+                return Ok(printed);
+            }
+        }
+        _ => {}
+    }
+
+    write_directive(block, &json, indent, sink)?;
+    Ok(Printed::Other)
+}
+
+fn write_directive<W>(
+    block: &BlockComponent,
+    json: &Value,
+    indent: usize,
+    sink: &mut W,
+) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    let type_name = block.type_name();
+    sink.write_char('@')?;
+    sink.write_str(type_name)?;
+    if let Some(id) = block.id() {
+        sink.write_char('#')?;
+        write_id(id, sink)?;
+    }
+    if let Some(Value::Object(props)) = json.get("props") {
+        sink.write_char('(')?;
+        write_props(props, &[], sink)?;
+        sink.write_char(')')?;
+    }
+    if json.get("slots").is_none_or(Value::is_null) {
+        return Ok(());
+    }
+
+    let slots = block.slots();
+    let primary = primary_slot(type_name).map(|(name, _)| name);
+    let children = slots
+        .iter()
+        .find(|(name, _)| Some(*name) == primary)
+        .map(|(_, slot)| *slot);
+    if let Some(Slot::Inlines(inlines)) = children {
+        if inlines.is_empty() {
+            return sink.write_str(" {}");
+        }
+        sink.write_str(" { ")?;
+        write_inlines(&inlines.iter().collect::<Vec<_>>(), false, sink)?;
+        return sink.write_str(" }");
+    }
+
+    let sections = slots
+        .iter()
+        .filter(|(name, slot)| {
+            Some(*name) != primary && (!slot.is_empty() || is_optional_slot(type_name, name))
+        })
+        .collect::<Vec<_>>();
+    if sections.is_empty() && children.is_none_or(|slot| slot.is_empty()) {
+        return sink.write_str(" {}");
+    }
+
+    sink.write_str(" {\n")?;
+    for (name, slot) in sections {
+        write_indent(indent + 1, sink)?;
+        write!(sink, ":{}", name)?;
+        match slot {
+            Slot::Inlines(inlines) if inlines.is_empty() => sink.write_str(" {}")?,
+            Slot::Inlines(inlines) => {
+                sink.write_str(" { ")?;
+                write_inlines(&inlines.iter().collect::<Vec<_>>(), false, sink)?;
+                sink.write_str(" }")?;
+            }
+            Slot::Components(components) if components.is_empty() => sink.write_str(" {}")?,
+            Slot::Components(components) => {
+                sink.write_str(" {\n")?;
+                write_blocks(components, indent + 2, false, sink)?;
+                write_indent(indent + 1, sink)?;
+                sink.write_char('}')?;
+            }
+        }
+        sink.write_char('\n')?;
+    }
+    if let Some(Slot::Components(components)) = children {
+        write_blocks(components, indent + 1, false, sink)?;
+    }
+    write_indent(indent, sink)?;
+    sink.write_char('}')
+}
+
+fn write_inlines<W>(inlines: &[&InlineComponent], line_start: bool, sink: &mut W) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    let mut previous_plain = false;
+    for (index, inline_component) in inlines.iter().enumerate() {
+        let json = serde_json::to_value(inline_component).map_err(|_| fmt::Error)?;
+        let empty = Map::new();
+        let props = json["props"].as_object().unwrap_or(&empty);
+        let id = inline_component.id();
+        match inline_component {
+            InlineComponent::Text(text) => {
+                let content = &text.props.text;
+                let styled = id.is_some()
+                    || props
+                        .iter()
+                        .any(|(name, value)| name != "text" && !value.is_null());
+                // Brackets keep runs apart and protect whitespace from trimming.
+                let bracketed = styled
+                    || content.is_empty()
+                    || previous_plain
+                    || (index == 0 && content.starts_with(char::is_whitespace))
+                    || (index + 1 == inlines.len() && content.ends_with(char::is_whitespace));
+                let plain = !bracketed;
+                if plain {
+                    write_plain(content, line_start && index == 0, sink)?;
+                } else {
+                    sink.write_char('[')?;
+                    for c in content.chars() {
+                        match c {
+                            '\\' | ']' => write!(sink, "\\{}", c)?,
+                            '\n' => sink.write_str("\\n")?,
+                            '\r' => sink.write_str("\\r")?,
+                            c => sink.write_char(c)?,
+                        }
+                    }
+                    sink.write_str("](")?;
+                    if let Some(id) = id {
+                        sink.write_char('#')?;
+                        write_id(id, sink)?;
+                        if styled
+                            && props
+                                .iter()
+                                .any(|(name, value)| name != "text" && !value.is_null())
+                        {
+                            sink.write_char(' ')?;
+                        }
+                    }
+                    write_props(props, &["text"], sink)?;
+                    sink.write_char(')')?;
+                }
+                previous_plain = plain;
+            }
+            InlineComponent::Icon(_) => {
+                sink.write_str("@Icon(")?;
+                if let Some(id) = id {
+                    sink.write_char('#')?;
+                    write_id(id, sink)?;
+                    sink.write_char(' ')?;
+                }
+                write_props(props, &[], sink)?;
+                sink.write_char(')')?;
+                previous_plain = false;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_plain<W>(text: &str, line_start: bool, sink: &mut W) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    let digits = text.bytes().take_while(u8::is_ascii_digit).count();
+    let escape_first = line_start
+        && (text.starts_with(['@', '#', '-', '`', '|', ':'])
+            || (digits > 0 && text[digits..].starts_with(". ")));
+    for (index, c) in text.char_indices() {
+        match c {
+            '\\' | '[' | ']' | '}' => write!(sink, "\\{}", c)?,
+            '\n' => sink.write_str("\\n")?,
+            '\r' => sink.write_str("\\r")?,
+            '@' if {
+                let rest = &text[index + 1..];
+                let name = rest
+                    .find(|c: char| !c.is_ascii_alphanumeric())
+                    .unwrap_or(rest.len());
+                name > 0 && rest[name..].starts_with('(')
+            } =>
+            {
+                sink.write_str("\\@")?
+            }
+            c if index == 0 && escape_first => write!(sink, "\\{}", c)?,
+            c => sink.write_char(c)?,
+        }
+    }
+    Ok(())
+}
+
+fn write_id<W>(id: &str, sink: &mut W) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    if !id.is_empty() && id.chars().all(is_id_char) {
+        sink.write_str(id)
+    } else {
+        write_string(id, sink)
+    }
+}
+
+fn write_props<W>(props: &Map<String, Value>, skip: &[&str], sink: &mut W) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    let mut first = true;
+    for (name, value) in props {
+        if value.is_null() || skip.contains(&name.as_str()) {
+            continue;
+        }
+        if !first {
+            sink.write_char(' ')?;
+        }
+        first = false;
+        match value {
+            Value::Bool(true) => sink.write_str(name)?,
+            Value::Bool(false) => write!(sink, "{}=false", name)?,
+            Value::Number(number) => write!(sink, "{}={}", name, number)?,
+            Value::String(string) => {
+                write!(sink, "{}=", name)?;
+                let bare = !string.is_empty()
+                    && string
+                        .chars()
+                        .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | '/' | ':'))
+                    && !matches!(string.as_str(), "true" | "false" | "null")
+                    && serde_json::from_str::<Number>(string).is_err();
+                if bare {
+                    sink.write_str(string)?;
+                } else {
+                    write_string(string, sink)?;
+                }
+            }
+            value => {
+                write!(sink, "{}=", name)?;
+                write_string(&value.to_string(), sink)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_string<W>(string: &str, sink: &mut W) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    sink.write_char('"')?;
+    for c in string.chars() {
+        match c {
+            '\\' | '"' => write!(sink, "\\{}", c)?,
+            '\n' => sink.write_str("\\n")?,
+            '\r' => sink.write_str("\\r")?,
+            '\t' => sink.write_str("\\t")?,
+            c => sink.write_char(c)?,
+        }
+    }
+    sink.write_char('"')
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{parse, to_string};
+    use crate::{Component, Divider, Heading, InlineComponent, Paragraph, Toggle, builder::text};
+
+    const SAMPLE: &str = r#"
+# Getting *started*
+
+Press [Ctrl](kbd) + [C](kbd) to copy,
+then paste \[anywhere\].
+
+- First
+- [Second](bold color=red)
+
+1. One
+2. Two
+
+@Callout#tip(type=tip) {
+  Mind the @Icon(src=/hot.svg alt=hot) plate.
+  ---
+}
+
+```rust
+fn main() {}
+```
+
+@Toggle {
+  :summary { More }
+  Hidden until opened.
+}
+"#;
+
+    fn round_trip(components: &[Component]) {
+        let text = to_string(components);
+        let parsed = parse(&text).unwrap_or_else(|error| panic!("{}\n{}", error, text));
+        assert_eq!(
+            serde_json::to_value(&parsed).unwrap(),
+            serde_json::to_value(components).unwrap(),
+            "{}",
+            text
+        );
+        assert_eq!(to_string(&parsed), text);
+    }
+
+    #[test]
+    fn test_parse() {
+        let components = parse(SAMPLE).unwrap();
+        let types = components
+            .iter()
+            .map(Component::type_name)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            types,
+            [
+                "Heading",
+                "Paragraph",
+                "List",
+                "List",
+                "Callout",
+                "CodeBlock",
+                "Toggle"
+            ]
+        );
+
+        let json = serde_json::to_value(&components).unwrap();
+        assert_eq!(json[0]["props"]["level"], 1);
+        assert_eq!(
+            json[0]["slots"]["default"][0]["props"]["text"],
+            "Getting *started*"
+        );
+        let paragraph = &json[1]["slots"]["default"];
+        assert_eq!(paragraph[0]["props"]["text"], "Press ");
+        assert_eq!(paragraph[1]["props"]["kbd"], true);
+        assert_eq!(
+            paragraph[4]["props"]["text"],
+            " to copy, then paste [anywhere]."
+        );
+        assert_eq!(json[2]["props"], serde_json::Value::Null);
+        assert_eq!(
+            json[2]["slots"]["default"][1]["slots"]["default"][0]["props"]["color"],
+            "red"
+        );
+        assert_eq!(json[3]["props"]["listStyle"], "ordered");
+        assert_eq!(json[4]["id"], "tip");
+        assert_eq!(json[4]["props"]["type"], "tip");
+        let callout = &json[4]["slots"]["default"];
+        assert_eq!(
+            callout[0]["slots"]["default"][1]["props"]["src"],
+            "/hot.svg"
+        );
+        assert_eq!(callout[1]["type"], "Divider");
+        assert_eq!(json[5]["props"]["code"], "fn main() {}");
+        assert_eq!(json[5]["props"]["language"], "rust");
+        assert_eq!(json[6]["slots"]["summary"][0]["props"]["text"], "More");
+
+        round_trip(&components);
+    }
+
+    #[test]
+    fn test_round_trip_edge_cases() {
+        let components = serde_json::from_str::<Vec<Component>>(
+            r##"[
+                {"type":"Paragraph","slots":{"default":[
+                    {"type":"Text","props":{"text":"# not a heading "}},
+                    {"type":"Text","props":{"text":"then"}},
+                    {"type":"Text","props":{"text":" spaced\n"}}
+                ]}},
+                {"type":"Paragraph","slots":{"default":[{"type":"Text","props":{"text":"1. not a list @Icon(x) {} \\"}}]}},
+                {"type":"Paragraph","slots":{"default":[{"type":"Icon","props":{"src":"a.svg"}}]}},
+                {"type":"Paragraph","slots":{"default":[]}},
+                {"type":"Heading","props":{"level":6},"slots":{"default":[{"type":"Text","id":"t 1","props":{"text":"]","href":"a b"}}]}},
+                {"type":"Divider","id":"cut"},
+                {"type":"CodeBlock","props":{"code":"```\n\n  indented\n","language":""}},
+                {"type":"CodeBlock","props":{"code":"x","language":" spaced "},"slots":{"default":[]}},
+                {"type":"List","props":{"listStyle":"unordered"},"slots":{"default":[
+                    {"type":"ListItem","slots":{"default":[{"type":"Text","props":{"text":"a"}}]}}
+                ]}},
+                {"type":"List","slots":{"default":[
+                    {"type":"ListItem","slots":{"default":[
+                        {"type":"Text","props":{"text":"a"}},
+                        {"type":"List","slots":{"default":[]}}
+                    ]}}
+                ]}},
+                {"type":"Tabs","slots":{"default":[
+                    {"type":"Tab","slots":{"labels":[],"contents":[{"type":"Text","props":{"text":"loose"}}]}}
+                ]}},
+                {"type":"Table","props":{"hasColumnHeader":false,"hasRowHeader":false},"slots":{"header":null,"body":[]}},
+                {"type":"Table","props":{"hasColumnHeader":true,"hasRowHeader":false},"slots":{"header":[],"body":[]}},
+                {"type":"Image","props":{"src":"true","alt":"42","width":10}},
+                {"type":"Fragment","slots":{"default":[
+                    {"type":"Paragraph","slots":{"default":[{"type":"Text","props":{"text":"a"}}]}},
+                    {"type":"Paragraph","slots":{"default":[{"type":"Text","props":{"text":"b"}}]}},
+                    {"type":"List","slots":{"default":[{"type":"ListItem","slots":{"default":[{"type":"Text","props":{"text":"c"}}]}}]}},
+                    {"type":"List","slots":{"default":[{"type":"ListItem","slots":{"default":[{"type":"Text","props":{"text":"d"}}]}}]}},
+                    {"type":"CodeBlock","props":{"code":"a\nb","language":"text"}}
+                ]}},
+                {"type":"Unsupported","props":null,"slots":null}
+            ]"##,
+        )
+        .unwrap();
+        round_trip(&components);
+
+        for components in [
+            vec![
+                Heading::h2(["Title"]).into(),
+                Paragraph::new([
+                    InlineComponent::from("Hello, "),
+                    text("world").bold().into(),
+                ])
+                .into(),
+                Toggle::new(["More"], [Divider::new()]).into(),
+            ],
+            serde_json::from_slice(include_bytes!("./jarkup.json")).unwrap(),
+        ] {
+            round_trip(&components);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let cases = [
+            ("@Nope", 1, 1, "unknown component type `Nope`"),
+            ("\n  @Text(text=a)", 2, 3, "`Text` is inline"),
+            (
+                "@Callout(type=tip colour=red)",
+                1,
+                19,
+                "`Callout` has no prop `colour`",
+            ),
+            ("@Callout(type=nope)", 1, 1, "invalid `Callout`"),
+            (
+                "@Toggle {\n  :labels { x }\n}",
+                2,
+                3,
+                "`Toggle` has no slot `labels`",
+            ),
+            ("@Divider { a }", 1, 12, "`Divider` has no slots"),
+            ("Hello [world", 1, 7, "unclosed `[`"),
+            ("@Fragment {\n  a\n", 3, 1, "expected `}`"),
+            ("a ] b", 1, 3, "unexpected `]`"),
+            ("```rust\nfn main() {}", 1, 1, "unclosed code fence"),
+            ("}", 1, 1, "unexpected `}`"),
+            ("[a](text=b)", 1, 5, "the text of a run"),
+            ("日本 @Image(src=a)", 1, 4, "`Image` is a block"),
+        ];
+        for (source, line, column, message) in cases {
+            let error = parse(source).unwrap_err();
+            assert_eq!((error.line, error.column), (line, column), "{}", source);
+            assert!(error.message.starts_with(message), "{}: {}", source, error);
+            assert!(source.get(error.span.clone()).is_some());
+        }
+
+        let deep = "@Fragment {".repeat(200) + &"}".repeat(200);
+        assert_eq!(parse(&deep).unwrap_err().message, "nesting too deep");
+    }
+}
@@ -0,0 +1,100 @@
+//! Tables of contents.
+//!
+//! [`toc`] lists the headings of a document in order, including those nested
+//! in toggles, columns and other containers, so that callers can render them
+//! flat or rebuild the hierarchy from their levels.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{BlockComponent, Component, InlineComponent, node::Slot, path::NodePath};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TocEntry {
+    /// Heading level, from 1 to 6.
+    pub level: u8,
+    /// Concatenated text of the heading.
+    pub text: String,
+    pub id: Option<String>,
+    pub path: NodePath,
+}
+
+/// Returns the headings of `components` in document order.
+pub fn toc(components: &[Component]) -> Vec<TocEntry> {
+    let mut entries = Vec::new();
+    for (index, component) in components.iter().enumerate() {
+        collect(component, NodePath::new(index), &mut entries);
+    }
+    entries
+}
+
+fn collect(component: &Component, path: NodePath, entries: &mut Vec<TocEntry>) {
+    if let Component::BlockComponent(BlockComponent::Heading(heading)) = component {
+        let text = heading
+            .slots
+            .default
+            .iter()
+            .filter_map(|inline_component| match inline_component {
+                InlineComponent::Text(text) => Some(text.props.text.as_str()),
+                InlineComponent::Icon(_) => None,
+            })
+            .collect();
+        entries.push(TocEntry {
+            level: heading.props.level.clone().into(),
+            text,
+            id: heading.id.clone(),
+            path,
+        });
+        return;
+    }
+
+    for (name, slot) in component.slots() {
+        if let Slot::Components(children) = slot {
+            for (index, child) in children.iter().enumerate() {
+                collect(child, path.child(name, index), entries);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::toc;
+    use crate::Component;
+
+    #[test]
+    fn test_toc() {
+        let components = serde_json::from_str::<Vec<Component>>(
+            r#"[
+                {"type":"Heading","id":"intro","props":{"level":1},"slots":{"default":[
+                    {"type":"Text","props":{"text":"Intro"}},
+                    {"type":"Icon","props":{"src":"/a.svg"}},
+                    {"type":"Text","props":{"text":"duction","bold":true}}
+                ]}},
+                {"type":"Paragraph","slots":{"default":[{"type":"Text","props":{"text":"Body"}}]}},
+                {"type":"Toggle","slots":{"summary":[],"default":[
+                    {"type":"Heading","props":{"level":3},"slots":{"default":[{"type":"Text","props":{"text":"Hidden"}}]}}
+                ]}}
+            ]"#,
+        )
+        .unwrap();
+
+        let entries = toc(&components);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].text, "Introduction");
+        assert_eq!(entries[0].id.as_deref(), Some("intro"));
+        assert_eq!(entries[0].path.to_string(), "/0");
+        assert_eq!(entries[1].level, 3);
+        assert_eq!(entries[1].text, "Hidden");
+        assert_eq!(entries[1].path.to_string(), "/2/slots/default/0");
+        assert_eq!(
+            serde_json::to_value(&entries[1]).unwrap(),
+            serde_json::json!({
+                "level": 3,
+                "text": "Hidden",
+                "id": null,
+                "path": "/2/slots/default/0"
+            })
+        );
+    }
+}
@@ -0,0 +1,85 @@
+//! Document validation.
+//!
+//! [`validate`] walks a document and reports every problem it finds, each
+//! tagged with the path of the offending node such as `/0/slots/default/2`.
+
+use std::fmt;
+
+use crate::{BlockComponent, Component, mermaid, node::Slot};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationErrorKind {
+    Mermaid(mermaid::MermaidError),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    /// Path of the offending node, e.g. `/0/slots/default/2`.
+    pub path: String,
+    pub kind: ValidationErrorKind,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ValidationErrorKind::Mermaid(error) => {
+                write!(f, "{}: invalid mermaid diagram: {}", self.path, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Validates `components` and returns every error found, in document order.
+pub fn validate(components: &[Component]) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    validate_components(components, "", &mut errors);
+    errors
+}
+
+fn validate_components(components: &[Component], parent: &str, errors: &mut Vec<ValidationError>) {
+    for (index, component) in components.iter().enumerate() {
+        let path = format!("{}/{}", parent, index);
+
+        if let Component::BlockComponent(BlockComponent::Mermaid(mermaid)) = component
+            && let Err(mermaid_errors) = mermaid::validate(&mermaid.props.code)
+        {
+            errors.extend(mermaid_errors.into_iter().map(|error| ValidationError {
+                path: path.clone(),
+                kind: ValidationErrorKind::Mermaid(error),
+            }));
+        }
+
+        for (name, slot) in component.slots() {
+            if let Slot::Components(children) = slot {
+                validate_components(children, &format!("{}/slots/{}", path, name), errors);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::validate;
+    use crate::Component;
+
+    #[test]
+    fn test_validate_nested_mermaid() {
+        let json = r#"[
+            {"type":"Mermaid","props":{"code":"graph TD\n  A-->B"}},
+            {"type":"Toggle","slots":{"summary":[],"default":[
+                {"type":"Mermaid","props":{"code":"graph TD\n  A[oops-->B"}}
+            ]}}
+        ]"#;
+        let components = serde_json::from_str::<Vec<Component>>(json).unwrap();
+        let errors = validate(&components);
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].to_string(),
+            "/1/slots/default/0: invalid mermaid diagram: line 2: unclosed `[`"
+        );
+    }
+}
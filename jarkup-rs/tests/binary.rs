@@ -0,0 +1,71 @@
+//! Round trips through the binary encodings, over the same corpus as
+//! `roundtrip.rs`.
+
+#![cfg(any(feature = "cbor", feature = "msgpack"))]
+
+use jarkup_rs::{Component, binary::BinaryError};
+
+type Encode = fn(&[Component]) -> Result<Vec<u8>, BinaryError>;
+type Decode = fn(&[u8]) -> Result<Vec<Component>, BinaryError>;
+
+fn corpus() -> Vec<Vec<Component>> {
+    [
+        include_bytes!("./seed.json").as_slice(),
+        include_bytes!("./corpus.json").as_slice(),
+        include_bytes!("../src/jarkup.json").as_slice(),
+    ]
+    .into_iter()
+    .map(|slice| serde_json::from_slice(slice).unwrap())
+    .collect()
+}
+
+fn assert_roundtrip(
+    name: &str,
+    (encode, decode): (Encode, Decode),
+    (compact_encode, compact_decode): (Encode, Decode),
+) {
+    for components in corpus() {
+        let json = serde_json::to_vec(&components).unwrap();
+        let bytes = encode(&components).unwrap();
+        let compact = compact_encode(&components).unwrap();
+        assert_eq!(decode(&bytes).unwrap(), components, "{}", name);
+        assert_eq!(
+            compact_decode(&compact).unwrap(),
+            components,
+            "{} compact",
+            name
+        );
+        assert!(bytes.len() < json.len(), "{} is larger than JSON", name);
+        assert!(
+            compact.len() < bytes.len(),
+            "{} compact is not smaller",
+            name
+        );
+    }
+    assert!(decode(b"\xff\x00").is_err());
+    assert!(compact_decode(&encode(&corpus()[0]).unwrap()).is_err());
+}
+
+#[cfg(feature = "cbor")]
+#[test]
+fn cbor() {
+    use jarkup_rs::binary::cbor;
+
+    assert_roundtrip(
+        "cbor",
+        (cbor::to_vec, cbor::from_slice),
+        (cbor::to_vec_compact, cbor::from_slice_compact),
+    );
+}
+
+#[cfg(feature = "msgpack")]
+#[test]
+fn msgpack() {
+    use jarkup_rs::binary::msgpack;
+
+    assert_roundtrip(
+        "msgpack",
+        (msgpack::to_vec, msgpack::from_slice),
+        (msgpack::to_vec_compact, msgpack::from_slice_compact),
+    );
+}
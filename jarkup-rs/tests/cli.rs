@@ -0,0 +1,283 @@
+use std::{
+    env, fs,
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpStream,
+    process::{Child, Command, Output, Stdio},
+    time::Duration,
+};
+
+use jarkup_rs::Component;
+use serde_json::Value;
+
+const DOCUMENT: &str = r#"[
+    {"type":"Heading","id":"intro","props":{"level":1},"slots":{"default":[{"type":"Text","props":{"text":"Intro"}}]}},
+    {"type":"Paragraph","slots":{"default":[
+        {"type":"Text","props":{"text":"Hello "}},
+        {"type":"Text","props":{"text":"world","bold":true}},
+        {"type":"Icon","props":{"src":"/a.svg"}}
+    ]}},
+    {"type":"Mermaid","props":{"code":"flowchart LR\n  A[Start --> B"}},
+    {"type":"Image","props":{"src":"/cat.png"}}
+]"#;
+
+fn jarkup(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_jarkup"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn reported(output: &Output) -> Vec<Value> {
+    String::from_utf8(output.stderr.clone())
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[test]
+fn convert() {
+    let text = jarkup(&["convert", "--to", "text"], DOCUMENT);
+    assert!(text.status.success());
+    assert!(stdout(&text).starts_with("@Heading#intro(level=1) { Intro }\n"));
+
+    let json = jarkup(&["convert", "--from=text"], &stdout(&text));
+    assert!(json.status.success());
+    assert_eq!(
+        serde_json::from_str::<Vec<Component>>(&stdout(&json)).unwrap(),
+        serde_json::from_str::<Vec<Component>>(DOCUMENT).unwrap()
+    );
+
+    let html = jarkup(
+        &["convert", "--from", "html", "--to", "text"],
+        "<h2>Hi</h2>",
+    );
+    assert_eq!(stdout(&html), "## Hi\n");
+
+    let markdown = jarkup(
+        &["convert", "--from", "markdown", "--to", "text"],
+        "## Hi *there*\n",
+    );
+    assert_eq!(stdout(&markdown), "## Hi [there](italic)\n");
+
+    let markdown = jarkup(&["convert", "--to", "markdown"], DOCUMENT);
+    assert!(stdout(&markdown).starts_with("# Intro\n\nHello **world**![](/a.svg)\n"));
+
+    let directory = env::temp_dir().join(format!("jarkup-convert-{}", std::process::id()));
+    fs::create_dir_all(&directory).unwrap();
+    let output = directory.join("document.txt");
+    let plain = jarkup(&["convert", "-o", output.to_str().unwrap()], DOCUMENT);
+    assert!(plain.status.success());
+    assert_eq!(
+        fs::read_to_string(&output).unwrap(),
+        "Intro\n\nHello world\n"
+    );
+    fs::remove_dir_all(directory).unwrap();
+
+    let plain = jarkup(&["convert", "--from", "plain"], "Hello");
+    assert_eq!(plain.status.code(), Some(2));
+}
+
+#[test]
+fn validate() {
+    let output = jarkup(&["validate"], DOCUMENT);
+    assert_eq!(output.status.code(), Some(1));
+    let errors = reported(&output);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0]["kind"], "validation");
+    assert_eq!(errors[0]["file"], "-");
+    assert_eq!(errors[0]["path"], "/2");
+
+    let output = jarkup(&["validate", "--from", "text"], "@Callout(type=nope)");
+    assert_eq!(output.status.code(), Some(1));
+    let errors = reported(&output);
+    assert_eq!(errors[0]["kind"], "parse");
+    assert_eq!(
+        (&errors[0]["line"], &errors[0]["column"]),
+        (&1.into(), &1.into())
+    );
+
+    assert!(jarkup(&["validate"], "[]").status.success());
+
+    let deep = r#"[{"type":"Callout","slots":{"default":"#.repeat(40) + "[]" + &"}}]".repeat(40);
+    let output = jarkup(&["validate"], &deep);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(reported(&output)[0]["kind"], "limit");
+}
+
+#[test]
+fn fmt() {
+    let formatted = jarkup(&["fmt"], DOCUMENT);
+    assert!(formatted.status.success());
+    let formatted = stdout(&formatted);
+    assert!(jarkup(&["fmt", "--check"], &formatted).status.success());
+
+    let output = jarkup(&["fmt", "--check"], DOCUMENT);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(reported(&output)[0]["kind"], "unformatted");
+}
+
+#[test]
+fn reports() {
+    let stats: Value = serde_json::from_str(&stdout(&jarkup(&["stats"], DOCUMENT))).unwrap();
+    assert_eq!(stats["topLevel"], 4);
+    assert_eq!(stats["components"], 8);
+    assert_eq!(stats["maxDepth"], 2);
+    assert_eq!(stats["words"], 3);
+    assert_eq!(stats["types"]["Text"], 3);
+
+    let assets: Value = serde_json::from_str(&stdout(&jarkup(&["assets"], DOCUMENT))).unwrap();
+    assert_eq!(assets["icons"][0], "/a.svg");
+    assert_eq!(assets["images"][0], "/cat.png");
+
+    let toc: Value = serde_json::from_str(&stdout(&jarkup(&["toc"], DOCUMENT))).unwrap();
+    assert_eq!(toc[0]["id"], "intro");
+    assert_eq!(toc[0]["text"], "Intro");
+}
+
+#[test]
+fn usage() {
+    let output = jarkup(&["frobnicate"], "");
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(reported(&output)[0]["kind"], "usage");
+
+    let output = jarkup(&["convert", "--to", "pdf"], DOCUMENT);
+    assert_eq!(output.status.code(), Some(2));
+
+    let output = jarkup(&["--help"], "");
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with("Usage: jarkup"));
+}
+
+/// Kills the server when the test ends, even on failure.
+struct Server(Child);
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+#[test]
+fn serve() {
+    let directory = env::temp_dir().join(format!("jarkup-serve-{}", std::process::id()));
+    fs::create_dir_all(directory.join("docs")).unwrap();
+    fs::write(directory.join("docs/page.jarkup"), "# Hello\n").unwrap();
+    fs::write(directory.join("notes.md"), "# Notes\n\nSome *Markdown*.\n").unwrap();
+    fs::write(directory.join("cat.txt"), "meow").unwrap();
+
+    let mut server = Server(
+        Command::new(env!("CARGO_BIN_EXE_jarkup"))
+            .args(["serve", "--port", "0"])
+            .arg(&directory)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap(),
+    );
+    let mut banner = String::new();
+    BufReader::new(server.0.stdout.take().unwrap())
+        .read_line(&mut banner)
+        .unwrap();
+    let address = banner.trim().rsplit("http://").next().unwrap().to_string();
+
+    let request = |path: &str| {
+        let mut stream = TcpStream::connect(&address).unwrap();
+        write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).unwrap();
+        stream
+    };
+    let get = |path: &str| {
+        let mut response = String::new();
+        request(path).read_to_string(&mut response).unwrap();
+        response
+    };
+
+    assert!(get("/").contains("<a href=\"/docs/page.html\">"));
+    let page = get("/docs/page.html");
+    assert!(page.starts_with("HTTP/1.1 200 OK"));
+    assert!(page.contains("<title>Hello</title>"));
+    assert!(page.contains(">Hello</h1>"));
+    assert!(page.contains("/_events"));
+    let notes = get("/notes.html");
+    assert!(notes.contains("<title>Notes</title>"));
+    assert!(notes.contains("<em>Markdown</em>"));
+    assert!(get("/cat.txt").ends_with("\r\n\r\nmeow"));
+    assert!(get("/../Cargo.toml").starts_with("HTTP/1.1 404"));
+
+    let mut events = request("/_events");
+    events
+        .set_read_timeout(Some(Duration::from_secs(10)))
+        .unwrap();
+    let mut received = Vec::new();
+    let mut read_until = |needle: &str| {
+        while !String::from_utf8_lossy(&received).contains(needle) {
+            let mut buffer = [0; 256];
+            let length = events.read(&mut buffer).unwrap();
+            assert!(length > 0, "event stream closed");
+            received.extend_from_slice(&buffer[..length]);
+        }
+    };
+    read_until("text/event-stream");
+
+    fs::write(directory.join("docs/page.jarkup"), "# Goodbye, world\n").unwrap();
+    read_until("event: reload");
+    assert!(get("/docs/page.html").contains(">Goodbye, world</h1>"));
+
+    fs::write(directory.join("docs/page.jarkup"), "@Nope\n").unwrap();
+    read_until("data: 2");
+    assert!(get("/docs/page.html").contains("docs/page.jarkup:1:1: unknown component type"));
+
+    drop(server);
+    fs::remove_dir_all(directory).unwrap();
+}
+
+#[test]
+fn build() {
+    let directory = env::temp_dir().join(format!("jarkup-build-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(directory.join("source")).unwrap();
+    fs::write(
+        directory.join("source/a.jarkup"),
+        "# A\n\n[B](href=jarkup:b)\n",
+    )
+    .unwrap();
+    let build = |directory: &std::path::Path| {
+        let source = directory.join("source");
+        let output = directory.join("public");
+        let args = ["build", "--title", "Docs", "-o"];
+        Command::new(env!("CARGO_BIN_EXE_jarkup"))
+            .args(args)
+            .arg(&output)
+            .arg(&source)
+            .output()
+            .unwrap()
+    };
+
+    let output = build(&directory);
+    assert_eq!(output.status.code(), Some(1));
+    let errors = reported(&output);
+    assert_eq!(errors[0]["kind"], "link");
+    assert_eq!(errors[0]["file"], "a");
+
+    fs::write(directory.join("source/b.json"), "[]").unwrap();
+    assert!(build(&directory).status.success());
+    let page = fs::read_to_string(directory.join("public/a.html")).unwrap();
+    assert!(page.contains("<title>A | Docs</title>"));
+    assert!(page.contains("href=\"b.html\""));
+    fs::remove_dir_all(directory).unwrap();
+}
@@ -0,0 +1,63 @@
+use jarkup_rs::{
+    Callout, CalloutType, CodeBlock, Component, Divider, Heading, Image, InlineComponent, List,
+    ListItem, Paragraph, Table, Tabs, Toggle,
+    builder::{icon, text},
+    jarkup,
+};
+
+#[test]
+fn jarkup_macro() {
+    let name = "world";
+    let document = jarkup![
+        h1["Title"],
+        p["Hello ", b[{ name }, i["!"]], a("https://example.com")["link"], icon("/a.svg")],
+        callout(warning)[p["Careful"]],
+        ol["One", li[p["Two"], ul["Nested"]]],
+        toggle["More"][p[code["x"]], hr],
+        tabs[tab["Rust"][code(rust)["fn main() {}"]]],
+        table(header)[["Name", "Value"], ["a", text("1").bold()]],
+        img("/cat.png", "A cat"),
+        { Divider::new() },
+    ];
+
+    let expected: Vec<Component> = vec![
+        Heading::h1(["Title"]).into(),
+        Paragraph::new([
+            InlineComponent::from(text("Hello ")),
+            text("world").bold().into(),
+            text("!").bold().italic().into(),
+            text("link").href("https://example.com").into(),
+            icon("/a.svg").into(),
+        ])
+        .into(),
+        Callout::new(CalloutType::Warning, [Paragraph::new(["Careful"])]).into(),
+        List::ordered([
+            ListItem::from("One"),
+            ListItem::new([
+                Component::from(Paragraph::new(["Two"])),
+                List::unordered(["Nested"]).into(),
+            ]),
+        ])
+        .into(),
+        Toggle::new(
+            ["More"],
+            [
+                Component::from(Paragraph::new([text("x").code()])),
+                Divider::new().into(),
+            ],
+        )
+        .into(),
+        Tabs::new([jarkup_rs::Tab::new(
+            ["Rust"],
+            [CodeBlock::new("fn main() {}", "rust")],
+        )])
+        .into(),
+        Table::from_rows([[text("Name"), text("Value")], [text("a"), text("1").bold()]])
+            .with_header()
+            .into(),
+        Image::new("/cat.png").alt("A cat").into(),
+        Divider::new().into(),
+    ];
+
+    assert_eq!(document, expected);
+}
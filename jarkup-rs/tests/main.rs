@@ -14,7 +14,6 @@ fn serialize() {
             id: None,
             slots: jarkup_rs::ParagraphSlots {
                 default: vec![inline],
-                ..Default::default()
             },
         },
     ));
@@ -30,3 +29,20 @@ fn deserialize() {
 
     println!("{}", serde_json::to_string(&results).unwrap());
 }
+
+#[test]
+fn build() {
+    use jarkup_rs::builder::text;
+
+    let paragraph: jarkup_rs::Component =
+        jarkup_rs::Paragraph::new([text("Hello, ").bold(), text("world!")]).into();
+
+    let expected = serde_json::from_str::<jarkup_rs::Component>(
+        r#"{"type":"Paragraph","slots":{"default":[
+            {"type":"Text","props":{"text":"Hello, ","bold":true}},
+            {"type":"Text","props":{"text":"world!"}}
+        ]}}"#,
+    )
+    .unwrap();
+    assert_eq!(paragraph, expected);
+}
@@ -0,0 +1,731 @@
+//! Round-trip conformance harness.
+//!
+//! Every [`Format`] exports each document of the corpus and imports the result
+//! back. Both the original and the round-tripped document are serialized to
+//! JSON, passed through the format's normalizer to erase what the format is
+//! known to lose, and compared structurally. New formats only need an entry in
+//! [`formats`].
+
+use jarkup_rs::Component;
+use serde_json::{Map, Value, json};
+
+struct Format {
+    name: &'static str,
+    export: fn(&[Component]) -> String,
+    import: fn(&str) -> Vec<Component>,
+    /// Erases information the format cannot carry.
+    normalize: fn(&mut Value),
+}
+
+fn formats() -> Vec<Format> {
+    vec![
+        Format {
+            name: "json",
+            export: |components| serde_json::to_string(components).unwrap(),
+            import: |json| serde_json::from_str(json).unwrap(),
+            normalize: |_| {},
+        },
+        Format {
+            name: "html",
+            export: jarkup_rs::html::to_string,
+            import: jarkup_rs::parse::html::parse,
+            normalize: normalize_html,
+        },
+        Format {
+            name: "markdown",
+            export: jarkup_rs::markdown::to_string,
+            import: jarkup_rs::parse::markdown::parse,
+            normalize: normalize_markdown,
+        },
+        Format {
+            name: "text",
+            export: jarkup_rs::syntax::to_string,
+            import: |text| jarkup_rs::syntax::parse(text).unwrap(),
+            normalize: |_| {},
+        },
+    ]
+}
+
+fn corpus() -> Vec<(&'static str, Vec<Component>)> {
+    let documents: [(&str, &[u8]); 3] = [
+        ("tests/seed.json", include_bytes!("./seed.json")),
+        ("tests/corpus.json", include_bytes!("./corpus.json")),
+        ("src/jarkup.json", include_bytes!("../src/jarkup.json")),
+    ];
+    documents
+        .into_iter()
+        .map(|(name, slice)| (name, serde_json::from_slice(slice).unwrap()))
+        .collect()
+}
+
+// Normalizers # -------------------------------------------------- #
+
+/// Applies `f` to every component object in `value`, children first.
+fn visit(value: &mut Value, f: &mut dyn FnMut(&mut Map<String, Value>)) {
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                visit(item, f);
+            }
+        }
+        Value::Object(object) => {
+            if let Some(Value::Object(slots)) = object.get_mut("slots") {
+                for slot in slots.values_mut() {
+                    visit(slot, f);
+                }
+            }
+            if object.contains_key("type") {
+                f(object);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Removes `null`, `false`, empty strings and empty objects, which every
+/// format treats as absent.
+fn strip_empty(value: &mut Value) {
+    match value {
+        Value::Array(items) => items.iter_mut().for_each(strip_empty),
+        Value::Object(object) => {
+            for item in object.values_mut() {
+                strip_empty(item);
+            }
+            object.retain(|_, item| match item {
+                Value::Null | Value::Bool(false) => false,
+                Value::String(string) => !string.is_empty(),
+                Value::Object(object) => !object.is_empty(),
+                _ => true,
+            });
+        }
+        _ => {}
+    }
+}
+
+/// Replaces components of the given types by the contents of their slots.
+fn unwrap_types(value: &mut Value, types: &[&str]) {
+    fn unwrap(items: Vec<Value>, types: &[&str]) -> Vec<Value> {
+        let mut unwrapped = Vec::new();
+        for mut item in items {
+            if let Some(Value::Object(slots)) = item.get_mut("slots") {
+                for slot in slots.values_mut() {
+                    if let Value::Array(children) = slot {
+                        *children = unwrap(std::mem::take(children), types);
+                    }
+                }
+            }
+            if types.contains(&item["type"].as_str().unwrap_or_default()) {
+                if let Some(Value::Object(slots)) = item.get_mut("slots") {
+                    for (_, slot) in std::mem::take(slots) {
+                        if let Value::Array(children) = slot {
+                            unwrapped.extend(children);
+                        }
+                    }
+                }
+            } else {
+                unwrapped.push(item);
+            }
+        }
+        unwrapped
+    }
+
+    if let Value::Array(items) = value {
+        *items = unwrap(std::mem::take(items), types);
+    }
+}
+
+/// Keeps the components of `value`, and of every slot below it, for which
+/// `keep` returns true.
+fn retain_children(value: &mut Value, keep: &dyn Fn(&Value) -> bool) {
+    visit(value, &mut |object| {
+        if let Some(Value::Object(slots)) = object.get_mut("slots") {
+            for slot in slots.values_mut() {
+                if let Value::Array(items) = slot {
+                    items.retain(keep);
+                }
+            }
+        }
+    });
+    if let Value::Array(items) = value {
+        items.retain(keep);
+    }
+}
+
+/// Whether `item` is a component of the given type with an empty slot.
+fn is_empty(item: &Value, r#type: &str) -> bool {
+    item["type"] == r#type
+        && item["slots"]["default"]
+            .as_array()
+            .is_none_or(|items| items.is_empty())
+}
+
+/// Merges adjacent text runs with identical props and collapses whitespace
+/// the way HTML does, trimming it at the edges of each slot.
+fn normalize_text(value: &mut Value) {
+    visit(value, &mut |object| {
+        let Some(Value::Object(slots)) = object.get_mut("slots") else {
+            return;
+        };
+        for slot in slots.values_mut() {
+            let Value::Array(items) = slot else {
+                continue;
+            };
+
+            let mut merged: Vec<Value> = Vec::new();
+            for mut item in std::mem::take(items) {
+                if item["type"] == "Text" {
+                    let text = item["props"]["text"].as_str().unwrap_or_default();
+                    let mut collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                    let previous_ends_with_space = match merged.last() {
+                        Some(last) if last["type"] == "Text" => last["props"]["text"]
+                            .as_str()
+                            .unwrap_or_default()
+                            .ends_with(' '),
+                        Some(_) => false,
+                        None => true,
+                    };
+                    if text.starts_with(char::is_whitespace) && !previous_ends_with_space {
+                        collapsed.insert(0, ' ');
+                    }
+                    if text.ends_with(char::is_whitespace) && !collapsed.ends_with(' ') {
+                        collapsed.push(' ');
+                    }
+                    if collapsed.is_empty() {
+                        continue;
+                    }
+                    item["props"]["text"] = Value::from(collapsed);
+
+                    if let Some(last) = merged.last_mut()
+                        && last["type"] == "Text"
+                    {
+                        let mut last_props = last["props"].clone();
+                        let mut item_props = item["props"].clone();
+                        last_props["text"] = Value::Null;
+                        item_props["text"] = Value::Null;
+                        if last_props == item_props {
+                            let text = format!(
+                                "{}{}",
+                                last["props"]["text"].as_str().unwrap_or_default(),
+                                item["props"]["text"].as_str().unwrap_or_default()
+                            );
+                            last["props"]["text"] = Value::from(text);
+                            continue;
+                        }
+                    }
+                }
+                merged.push(item);
+            }
+
+            if let Some(first) = merged.first_mut().filter(|item| item["type"] == "Text") {
+                let text = first["props"]["text"].as_str().unwrap_or_default();
+                first["props"]["text"] = Value::from(text.trim_start());
+            }
+            if let Some(last) = merged.last_mut().filter(|item| item["type"] == "Text") {
+                let text = last["props"]["text"].as_str().unwrap_or_default();
+                last["props"]["text"] = Value::from(text.trim_end());
+            }
+            merged.retain(|item| item["type"] != "Text" || item["props"]["text"] != "");
+            *items = merged;
+        }
+    });
+}
+
+/// Rewrites the components the HTML round trip is known to turn into others:
+///
+/// - `Fragment`, `ColumnList`, `Column` and `Tabs` render as plain `<div>`s
+///   and are replaced by their children.
+/// - `Tab` renders as a label `<div>` and a content `<div>`, and comes back
+///   as a paragraph holding its labels followed by its contents.
+/// - `Mermaid` renders as a `<pre>` and comes back as a `plaintext` `CodeBlock`.
+/// - `Bookmark` and `File` render as links, and come back as linked text in a
+///   paragraph, merged with the links around them.
+/// - The runs in the slot of a `CodeBlock` render in place of its code, and
+///   come back as its code without the runs.
+fn lossy_html(value: &mut Value) {
+    fn link(text: String, href: &Value) -> Value {
+        json!({"type":"Paragraph","slots":{"default":[
+            {"type":"Text","props":{"text":text,"href":href}}
+        ]}})
+    }
+
+    visit(value, &mut |object| {
+        let props = object.get("props").cloned().unwrap_or_default();
+        let text = |key: &str| props[key].as_str().unwrap_or_default().to_string();
+        let replacement = match object["type"].as_str().unwrap_or_default() {
+            "Tab" => {
+                let labels =
+                    json!({"type":"Paragraph","slots":{"default":object["slots"]["labels"]}});
+                let mut children = vec![labels];
+                children.extend(
+                    object["slots"]["contents"]
+                        .as_array()
+                        .cloned()
+                        .unwrap_or_default(),
+                );
+                json!({"type":"Tab","slots":{"default":children}})
+            }
+            "Mermaid" => {
+                json!({"type":"CodeBlock","props":{"code":props["code"],"language":"plaintext"}})
+            }
+            "Bookmark" => {
+                let title = props["title"]
+                    .as_str()
+                    .map(str::to_string)
+                    .unwrap_or_else(|| text("url"));
+                link(title + &text("description"), &props["url"])
+            }
+            "File" => {
+                let name = props["name"]
+                    .as_str()
+                    .map(str::to_string)
+                    .unwrap_or_else(|| text("src"));
+                link(name, &props["src"])
+            }
+            "CodeBlock" => {
+                let runs = object["slots"]["default"].as_array().cloned();
+                if let Some(runs) = runs.filter(|runs| !runs.is_empty()) {
+                    let code = runs
+                        .iter()
+                        .map(|run| run["props"]["text"].as_str().unwrap_or_default())
+                        .collect::<String>();
+                    object["props"]["code"] = Value::from(code);
+                }
+                object.remove("slots");
+                return;
+            }
+            _ => return,
+        };
+        let Value::Object(replacement) = replacement else {
+            return;
+        };
+        *object = replacement;
+    });
+    unwrap_types(value, &["Fragment", "ColumnList", "Column", "Tabs", "Tab"]);
+
+    // Adjacent paragraphs holding only links are merged on import.
+    fn only_links(item: &Value) -> bool {
+        item["type"] == "Paragraph"
+            && item.get("props").is_none_or(Value::is_null)
+            && item["slots"]["default"].as_array().is_some_and(|items| {
+                items
+                    .iter()
+                    .all(|item| item["type"] == "Text" && item["props"]["href"].is_string())
+            })
+    }
+    fn merge(items: &mut Vec<Value>) {
+        let mut merged: Vec<Value> = Vec::new();
+        for item in std::mem::take(items) {
+            if let Some(last) = merged.last_mut()
+                && only_links(last)
+                && only_links(&item)
+                && let Some(Value::Array(runs)) = last["slots"].get_mut("default")
+            {
+                runs.extend(
+                    item["slots"]["default"]
+                        .as_array()
+                        .cloned()
+                        .unwrap_or_default(),
+                );
+                continue;
+            }
+            merged.push(item);
+        }
+        *items = merged;
+    }
+    if let Value::Array(items) = value {
+        merge(items);
+    }
+    visit(value, &mut |object| {
+        if let Some(Value::Object(slots)) = object.get_mut("slots") {
+            for slot in slots.values_mut() {
+                if let Value::Array(items) = slot {
+                    merge(items);
+                }
+            }
+        }
+    });
+}
+
+fn normalize_html(value: &mut Value) {
+    lossy_html(value);
+    normalize_text(value);
+
+    visit(value, &mut |object| {
+        let r#type = object["type"].as_str().unwrap_or_default().to_string();
+        // Only headings carry their id through the importer.
+        if r#type != "Heading" {
+            object.remove("id");
+        }
+        if let Some(Value::Object(props)) = object.get_mut("props") {
+            // Derived from the table structure on import.
+            props.remove("hasColumnHeader");
+            props.remove("hasRowHeader");
+            if props.get("listStyle") == Some(&Value::from("unordered")) {
+                props.remove("listStyle");
+            }
+        }
+    });
+
+    // Paragraphs without text are dropped by the importer.
+    strip_empty(value);
+    retain_children(value, &|item| !is_empty(item, "Paragraph"));
+    strip_empty(value);
+}
+
+/// Rewrites the components the Markdown round trip is known to turn into
+/// others:
+///
+/// - `Fragment`, `ColumnList`, `Column` and `Tabs` have no syntax and are
+///   replaced by their children, and `Unsupported` is dropped.
+/// - `Tab` renders as its labels in bold followed by its contents.
+/// - `Bookmark` and `File` render as links and come back as linked text in a
+///   paragraph.
+/// - The highlighted runs of a `CodeBlock` are not kept.
+/// - The caption of a `Table` and the `cite` of a `BlockQuote` render as a
+///   paragraph after the table and at the end of the quote.
+/// - Tables come back with their first row as the header and their rows
+///   padded to the same number of cells.
+/// - List items come back with the text they start with inline, and anything
+///   else in a list as a list item.
+/// - Inline components outside of paragraphs come back in paragraphs, and a
+///   paragraph holding only an icon as an `Image`.
+fn lossy_markdown(value: &mut Value) {
+    fn paragraph(default: Value) -> Value {
+        json!({"type":"Paragraph","slots":{"default":default}})
+    }
+    fn link(text: String, href: &Value) -> Value {
+        paragraph(json!([{"type":"Text","props":{"text":text,"href":href}}]))
+    }
+    fn is_inline(item: &Value) -> bool {
+        matches!(item["type"].as_str(), Some("Text" | "Icon"))
+    }
+
+    visit(value, &mut |object| {
+        let props = object.get("props").cloned().unwrap_or_default();
+        let text = |key: &str| props[key].as_str().unwrap_or_default().to_string();
+        let replacement = match object["type"].as_str().unwrap_or_default() {
+            "Tab" => {
+                let mut labels = object["slots"]["labels"].clone();
+                for label in labels.as_array_mut().into_iter().flatten() {
+                    if label["type"] == "Text" {
+                        label["props"]["bold"] = Value::from(true);
+                    }
+                }
+                let mut children = vec![paragraph(labels)];
+                children.extend(
+                    object["slots"]["contents"]
+                        .as_array()
+                        .cloned()
+                        .unwrap_or_default(),
+                );
+                json!({"type":"Fragment","slots":{"default":children}})
+            }
+            "Bookmark" => {
+                let title = props["title"]
+                    .as_str()
+                    .map(str::to_string)
+                    .unwrap_or_else(|| text("url"));
+                link(title, &props["url"])
+            }
+            "File" => {
+                let name = props["name"]
+                    .as_str()
+                    .map(str::to_string)
+                    .unwrap_or_else(|| text("src"));
+                link(name, &props["src"])
+            }
+            "CodeBlock" => {
+                object.remove("slots");
+                return;
+            }
+            "BlockQuote" if props["cite"].is_string() => {
+                let mut children = object["slots"]["default"]
+                    .as_array()
+                    .cloned()
+                    .unwrap_or_default();
+                children.push(paragraph(json!([
+                    {"type":"Text","props":{"text":format!("— {}", text("cite"))}}
+                ])));
+                json!({"type":"BlockQuote","slots":{"default":children}})
+            }
+            "Table" => {
+                let mut rows = Vec::new();
+                for slot in ["header", "body"] {
+                    for row in object["slots"][slot].as_array().into_iter().flatten() {
+                        let mut cells = row["slots"]["default"]
+                            .as_array()
+                            .cloned()
+                            .unwrap_or_default();
+                        for cell in &mut cells {
+                            cell.as_object_mut().map(|cell| cell.remove("props"));
+                        }
+                        while cells.last().is_some_and(|cell| {
+                            cell["slots"]["default"]
+                                .as_array()
+                                .is_none_or(|items| items.is_empty())
+                        }) {
+                            cells.pop();
+                        }
+                        if !cells.is_empty() {
+                            rows.push(json!({"type":"TableRow","slots":{"default":cells}}));
+                        }
+                    }
+                }
+                let table = json!({"type":"Table","slots":{"body":rows}});
+                match props["caption"].as_str() {
+                    Some(caption) => json!({"type":"Fragment","slots":{"default":[
+                        table,
+                        paragraph(json!([{"type":"Text","props":{"text":caption}}]))
+                    ]}}),
+                    None => table,
+                }
+            }
+            "List" => {
+                let items = object["slots"]["default"]
+                    .as_array()
+                    .cloned()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|item| match item["type"].as_str() {
+                        Some("ListItem") => item,
+                        _ => json!({"type":"ListItem","slots":{"default":[item]}}),
+                    })
+                    .collect::<Vec<_>>();
+                let list_style = props["listStyle"].as_str().unwrap_or("unordered");
+                json!({"type":"List","props":{"listStyle":list_style},"slots":{"default":items}})
+            }
+            "Callout" => {
+                let r#type = props["type"].as_str().unwrap_or("note");
+                json!({"type":"Callout","props":{"type":r#type},"slots":object["slots"]})
+            }
+            _ => return,
+        };
+        let Value::Object(replacement) = replacement else {
+            return;
+        };
+        *object = replacement;
+    });
+    unwrap_types(
+        value,
+        &["Fragment", "ColumnList", "Column", "Tabs", "Unsupported"],
+    );
+
+    // Block slots wrap inline runs in paragraphs, and list items unwrap the
+    // paragraph they start with.
+    fn wrap_inlines(items: &mut Vec<Value>) {
+        let mut wrapped: Vec<Value> = Vec::new();
+        for item in std::mem::take(items) {
+            if !is_inline(&item) {
+                wrapped.push(item);
+                continue;
+            }
+            if let Some(last) = wrapped.last_mut()
+                && last["type"] == "Paragraph"
+                && last.get("wrapped").is_some()
+                && let Some(Value::Array(runs)) = last["slots"].get_mut("default")
+            {
+                runs.push(item);
+                continue;
+            }
+            let mut paragraph = paragraph(json!([item]));
+            paragraph["wrapped"] = Value::from(true);
+            wrapped.push(paragraph);
+        }
+        for item in &mut wrapped {
+            item.as_object_mut().map(|item| item.remove("wrapped"));
+        }
+        *items = wrapped;
+    }
+    if let Value::Array(items) = value {
+        wrap_inlines(items);
+    }
+    visit(value, &mut |object| {
+        let r#type = object["type"].as_str().unwrap_or_default().to_string();
+        let Some(Value::Object(slots)) = object.get_mut("slots") else {
+            return;
+        };
+        for (name, slot) in slots.iter_mut() {
+            let Value::Array(items) = slot else {
+                continue;
+            };
+            match (r#type.as_str(), name.as_str()) {
+                ("ListItem", _)
+                    if items
+                        .first()
+                        .is_some_and(|item| item["type"] == "Paragraph") =>
+                {
+                    let first = items.remove(0);
+                    let runs = first["slots"]["default"]
+                        .as_array()
+                        .cloned()
+                        .unwrap_or_default();
+                    items.splice(0..0, runs);
+                }
+                ("BlockQuote" | "Callout" | "Toggle", "default") => wrap_inlines(items),
+                _ => {}
+            }
+        }
+    });
+
+    // Paragraphs holding only an icon are images.
+    fn images(items: &mut [Value]) {
+        for item in items {
+            let runs = item["slots"]["default"].as_array();
+            if item["type"] == "Paragraph"
+                && let Some([icon]) = runs.map(Vec::as_slice)
+                && icon["type"] == "Icon"
+            {
+                *item = json!({"type":"Image","props":icon["props"]});
+            }
+        }
+    }
+    if let Value::Array(items) = value {
+        images(items);
+    }
+    visit(value, &mut |object| {
+        if let Some(Value::Object(slots)) = object.get_mut("slots") {
+            for slot in slots.values_mut() {
+                if let Value::Array(items) = slot {
+                    images(items);
+                }
+            }
+        }
+    });
+}
+
+fn normalize_markdown(value: &mut Value) {
+    lossy_markdown(value);
+    normalize_text(value);
+
+    visit(value, &mut |object| {
+        object.remove("id");
+        let r#type = object["type"].as_str().unwrap_or_default().to_string();
+        if let Some(Value::Object(props)) = object.get_mut("props") {
+            match r#type.as_str() {
+                "Text" => {
+                    props.remove("color");
+                    props.remove("backgroundColor");
+                    props.remove("favicon");
+                }
+                "Image" => props.retain(|key, _| key == "src" || key == "alt"),
+                "Paragraph" => props.clear(),
+                _ => {}
+            }
+        }
+    });
+
+    // Paragraphs and headings without text are dropped by the importer.
+    strip_empty(value);
+    retain_children(value, &|item| {
+        !is_empty(item, "Paragraph") && !is_empty(item, "Heading")
+    });
+    strip_empty(value);
+}
+
+// Comparison # -------------------------------------------------- #
+
+/// Records the JSON pointer of every place where `expected` and `actual` differ.
+fn differences(expected: &Value, actual: &Value, path: &str, report: &mut Vec<String>) {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            for key in expected
+                .keys()
+                .chain(actual.keys().filter(|key| !expected.contains_key(*key)))
+            {
+                differences(
+                    expected.get(key).unwrap_or(&Value::Null),
+                    actual.get(key).unwrap_or(&Value::Null),
+                    &format!("{}/{}", path, key),
+                    report,
+                );
+            }
+        }
+        (Value::Array(expected), Value::Array(actual)) if expected.len() == actual.len() => {
+            for (index, (expected, actual)) in expected.iter().zip(actual).enumerate() {
+                differences(expected, actual, &format!("{}/{}", path, index), report);
+            }
+        }
+        (expected, actual) if expected != actual => {
+            report.push(format!(
+                "{}: expected {} but found {}",
+                path, expected, actual
+            ));
+        }
+        _ => {}
+    }
+}
+
+#[test]
+fn corpus_covers_every_component() {
+    const TYPES: &[&str] = &[
+        "Text",
+        "Icon",
+        "Fragment",
+        "Heading",
+        "Paragraph",
+        "ListItem",
+        "List",
+        "BlockQuote",
+        "Callout",
+        "Divider",
+        "Toggle",
+        "Bookmark",
+        "File",
+        "Image",
+        "CodeBlock",
+        "Katex",
+        "Mermaid",
+        "Tab",
+        "Tabs",
+        "Table",
+        "TableRow",
+        "TableCell",
+        "ColumnList",
+        "Column",
+        "Unsupported",
+    ];
+
+    let mut value = serde_json::from_slice::<Value>(include_bytes!("./corpus.json")).unwrap();
+    let mut found = std::collections::HashSet::new();
+    visit(&mut value, &mut |object| {
+        found.insert(object["type"].as_str().unwrap_or_default().to_string());
+    });
+    for r#type in TYPES {
+        assert!(
+            found.contains(*r#type),
+            "tests/corpus.json has no {}",
+            r#type
+        );
+    }
+}
+
+#[test]
+fn round_trip() {
+    let mut report = Vec::new();
+
+    for format in formats() {
+        for (name, components) in corpus() {
+            let exported = (format.export)(&components);
+            let imported = (format.import)(&exported);
+
+            let mut expected = serde_json::to_value(&components).unwrap();
+            let mut actual = serde_json::to_value(&imported).unwrap();
+            (format.normalize)(&mut expected);
+            (format.normalize)(&mut actual);
+
+            let mut differences_found = Vec::new();
+            differences(&expected, &actual, "", &mut differences_found);
+            report.extend(
+                differences_found
+                    .into_iter()
+                    .map(|difference| format!("[{}] {}{}", format.name, name, difference)),
+            );
+        }
+    }
+
+    assert!(
+        report.is_empty(),
+        "round-trip differences:\n{}",
+        report.join("\n")
+    );
+}
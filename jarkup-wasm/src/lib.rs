@@ -0,0 +1,123 @@
+//! WebAssembly bindings for jarkup.
+//!
+//! Every function takes a document as a jarkup JSON string, the same JSON the
+//! `jarkup-ts` types describe, and throws an `Error` when it is not one or
+//! exceeds the default `Limits` of `jarkup_rs::limits`. The package built with
+//! `wasm-pack` is exposed by `jarkup-ts` as `jarkup-ts/wasm`.
+
+use jarkup_rs::{
+    Assets, Component, html,
+    limits::{self, Limits},
+    markdown,
+    validate::{self, ValidationErrorKind},
+};
+use serde_json::{Value, json};
+use wasm_bindgen::prelude::*;
+
+/// Validates a document and returns a JSON array of the problems found, each
+/// as `{ "path", "kind", "message" }`.
+#[wasm_bindgen]
+pub fn validate(document: &str) -> Result<String, JsError> {
+    run_validate(document).map_err(|error| JsError::new(&error))
+}
+
+/// Renders a document to an HTML fragment.
+#[wasm_bindgen(js_name = renderHtml)]
+pub fn render_html(document: &str) -> Result<String, JsError> {
+    run_render_html(document).map_err(|error| JsError::new(&error))
+}
+
+/// Renders a document to GitHub-flavored Markdown.
+#[wasm_bindgen(js_name = toMarkdown)]
+pub fn to_markdown(document: &str) -> Result<String, JsError> {
+    run_to_markdown(document).map_err(|error| JsError::new(&error))
+}
+
+/// Re-serializes a document canonically, dropping unset props and unknown
+/// fields.
+#[wasm_bindgen]
+pub fn normalize(document: &str) -> Result<String, JsError> {
+    run_normalize(document).map_err(|error| JsError::new(&error))
+}
+
+/// Returns the icons, files and images a document references as JSON.
+#[wasm_bindgen(js_name = extractAssets)]
+pub fn extract_assets(document: &str) -> Result<String, JsError> {
+    run_extract_assets(document).map_err(|error| JsError::new(&error))
+}
+
+// `JsError` only works on wasm targets, so the bindings above are thin
+// wrappers around these.
+
+fn parse(document: &str) -> Result<Vec<Component>, String> {
+    limits::from_str(document, &Limits::default()).map_err(|error| error.to_string())
+}
+
+fn run_validate(document: &str) -> Result<String, String> {
+    let errors = validate::validate(&parse(document)?)
+        .into_iter()
+        .map(|error| {
+            let (kind, message) = match &error.kind {
+                ValidationErrorKind::Mermaid(mermaid) => ("mermaid", mermaid.to_string()),
+            };
+            json!({ "path": error.path, "kind": kind, "message": message })
+        })
+        .collect::<Vec<Value>>();
+    Ok(Value::Array(errors).to_string())
+}
+
+fn run_render_html(document: &str) -> Result<String, String> {
+    Ok(html::to_string(&parse(document)?))
+}
+
+fn run_to_markdown(document: &str) -> Result<String, String> {
+    Ok(markdown::to_string(&parse(document)?))
+}
+
+fn run_normalize(document: &str) -> Result<String, String> {
+    serde_json::to_string(&parse(document)?).map_err(|error| error.to_string())
+}
+
+fn run_extract_assets(document: &str) -> Result<String, String> {
+    let assets = parse(document)?
+        .into_iter()
+        .map(Component::extract_assets)
+        .fold(Assets::default(), |assets, more| assets + more);
+    serde_json::to_string(&assets).map_err(|error| error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const DOCUMENT: &str = r#"[
+        {"type":"Heading","props":{"level":1,"color":null},"slots":{"default":[{"type":"Text","props":{"text":"Hi"}}]}},
+        {"type":"Mermaid","props":{"code":"flowchart LR\n  A[Start --> B"}},
+        {"type":"Image","props":{"src":"/cat.png"}}
+    ]"#;
+
+    #[test]
+    fn test_bindings() {
+        let errors: Value = serde_json::from_str(&run_validate(DOCUMENT).unwrap()).unwrap();
+        assert_eq!(errors[0]["path"], "/1");
+        assert_eq!(errors[0]["kind"], "mermaid");
+
+        assert!(run_render_html(DOCUMENT).unwrap().contains(">Hi</h1>"));
+        assert!(run_to_markdown(DOCUMENT).unwrap().starts_with("# Hi\n"));
+        assert!(!run_normalize(DOCUMENT).unwrap().contains("color"));
+        assert_eq!(
+            run_extract_assets(DOCUMENT).unwrap(),
+            r#"{"icons":[],"files":[],"images":["/cat.png"]}"#
+        );
+
+        assert!(
+            run_normalize("{}")
+                .unwrap_err()
+                .starts_with("invalid document")
+        );
+        let deep =
+            r#"[{"type":"Callout","slots":{"default":"#.repeat(40) + "[]" + &"}}]".repeat(40);
+        assert!(run_normalize(&deep).unwrap_err().contains("nested deeper"));
+    }
+}